@@ -40,6 +40,13 @@ pub enum Actions {
         /// Output ANISE binary file
         outfile: PathBuf,
     },
+    /// Convert the provided spacecraft clock kernel into an ANISE dataset
+    ConvertSclk {
+        /// Path to the SCLK (e.g. cas00172.tsc)
+        sclkfile: PathBuf,
+        /// Output ANISE binary file
+        outfile: PathBuf,
+    },
     /// Truncate the segment of the provided ID of the input NAIF DAF file to the provided start and end epochs
     /// Limitation: this may not work correctly if there are several segments with the same ID.
     /// Only works with Chebyshev Type 2 data types (i.e. planetary ephemerides).
@@ -47,6 +54,20 @@ pub enum Actions {
     /// Remove the segment of the provided ID of the input NAIF DAF file.
     /// Limitation: this may not work correctly if there are several segments with the same ID.
     RmDAFById(RmById),
+    /// Merge the segments of the input NAIF DAF files, all SPK or all BPC, into a single DAF file.
+    /// Overlapping segments of the same ID are resolved as SPICE would if the inputs were loaded in order:
+    /// later files take precedence, and so do later segments within a file.
+    MergeDAF(MergeDAF),
+    /// Writes a copy of the input NAIF DAF file in the endianness of this platform (e.g. to convert a big-endian file)
+    NativeEndianDAF {
+        /// Input DAF file, SPK, BPC or CK
+        input: PathBuf,
+        /// Output DAF file path
+        output: PathBuf,
+    },
+    /// Prints the comments of the input NAIF DAF file, or writes a copy of it with edited comments.
+    /// The comment area is resized as needed, so the segments are left untouched.
+    Comment(CommentDAF),
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Args)]
@@ -72,3 +93,34 @@ pub(crate) struct TruncateById {
     /// New end epoch of the segment
     pub end: Option<Epoch>,
 }
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Args)]
+pub(crate) struct MergeDAF {
+    /// Input DAF files, in increasing order of priority
+    #[clap(required = true)]
+    pub inputs: Vec<PathBuf>,
+    /// Output DAF file path
+    #[clap(short, long)]
+    pub output: PathBuf,
+    /// Only keep the coverage of each segment that is not overridden by a later segment
+    #[clap(long)]
+    pub winning_only: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Args)]
+pub(crate) struct CommentDAF {
+    /// Input DAF file, SPK, BPC or CK
+    pub input: PathBuf,
+    /// Output DAF file path, required to edit the comments
+    #[clap(short, long)]
+    pub output: Option<PathBuf>,
+    /// Replace the comments with the content of this text file
+    #[clap(long, conflicts_with_all = ["append", "clear"])]
+    pub set: Option<PathBuf>,
+    /// Append the content of this text file to the comments
+    #[clap(long, conflicts_with = "clear")]
+    pub append: Option<PathBuf>,
+    /// Remove all of the comments
+    #[clap(long)]
+    pub clear: bool,
+}
@@ -1,15 +1,18 @@
 extern crate pretty_env_logger;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::env::{set_var, var};
 use std::io;
 use std::path::PathBuf;
 
 use anise::math::interpolation::InterpolationError;
+use anise::naif::ck::CKSummaryRecord;
+use anise::naif::daf::daf::MutDAF;
 use anise::naif::daf::datatypes::Type2ChebyshevSet;
 use anise::naif::daf::{DafDataType, NAIFDataSet, DAF};
 use anise::naif::pck::BPCSummaryRecord;
 use anise::naif::pretty_print::NAIFPrettyPrint;
 use anise::naif::spk::summary::SPKSummaryRecord;
+use anise::naif::Endian;
 use bytes::Bytes;
 use clap::Parser;
 use log::info;
@@ -18,11 +21,11 @@ use zerocopy::FromBytes;
 
 use anise::file2heap;
 use anise::naif::daf::{file_record::FileRecordError, DAFError, FileRecord, NAIFRecord};
-use anise::naif::kpl::parser::{convert_fk, convert_tpc};
+use anise::naif::kpl::parser::{convert_fk, convert_sclk, convert_tpc};
 use anise::prelude::*;
 use anise::structure::dataset::{DataSetError, DataSetType};
 use anise::structure::metadata::Metadata;
-use anise::structure::{EulerParameterDataSet, PlanetaryDataSet, SpacecraftDataSet};
+use anise::structure::{EulerParameterDataSet, PlanetaryDataSet, SCLKDataSet, SpacecraftDataSet};
 
 mod args;
 use args::{Actions, CliArgs};
@@ -109,6 +112,13 @@ fn main() -> Result<(), CliErrors> {
                         println!("{dataset}");
                         Ok(())
                     }
+                    DataSetType::SCLKData => {
+                        // Decode as spacecraft clock data
+                        let dataset =
+                            SCLKDataSet::try_from_bytes(bytes).context(CliDataSetSnafu)?;
+                        println!("{dataset}");
+                        Ok(())
+                    }
                 }
             } else {
                 // Load the header only
@@ -126,6 +136,12 @@ fn main() -> Result<(), CliErrors> {
                         info!("[OK] Checksum matches");
                         Ok(())
                     }
+                    "CK" => {
+                        info!("Loading {path_str:?} as DAF/CK");
+                        CK::check_then_parse(bytes, crc32_checksum).context(CliDAFSnafu)?;
+                        info!("[OK] Checksum matches");
+                        Ok(())
+                    }
                     _ => unreachable!(),
                 }
             }
@@ -134,8 +150,10 @@ fn main() -> Result<(), CliErrors> {
             let (bytes, file_record) = read_and_record(file.clone())?;
 
             match file_record.identification().context(CliFileRecordSnafu)? {
-                "PCK" => inspect::<BPCSummaryRecord>(file, bytes),
-                "SPK" => inspect::<SPKSummaryRecord>(file, bytes),
+                "PCK" => inspect::<BPCSummaryRecord>(file, bytes, true),
+                "SPK" => inspect::<SPKSummaryRecord>(file, bytes, true),
+                // The segments of CKs are bounded by SCLK ticks, not epochs
+                "CK" => inspect::<CKSummaryRecord>(file, bytes, false),
                 fileid => Err(CliErrors::ArgumentError {
                     arg: format!("{fileid} is not supported yet"),
                 }),
@@ -159,6 +177,13 @@ fn main() -> Result<(), CliErrors> {
 
             Ok(())
         }
+        Actions::ConvertSclk { sclkfile, outfile } => {
+            let dataset = convert_sclk(sclkfile, false).context(CliDataSetSnafu)?;
+
+            dataset.save_as(&outfile, false).context(CliDataSetSnafu)?;
+
+            Ok(())
+        }
         Actions::TruncDAFById(action) => {
             ensure!(
                 action.start.is_some() || action.end.is_some(),
@@ -183,6 +208,60 @@ fn main() -> Result<(), CliErrors> {
             match file_record.identification().context(CliFileRecordSnafu)? {
                 "PCK" => rm_daf_by_id::<BPCSummaryRecord>(action, bytes),
                 "SPK" => rm_daf_by_id::<SPKSummaryRecord>(action, bytes),
+                "CK" => rm_daf_by_id::<CKSummaryRecord>(action, bytes),
+                fileid => Err(CliErrors::ArgumentError {
+                    arg: format!("{fileid} is not supported yet"),
+                }),
+            }
+        }
+        Actions::MergeDAF(action) => {
+            let mut all_bytes = Vec::with_capacity(action.inputs.len());
+            let mut fileids = HashSet::new();
+            for input in &action.inputs {
+                let (bytes, file_record) = read_and_record(input.clone())?;
+                fileids.insert(
+                    file_record
+                        .identification()
+                        .context(CliFileRecordSnafu)?
+                        .to_string(),
+                );
+                all_bytes.push(bytes);
+            }
+
+            ensure!(
+                fileids.len() == 1,
+                ArgumentSnafu {
+                    arg: format!("cannot merge different kinds of DAF files: {fileids:?}")
+                }
+            );
+
+            match fileids.iter().next().unwrap().as_str() {
+                "PCK" => merge_daf::<BPCSummaryRecord>(action, all_bytes),
+                "SPK" => merge_daf::<SPKSummaryRecord>(action, all_bytes),
+                fileid => Err(CliErrors::ArgumentError {
+                    arg: format!("{fileid} is not supported yet"),
+                }),
+            }
+        }
+        Actions::NativeEndianDAF { input, output } => {
+            let (bytes, file_record) = read_and_record(input.clone())?;
+
+            match file_record.identification().context(CliFileRecordSnafu)? {
+                "PCK" => native_endian_daf::<BPCSummaryRecord>(input, output, bytes),
+                "SPK" => native_endian_daf::<SPKSummaryRecord>(input, output, bytes),
+                "CK" => native_endian_daf::<CKSummaryRecord>(input, output, bytes),
+                fileid => Err(CliErrors::ArgumentError {
+                    arg: format!("{fileid} is not supported yet"),
+                }),
+            }
+        }
+        Actions::Comment(action) => {
+            let (bytes, file_record) = read_and_record(action.input.clone())?;
+
+            match file_record.identification().context(CliFileRecordSnafu)? {
+                "PCK" => comment_daf::<BPCSummaryRecord>(action, bytes),
+                "SPK" => comment_daf::<SPKSummaryRecord>(action, bytes),
+                "CK" => comment_daf::<CKSummaryRecord>(action, bytes),
                 fileid => Err(CliErrors::ArgumentError {
                     arg: format!("{fileid} is not supported yet"),
                 }),
@@ -198,7 +277,7 @@ fn read_and_record(path_str: PathBuf) -> Result<(bytes::Bytes, FileRecord), CliE
     Ok((bytes, file_record))
 }
 
-fn inspect<R>(path_str: PathBuf, bytes: Bytes) -> Result<(), CliErrors>
+fn inspect<R>(path_str: PathBuf, bytes: Bytes, with_coverage: bool) -> Result<(), CliErrors>
 where
     R: NAIFSummaryRecord,
     DAF<R>: NAIFPrettyPrint,
@@ -213,6 +292,38 @@ where
         println!("(File has no comments)");
     }
     println!("{}", fmt.describe());
+
+    if with_coverage {
+        let coverages = fmt.coverages().context(CliDAFSnafu)?;
+        let mut ids: Vec<_> = coverages.keys().copied().collect();
+        ids.sort_unstable();
+
+        let mut num_segments: HashMap<_, usize> = HashMap::new();
+        for (_, summary) in fmt.summaries().context(CliDAFSnafu)? {
+            if !summary.is_empty() {
+                *num_segments.entry(summary.id()).or_default() += 1;
+            }
+        }
+
+        println!("== COVERAGE ==");
+        for id in ids {
+            let coverage = &coverages[&id];
+            let gaps = coverage.gaps();
+            println!(
+                "{id}: from {} to {}, {} segment(s) in {} interval(s) with {} gap(s)",
+                coverage.start().unwrap(),
+                coverage.end().unwrap(),
+                num_segments[&id],
+                coverage.len(),
+                gaps.len()
+            );
+            for (start, end) in gaps.intervals() {
+                println!("\tgap from {start} to {end} ({})", *end - *start);
+            }
+        }
+        println!("== END ==");
+    }
+
     Ok(())
 }
 
@@ -227,7 +338,7 @@ where
     let fmt = DAF::<R>::parse(bytes).context(CliDAFSnafu)?;
 
     let mut ids = HashSet::new();
-    for summary in fmt.data_summaries().context(CliDAFSnafu)? {
+    for (_, summary) in fmt.summaries().context(CliDAFSnafu)? {
         ids.insert(summary.id());
     }
 
@@ -261,7 +372,7 @@ where
     let fmt = DAF::<R>::parse(bytes).context(CliDAFSnafu)?;
 
     let mut ids = HashSet::new();
-    for summary in fmt.data_summaries().context(CliDAFSnafu)? {
+    for (_, summary) in fmt.summaries().context(CliDAFSnafu)? {
         ids.insert(summary.id());
     }
 
@@ -302,3 +413,105 @@ where
 
     Ok(())
 }
+
+fn merge_daf<R>(
+    args::MergeDAF {
+        inputs,
+        output,
+        winning_only,
+    }: args::MergeDAF,
+    all_bytes: Vec<Bytes>,
+) -> Result<(), CliErrors>
+where
+    R: NAIFSummaryRecord,
+{
+    let mut dafs = Vec::with_capacity(all_bytes.len());
+    for (input, bytes) in inputs.iter().zip(all_bytes) {
+        info!("Loading {input:?}");
+        dafs.push(DAF::<R>::parse(bytes).context(CliDAFSnafu)?);
+    }
+
+    let merged = MutDAF::merge(&dafs, winning_only).context(CliDAFSnafu)?;
+    info!(
+        "Merged {} segments",
+        merged
+            .summaries()
+            .context(CliDAFSnafu)?
+            .filter(|(_, summary)| !summary.is_empty())
+            .count()
+    );
+
+    info!("Saving file to {output:?}");
+    merged.persist(output).context(FilePersistSnafu)?;
+
+    Ok(())
+}
+
+fn native_endian_daf<R>(input: PathBuf, output: PathBuf, bytes: Bytes) -> Result<(), CliErrors>
+where
+    R: NAIFSummaryRecord,
+{
+    info!(
+        "Loading {input:?} as a {:?} endian DAF",
+        file_record_endianness(&bytes)?
+    );
+    // Parsing the DAF converts it to the native endianness.
+    let fmt = DAF::<R>::parse(bytes).context(CliDAFSnafu)?;
+
+    info!("Saving file to {output:?}");
+    fmt.persist(output).context(FilePersistSnafu)?;
+
+    Ok(())
+}
+
+fn file_record_endianness(bytes: &Bytes) -> Result<Endian, CliErrors> {
+    FileRecord::read_from(&bytes[..FileRecord::SIZE])
+        .unwrap()
+        .file_endianness()
+        .context(CliFileRecordSnafu)
+}
+
+fn comment_daf<R>(
+    args::CommentDAF {
+        input,
+        output,
+        set,
+        append,
+        clear,
+    }: args::CommentDAF,
+    bytes: Bytes,
+) -> Result<(), CliErrors>
+where
+    R: NAIFSummaryRecord,
+{
+    info!("Loading {input:?}");
+    let mut daf = MutDAF::<R>::parse(bytes).context(CliDAFSnafu)?;
+
+    if set.is_none() && append.is_none() && !clear {
+        if let Some(comments) = daf.comments().context(CliDAFSnafu)? {
+            println!("{comments}");
+        } else {
+            println!("(File has no comments)");
+        }
+        return Ok(());
+    }
+
+    let output = output.ok_or_else(|| CliErrors::ArgumentError {
+        arg: "OUTPUT is required to edit the comments".to_string(),
+    })?;
+
+    if let Some(path) = set {
+        let comments = std::fs::read_to_string(path).context(FileNotFoundSnafu)?;
+        daf.set_comments(&comments).context(CliDAFSnafu)?;
+    } else if let Some(path) = append {
+        let comments = std::fs::read_to_string(path).context(FileNotFoundSnafu)?;
+        daf.append_comments(&comments).context(CliDAFSnafu)?;
+    } else {
+        daf.clear_comments().context(CliDAFSnafu)?;
+    }
+
+    info!("Saving file to {output:?}");
+    daf.persist(output).context(FilePersistSnafu)?;
+
+    Ok(())
+}
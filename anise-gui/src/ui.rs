@@ -154,12 +154,12 @@ impl eframe::App for UiApp {
                                 let (label, crc) = if self.almanac.num_loaded_spk() == 1 {
                                     (
                                         "DAF/SPK",
-                                        self.almanac.spk_data[0].as_ref().unwrap().crc32(),
+                                        self.almanac.spk_data[0].crc32(),
                                     )
                                 } else if self.almanac.num_loaded_bpc() == 1 {
                                     (
                                         "DAF/PCK",
-                                        self.almanac.bpc_data[0].as_ref().unwrap().crc32(),
+                                        self.almanac.bpc_data[0].crc32(),
                                     )
                                 } else if !self.almanac.planetary_data.is_empty() {
                                     ("ANISE/PCA", self.almanac.planetary_data.crc32())
@@ -255,16 +255,12 @@ impl eframe::App for UiApp {
                                             })
                                             .body(|mut body| {
                                                 let pck =
-                                                    self.almanac.bpc_data[0].as_ref().unwrap();
+                                                    &self.almanac.bpc_data[0];
 
                                                 for (sno, summary) in
-                                                    pck.data_summaries().unwrap().iter().enumerate()
+                                                    pck.summaries().unwrap()
                                                 {
-                                                    let name_rcrd = pck.name_record().unwrap();
-                                                    let name = name_rcrd.nth_name(
-                                                        sno,
-                                                        pck.file_record().unwrap().summary_size(),
-                                                    );
+                                                    let name = pck.nth_name(sno).unwrap();
                                                     if summary.is_empty() {
                                                         continue;
                                                     }
@@ -406,16 +402,12 @@ impl eframe::App for UiApp {
                                             })
                                             .body(|mut body| {
                                                 let spk =
-                                                    self.almanac.spk_data[0].as_ref().unwrap();
+                                                    &self.almanac.spk_data[0];
 
                                                 for (sno, summary) in
-                                                    spk.data_summaries().unwrap().iter().enumerate()
+                                                    spk.summaries().unwrap()
                                                 {
-                                                    let name_rcrd = spk.name_record().unwrap();
-                                                    let name = name_rcrd.nth_name(
-                                                        sno,
-                                                        spk.file_record().unwrap().summary_size(),
-                                                    );
+                                                    let name = spk.nth_name(sno).unwrap();
                                                     if summary.is_empty() {
                                                         continue;
                                                     }
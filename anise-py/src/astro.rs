@@ -8,7 +8,7 @@
  * Documentation: https://nyxspace.com/
  */
 
-use anise::astro::AzElRange;
+use anise::astro::{AzElRange, EclipseState, Occultation};
 use anise::structure::planetocentric::ellipsoid::Ellipsoid;
 use pyo3::prelude::*;
 use pyo3::py_run;
@@ -24,6 +24,8 @@ pub(crate) fn register_astro(parent_module: &Bound<'_, PyModule>) -> PyResult<()
     sm.add_class::<Frame>()?;
     sm.add_class::<Orbit>()?;
     sm.add_class::<AzElRange>()?;
+    sm.add_class::<EclipseState>()?;
+    sm.add_class::<Occultation>()?;
 
     register_constants(&sm)?;
 
@@ -8,9 +8,11 @@
  * Documentation: https://nyxspace.com/
  */
 
+use ::anise::almanac::kernels::{KernelInfo, KernelKind};
 use ::anise::almanac::metaload::{MetaAlmanac, MetaFile};
 use ::anise::almanac::Almanac;
 use ::anise::astro::Aberration;
+use ::anise::math::window::Window;
 use hifitime::leap_seconds::{LatestLeapSeconds, LeapSecondsFile};
 use hifitime::prelude::*;
 use hifitime::ut1::Ut1Provider;
@@ -33,6 +35,9 @@ fn anise(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Aberration>()?;
     m.add_class::<MetaAlmanac>()?;
     m.add_class::<MetaFile>()?;
+    m.add_class::<KernelInfo>()?;
+    m.add_class::<KernelKind>()?;
+    m.add_class::<Window>()?;
     Ok(())
 }
 
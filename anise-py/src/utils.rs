@@ -10,7 +10,9 @@
 
 use std::path::PathBuf;
 
-use anise::naif::kpl::parser::{convert_fk as convert_fk_rs, convert_tpc as convert_tpc_rs};
+use anise::naif::kpl::parser::{
+    convert_fk as convert_fk_rs, convert_sclk as convert_sclk_rs, convert_tpc as convert_tpc_rs,
+};
 use anise::structure::dataset::DataSetError;
 use anise::structure::planetocentric::ellipsoid::Ellipsoid;
 use pyo3::{prelude::*, py_run};
@@ -20,6 +22,7 @@ pub(crate) fn register_utils(parent_module: &Bound<'_, PyModule>) -> PyResult<()
     sm.add_class::<Ellipsoid>()?;
     sm.add_function(wrap_pyfunction!(convert_fk, &sm)?)?;
     sm.add_function(wrap_pyfunction!(convert_tpc, &sm)?)?;
+    sm.add_function(wrap_pyfunction!(convert_sclk, &sm)?)?;
 
     Python::with_gil(|py| {
         py_run!(py, sm, "import sys; sys.modules['anise.utils'] = sm");
@@ -65,3 +68,21 @@ fn convert_tpc(
 
     Ok(())
 }
+
+/// Converts a KPL/SCLK file, that defines spacecraft clocks, into the SCLKDataSet equivalent ANISE file.
+#[pyfunction]
+fn convert_sclk(
+    sclk_file_path: String,
+    anise_output_path: String,
+    show_comments: Option<bool>,
+    overwrite: Option<bool>,
+) -> Result<(), DataSetError> {
+    let dataset = convert_sclk_rs(sclk_file_path, show_comments.unwrap_or(false))?;
+
+    dataset.save_as(
+        &PathBuf::from(anise_output_path),
+        overwrite.unwrap_or(false),
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use anise::{
+    almanac::index::SegmentIndex, constants::frames::EARTH_J2000, math::cartesian::CartesianState,
+    math::Vector3, naif::spk::builder::SPKBuilder, prelude::*,
+};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const NUM_SPKS: i32 = 200;
+const NUM_SEGMENTS: i64 = 20;
+const NUM_QUERIES: f64 = 100.0;
+
+/// Builds a constellation of spacecraft, each in its own SPK made of hour long segments.
+fn constellation(start_epoch: Epoch) -> Almanac {
+    let mut almanac = Almanac::default();
+    for sc_no in 0..NUM_SPKS {
+        let mut builder = SPKBuilder::new("CONSTELLATION");
+        for segment in 0..NUM_SEGMENTS {
+            let states: Vec<CartesianState> = (0..=6)
+                .map(|i| CartesianState {
+                    radius_km: Vector3::new(7000.0, sc_no as f64, (segment * 6 + i) as f64),
+                    velocity_km_s: Vector3::new(0.0, 0.0, 1.0 / 600.0),
+                    epoch: start_epoch + (segment * 60 + i * 10).minutes(),
+                    frame: EARTH_J2000,
+                })
+                .collect();
+            builder
+                .add_hermite_segment("SEGMENT", -1000 - sc_no, &states, 4)
+                .unwrap();
+        }
+        almanac = almanac.with_spk(builder.build().unwrap()).unwrap();
+    }
+    almanac
+}
+
+fn benchmark_constellation(ctx: &Almanac, time_it: TimeSeries) {
+    // The first spacecraft is in the SPK that was loaded first, so it is the last one to be scanned.
+    let my_sc_j2k = Frame::from_ephem_j2000(-1000);
+    for epoch in time_it {
+        black_box(
+            ctx.translate_geometric(my_sc_j2k, EARTH_J2000, epoch)
+                .unwrap(),
+        );
+    }
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let start_epoch = Epoch::from_gregorian_at_midnight(2030, 1, 1, TimeScale::UTC);
+    let end_epoch = start_epoch + NUM_SEGMENTS.hours();
+    let time_step = ((end_epoch - start_epoch).to_seconds() / NUM_QUERIES).seconds();
+    // Stay clear of the segment boundaries, which are rounded when stored in the SPK.
+    let time_it =
+        TimeSeries::exclusive(start_epoch + 1.minutes(), end_epoch - time_step, time_step);
+
+    let indexed = constellation(start_epoch);
+    // Without the segment index, all of the summaries of all of the SPKs are scanned on every query.
+    let scanned = Almanac {
+        spk_index: SegmentIndex::default(),
+        ..indexed.clone()
+    };
+
+    c.bench_function("ANISE constellation indexed", |b| {
+        b.iter(|| benchmark_constellation(&indexed, time_it.clone()))
+    });
+
+    c.bench_function("ANISE constellation scanned", |b| {
+        b.iter(|| benchmark_constellation(&scanned, time_it.clone()))
+    });
+}
+
+criterion_group!(segment_index, criterion_benchmark);
+criterion_main!(segment_index);
@@ -0,0 +1,557 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use hifitime::{Epoch, TimeSeries};
+use snafu::ResultExt;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use super::{index::SegmentSpan, Almanac};
+use crate::{
+    constants::orientations::{ECLIPJ2000, J2000},
+    ephemerides::{paths::MAX_TREE_DEPTH, EphemerisError},
+    errors::{AlmanacResult, EphemerisSnafu, OrientationSnafu},
+    math::{cartesian::CartesianState, rotation::DCM},
+    orientations::{OrientationError, OrientationPhysicsSnafu},
+    prelude::{Aberration, Frame},
+};
+
+impl Almanac {
+    /// Returns the states of the target frame as seen from the observer frame at each of the provided epochs,
+    /// cf. [Almanac::translate].
+    ///
+    /// Without aberration corrections, the ephemeris path between both frames is only resolved again once one of
+    /// the segments along it is no longer the one in use, so sorted epochs are the fastest to compute.
+    ///
+    /// # Warning
+    /// This function only performs the translation and no rotation whatsoever. Use the `transform_many` function
+    /// instead to include rotations.
+    pub fn translate_many(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        epochs: &[Epoch],
+        ab_corr: Option<Aberration>,
+    ) -> Result<Vec<CartesianState>, EphemerisError> {
+        in_chunks(epochs, |epochs| {
+            self.translate_chunk(target_frame, observer_frame, epochs, ab_corr)
+        })
+    }
+
+    /// Returns the states of the target frame as seen from the observer frame at each epoch of the time series,
+    /// cf. [Almanac::translate_many].
+    pub fn translate_series(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        time_series: TimeSeries,
+        ab_corr: Option<Aberration>,
+    ) -> Result<Vec<CartesianState>, EphemerisError> {
+        let epochs: Vec<Epoch> = time_series.collect();
+        self.translate_many(target_frame, observer_frame, &epochs, ab_corr)
+    }
+
+    /// Returns the DCMs needed to rotate the `from_frame` to the `to_frame` at each of the provided epochs,
+    /// cf. [Almanac::rotate_from_to].
+    ///
+    /// As for the translations, the orientation path between both frames is only resolved again once one of the BPC
+    /// segments along it is no longer the one in use.
+    pub fn rotate_many(
+        &self,
+        from_frame: Frame,
+        to_frame: Frame,
+        epochs: &[Epoch],
+    ) -> Result<Vec<DCM>, OrientationError> {
+        in_chunks(epochs, |epochs| {
+            self.rotate_chunk(from_frame, to_frame, epochs)
+        })
+    }
+
+    /// Returns the DCMs needed to rotate the `from_frame` to the `to_frame` at each epoch of the time series,
+    /// cf. [Almanac::rotate_many].
+    pub fn rotate_series(
+        &self,
+        from_frame: Frame,
+        to_frame: Frame,
+        time_series: TimeSeries,
+    ) -> Result<Vec<DCM>, OrientationError> {
+        let epochs: Vec<Epoch> = time_series.collect();
+        self.rotate_many(from_frame, to_frame, &epochs)
+    }
+
+    /// Returns the Cartesian states needed to transform the `target_frame` to the `observer_frame` at each of the
+    /// provided epochs, cf. [Almanac::transform] and [Almanac::translate_many].
+    pub fn transform_many(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        epochs: &[Epoch],
+        ab_corr: Option<Aberration>,
+    ) -> AlmanacResult<Vec<CartesianState>> {
+        let states = self
+            .translate_many(target_frame, observer_frame, epochs, ab_corr)
+            .context(EphemerisSnafu {
+                action: "batch transform from/to",
+            })?;
+
+        let dcms = self
+            .rotate_many(target_frame, observer_frame, epochs)
+            .context(OrientationSnafu {
+                action: "batch transform from/to",
+            })?;
+
+        states
+            .into_iter()
+            .zip(dcms)
+            .map(|(state, dcm)| {
+                (dcm * state)
+                    .context(OrientationPhysicsSnafu {})
+                    .context(OrientationSnafu {
+                        action: "batch transform from/to",
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns the Cartesian states needed to transform the `target_frame` to the `observer_frame` at each epoch of
+    /// the time series, cf. [Almanac::transform_many].
+    pub fn transform_series(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        time_series: TimeSeries,
+        ab_corr: Option<Aberration>,
+    ) -> AlmanacResult<Vec<CartesianState>> {
+        let epochs: Vec<Epoch> = time_series.collect();
+        self.transform_many(target_frame, observer_frame, &epochs, ab_corr)
+    }
+
+    /// Translates at each epoch of this chunk, reusing the ephemeris path while its segments remain in use.
+    fn translate_chunk(
+        &self,
+        target_frame: Frame,
+        mut observer_frame: Frame,
+        epochs: &[Epoch],
+        ab_corr: Option<Aberration>,
+    ) -> Result<Vec<CartesianState>, EphemerisError> {
+        if ab_corr.is_some() || observer_frame == target_frame {
+            // Light time corrections are computed at other epochs than the requested ones.
+            return epochs
+                .iter()
+                .map(|epoch| self.translate(target_frame, observer_frame, *epoch, ab_corr))
+                .collect();
+        }
+
+        // Same as in the translation of a single epoch.
+        if let Ok(obs_frame_info) = self.frame_from_uid(observer_frame) {
+            observer_frame = obs_frame_info;
+        }
+
+        let mut states = Vec::with_capacity(epochs.len());
+        let mut cached_path: Option<(_, Vec<SegmentSpan>)> = None;
+        for epoch in epochs.iter().copied() {
+            let path = match &cached_path {
+                Some((path, spans)) if spans.iter().all(|span| span.contains(epoch)) => *path,
+                _ => {
+                    let path = self.common_ephemeris_path(observer_frame, target_frame, epoch)?;
+                    cached_path = self
+                        .ephemeris_path_spans(observer_frame, target_frame, epoch)
+                        .map(|spans| (path, spans));
+                    path
+                }
+            };
+
+            states.push(self.translate_along_path(target_frame, observer_frame, epoch, path)?);
+        }
+
+        Ok(states)
+    }
+
+    /// Returns the spans of the SPK segments from both frames up to the ephemeris root at this epoch, or None if the
+    /// SPK index is not up to date.
+    fn ephemeris_path_spans(
+        &self,
+        observer_frame: Frame,
+        target_frame: Frame,
+        epoch: Epoch,
+    ) -> Option<Vec<SegmentSpan>> {
+        if self.spk_index.num_kernels() != self.num_loaded_spk() {
+            return None;
+        }
+
+        let root = self.try_find_ephemeris_root().ok()?;
+        let mut spans = Vec::new();
+        for frame in [observer_frame, target_frame] {
+            let mut id = frame.ephemeris_id;
+            for _ in 0..MAX_TREE_DEPTH {
+                if id == root {
+                    break;
+                }
+                let ((spk_no, idx_in_spk), span) =
+                    self.spk_index.segment_span_at_epoch(id, epoch)?;
+                spans.push(span);
+                id = self.spk_data[spk_no]
+                    .nth_summary(idx_in_spk)
+                    .ok()?
+                    .center_id;
+            }
+        }
+
+        Some(spans)
+    }
+
+    /// Rotates at each epoch of this chunk, reusing the orientation path while its BPC segments remain in use.
+    fn rotate_chunk(
+        &self,
+        from_frame: Frame,
+        mut to_frame: Frame,
+        epochs: &[Epoch],
+    ) -> Result<Vec<DCM>, OrientationError> {
+        // Same as in the rotation of a single epoch.
+        if let Ok(to_frame_info) = self.frame_from_uid(to_frame) {
+            to_frame = to_frame_info;
+        }
+
+        if from_frame.orient_origin_match(to_frame) {
+            return epochs
+                .iter()
+                .map(|epoch| self.rotate_from_to(from_frame, to_frame, *epoch))
+                .collect();
+        }
+
+        let mut dcms = Vec::with_capacity(epochs.len());
+        let mut cached_path: Option<(_, Vec<SegmentSpan>)> = None;
+        for epoch in epochs.iter().copied() {
+            let path = match &cached_path {
+                Some((path, spans)) if spans.iter().all(|span| span.contains(epoch)) => *path,
+                _ => {
+                    let path = self.common_orientation_path(from_frame, to_frame, epoch)?;
+                    cached_path = self
+                        .orientation_path_spans(from_frame, to_frame, epoch)
+                        .map(|spans| (path, spans));
+                    path
+                }
+            };
+
+            dcms.push(self.rotate_along_path(from_frame, to_frame, epoch, path)?);
+        }
+
+        Ok(dcms)
+    }
+
+    /// Returns the spans of the BPC segments from both frames up to the orientation root at this epoch, or None if the
+    /// BPC index is not up to date or if the path may change for another reason than the end of one of these spans.
+    fn orientation_path_spans(
+        &self,
+        from_frame: Frame,
+        to_frame: Frame,
+        epoch: Epoch,
+    ) -> Option<Vec<SegmentSpan>> {
+        if self.bpc_index.num_kernels() != self.num_loaded_bpc() {
+            return None;
+        }
+
+        let root = self.try_find_orientation_root().ok()?;
+        let mut spans = Vec::new();
+        for frame in [from_frame, to_frame] {
+            let mut id = frame.orientation_id;
+            for _ in 0..MAX_TREE_DEPTH {
+                if id == root || id == J2000 || id == ECLIPJ2000 {
+                    // The rotations above these nodes do not depend on any segment.
+                    break;
+                }
+                // The parents are searched in the same order as when computing the path.
+                if let Some(((bpc_no, idx_in_bpc), span)) =
+                    self.bpc_index.segment_span_at_epoch(id, epoch)
+                {
+                    spans.push(span);
+                    id = self.bpc_data[bpc_no]
+                        .nth_summary(idx_in_bpc)
+                        .ok()?
+                        .inertial_frame_id;
+                } else if self.bpc_index.contains_id(id) || self.ck_summaries(id).is_ok() {
+                    // A BPC segment of this ID may start at a later epoch, and the CK segments are not indexed.
+                    return None;
+                } else if let Ok(planetary_data) = self.planetary_data.get_by_id(id) {
+                    id = planetary_data.parent_id;
+                } else {
+                    id = self.euler_param_data.get_by_id(id).ok()?.from;
+                }
+            }
+        }
+
+        Some(spans)
+    }
+}
+
+/// Computes the values at all of these epochs, splitting them in chunks computed in parallel with the `parallel`
+/// feature.
+fn in_chunks<T, E, F>(epochs: &[Epoch], compute: F) -> Result<Vec<T>, E>
+where
+    T: Send,
+    E: Send,
+    F: Fn(&[Epoch]) -> Result<Vec<T>, E> + Send + Sync,
+{
+    #[cfg(feature = "parallel")]
+    {
+        // Contiguous chunks keep the benefits of sorted epochs.
+        let chunk_len = epochs.len().div_ceil(rayon::current_num_threads()).max(1);
+        let chunks = epochs
+            .par_chunks(chunk_len)
+            .map(compute)
+            .collect::<Result<Vec<_>, E>>()?;
+        Ok(chunks.into_iter().flatten().collect())
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    compute(epochs)
+}
+
+#[cfg(test)]
+mod ut_batch {
+    use crate::constants::celestial_objects::MARS;
+    use crate::constants::frames::{EARTH_J2000, MOON_J2000};
+    use crate::constants::orientations::J2000;
+    use crate::math::Vector3;
+    use crate::naif::daf::daf::MutDAF;
+    use crate::naif::daf::DafDataType;
+    use crate::naif::pck::BPCSummaryRecord;
+    use crate::naif::spk::fixtures::{hermite_spk, sampled_states};
+    use crate::naif::{BPC, SPK};
+    use crate::prelude::*;
+    use crate::structure::planetocentric::phaseangle::PhaseAngle;
+    use crate::structure::planetocentric::PlanetaryData;
+
+    /// Builds an SPK with hour long segments of an ID, cycling through these centers.
+    fn build_spk(id: i32, centers: &[Frame], start_epoch: Epoch, num_segments: i64) -> SPK {
+        hermite_spk(
+            "BATCH",
+            (0..num_segments).map(|segment| {
+                let states = sampled_states(
+                    centers[segment as usize % centers.len()],
+                    start_epoch + (segment * 60).minutes(),
+                    10.minutes(),
+                    0..=6,
+                    |i| {
+                        (
+                            Vector3::new(7000.0, segment as f64, i as f64),
+                            Vector3::new(0.0, 0.0, 1.0 / 600.0),
+                        )
+                    },
+                );
+                (format!("SEGMENT {segment}"), id, states, 4)
+            }),
+        )
+    }
+
+    /// Builds a BPC with hour long Type 2 segments of an ID, cycling through these inertial frames, whose angles
+    /// vary linearly in each segment.
+    fn build_bpc(id: i32, inertial_frames: &[i32], start_epoch: Epoch, num_segments: i64) -> BPC {
+        let mut daf = MutDAF::<BPCSummaryRecord>::empty("DAF/PCK", 2, 5, "BATCH", &[]).unwrap();
+        for segment in 0..num_segments {
+            let start_et_s = (start_epoch + (segment * 60).minutes()).to_et_seconds();
+            let summary = BPCSummaryRecord {
+                start_epoch_et_s: start_et_s,
+                end_epoch_et_s: start_et_s + 3600.0,
+                frame_id: id,
+                inertial_frame_id: inertial_frames[segment as usize % inertial_frames.len()],
+                data_type_i: DafDataType::Type2ChebyshevTriplet as i32,
+                ..Default::default()
+            };
+            // A single record with two coefficients per angle, followed by its start, length, size and count.
+            let offset = 0.1 * segment as f64;
+            let data = [
+                start_et_s + 1800.0,
+                1800.0,
+                0.1 + offset,
+                0.01,
+                0.2,
+                -0.02,
+                0.3 + offset,
+                0.5,
+                start_et_s,
+                3600.0,
+                8.0,
+                1.0,
+            ];
+            daf.append_data(summary, &format!("SEGMENT {segment}"), &data)
+                .unwrap();
+        }
+        BPC::parse(daf.bytes).unwrap()
+    }
+
+    #[test]
+    fn same_rotations_as_single_epochs() {
+        let start_epoch = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        // The first frame is alternatively relative to J2000 and to a third frame, and the second BPC takes over from
+        // the first one in the middle of its second segment. The second frame is relative to the first one.
+        let mut almanac = Almanac::default()
+            .with_bpc(build_bpc(-3000, &[J2000], start_epoch, 4))
+            .unwrap()
+            .with_bpc(build_bpc(-1000, &[J2000, -3000], start_epoch, 4))
+            .unwrap()
+            .with_bpc(build_bpc(-1000, &[J2000], start_epoch + 90.minutes(), 1))
+            .unwrap()
+            .with_bpc(build_bpc(-2000, &[-1000], start_epoch, 4))
+            .unwrap()
+            .with_spk(build_spk(-10, &[EARTH_J2000], start_epoch, 4))
+            .unwrap();
+        // The IAU Mars frame is only defined by its planetary constants.
+        let iau_mars = PlanetaryData {
+            object_id: MARS,
+            parent_id: J2000,
+            pole_right_ascension: Some(PhaseAngle {
+                offset_deg: 317.68143,
+                rate_deg: -0.1061,
+                ..Default::default()
+            }),
+            pole_declination: Some(PhaseAngle {
+                offset_deg: 52.8865,
+                rate_deg: -0.0609,
+                ..Default::default()
+            }),
+            prime_meridian: Some(PhaseAngle {
+                offset_deg: 176.63,
+                rate_deg: 350.89198226,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        almanac
+            .planetary_data
+            .push(iau_mars, Some(MARS), None)
+            .unwrap();
+        let iau_mars_frame = Frame::new(MARS, MARS);
+
+        let body_frame = Frame::new(399, -2000);
+        // The epochs avoid the ends of the segments, which are computed in ET seconds.
+        let time_series = TimeSeries::inclusive(
+            start_epoch + 1.minutes(),
+            start_epoch + 3.hours() + 55.minutes(),
+            6.minutes(),
+        );
+        let epochs: Vec<Epoch> = time_series.clone().collect();
+
+        // The path is cached with the spans of all of its segments.
+        for (minutes, num_spans) in [(1, 2), (61, 3), (91, 2)] {
+            let spans = almanac
+                .orientation_path_spans(body_frame, EARTH_J2000, start_epoch + minutes.minutes())
+                .unwrap();
+            assert_eq!(spans.len(), num_spans);
+        }
+        // The planetary frames do not depend on any segment.
+        let spans = almanac
+            .orientation_path_spans(iau_mars_frame, body_frame, start_epoch)
+            .unwrap();
+        assert_eq!(spans.len(), 2);
+
+        // Both directions, so that the segments are on either side of the path.
+        for (from_frame, to_frame) in [
+            (body_frame, EARTH_J2000),
+            (EARTH_J2000, body_frame),
+            (iau_mars_frame, EARTH_J2000),
+            (body_frame, iau_mars_frame),
+        ] {
+            let dcms = almanac
+                .rotate_series(from_frame, to_frame, time_series.clone())
+                .unwrap();
+            assert_eq!(dcms.len(), epochs.len());
+            for (dcm, epoch) in dcms.iter().zip(&epochs) {
+                let expected = almanac
+                    .rotate_from_to(from_frame, to_frame, *epoch)
+                    .unwrap();
+                assert_eq!((dcm.from, dcm.to), (expected.from, expected.to));
+                assert_eq!(dcm.rot_mat, expected.rot_mat, "{epoch}");
+                assert_eq!(dcm.rot_mat_dt, expected.rot_mat_dt, "{epoch}");
+            }
+        }
+
+        // The rotations of the batch transforms are the same too, including for unsorted epochs.
+        let sc_frame = Frame::new(-10, -2000);
+        let reversed: Vec<Epoch> = epochs.iter().rev().copied().collect();
+        let states = almanac
+            .transform_many(sc_frame, EARTH_J2000, &reversed, None)
+            .unwrap();
+        for (state, epoch) in states.iter().zip(&reversed) {
+            assert_eq!(
+                *state,
+                almanac
+                    .transform(sc_frame, EARTH_J2000, *epoch, None)
+                    .unwrap(),
+                "{epoch}"
+            );
+        }
+
+        // Errors are reported as for a single epoch.
+        assert!(almanac
+            .rotate_many(body_frame, EARTH_J2000, &[start_epoch + 5.hours()])
+            .is_err());
+    }
+
+    #[test]
+    fn same_as_single_epochs() {
+        let start_epoch = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        // The spacecraft is alternatively relative to the Earth and the Moon, and the second SPK takes over from the
+        // first one in the middle of its second segment.
+        let almanac = Almanac::default()
+            .with_spk(build_spk(-10, &[EARTH_J2000, MOON_J2000], start_epoch, 4))
+            .unwrap()
+            .with_spk(build_spk(
+                -10,
+                &[EARTH_J2000],
+                start_epoch + 90.minutes(),
+                1,
+            ))
+            .unwrap()
+            .with_spk(build_spk(399, &[MOON_J2000], start_epoch, 4))
+            .unwrap();
+
+        let sc_j2k = Frame::from_ephem_j2000(-10);
+        let time_series = TimeSeries::inclusive(
+            start_epoch + 1.minutes(),
+            start_epoch + 3.hours() + 59.minutes(),
+            7.minutes(),
+        );
+        let epochs: Vec<Epoch> = time_series.clone().collect();
+
+        let states = almanac
+            .translate_series(sc_j2k, EARTH_J2000, time_series, None)
+            .unwrap();
+        assert_eq!(states.len(), epochs.len());
+        for (state, epoch) in states.iter().zip(&epochs) {
+            assert_eq!(
+                *state,
+                almanac
+                    .translate(sc_j2k, EARTH_J2000, *epoch, None)
+                    .unwrap(),
+                "{epoch}"
+            );
+        }
+
+        // Unsorted epochs are also supported, at the cost of resolving the path more often.
+        let reversed: Vec<Epoch> = epochs.iter().rev().copied().collect();
+        let states = almanac
+            .transform_many(sc_j2k, MOON_J2000, &reversed, None)
+            .unwrap();
+        for (state, epoch) in states.iter().zip(&reversed) {
+            assert_eq!(
+                *state,
+                almanac.transform(sc_j2k, MOON_J2000, *epoch, None).unwrap(),
+                "{epoch}"
+            );
+        }
+
+        // Errors are reported as for a single epoch.
+        assert!(almanac
+            .translate_many(sc_j2k, EARTH_J2000, &[start_epoch + 5.hours()], None)
+            .is_err());
+    }
+}
@@ -14,15 +14,16 @@ use hifitime::Epoch;
 
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
-use snafu::ensure;
+use snafu::{ensure, ResultExt};
 
+use crate::math::window::Window;
 use crate::naif::daf::NAIFSummaryRecord;
 use crate::naif::pck::BPCSummaryRecord;
 use crate::naif::BPC;
-use crate::orientations::{NoOrientationsLoadedSnafu, OrientationError};
+use crate::orientations::{BPCSnafu, NoOrientationsLoadedSnafu, OrientationError};
 use crate::{naif::daf::DAFError, NaifId};
 
-use super::{Almanac, MAX_LOADED_BPCS};
+use super::{index::SegmentIndex, kernels::KernelKind, Almanac};
 
 impl Almanac {
     pub fn from_bpc(bpc: BPC) -> Result<Almanac, OrientationError> {
@@ -34,33 +35,18 @@ impl Almanac {
     pub fn with_bpc(&self, bpc: BPC) -> Result<Self, OrientationError> {
         // This is just a bunch of pointers so it doesn't use much memory.
         let mut me = self.clone();
-        let mut data_idx = MAX_LOADED_BPCS;
-        for (idx, item) in self.bpc_data.iter().enumerate() {
-            if item.is_none() {
-                data_idx = idx;
-                break;
-            }
-        }
-        if data_idx == MAX_LOADED_BPCS {
-            return Err(OrientationError::StructureIsFull {
-                max_slots: MAX_LOADED_BPCS,
-            });
+        me.record_kernel(KernelKind::BPC, bpc.crc32_checksum);
+        if me.bpc_index.num_kernels() != me.bpc_data.len() {
+            // The BPC data was modified directly, so the index is rebuilt.
+            me.bpc_index = SegmentIndex::new(&me.bpc_data);
         }
-        me.bpc_data[data_idx] = Some(bpc);
+        me.bpc_index.push(&bpc);
+        me.bpc_data.push(bpc);
         Ok(me)
     }
 
     pub fn num_loaded_bpc(&self) -> usize {
-        let mut count = 0;
-        for maybe in &self.bpc_data {
-            if maybe.is_none() {
-                break;
-            } else {
-                count += 1;
-            }
-        }
-
-        count
+        self.bpc_data.len()
     }
 
     /// Returns the summary given the name of the summary record if that summary has data defined at the requested epoch and the BPC where this name was found to be valid at that epoch.
@@ -69,14 +55,7 @@ impl Almanac {
         name: &str,
         epoch: Epoch,
     ) -> Result<(&BPCSummaryRecord, usize, usize), OrientationError> {
-        for (no, maybe_bpc) in self
-            .bpc_data
-            .iter()
-            .take(self.num_loaded_bpc())
-            .rev()
-            .enumerate()
-        {
-            let bpc = maybe_bpc.as_ref().unwrap();
+        for (no, bpc) in self.bpc_data.iter().rev().enumerate() {
             if let Ok((summary, idx_in_bpc)) = bpc.summary_from_name_at_epoch(name, epoch) {
                 return Ok((summary, no, idx_in_bpc));
             }
@@ -99,17 +78,22 @@ impl Almanac {
         id: i32,
         epoch: Epoch,
     ) -> Result<(&BPCSummaryRecord, usize, usize), OrientationError> {
-        for (no, maybe_bpc) in self
-            .bpc_data
-            .iter()
-            .take(self.num_loaded_bpc())
-            .rev()
-            .enumerate()
-        {
-            let bpc = maybe_bpc.as_ref().unwrap();
-            if let Ok((summary, idx_in_bpc)) = bpc.summary_from_id_at_epoch(id, epoch) {
-                // NOTE: We're iterating backward, so the correct BPC number is "total loaded" minus "current iteration".
-                return Ok((summary, self.num_loaded_bpc() - no - 1, idx_in_bpc));
+        if self.bpc_index.num_kernels() == self.num_loaded_bpc() {
+            if let Some((bpc_no, idx_in_bpc)) = self.bpc_index.segment_at_epoch(id, epoch) {
+                let summary = self.bpc_data[bpc_no]
+                    .nth_summary(idx_in_bpc)
+                    .context(BPCSnafu {
+                        action: "fetching indexed BPC summary",
+                    })?;
+                return Ok((summary, bpc_no, idx_in_bpc));
+            }
+        } else {
+            // The BPC data was modified directly, so search all of the summaries.
+            for (no, bpc) in self.bpc_data.iter().rev().enumerate() {
+                if let Ok((summary, idx_in_bpc)) = bpc.summary_from_id_at_epoch(id, epoch) {
+                    // NOTE: We're iterating backward, so the correct BPC number is "total loaded" minus "current iteration".
+                    return Ok((summary, self.num_loaded_bpc() - no - 1, idx_in_bpc));
+                }
             }
         }
 
@@ -129,14 +113,7 @@ impl Almanac {
         &self,
         name: &str,
     ) -> Result<(&BPCSummaryRecord, usize, usize), OrientationError> {
-        for (bpc_no, maybe_bpc) in self
-            .bpc_data
-            .iter()
-            .take(self.num_loaded_bpc())
-            .rev()
-            .enumerate()
-        {
-            let bpc = maybe_bpc.as_ref().unwrap();
+        for (bpc_no, bpc) in self.bpc_data.iter().rev().enumerate() {
             if let Ok((summary, idx_in_bpc)) = bpc.summary_from_name(name) {
                 return Ok((summary, bpc_no, idx_in_bpc));
             }
@@ -157,14 +134,7 @@ impl Almanac {
         &self,
         id: i32,
     ) -> Result<(&BPCSummaryRecord, usize, usize), OrientationError> {
-        for (no, maybe_bpc) in self
-            .bpc_data
-            .iter()
-            .take(self.num_loaded_bpc())
-            .rev()
-            .enumerate()
-        {
-            let bpc = maybe_bpc.as_ref().unwrap();
+        for (no, bpc) in self.bpc_data.iter().rev().enumerate() {
             if let Ok((summary, idx_in_bpc)) = bpc.summary_from_id(id) {
                 // NOTE: We're iterating backward, so the correct BPC number is "total loaded" minus "current iteration".
                 return Ok((summary, self.num_loaded_bpc() - no - 1, idx_in_bpc));
@@ -185,10 +155,9 @@ impl Almanac {
     pub fn bpc_summaries(&self, id: NaifId) -> Result<Vec<BPCSummaryRecord>, OrientationError> {
         let mut summaries = vec![];
 
-        for maybe_bpc in self.bpc_data.iter().take(self.num_loaded_bpc()).rev() {
-            let bpc = maybe_bpc.as_ref().unwrap();
-            if let Ok(these_summaries) = bpc.data_summaries() {
-                for summary in these_summaries {
+        for bpc in self.bpc_data.iter().rev() {
+            if let Ok(these_summaries) = bpc.summaries() {
+                for (_, summary) in these_summaries {
                     if summary.id() == id {
                         summaries.push(*summary);
                     }
@@ -235,10 +204,9 @@ impl Almanac {
         ensure!(self.num_loaded_bpc() > 0, NoOrientationsLoadedSnafu);
 
         let mut domains = HashMap::new();
-        for maybe_bpc in self.bpc_data.iter().take(self.num_loaded_bpc()).rev() {
-            let bpc = maybe_bpc.as_ref().unwrap();
-            if let Ok(these_summaries) = bpc.data_summaries() {
-                for summary in these_summaries {
+        for bpc in self.bpc_data.iter().rev() {
+            if let Ok(these_summaries) = bpc.summaries() {
+                for (_, summary) in these_summaries {
                     let this_id = summary.id();
                     match domains.get_mut(&this_id) {
                         Some((ref mut cur_start, ref mut cur_end)) => {
@@ -259,6 +227,46 @@ impl Almanac {
 
         Ok(domains)
     }
+
+    /// Returns the window of the epochs covered by the loaded BPC segments of this ID, including the gaps between
+    /// segments and across files, like SPICE's `pckcov`.
+    pub fn bpc_coverage(&self, id: NaifId) -> Result<Window, OrientationError> {
+        let mut coverage = Window::default();
+        for bpc in &self.bpc_data {
+            if let Ok(window) = bpc.coverage(id) {
+                coverage = coverage.union(&window);
+            }
+        }
+
+        if coverage.is_empty() {
+            Err(OrientationError::BPC {
+                action: "searching for BPC summary",
+                source: DAFError::SummaryIdError { kind: "BPC", id },
+            })
+        } else {
+            Ok(coverage)
+        }
+    }
+
+    /// Returns a map of each loaded BPC ID to its coverage window, cf. [Almanac::bpc_coverage].
+    ///
+    /// # Warning
+    /// This function performs a memory allocation.
+    pub fn bpc_coverages(&self) -> Result<HashMap<NaifId, Window>, OrientationError> {
+        ensure!(self.num_loaded_bpc() > 0, NoOrientationsLoadedSnafu);
+
+        let mut coverages: HashMap<NaifId, Window> = HashMap::new();
+        for bpc in &self.bpc_data {
+            if let Ok(windows) = bpc.coverages() {
+                for (id, window) in windows {
+                    let coverage = coverages.entry(id).or_default();
+                    *coverage = coverage.union(&window);
+                }
+            }
+        }
+
+        Ok(coverages)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,318 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use hifitime::Epoch;
+use snafu::ResultExt;
+
+use crate::math::rotation::Quaternion;
+use crate::math::Vector3;
+use crate::naif::ck::CKSummaryRecord;
+use crate::naif::daf::datatypes::{
+    CKConstantRateSetType2, CKDiscreteSetType3, CKInterpolatedSetType5,
+};
+use crate::naif::daf::{DAFError, DafDataType, NAIFSummaryRecord};
+use crate::naif::CK;
+use crate::orientations::{
+    CKSnafu, OrientationError, OrientationInterpolationSnafu, OrientationSCLKSnafu,
+};
+use crate::NaifId;
+
+use super::{kernels::KernelKind, Almanac};
+
+impl Almanac {
+    /// Loads a C-matrix kernel, i.e. attitude data.
+    ///
+    /// The CK data is time tagged in spacecraft clock ticks, so the SCLK of the spacecraft must also be loaded to query it.
+    pub fn with_ck(&self, ck: CK) -> Result<Self, OrientationError> {
+        let mut me = self.clone();
+        me.record_kernel(KernelKind::CK, ck.crc32_checksum);
+        me.ck_data.push(ck);
+        Ok(me)
+    }
+
+    pub fn num_loaded_ck(&self) -> usize {
+        self.ck_data.len()
+    }
+
+    /// Returns the summary of the instrument `id` whose data covers the requested epoch, along with the number of the CK
+    /// where it was found and the index of the summary in that CK.
+    ///
+    /// The epoch is converted into the clock ticks of the spacecraft of the instrument with the loaded SCLK.
+    pub fn ck_summary_at_epoch(
+        &self,
+        id: NaifId,
+        epoch: Epoch,
+    ) -> Result<(&CKSummaryRecord, usize, usize), OrientationError> {
+        // The ticks are only computed if this instrument has data, so that a missing SCLK is only reported when needed.
+        let mut ticks = None;
+        for (ck_no, ck) in self.ck_data.iter().enumerate().rev() {
+            for (idx_in_ck, summary) in ck.summaries().context(CKSnafu {
+                action: "searching for CK summary",
+            })? {
+                if summary.is_empty() || summary.instrument_id != id {
+                    continue;
+                }
+                let ticks = match ticks {
+                    Some(ticks) => ticks,
+                    None => *ticks.insert(
+                        self.sclk(summary.clock_id())
+                            .and_then(|sclk| sclk.epoch_to_ticks(epoch))
+                            .context(OrientationSCLKSnafu)?,
+                    ),
+                };
+                if summary.covers_ticks(ticks) {
+                    return Ok((summary, ck_no, idx_in_ck));
+                }
+            }
+        }
+
+        // If we're reached this point, there is no relevant summary at this epoch.
+        Err(OrientationError::CK {
+            action: "searching for CK summary",
+            source: DAFError::SummaryIdAtEpochError {
+                kind: "CK",
+                id,
+                epoch,
+            },
+        })
+    }
+
+    /// Returns the attitude of the instrument `id` at the requested epoch: the quaternion from its reference frame, and,
+    /// if available in the CK, the angular velocity with respect to that frame, expressed in that frame, in radians per second.
+    pub fn ck_attitude(
+        &self,
+        id: NaifId,
+        epoch: Epoch,
+    ) -> Result<(Quaternion, Option<Vector3>), OrientationError> {
+        let (summary, ck_no, idx_in_ck) = self.ck_summary_at_epoch(id, epoch)?;
+        self.ck_attitude_from_summary(summary, ck_no, idx_in_ck, epoch)
+    }
+
+    /// Evaluates the attitude data of the provided summary, as returned by `ck_summary_at_epoch`.
+    pub(crate) fn ck_attitude_from_summary(
+        &self,
+        summary: &CKSummaryRecord,
+        ck_no: usize,
+        idx_in_ck: usize,
+        epoch: Epoch,
+    ) -> Result<(Quaternion, Option<Vector3>), OrientationError> {
+        let ck_data = self
+            .ck_data
+            .get(ck_no)
+            .ok_or(OrientationError::Unreachable)?;
+
+        let ticks = self
+            .sclk(summary.clock_id())
+            .and_then(|sclk| sclk.epoch_to_ticks(epoch))
+            .context(OrientationSCLKSnafu)?;
+
+        let (mut q, av) = match summary.data_type()? {
+            DafDataType::Type2ChebyshevTriplet => {
+                let data = ck_data
+                    .nth_data::<CKConstantRateSetType2>(idx_in_ck)
+                    .context(CKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate_ticks(ticks, summary)
+                    .context(OrientationInterpolationSnafu)?
+            }
+            DafDataType::Type3ChebyshevSextuplet => {
+                let data = ck_data
+                    .nth_data::<CKDiscreteSetType3>(idx_in_ck)
+                    .context(CKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate_ticks(ticks, summary)
+                    .context(OrientationInterpolationSnafu)?
+            }
+            DafDataType::Type5DiscreteStates => {
+                let data = ck_data
+                    .nth_data::<CKInterpolatedSetType5>(idx_in_ck)
+                    .context(CKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate_ticks(ticks, summary)
+                    .context(OrientationInterpolationSnafu)?
+            }
+            dtype => {
+                return Err(OrientationError::CK {
+                    action: "attitude computation",
+                    source: DAFError::UnsupportedDatatype {
+                        dtype,
+                        kind: "CK computations",
+                    },
+                })
+            }
+        };
+
+        // The data sets do not know the reference frame, which is only stored in the summary.
+        q.from = summary.reference_frame_id;
+
+        Ok((q, av))
+    }
+
+    /// Returns all of the summaries of the instrument `id`, in the order in which they will be used, i.e. in reverse loading order.
+    pub fn ck_summaries(&self, id: NaifId) -> Result<Vec<CKSummaryRecord>, OrientationError> {
+        let mut summaries = vec![];
+
+        for ck in self.ck_data.iter().rev() {
+            if let Ok(these_summaries) = ck.summaries() {
+                for (_, summary) in these_summaries {
+                    if !summary.is_empty() && summary.instrument_id == id {
+                        summaries.push(*summary);
+                    }
+                }
+            }
+        }
+
+        if summaries.is_empty() {
+            Err(OrientationError::CK {
+                action: "searching for CK summary",
+                source: DAFError::SummaryIdError { kind: "CK", id },
+            })
+        } else {
+            Ok(summaries)
+        }
+    }
+}
+
+#[cfg(test)]
+mod ut_almanac_ck {
+    use bytes::Bytes;
+    use zerocopy::AsBytes;
+
+    use crate::constants::frames::EARTH_J2000;
+    use crate::constants::orientations::J2000;
+    use crate::math::rotation::r3;
+    use crate::naif::ck::CKSummaryRecord;
+    use crate::naif::daf::{FileRecord, NAIFRecord, RCRD_LEN};
+    use crate::naif::sclk::{SCLKCoefficient, SCLK};
+    use crate::prelude::{Almanac, Epoch, Frame, Unit};
+
+    const RATE_RAD_S: f64 = 0.01;
+    const INSTRUMENT: i32 = -82000;
+
+    /// Builds a DAF/CK with a single Type 2 segment of a rotation about Z at constant rate, starting at ten seconds past J2000 ET.
+    fn ck_bytes() -> Bytes {
+        let mut file_record = FileRecord {
+            nd: 2,
+            ni: 6,
+            forward: 2,
+            backward: 2,
+            ..Default::default()
+        };
+        file_record.id_str.copy_from_slice(b"DAF/CK  ");
+        file_record.endian_str.copy_from_slice(b"LTL-IEEE");
+        file_record.internal_filename.fill(b' ');
+
+        // One hour of data at one thousand ticks per second, starting at 10 seconds past J2000 (so 10_000 ticks)
+        let mut data = vec![1.0, 0.0, 0.0, 0.0, 0.0, 0.0, RATE_RAD_S, 1e-3];
+        data.extend([10_000.0, 3_610_000.0]);
+
+        let start_idx = 3 * RCRD_LEN / 8 + 1;
+        let summary = CKSummaryRecord {
+            start_sclk_ticks: 10_000.0,
+            end_sclk_ticks: 3_610_000.0,
+            instrument_id: INSTRUMENT,
+            reference_frame_id: J2000,
+            data_type_i: 2,
+            angular_velocity_flag: 1,
+            start_idx: start_idx as i32,
+            end_idx: (start_idx + data.len() - 1) as i32,
+        };
+        file_record.free_addr = (start_idx + data.len()) as u32;
+
+        let mut bytes = file_record.as_bytes().to_vec();
+        bytes.resize(RCRD_LEN, 0);
+        // Summary record: next, previous, and number of summaries
+        bytes.extend([0.0_f64, 0.0, 1.0].as_bytes());
+        bytes.extend(summary.as_bytes());
+        bytes.resize(2 * RCRD_LEN, 0);
+        // Name record
+        bytes.extend(b"TEST CK SEGMENT");
+        bytes.resize(2 * RCRD_LEN + CKSummaryRecord::SIZE, b' ');
+        bytes.resize(3 * RCRD_LEN, 0);
+        bytes.extend(data.as_bytes());
+
+        Bytes::from(bytes)
+    }
+
+    fn sclk() -> SCLK {
+        SCLK::new(
+            -82,
+            vec![SCLKCoefficient {
+                ticks: 0.0,
+                parallel_time_s: 0.0,
+                rate_s_per_tick: 1e-3,
+            }],
+        )
+    }
+
+    #[test]
+    fn ck_nothing_loaded() {
+        let almanac = Almanac::default();
+        let e = Epoch::from_et_seconds(100.0);
+        assert!(almanac.ck_summary_at_epoch(INSTRUMENT, e).is_err());
+        assert!(almanac.ck_summaries(INSTRUMENT).is_err());
+        assert!(almanac.sclk(-82).is_err());
+    }
+
+    #[test]
+    fn ck_type2_rotation() {
+        let almanac = Almanac::default().load_from_bytes(ck_bytes()).unwrap();
+        assert_eq!(almanac.num_loaded_ck(), 1);
+        assert_eq!(almanac.ck_summaries(INSTRUMENT).unwrap().len(), 1);
+
+        let epoch = Epoch::from_et_seconds(130.0);
+        // Without the spacecraft clock, the data cannot be queried.
+        assert!(almanac.ck_attitude(INSTRUMENT, epoch).is_err());
+
+        let almanac = almanac.with_sclk(sclk()).unwrap();
+        // Loading a clock of the same ID replaces it.
+        let almanac = almanac.with_sclk(sclk()).unwrap();
+        assert_eq!(almanac.sclk_data.len(), 1);
+
+        let (q, av) = almanac.ck_attitude(INSTRUMENT, epoch).unwrap();
+        assert_eq!(q.from, J2000);
+        assert_eq!(q.to, INSTRUMENT);
+        assert!((av.unwrap()[2] - RATE_RAD_S).abs() < 1e-12);
+
+        // Rotation of 120 seconds about Z since the start of the segment
+        let instrument = Frame::new(-82, INSTRUMENT);
+        let dcm = almanac
+            .rotate_from_to(EARTH_J2000, instrument, epoch)
+            .unwrap();
+        assert_eq!(dcm.from, J2000);
+        assert_eq!(dcm.to, INSTRUMENT);
+        assert!((dcm.rot_mat - r3(RATE_RAD_S * 120.0)).norm() < 1e-12);
+
+        // Check the time derivative with finite differencing.
+        let step = Unit::Millisecond * 1;
+        let before = almanac
+            .rotate_from_to(EARTH_J2000, instrument, epoch - step)
+            .unwrap();
+        let after = almanac
+            .rotate_from_to(EARTH_J2000, instrument, epoch + step)
+            .unwrap();
+        let fd = (after.rot_mat - before.rot_mat) / 2e-3;
+        assert!((dcm.rot_mat_dt.unwrap() - fd).norm() < 1e-9);
+
+        // And the inverse rotation
+        let inv = almanac
+            .rotate_from_to(instrument, EARTH_J2000, epoch)
+            .unwrap();
+        assert!((inv.rot_mat - r3(-RATE_RAD_S * 120.0)).norm() < 1e-12);
+
+        // Outside of the segment
+        assert!(almanac
+            .ck_attitude(INSTRUMENT, Epoch::from_et_seconds(5.0))
+            .is_err());
+    }
+}
@@ -0,0 +1,353 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use hifitime::Epoch;
+use snafu::ResultExt;
+
+use super::{
+    events::{EventCondition, EventSearchConfig},
+    Almanac,
+};
+use crate::{
+    astro::{occultation::visible_fraction, Aberration, Occultation},
+    constants::frames::SUN_J2000,
+    ephemerides::EphemerisPhysicsSnafu,
+    errors::{AlmanacResult, EphemerisSnafu, OrientationSnafu, PhysicsError},
+    math::window::Window,
+    prelude::{Frame, Orbit},
+};
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+#[cfg_attr(feature = "python", pymethods)]
+impl Almanac {
+    /// Computes the occultation of the back frame by the front frame, as seen by the observer, like SPICE's `occult`.
+    ///
+    /// Both frames must have a shape, either loaded in the planetary data of this Almanac or set on the frames.
+    ///
+    /// # Algorithm
+    /// 1. Compute the positions of the centers of both bodies as seen from the observer.
+    /// 2. Compute the apparent radius of each body as that of the disk with the same area as its silhouette.
+    /// 3. Compute the fraction of the disk of the back body that is not covered by the disk of the front body, which
+    ///    can only hide it if it is closer to the observer.
+    pub fn occultation(
+        &self,
+        back_frame: Frame,
+        front_frame: Frame,
+        observer: Orbit,
+        ab_corr: Option<Aberration>,
+    ) -> AlmanacResult<Occultation> {
+        let disks = self.apparent_disks(back_frame, front_frame, observer, ab_corr)?;
+
+        Ok(Occultation {
+            epoch: observer.epoch,
+            visible_fraction: if disks.front_is_closer {
+                visible_fraction(disks.back_radius, disks.front_radius, disks.separation)
+            } else {
+                1.0
+            },
+            back_frame: disks.back_frame,
+            front_frame: disks.front_frame,
+        })
+    }
+
+    /// Computes the occultation of the Sun by the eclipsing frame as seen by the observer, whose eclipse state is
+    /// umbra, penumbra or sunlit, cf. [Almanac::occultation].
+    pub fn solar_eclipsing(
+        &self,
+        eclipsing_frame: Frame,
+        observer: Orbit,
+        ab_corr: Option<Aberration>,
+    ) -> AlmanacResult<Occultation> {
+        self.occultation(SUN_J2000, eclipsing_frame, observer, ab_corr)
+    }
+}
+
+impl Almanac {
+    /// Searches the window for the eclipses of the Sun by the eclipsing frame, as seen from the observer frame, e.g.
+    /// a spacecraft in a loaded SPK.
+    ///
+    /// The entry and exit epochs are found with the event finder, cf. [Almanac::find_events], as the epochs when the
+    /// disk of the eclipsing body starts and stops overlapping or covering the solar disk.
+    pub fn find_eclipses(
+        &self,
+        eclipsing_frame: Frame,
+        observer_frame: Frame,
+        ab_corr: Option<Aberration>,
+        window: &Window,
+        config: EventSearchConfig,
+    ) -> AlmanacResult<EclipseWindows> {
+        let observer_center = Frame::from_ephem_j2000(eclipsing_frame.ephemeris_id);
+
+        // Angle between the edges of both disks, negative when they overlap or, for the umbra, when the eclipsing
+        // disk covers the solar disk.
+        let margin = |almanac: &Almanac, epoch: Epoch, umbra: bool| -> AlmanacResult<f64> {
+            let observer = almanac.transform(observer_frame, observer_center, epoch, None)?;
+            let disks = almanac.apparent_disks(SUN_J2000, eclipsing_frame, observer, ab_corr)?;
+            Ok(if !disks.front_is_closer {
+                core::f64::consts::PI
+            } else if umbra {
+                disks.separation - (disks.front_radius - disks.back_radius)
+            } else {
+                disks.separation - (disks.back_radius + disks.front_radius)
+            })
+        };
+
+        Ok(EclipseWindows {
+            eclipse: self.find_events(
+                |almanac, epoch| margin(almanac, epoch, false),
+                EventCondition::LessThan(0.0),
+                window,
+                config,
+            )?,
+            umbra: self.find_events(
+                |almanac, epoch| margin(almanac, epoch, true),
+                EventCondition::LessThan(0.0),
+                window,
+                config,
+            )?,
+        })
+    }
+
+    /// Returns the apparent disks of both bodies as seen by the observer.
+    fn apparent_disks(
+        &self,
+        back_frame: Frame,
+        front_frame: Frame,
+        observer: Orbit,
+        ab_corr: Option<Aberration>,
+    ) -> AlmanacResult<ApparentDisks> {
+        let mut disks = Vec::with_capacity(2);
+        for frame in [back_frame, front_frame] {
+            // Same as in the translations, the shapes of the frames are loaded from the planetary data if available.
+            let frame = self.frame_from_uid(frame).unwrap_or(frame);
+            let shape = frame
+                .shape
+                .ok_or(PhysicsError::MissingFrameData {
+                    action: "computing occultation",
+                    data: "shape",
+                    frame: frame.into(),
+                })
+                .context(EphemerisPhysicsSnafu { action: "" })
+                .context(EphemerisSnafu {
+                    action: "computing occultation",
+                })?;
+
+            let line_of_sight = self
+                .transform(frame, observer.frame, observer.epoch, ab_corr)?
+                .radius_km
+                - observer.radius_km;
+
+            // Only ellipsoids that are not spheres depend on their orientation.
+            let radius_km = if shape.is_sphere() {
+                shape.semi_major_equatorial_radius_km
+            } else {
+                let dcm = self
+                    .rotate_from_to(observer.frame, frame, observer.epoch)
+                    .context(OrientationSnafu {
+                        action: "computing occultation",
+                    })?;
+                shape.equivalent_radius_km(dcm * line_of_sight)
+            };
+
+            disks.push((frame, line_of_sight, radius_km));
+        }
+
+        let (back_frame, back_los, back_radius_km) = disks[0];
+        let (front_frame, front_los, front_radius_km) = disks[1];
+        // From within a body, its disk covers the whole sky.
+        let angular_radius =
+            |radius_km: f64, distance_km: f64| (radius_km / distance_km).min(1.0).asin();
+
+        Ok(ApparentDisks {
+            back_frame,
+            front_frame,
+            back_radius: angular_radius(back_radius_km, back_los.norm()),
+            front_radius: angular_radius(front_radius_km, front_los.norm()),
+            separation: back_los.angle(&front_los),
+            front_is_closer: front_los.norm() < back_los.norm(),
+        })
+    }
+}
+
+/// Eclipses found by [Almanac::find_eclipses].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EclipseWindows {
+    /// Epochs when at least part of the solar disk is hidden, i.e. in penumbra or in umbra
+    pub eclipse: Window,
+    /// Epochs when the solar disk is entirely hidden
+    pub umbra: Window,
+}
+
+impl EclipseWindows {
+    /// Returns the epochs when only part of the solar disk is hidden, including the epochs of entry into and exit
+    /// from the umbra.
+    pub fn penumbra(&self) -> Window {
+        self.eclipse.difference(&self.umbra)
+    }
+}
+
+/// Apparent disks of two bodies seen by an observer, with angles in radians.
+struct ApparentDisks {
+    back_frame: Frame,
+    front_frame: Frame,
+    back_radius: f64,
+    front_radius: f64,
+    separation: f64,
+    front_is_closer: bool,
+}
+
+#[cfg(test)]
+mod ut_eclipse {
+    use super::EclipseWindows;
+    use crate::almanac::events::EventSearchConfig;
+    use crate::astro::occultation::visible_fraction;
+    use crate::astro::EclipseState;
+    use crate::constants::celestial_objects::{EARTH, SUN};
+    use crate::constants::frames::{EARTH_J2000, MOON_J2000, SUN_J2000};
+    use crate::math::cartesian::CartesianState;
+    use crate::math::window::Window;
+    use crate::math::Vector3;
+    use crate::naif::spk::fixtures::{hermite_spk, sampled_states};
+    use crate::prelude::*;
+    use crate::structure::planetocentric::{ellipsoid::Ellipsoid, PlanetaryData};
+
+    const AU_KM: f64 = 149_597_870.7;
+    const SUN_RADIUS_KM: f64 = 695_700.0;
+    const EARTH_RADIUS_KM: f64 = 6378.1366;
+
+    #[test]
+    fn disks_overlap() {
+        assert_eq!(visible_fraction(1.0, 0.5, 2.0), 1.0);
+        assert_eq!(visible_fraction(1.0, 2.0, 0.5), 0.0);
+        // Annular eclipse
+        assert!((visible_fraction(1.0, 0.5, 0.2) - 0.75).abs() < f64::EPSILON);
+        // Same disks half way apart hide about 39 % of each other.
+        let fraction = visible_fraction(1.0, 1.0, 1.0);
+        assert!(
+            (fraction - (1.0 - 0.391_002_218)).abs() < 1e-9,
+            "{fraction}"
+        );
+        // The overlap is continuous.
+        assert!(visible_fraction(1.0, 0.5, 1.5 - 1e-9) > 1.0 - 1e-9);
+        assert!(visible_fraction(1.0, 0.5, 0.5 + 1e-9) < 0.75 + 1e-6);
+
+        let earth = Ellipsoid::from_spheroid(6378.1366, 6356.7519);
+        assert!((earth.equivalent_radius_km(Vector3::z()) - 6378.1366).abs() < 1e-9);
+        assert!(
+            (earth.equivalent_radius_km(Vector3::x()) - (6378.1366_f64 * 6356.7519).sqrt()).abs()
+                < 1e-9
+        );
+        let sphere = Ellipsoid::from_sphere(1737.4);
+        assert!((sphere.equivalent_radius_km(Vector3::new(1.0, 2.0, 3.0)) - 1737.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn eclipse_of_straight_pass() {
+        let t0 = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        // The Earth is fixed one astronomical unit away from the Sun.
+        let earth_states = sampled_states(SUN_J2000, t0, 1.hours(), 0..=4, |_| {
+            (Vector3::new(AU_KM, 0.0, 0.0), Vector3::zeros())
+        });
+        // The spacecraft crosses the shadow of the Earth on a straight line, 7000 km behind it.
+        let speed_km_s = 40_000.0 / 14_400.0;
+        let sc_states = sampled_states(EARTH_J2000, t0, 1.minutes(), 0..=240, |minute| {
+            (
+                Vector3::new(7000.0, -20_000.0 + speed_km_s * 60.0 * minute as f64, 0.0),
+                Vector3::new(0.0, speed_km_s, 0.0),
+            )
+        });
+        let spk = hermite_spk(
+            "ECLIPSE",
+            [
+                ("EARTH", 399, earth_states, 2),
+                ("SPACECRAFT", -10, sc_states, 2),
+            ],
+        );
+        let mut almanac = Almanac::default().with_spk(spk).unwrap();
+        for (id, radius_km) in [(SUN, SUN_RADIUS_KM), (EARTH, EARTH_RADIUS_KM)] {
+            let data = PlanetaryData {
+                object_id: id,
+                shape: Some(Ellipsoid::from_sphere(radius_km)),
+                ..Default::default()
+            };
+            almanac.planetary_data.push(data, Some(id), None).unwrap();
+        }
+
+        let sc_j2k = Frame::from_ephem_j2000(-10);
+        let observer_at = |epoch| almanac.transform(sc_j2k, EARTH_J2000, epoch, None).unwrap();
+
+        let state_at = |epoch| {
+            almanac
+                .occultation(SUN_J2000, EARTH_J2000, observer_at(epoch), None)
+                .unwrap()
+                .eclipse_state()
+        };
+        assert_eq!(state_at(t0 + 30.minutes()), EclipseState::Sunlit);
+        assert_eq!(state_at(t0 + 2.hours()), EclipseState::Umbra);
+        assert_eq!(state_at(t0 + 210.minutes()), EclipseState::Sunlit);
+        // The Sun is not hidden from the other side of the Earth.
+        let facing_sun = CartesianState {
+            radius_km: Vector3::new(-7000.0, 0.0, 0.0),
+            ..observer_at(t0 + 2.hours())
+        };
+        assert!(almanac
+            .occultation(SUN_J2000, EARTH_J2000, facing_sun, None)
+            .unwrap()
+            .is_visible());
+        // Nor does the Earth hide behind the Sun.
+        assert!(almanac
+            .occultation(EARTH_J2000, SUN_J2000, observer_at(t0 + 2.hours()), None)
+            .unwrap()
+            .is_visible());
+        // The shapes are required.
+        assert!(almanac
+            .solar_eclipsing(MOON_J2000, observer_at(t0), None)
+            .is_err());
+
+        let window = Window::from_intervals([(t0 + 1.minutes(), t0 + 239.minutes())]);
+        let config = EventSearchConfig::default();
+        let eclipses: EclipseWindows = almanac
+            .find_eclipses(EARTH_J2000, sc_j2k, None, &window, config)
+            .unwrap();
+        assert_eq!(eclipses.eclipse.len(), 1);
+        assert_eq!(eclipses.umbra.len(), 1);
+        assert_eq!(eclipses.penumbra().len(), 2);
+
+        // The pass is symmetric around the middle of the window.
+        let (entry, exit) = eclipses.eclipse.intervals()[0];
+        let (umbra_entry, umbra_exit) = eclipses.umbra.intervals()[0];
+        let middle = t0 + 2.hours();
+        assert!(((middle - entry) - (exit - middle)).abs() < 2 * config.epoch_precision);
+        assert!(
+            ((middle - umbra_entry) - (umbra_exit - middle)).abs() < 2 * config.epoch_precision
+        );
+        // The penumbra is the shadow cone widening by about twice the Sun radius over the astronomical unit.
+        let penumbra_km = 2.0 * 7000.0 * SUN_RADIUS_KM / AU_KM;
+        let penumbra_s = (umbra_entry - entry).to_seconds();
+        assert!(
+            (penumbra_s - penumbra_km / speed_km_s).abs() < 0.1,
+            "{penumbra_s}"
+        );
+
+        // The fractions at the epochs of the events match the lighting conditions.
+        let fraction_at = |epoch| {
+            almanac
+                .solar_eclipsing(EARTH_J2000, observer_at(epoch), None)
+                .unwrap()
+                .visible_fraction
+        };
+        assert!(fraction_at(entry) > 1.0 - 1e-3);
+        assert!(fraction_at(umbra_entry) < 1e-3);
+        let half_way = entry + (umbra_entry - entry) / 2;
+        assert!((fraction_at(half_way) - 0.5).abs() < 0.05);
+    }
+}
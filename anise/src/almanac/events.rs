@@ -0,0 +1,679 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use hifitime::{Duration, Epoch, TimeUnits};
+use snafu::ResultExt;
+
+use super::Almanac;
+use crate::{
+    astro::{orbit::Orbit, Aberration},
+    errors::{AlmanacError, AlmanacResult, EphemerisSnafu},
+    math::window::Window,
+    prelude::Frame,
+    NaifId,
+};
+
+/// Condition that a scalar quantity must meet in an event search, like the relations of SPICE's `gfevnt`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EventCondition {
+    /// The quantity equals this value, i.e. it crosses it.
+    Equals(f64),
+    /// The quantity is less than this value.
+    LessThan(f64),
+    /// The quantity is greater than this value.
+    GreaterThan(f64),
+    /// The quantity reaches a local minimum, excluding the bounds of the search window.
+    LocalMinimum,
+    /// The quantity reaches a local maximum, excluding the bounds of the search window.
+    LocalMaximum,
+}
+
+/// Configuration of an event search.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct EventSearchConfig {
+    /// Longest step between two evaluations of the quantity. As in SPICE, it must be shorter than the shortest event
+    /// and than the shortest time between two events, or some events may be missed.
+    pub max_step: Duration,
+    /// Shortest step between two evaluations, used when the quantity is quickly getting close to the searched
+    /// value or extremum.
+    pub min_step: Duration,
+    /// Precision of the epochs of the events.
+    pub epoch_precision: Duration,
+}
+
+impl Default for EventSearchConfig {
+    /// Steps of one second to one minute, and events found to the millisecond.
+    fn default() -> Self {
+        Self {
+            max_step: 1.minutes(),
+            min_step: 1.seconds(),
+            epoch_precision: 1.milliseconds(),
+        }
+    }
+}
+
+impl Almanac {
+    /// Searches the window for the epochs when the quantity meets the condition, like SPICE's `gfevnt`.
+    ///
+    /// The quantity is any function of the Almanac and of the epoch. For the `LessThan` and `GreaterThan` conditions,
+    /// the result is the window during which the condition holds. For the other conditions, the result is made of
+    /// the single epochs of the events.
+    ///
+    /// # Algorithm
+    /// 1. In each interval of the window, evaluate the quantity from the start, with a step that shrinks as the
+    ///    quantity (or its rate, for extrema) is about to cross zero, and never exceeds the maximum step.
+    /// 2. When the quantity crosses the searched value between two evaluations, refine the crossing epoch by
+    ///    bracketing, alternating between the false position and bisection until the bracket is within the
+    ///    requested precision.
+    /// 3. When three successive evaluations bracket an extremum, refine it with a golden section search.
+    pub fn find_events<F>(
+        &self,
+        quantity: F,
+        condition: EventCondition,
+        window: &Window,
+        config: EventSearchConfig,
+    ) -> AlmanacResult<Window>
+    where
+        F: Fn(&Almanac, Epoch) -> AlmanacResult<f64>,
+    {
+        if config.min_step <= Duration::ZERO
+            || config.max_step < config.min_step
+            || config.epoch_precision <= Duration::ZERO
+        {
+            return Err(AlmanacError::GenericError {
+                err: format!("invalid event search configuration {config:?}"),
+            });
+        }
+
+        let search = EventSearch {
+            almanac: self,
+            quantity,
+            condition,
+            config,
+        };
+
+        let mut events = vec![];
+        for (start, end) in window.intervals().iter().copied() {
+            match condition {
+                EventCondition::LocalMinimum | EventCondition::LocalMaximum => {
+                    search.extrema(start, end, &mut events)?
+                }
+                _ => search.crossings(start, end, &mut events)?,
+            }
+        }
+
+        Ok(Window::from_intervals(events))
+    }
+
+    /// Searches the window for the epochs when the range between the target and the observer, in kilometers, meets
+    /// the condition, like SPICE's `gfdist`.
+    pub fn find_range_events(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        ab_corr: Option<Aberration>,
+        condition: EventCondition,
+        window: &Window,
+        config: EventSearchConfig,
+    ) -> AlmanacResult<Window> {
+        self.find_events(
+            |almanac, epoch| {
+                Ok(almanac
+                    .translate(target_frame, observer_frame, epoch, ab_corr)
+                    .context(EphemerisSnafu {
+                        action: "computing range for event search",
+                    })?
+                    .rmag_km())
+            },
+            condition,
+            window,
+            config,
+        )
+    }
+
+    /// Searches the window for the epochs when the elevation of the target seen from the ground station, in
+    /// degrees, meets the condition.
+    ///
+    /// The station is fixed in its body fixed frame, e.g. built with [Orbit::try_latlongalt], so only its epoch is
+    /// updated during the search.
+    pub fn find_elevation_events(
+        &self,
+        target_frame: Frame,
+        station: Orbit,
+        condition: EventCondition,
+        window: &Window,
+        config: EventSearchConfig,
+    ) -> AlmanacResult<Window> {
+        self.find_events(
+            |almanac, epoch| {
+                let tx = Orbit { epoch, ..station };
+                let rx = almanac.transform(target_frame, station.frame, epoch, None)?;
+                Ok(almanac.azimuth_elevation_range_sez(rx, tx)?.elevation_deg)
+            },
+            condition,
+            window,
+            config,
+        )
+    }
+
+    /// Searches the window for the epochs when the Sun angle between the target and the observer, in degrees,
+    /// meets the condition, cf. [Almanac::sun_angle_deg].
+    pub fn find_sun_angle_events(
+        &self,
+        target_id: NaifId,
+        observer_id: NaifId,
+        condition: EventCondition,
+        window: &Window,
+        config: EventSearchConfig,
+    ) -> AlmanacResult<Window> {
+        self.find_events(
+            |almanac, epoch| {
+                almanac
+                    .sun_angle_deg(target_id, observer_id, epoch)
+                    .context(EphemerisSnafu {
+                        action: "computing sun angle for event search",
+                    })
+            },
+            condition,
+            window,
+            config,
+        )
+    }
+}
+
+/// Search of the events of a quantity in the intervals of a window.
+struct EventSearch<'a, F> {
+    almanac: &'a Almanac,
+    quantity: F,
+    condition: EventCondition,
+    config: EventSearchConfig,
+}
+
+impl<F> EventSearch<'_, F>
+where
+    F: Fn(&Almanac, Epoch) -> AlmanacResult<f64>,
+{
+    /// Returns the quantity minus the searched value, if any.
+    fn offset(&self, epoch: Epoch) -> AlmanacResult<f64> {
+        let value = (self.quantity)(self.almanac, epoch)?;
+        Ok(match self.condition {
+            EventCondition::Equals(searched)
+            | EventCondition::LessThan(searched)
+            | EventCondition::GreaterThan(searched) => value - searched,
+            EventCondition::LocalMinimum | EventCondition::LocalMaximum => value,
+        })
+    }
+
+    /// Returns whether the condition holds for this offset. For `Equals`, the crossings are the changes of this state.
+    fn holds(&self, offset: f64) -> bool {
+        match self.condition {
+            EventCondition::LessThan(_) => offset < 0.0,
+            _ => offset > 0.0,
+        }
+    }
+
+    /// Returns the next step given the current value and how fast it is approaching zero.
+    fn step(&self, value: f64, rate: Option<f64>) -> Duration {
+        match rate {
+            Some(rate) if rate != 0.0 && value * rate < 0.0 => (-value / rate)
+                .seconds()
+                .clamp(self.config.min_step, self.config.max_step),
+            _ => self.config.max_step,
+        }
+    }
+
+    /// Returns the epoch following `epoch` after this step, without going past the end.
+    fn next_epoch(epoch: Epoch, step: Duration, end: Epoch) -> Epoch {
+        if end - epoch <= step {
+            end
+        } else {
+            epoch + step
+        }
+    }
+
+    /// Finds the crossings of the searched value between these epochs.
+    fn crossings(
+        &self,
+        start: Epoch,
+        end: Epoch,
+        events: &mut Vec<(Epoch, Epoch)>,
+    ) -> AlmanacResult<()> {
+        let mut epoch = start;
+        let mut offset = self.offset(epoch)?;
+        let mut rate = None;
+        let mut holds_since = self.holds(offset).then_some(start);
+
+        while epoch < end {
+            let next_epoch = Self::next_epoch(epoch, self.step(offset, rate), end);
+            let next_offset = self.offset(next_epoch)?;
+
+            if self.holds(offset) != self.holds(next_offset) {
+                let crossing = self.refine_crossing((epoch, offset), (next_epoch, next_offset))?;
+                match self.condition {
+                    EventCondition::Equals(_) => events.push((crossing, crossing)),
+                    _ => {
+                        if let Some(since) = holds_since.take() {
+                            events.push((since, crossing));
+                        } else {
+                            holds_since = Some(crossing);
+                        }
+                    }
+                }
+            }
+
+            rate = Some((next_offset - offset) / (next_epoch - epoch).to_seconds());
+            epoch = next_epoch;
+            offset = next_offset;
+        }
+
+        if let (EventCondition::LessThan(_) | EventCondition::GreaterThan(_), Some(since)) =
+            (self.condition, holds_since)
+        {
+            events.push((since, end));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the epoch of the crossing between these two evaluations, where the condition differs.
+    fn refine_crossing(
+        &self,
+        (start, start_offset): (Epoch, f64),
+        (end, end_offset): (Epoch, f64),
+    ) -> AlmanacResult<Epoch> {
+        // Work in seconds past the start of the bracket.
+        let (mut lo, mut lo_offset) = (0.0, start_offset);
+        let (mut hi, mut hi_offset) = ((end - start).to_seconds(), end_offset);
+        let precision_s = self.config.epoch_precision.to_seconds();
+
+        let mut iteration = 0;
+        while hi - lo > precision_s {
+            // The false position converges quickly on smooth quantities, and the bisection ensures that the bracket
+            // shrinks at least by half every other iteration.
+            let false_position = (lo * hi_offset - hi * lo_offset) / (hi_offset - lo_offset);
+            let x = if iteration % 2 == 0 && false_position > lo && false_position < hi {
+                false_position
+            } else {
+                0.5 * (lo + hi)
+            };
+            iteration += 1;
+
+            let x_offset = self.offset(start + x.seconds())?;
+            if self.holds(x_offset) == self.holds(lo_offset) {
+                (lo, lo_offset) = (x, x_offset);
+            } else {
+                (hi, hi_offset) = (x, x_offset);
+            }
+        }
+
+        Ok(start + (0.5 * (lo + hi)).seconds())
+    }
+
+    /// Finds the local extrema of the quantity strictly between these epochs.
+    fn extrema(
+        &self,
+        start: Epoch,
+        end: Epoch,
+        events: &mut Vec<(Epoch, Epoch)>,
+    ) -> AlmanacResult<()> {
+        // Maxima are searched as the minima of the opposite of the quantity.
+        let sign = match self.condition {
+            EventCondition::LocalMaximum => -1.0,
+            _ => 1.0,
+        };
+
+        let mut prev: Option<(Epoch, f64)> = None;
+        let mut prev_rate: Option<f64> = None;
+        let mut epoch = start;
+        let mut value = sign * self.offset(epoch)?;
+
+        while epoch < end {
+            let rate = prev.map(|(prev_epoch, prev_value)| {
+                (value - prev_value) / (epoch - prev_epoch).to_seconds()
+            });
+            // The step shrinks as the rate of the quantity is about to cross zero.
+            let step = match (prev, rate, prev_rate) {
+                (Some((prev_epoch, _)), Some(rate), Some(prev_rate)) => self.step(
+                    rate,
+                    Some((rate - prev_rate) / (epoch - prev_epoch).to_seconds()),
+                ),
+                _ => self.config.max_step,
+            };
+
+            let next_epoch = Self::next_epoch(epoch, step, end);
+            let next_value = sign * self.offset(next_epoch)?;
+
+            if let Some((prev_epoch, prev_value)) = prev {
+                if value < prev_value && next_value >= value {
+                    let extremum = self.refine_minimum(prev_epoch, next_epoch, sign)?;
+                    events.push((extremum, extremum));
+                }
+            }
+
+            prev = Some((epoch, value));
+            prev_rate = rate;
+            epoch = next_epoch;
+            value = next_value;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the epoch of the minimum of the quantity times the sign between these epochs, with a golden section
+    /// search.
+    fn refine_minimum(&self, start: Epoch, end: Epoch, sign: f64) -> AlmanacResult<Epoch> {
+        let inv_phi = (5.0_f64.sqrt() - 1.0) / 2.0;
+        let value = |x: f64| -> AlmanacResult<f64> { Ok(sign * self.offset(start + x.seconds())?) };
+
+        let (mut a, mut b) = (0.0, (end - start).to_seconds());
+        let mut c = b - inv_phi * (b - a);
+        let mut d = a + inv_phi * (b - a);
+        let (mut value_c, mut value_d) = (value(c)?, value(d)?);
+
+        while b - a > self.config.epoch_precision.to_seconds() {
+            if value_c < value_d {
+                b = d;
+                d = c;
+                value_d = value_c;
+                c = b - inv_phi * (b - a);
+                value_c = value(c)?;
+            } else {
+                a = c;
+                c = d;
+                value_c = value_d;
+                d = a + inv_phi * (b - a);
+                value_d = value(d)?;
+            }
+        }
+
+        Ok(start + (0.5 * (a + b)).seconds())
+    }
+}
+
+#[cfg(test)]
+mod ut_events {
+    use super::{EventCondition, EventSearchConfig};
+    use crate::constants::celestial_objects::EARTH;
+    use crate::constants::frames::{EARTH_J2000, SUN_J2000};
+    use crate::math::cartesian::CartesianState;
+    use crate::math::window::Window;
+    use crate::math::Vector3;
+    use crate::naif::spk::fixtures::{circular_at_rate, hermite_spk, sampled_states};
+    use crate::prelude::*;
+    use crate::structure::planetocentric::ellipsoid::Ellipsoid;
+    use core::f64::consts::TAU;
+
+    /// Builds the states of a circular equatorial orbit of this radius around the Earth, with a period of two hours,
+    /// every minute for four hours.
+    fn circular_states(t0: Epoch, radius_km: f64) -> Vec<CartesianState> {
+        sampled_states(EARTH_J2000, t0, 1.minutes(), 0..=240, |minute| {
+            circular_at_rate(radius_km, TAU / 7200.0, minute as f64 * 60.0)
+        })
+    }
+
+    /// Asserts that the events are single epochs at these fractions of an hour past the reference epoch.
+    fn assert_events_at(events: &Window, t0: Epoch, hours: &[f64], tolerance: Duration) {
+        assert_eq!(events.len(), hours.len(), "{events}");
+        for ((start, end), expected) in events.intervals().iter().zip(hours) {
+            assert_eq!(start, end);
+            let expected = t0 + expected.hours();
+            assert!(
+                (*start - expected).abs() < tolerance,
+                "{start} != {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn analytic_conditions() {
+        let t0 = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        let window = Window::from_intervals([(t0, t0 + 3.hours())]);
+        let config = EventSearchConfig::default();
+        let almanac = Almanac::default();
+
+        // One period per hour, with a maximum at 3/12 and a minimum at 9/12 of each hour.
+        let sine = |_: &Almanac, epoch: Epoch| Ok((TAU * (epoch - t0).to_unit(Unit::Hour)).sin());
+        let find = |condition| {
+            almanac
+                .find_events(sine, condition, &window, config)
+                .unwrap()
+        };
+
+        let crossings = find(EventCondition::Equals(0.5));
+        assert_events_at(
+            &crossings,
+            t0,
+            &[1.0, 5.0, 13.0, 17.0, 25.0, 29.0].map(|twelfths| twelfths / 12.0),
+            config.epoch_precision,
+        );
+
+        let above = find(EventCondition::GreaterThan(0.5));
+        assert_eq!(above.len(), 3);
+        for ((start, end), crossings) in above
+            .intervals()
+            .iter()
+            .zip(crossings.intervals().chunks(2))
+        {
+            assert_eq!((*start, *end), (crossings[0].0, crossings[1].0));
+        }
+
+        // The intervals below the value are clipped to the window.
+        let below = find(EventCondition::LessThan(0.5));
+        assert_eq!(below.len(), 4);
+        assert_eq!(below.start(), Some(t0));
+        assert_eq!(below.end(), Some(t0 + 3.hours()));
+        assert_eq!(below.union(&above), window);
+
+        assert_events_at(
+            &find(EventCondition::LocalMaximum),
+            t0,
+            &[3.0, 15.0, 27.0].map(|twelfths| twelfths / 12.0),
+            config.epoch_precision,
+        );
+        assert_events_at(
+            &find(EventCondition::LocalMinimum),
+            t0,
+            &[9.0, 21.0, 33.0].map(|twelfths| twelfths / 12.0),
+            config.epoch_precision,
+        );
+
+        // Extrema on the bounds of the window are not events.
+        let window = Window::from_intervals([(t0 + 15.minutes(), t0 + 45.minutes())]);
+        assert!(almanac
+            .find_events(sine, EventCondition::LocalMaximum, &window, config)
+            .unwrap()
+            .is_empty());
+
+        let invalid = EventSearchConfig {
+            min_step: 2.minutes(),
+            ..config
+        };
+        assert!(almanac
+            .find_events(sine, EventCondition::LocalMaximum, &window, invalid)
+            .is_err());
+    }
+
+    #[test]
+    fn range_events() {
+        let t0 = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        // The range oscillates between 6000 and 8000 km with a period of two hours.
+        let omega = TAU / 7200.0;
+        let states = sampled_states(EARTH_J2000, t0, 1.minutes(), 0..=240, |minute| {
+            let t_s = minute as f64 * 60.0;
+            (
+                Vector3::new(7000.0 + 1000.0 * (omega * t_s).sin(), 0.0, 0.0),
+                Vector3::new(1000.0 * omega * (omega * t_s).cos(), 0.0, 0.0),
+            )
+        });
+        let almanac = Almanac::default()
+            .with_spk(hermite_spk("EVENTS", [("OSCILLATOR", -10, states, 8)]))
+            .unwrap();
+
+        let sc_j2k = Frame::from_ephem_j2000(-10);
+        let window = Window::from_intervals([(t0 + 1.minutes(), t0 + 239.minutes())]);
+        let config = EventSearchConfig::default();
+
+        let crossings = almanac
+            .find_range_events(
+                sc_j2k,
+                EARTH_J2000,
+                None,
+                EventCondition::Equals(7500.0),
+                &window,
+                config,
+            )
+            .unwrap();
+        assert_events_at(
+            &crossings,
+            t0,
+            &[1.0 / 6.0, 5.0 / 6.0, 13.0 / 6.0, 17.0 / 6.0],
+            10.milliseconds(),
+        );
+
+        let closest = almanac
+            .find_range_events(
+                sc_j2k,
+                EARTH_J2000,
+                None,
+                EventCondition::LocalMinimum,
+                &window,
+                config,
+            )
+            .unwrap();
+        assert_events_at(&closest, t0, &[1.5, 3.5], 10.milliseconds());
+    }
+
+    #[test]
+    fn elevation_events() {
+        let t0 = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        let orbit_radius_km = 10_000.0;
+        let states = circular_states(t0, orbit_radius_km);
+        let almanac = Almanac::default()
+            .with_spk(hermite_spk("EVENTS", [("CIRCULAR", -10, states, 8)]))
+            .unwrap();
+
+        // The station is on a spherical Earth which does not rotate, at 30 degrees of latitude below the orbit.
+        let earth_radius_km = 6378.0;
+        let station_frame = Frame {
+            shape: Some(Ellipsoid::from_sphere(earth_radius_km)),
+            ..EARTH_J2000
+        };
+        let latitude_deg: f64 = 30.0;
+        let station =
+            Orbit::try_latlongalt(latitude_deg, 0.0, 0.0, 0.0, t0, station_frame).unwrap();
+
+        let sc_j2k = Frame::from_ephem_j2000(-10);
+        let window = Window::from_intervals([(t0 + 1.minutes(), t0 + 239.minutes())]);
+        let config = EventSearchConfig::default();
+        let find = |condition| {
+            almanac
+                .find_elevation_events(sc_j2k, station, condition, &window, config)
+                .unwrap()
+        };
+
+        // The spacecraft rises and sets when it is in the horizontal plane of the station, i.e. when the projection
+        // of its position on the zenith of the station is the radius of the Earth.
+        let cos_angle = earth_radius_km / (orbit_radius_km * latitude_deg.to_radians().cos());
+        let half_pass_hours = cos_angle.acos() / TAU * 2.0;
+        let horizon = [
+            half_pass_hours,
+            2.0 - half_pass_hours,
+            2.0 + half_pass_hours,
+            4.0 - half_pass_hours,
+        ];
+        let crossings = find(EventCondition::Equals(0.0));
+        assert_events_at(&crossings, t0, &horizon, 10.milliseconds());
+
+        // The spacecraft is visible until it sets, and from when it rises.
+        let visible = find(EventCondition::GreaterThan(0.0));
+        assert_eq!(visible.len(), 3);
+        assert_eq!(visible.start(), Some(t0 + 1.minutes()));
+        assert_eq!(visible.end(), Some(t0 + 239.minutes()));
+        assert_eq!(
+            visible.intervals()[1],
+            (crossings.intervals()[1].0, crossings.intervals()[2].0)
+        );
+
+        // The highest elevation is when the spacecraft crosses the meridian of the station.
+        let culminations = find(EventCondition::LocalMaximum);
+        assert_events_at(&culminations, t0, &[2.0], 10.milliseconds());
+        let rx = almanac
+            .transform(sc_j2k, station_frame, t0 + 2.hours(), None)
+            .unwrap();
+        let max_elevation_deg = almanac
+            .azimuth_elevation_range_sez(
+                rx,
+                Orbit {
+                    epoch: t0 + 2.hours(),
+                    ..station
+                },
+            )
+            .unwrap()
+            .elevation_deg;
+        let zenith = Vector3::new(
+            latitude_deg.to_radians().cos(),
+            0.0,
+            latitude_deg.to_radians().sin(),
+        );
+        let rho = Vector3::new(orbit_radius_km, 0.0, 0.0) - earth_radius_km * zenith;
+        let expected_deg = (rho.dot(&zenith) / rho.norm()).asin().to_degrees();
+        assert!((max_elevation_deg - expected_deg).abs() < 1e-6);
+    }
+
+    #[test]
+    fn sun_angle_events() {
+        let t0 = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        // The Sun is fixed along -X from the Earth, and the spacecraft is on a circular equatorial orbit, so the Sun
+        // angle seen from the Earth is 180 degrees minus the angle traveled by the spacecraft from +X.
+        let earth_states = sampled_states(SUN_J2000, t0, 1.hours(), 0..=4, |_| {
+            (Vector3::new(1.5e8, 0.0, 0.0), Vector3::zeros())
+        });
+        let spk = hermite_spk(
+            "EVENTS",
+            [
+                ("EARTH", EARTH, earth_states, 2),
+                ("CIRCULAR", -10, circular_states(t0, 7000.0), 8),
+            ],
+        );
+        let almanac = Almanac::default().with_spk(spk).unwrap();
+
+        let window = Window::from_intervals([(t0 + 1.minutes(), t0 + 239.minutes())]);
+        let config = EventSearchConfig::default();
+        let find = |condition| {
+            almanac
+                .find_sun_angle_events(-10, EARTH, condition, &window, config)
+                .unwrap()
+        };
+
+        // The spacecraft is a quarter of an orbit away from the Sun direction.
+        assert_events_at(
+            &find(EventCondition::Equals(90.0)),
+            t0,
+            &[0.5, 1.5, 2.5, 3.5],
+            10.milliseconds(),
+        );
+
+        // The spacecraft is within 45 degrees of the Sun direction for a quarter of each orbit, centered on it.
+        let near_sun = find(EventCondition::LessThan(45.0));
+        assert_eq!(near_sun.len(), 2);
+        for ((start, end), center_hours) in near_sun.intervals().iter().zip([1.0, 3.0]) {
+            assert!((*start - (t0 + (center_hours - 0.25).hours())).abs() < 10.milliseconds());
+            assert!((*end - (t0 + (center_hours + 0.25).hours())).abs() < 10.milliseconds());
+        }
+
+        // The spacecraft is furthest from the Sun direction when it is on the other side of the Earth.
+        assert_events_at(
+            &find(EventCondition::LocalMaximum),
+            t0,
+            &[2.0],
+            10.milliseconds(),
+        );
+    }
+}
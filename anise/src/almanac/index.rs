@@ -0,0 +1,309 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use fnv::FnvHashMap;
+use hifitime::Epoch;
+
+use crate::naif::daf::{daf::GenericDAF, daf::MutKind, NAIFSummaryRecord};
+use crate::NaifId;
+
+/// Segment of a loaded kernel, as stored in a [SegmentIndex].
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct IndexedSegment {
+    start: Epoch,
+    end: Epoch,
+    /// Latest end epoch of this segment and of all of the segments of this ID that start before it
+    max_end: Epoch,
+    kernel_no: usize,
+    idx_in_kernel: usize,
+}
+
+impl IndexedSegment {
+    /// Segments of the kernels loaded last take precedence, then the last segments of a kernel, as in SPICE.
+    fn priority(&self) -> (usize, usize) {
+        (self.kernel_no, self.idx_in_kernel)
+    }
+}
+
+/// Index of the segments of the loaded SPKs or BPCs, by NAIF ID and then by start epoch.
+///
+/// Finding the segment of an ID at an epoch is a binary search on the segments of that ID, instead of a scan of all
+/// of the segments of all of the loaded kernels.
+#[derive(Clone, Debug, Default)]
+pub struct SegmentIndex {
+    by_id: FnvHashMap<NaifId, Vec<IndexedSegment>>,
+    /// Center with the lowest absolute ID of all of the segments, cf. [crate::almanac::Almanac::try_find_ephemeris_root]
+    root: Option<NaifId>,
+    num_kernels: usize,
+}
+
+impl SegmentIndex {
+    /// Builds the index of these kernels, provided in loading order.
+    pub fn new<R: NAIFSummaryRecord, W: MutKind>(kernels: &[GenericDAF<R, W>]) -> Self {
+        let mut index = Self::default();
+        for kernel in kernels {
+            index.push(kernel);
+        }
+        index
+    }
+
+    /// Adds the segments of this kernel, which has precedence over all of the kernels already indexed.
+    pub fn push<R: NAIFSummaryRecord, W: MutKind>(&mut self, kernel: &GenericDAF<R, W>) {
+        let kernel_no = self.num_kernels;
+        self.num_kernels += 1;
+
+        let Ok(summaries) = kernel.summaries() else {
+            return;
+        };
+
+        let mut updated = vec![];
+        let mut kernel_root: Option<NaifId> = None;
+        for (idx_in_kernel, summary) in summaries {
+            if summary.is_empty() {
+                continue;
+            }
+            if let Some(center) = summary.center() {
+                if kernel_root.is_none_or(|root| center.abs() < root.abs()) {
+                    kernel_root = Some(center);
+                }
+            }
+            let (start, end) = (summary.start_epoch(), summary.end_epoch());
+            self.by_id
+                .entry(summary.id())
+                .or_default()
+                .push(IndexedSegment {
+                    start,
+                    end,
+                    max_end: end,
+                    kernel_no,
+                    idx_in_kernel,
+                });
+            updated.push(summary.id());
+        }
+
+        // On a tie, the root of the kernel loaded last is used, as it would be found first.
+        if let Some(center) = kernel_root {
+            if self.root.is_none_or(|root| center.abs() <= root.abs()) {
+                self.root = Some(center);
+            }
+        }
+
+        updated.sort_unstable();
+        updated.dedup();
+        for id in updated {
+            let segments = self.by_id.get_mut(&id).unwrap();
+            // The sort is stable, so segments starting at the same epoch stay in priority order.
+            segments.sort_by_key(|segment| segment.start);
+            let mut max_end = segments[0].end;
+            for segment in segments.iter_mut() {
+                max_end = max_end.max(segment.end);
+                segment.max_end = max_end;
+            }
+        }
+    }
+
+    /// Returns the number of kernels that were indexed.
+    pub fn num_kernels(&self) -> usize {
+        self.num_kernels
+    }
+
+    /// Returns whether any segment of this ID was indexed, regardless of its epochs.
+    pub fn contains_id(&self, id: NaifId) -> bool {
+        self.by_id.contains_key(&id)
+    }
+
+    /// Returns the center with the lowest absolute NAIF ID of all of the indexed segments, if they have centers.
+    pub fn root(&self) -> Option<NaifId> {
+        self.root
+    }
+
+    /// Returns the number of the kernel and the index in that kernel of the segment of this ID that covers this
+    /// epoch, with the loading precedence of SPICE.
+    pub fn segment_at_epoch(&self, id: NaifId, epoch: Epoch) -> Option<(usize, usize)> {
+        self.selected_segment(id, epoch)
+            .map(|(_, segment)| segment.priority())
+    }
+
+    /// Returns the same segment as [SegmentIndex::segment_at_epoch] and the span of epochs from this epoch onward
+    /// during which that segment remains the one used for this ID.
+    pub fn segment_span_at_epoch(
+        &self,
+        id: NaifId,
+        epoch: Epoch,
+    ) -> Option<((usize, usize), SegmentSpan)> {
+        let (later, selected) = self.selected_segment(id, epoch)?;
+
+        // Segments with a higher priority that start later take over from the selected segment.
+        let preempted_at = later
+            .iter()
+            .take_while(|segment| segment.start <= selected.end)
+            .find(|segment| segment.priority() > selected.priority())
+            .map(|segment| segment.start);
+
+        Some((
+            selected.priority(),
+            SegmentSpan {
+                start: epoch,
+                end: selected.end,
+                preempted_at,
+            },
+        ))
+    }
+
+    /// Returns the segments of this ID that start after this epoch and the segment that covers it.
+    fn selected_segment(
+        &self,
+        id: NaifId,
+        epoch: Epoch,
+    ) -> Option<(&[IndexedSegment], &IndexedSegment)> {
+        let segments = self.by_id.get(&id)?;
+        // All of the segments covering this epoch start before it. The epoch is always on the left of the comparisons,
+        // like in the DAF summary search, because comparing epochs converts them to the time scale of the left one.
+        let (candidates, later) =
+            segments.split_at(segments.partition_point(|segment| epoch >= segment.start));
+
+        let mut best: Option<&IndexedSegment> = None;
+        for segment in candidates.iter().rev() {
+            if epoch > segment.max_end {
+                // None of the earlier segments reach this epoch either.
+                break;
+            }
+            if epoch <= segment.end && best.is_none_or(|best| segment.priority() > best.priority())
+            {
+                best = Some(segment);
+            }
+        }
+
+        best.map(|segment| (later, segment))
+    }
+}
+
+/// Epochs during which the same segment is used for an ID, cf. [SegmentIndex::segment_span_at_epoch].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SegmentSpan {
+    /// Epoch at which the segment was looked up
+    pub start: Epoch,
+    /// Last epoch covered by the segment
+    pub end: Epoch,
+    /// Start of the first segment loaded with a higher priority after the start of the span, if any
+    pub preempted_at: Option<Epoch>,
+}
+
+impl SegmentSpan {
+    /// Returns whether the segment of this span is still the one used at this epoch.
+    pub fn contains(&self, epoch: Epoch) -> bool {
+        epoch >= self.start
+            && epoch <= self.end
+            && self
+                .preempted_at
+                .is_none_or(|preempted_at| epoch < preempted_at)
+    }
+}
+
+#[cfg(test)]
+mod ut_index {
+    use super::SegmentIndex;
+    use crate::almanac::Almanac;
+    use crate::constants::frames::EARTH_J2000;
+    use crate::math::Vector3;
+    use crate::naif::spk::fixtures::{hermite_spk, sampled_states};
+    use crate::naif::SPK;
+    use hifitime::{Epoch, TimeUnits};
+
+    /// Builds an SPK with a segment for each ID, from and to these minutes past the reference epoch.
+    fn build_spk(segments: &[(i32, i64, i64)]) -> SPK {
+        let t0 = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        hermite_spk(
+            "INDEX",
+            segments.iter().enumerate().map(|(no, (id, start, end))| {
+                let states = sampled_states(EARTH_J2000, t0, 1.minutes(), *start..=*end, |_| {
+                    (Vector3::new(7000.0, no as f64, 0.0), Vector3::zeros())
+                });
+                (format!("SEGMENT {no}"), *id, states, 2)
+            }),
+        )
+    }
+
+    #[test]
+    fn same_segments_as_scan() {
+        let almanac = Almanac::default()
+            .with_spk(build_spk(&[(-1, 0, 60), (-1, 60, 120), (-2, 0, 120)]))
+            .unwrap()
+            .with_spk(build_spk(&[(-1, 30, 90)]))
+            .unwrap()
+            .with_spk(build_spk(&[(-1, 100, 110), (-2, 10, 20), (-1, 105, 200)]))
+            .unwrap();
+        assert_eq!(almanac.spk_index.num_kernels(), 3);
+
+        // Without the index, the summaries of all of the loaded SPKs are scanned.
+        let unindexed = Almanac {
+            spk_index: SegmentIndex::default(),
+            ..almanac.clone()
+        };
+
+        let t0 = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        for seconds in (-600..=13_000).step_by(15) {
+            let epoch = t0 + seconds.seconds();
+            for id in [-1, -2, -3] {
+                let indexed = almanac.spk_summary_at_epoch(id, epoch).ok();
+                let scanned = unindexed.spk_summary_at_epoch(id, epoch).ok();
+                assert_eq!(indexed, scanned, "{id} at {epoch}");
+            }
+        }
+
+        assert_eq!(almanac.spk_index.root(), Some(399));
+        assert_eq!(
+            almanac.try_find_ephemeris_root().unwrap(),
+            unindexed.try_find_ephemeris_root().unwrap()
+        );
+
+        // Last loaded kernel first, then last segment first.
+        let (_, spk_no, idx) = almanac.spk_summary_at_epoch(-1, t0 + 45.minutes()).unwrap();
+        assert_eq!((spk_no, idx), (1, 0));
+        let (_, spk_no, idx) = almanac
+            .spk_summary_at_epoch(-1, t0 + 107.minutes())
+            .unwrap();
+        assert_eq!((spk_no, idx), (2, 2));
+        let (_, spk_no, idx) = almanac
+            .spk_summary_at_epoch(-2, t0 + 100.minutes())
+            .unwrap();
+        assert_eq!((spk_no, idx), (0, 2));
+
+        // Unloading a kernel updates the index.
+        let unloaded = Almanac::default()
+            .load_from_bytes(build_spk(&[(-1, 0, 60)]).bytes)
+            .unwrap()
+            .with_spk(build_spk(&[(-1, 30, 90)]))
+            .unwrap();
+        let kernels = unloaded.loaded_kernels();
+        assert_eq!(
+            unloaded
+                .spk_summary_at_epoch(-1, t0 + 45.minutes())
+                .unwrap()
+                .1,
+            1
+        );
+        let mut first_only = unloaded.clone();
+        first_only.kernels[1].alias = Some("second".to_string());
+        let first_only = first_only.unload("second").unwrap();
+        assert_eq!(first_only.loaded_kernels(), vec![kernels[0].clone()]);
+        assert_eq!(first_only.spk_index.num_kernels(), 1);
+        assert_eq!(
+            first_only
+                .spk_summary_at_epoch(-1, t0 + 45.minutes())
+                .unwrap()
+                .1,
+            0
+        );
+        assert!(first_only
+            .spk_summary_at_epoch(-1, t0 + 75.minutes())
+            .is_err());
+    }
+}
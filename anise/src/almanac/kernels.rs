@@ -0,0 +1,373 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use bytes::Bytes;
+use core::fmt;
+use log::info;
+use snafu::ResultExt;
+
+use super::{index::SegmentIndex, Almanac};
+use crate::errors::{AlmanacError, AlmanacResult, LoadingSnafu};
+use crate::file2heap;
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+/// Kind of the NAIF kernels that are loaded as files in an Almanac.
+#[cfg_attr(feature = "python", pyclass)]
+#[cfg_attr(feature = "python", pyo3(module = "anise"))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum KernelKind {
+    SPK,
+    BPC,
+    CK,
+}
+
+/// Record of a NAIF kernel loaded in an Almanac, used to list, unload and replace the loaded kernels.
+///
+/// The data sets (planetary data, spacecraft data, Euler parameters and spacecraft clocks) are merged when loaded,
+/// so they do not have a record.
+#[cfg_attr(feature = "python", pyclass)]
+#[cfg_attr(feature = "python", pyo3(module = "anise"))]
+#[cfg_attr(feature = "python", pyo3(get_all))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KernelInfo {
+    pub kind: KernelKind,
+    /// Local path this kernel was loaded from, if it was loaded from a file
+    pub source: Option<String>,
+    /// URI of the MetaFile this kernel was fetched from, if it was loaded by a MetaAlmanac
+    pub uri: Option<String>,
+    /// Alias given to this kernel when it was loaded, if any
+    pub alias: Option<String>,
+    /// CRC32 checksum of the bytes of this kernel
+    pub crc32: u32,
+    /// Position of this kernel in the loading order: the kernels loaded later take precedence
+    pub load_order: usize,
+}
+
+impl KernelInfo {
+    /// Returns whether this kernel was loaded from this path or URI, or with this alias.
+    pub fn matches(&self, path_or_alias: &str) -> bool {
+        self.source.as_deref() == Some(path_or_alias)
+            || self.uri.as_deref() == Some(path_or_alias)
+            || self.alias.as_deref() == Some(path_or_alias)
+    }
+}
+
+impl fmt::Display for KernelInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{} {:?}", self.load_order, self.kind)?;
+        if let Some(alias) = &self.alias {
+            write!(f, " `{alias}`")?;
+        }
+        if let Some(source) = &self.source {
+            write!(f, " from {source}")?;
+        }
+        if let Some(uri) = &self.uri {
+            write!(f, " ({uri})")?;
+        }
+        write!(f, " (CRC32 {:08x})", self.crc32)
+    }
+}
+
+impl Almanac {
+    /// Records a kernel that was just appended to the kernels of its kind.
+    pub(crate) fn record_kernel(&mut self, kind: KernelKind, crc32: u32) {
+        let load_order = self
+            .kernels
+            .iter()
+            .map(|kernel| kernel.load_order + 1)
+            .max()
+            .unwrap_or(0);
+
+        self.kernels.push(KernelInfo {
+            kind,
+            source: None,
+            uri: None,
+            alias: None,
+            crc32,
+            load_order,
+        });
+    }
+
+    /// Returns the index in `kernels` and the index in the data of its kind of the most recently loaded kernel with this
+    /// path or alias.
+    fn find_kernel(&self, path_or_alias: &str) -> AlmanacResult<(usize, usize)> {
+        let (idx, kernel) = self
+            .kernels
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, kernel)| kernel.matches(path_or_alias))
+            .ok_or_else(|| AlmanacError::GenericError {
+                err: format!("no kernel loaded from or as `{path_or_alias}`"),
+            })?;
+
+        // The kernels of each kind are stored in loading order, like their records.
+        let data_idx = self.kernels[..idx]
+            .iter()
+            .filter(|other| other.kind == kernel.kind)
+            .count();
+
+        Ok((idx, data_idx))
+    }
+
+    /// Removes the kernel of this index in `kernels`, and its data.
+    fn remove_kernel(&mut self, idx: usize, data_idx: usize) {
+        let kernel = self.kernels.remove(idx);
+        match kernel.kind {
+            KernelKind::SPK => {
+                self.spk_data.remove(data_idx);
+            }
+            KernelKind::BPC => {
+                self.bpc_data.remove(data_idx);
+            }
+            KernelKind::CK => {
+                self.ck_data.remove(data_idx);
+            }
+        }
+        info!("unloaded {kernel}");
+        self.rebuild_indexes();
+    }
+
+    /// Rebuilds the segment indexes, which must be done when kernels are removed or replaced.
+    fn rebuild_indexes(&mut self) {
+        self.spk_index = SegmentIndex::new(&self.spk_data);
+        self.bpc_index = SegmentIndex::new(&self.bpc_data);
+    }
+
+    /// Replaces the most recently loaded kernel with this path, URI or alias by the only kernel of `new`.
+    fn replace_with(&self, path_or_alias: &str, new: Almanac) -> AlmanacResult<Self> {
+        let (idx, data_idx) = self.find_kernel(path_or_alias)?;
+        let kind = self.kernels[idx].kind;
+
+        let mut me = self.clone();
+        match (kind, new.kernels.as_slice()) {
+            (KernelKind::SPK, [new_kernel]) if new_kernel.kind == kind => {
+                me.spk_data[data_idx] = new.spk_data[0].clone();
+            }
+            (KernelKind::BPC, [new_kernel]) if new_kernel.kind == kind => {
+                me.bpc_data[data_idx] = new.bpc_data[0].clone();
+            }
+            (KernelKind::CK, [new_kernel]) if new_kernel.kind == kind => {
+                me.ck_data[data_idx] = new.ck_data[0].clone();
+            }
+            _ => {
+                return Err(AlmanacError::GenericError {
+                    err: format!(
+                        "`{path_or_alias}` is a {kind:?} and can only be replaced by a {kind:?}"
+                    ),
+                })
+            }
+        }
+
+        me.rebuild_indexes();
+
+        // The replacement keeps the alias and the precedence of the kernel it replaces.
+        let kernel = &mut me.kernels[idx];
+        kernel.source = new.kernels[0].source.clone();
+        kernel.uri = new.kernels[0].uri.clone();
+        kernel.crc32 = new.kernels[0].crc32;
+        info!("replaced `{path_or_alias}` by {kernel}");
+
+        Ok(me)
+    }
+
+    /// Replaces the most recently loaded kernel with this path or alias by the kernel in these bytes.
+    ///
+    /// The replacement must be of the same kind. It keeps the alias and the loading order of the kernel it replaces,
+    /// so it has the same precedence.
+    pub fn replace_from_bytes(&self, path_or_alias: &str, bytes: Bytes) -> AlmanacResult<Self> {
+        self.replace_with(path_or_alias, Almanac::default().load_from_bytes(bytes)?)
+    }
+
+    /// Loads the provided local path like [Almanac::load], and records the URI of the MetaFile it was fetched from.
+    pub(crate) fn load_from_uri(&self, path: &str, uri: &str) -> AlmanacResult<Self> {
+        let mut me = self.load(path)?;
+        if me.kernels.len() > self.kernels.len() {
+            if let Some(kernel) = me.kernels.last_mut() {
+                kernel.uri = Some(uri.to_string());
+            }
+        }
+        Ok(me)
+    }
+}
+
+#[cfg_attr(feature = "python", pymethods)]
+impl Almanac {
+    /// Returns the NAIF kernels loaded in this Almanac, in loading order.
+    pub fn loaded_kernels(&self) -> Vec<KernelInfo> {
+        self.kernels.clone()
+    }
+
+    /// Loads the provided path like [Almanac::load], and gives the loaded kernel an alias to unload or replace it.
+    ///
+    /// The alias is ignored for the data sets, which are merged into the existing ones.
+    pub fn load_as(&self, path: &str, alias: &str) -> AlmanacResult<Self> {
+        let mut me = self.load(path)?;
+        if me.kernels.len() > self.kernels.len() {
+            if let Some(kernel) = me.kernels.last_mut() {
+                kernel.alias = Some(alias.to_string());
+            }
+        }
+        Ok(me)
+    }
+
+    /// Unloads all of the kernels that were loaded from this path or URI, or with this alias, like SPICE's `unload`.
+    pub fn unload(&self, path_or_alias: &str) -> AlmanacResult<Self> {
+        let mut me = self.clone();
+        let (idx, data_idx) = me.find_kernel(path_or_alias)?;
+        me.remove_kernel(idx, data_idx);
+        while let Ok((idx, data_idx)) = me.find_kernel(path_or_alias) {
+            me.remove_kernel(idx, data_idx);
+        }
+        Ok(me)
+    }
+
+    /// Unloads all of the NAIF kernels of this Almanac, like SPICE's `kclear`, keeping its data sets.
+    pub fn unload_all(&self) -> Self {
+        let mut me = self.clone();
+        me.spk_data.clear();
+        me.bpc_data.clear();
+        me.ck_data.clear();
+        me.kernels.clear();
+        me.rebuild_indexes();
+        me
+    }
+
+    /// Replaces the most recently loaded kernel with this path or alias by the kernel at the provided path, e.g. to
+    /// hot swap predicted ephemerides.
+    ///
+    /// The replacement must be of the same kind. It keeps the alias and the loading order of the kernel it replaces,
+    /// so it has the same precedence.
+    pub fn replace(&self, path_or_alias: &str, path: &str) -> AlmanacResult<Self> {
+        // Check that the kernel exists before reading the replacement.
+        self.find_kernel(path_or_alias)?;
+        let bytes = file2heap!(path).context(LoadingSnafu {
+            path: path.to_string(),
+        })?;
+        let mut new = Almanac::default().load_from_bytes(bytes)?;
+        if let Some(kernel) = new.kernels.last_mut() {
+            kernel.source = Some(path.to_string());
+        }
+        self.replace_with(path_or_alias, new)
+    }
+}
+
+#[cfg(test)]
+mod ut_kernels {
+    use super::{KernelInfo, KernelKind};
+    use crate::almanac::Almanac;
+    use crate::constants::frames::EARTH_J2000;
+    use crate::math::Vector3;
+    use crate::naif::spk::fixtures::{hermite_spk, sampled_states};
+    use crate::prelude::Frame;
+    use hifitime::{Epoch, TimeUnits};
+
+    /// Writes an SPK of a spacecraft at a fixed distance along X from the Earth, and returns its path.
+    fn write_spk(name: &str, id: i32, x_km: f64) -> String {
+        let start = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        let states = sampled_states(EARTH_J2000, start, 1.minutes(), 0..=10, |_| {
+            (Vector3::new(x_km, 0.0, 0.0), Vector3::zeros())
+        });
+
+        let path = format!("../target/ut-kernels-{name}.bsp");
+        hermite_spk(name, [(name, id, states, 4)])
+            .persist(&path)
+            .unwrap();
+        path
+    }
+
+    #[test]
+    fn load_unload_replace() {
+        let epoch = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1) + 5.minutes();
+        let x_km = |almanac: &Almanac, id: i32| {
+            almanac
+                .translate(Frame::from_ephem_j2000(id), EARTH_J2000, epoch, None)
+                .unwrap()
+                .radius_km
+                .x
+        };
+
+        let reference = write_spk("reference", -1001, 7000.0);
+        let predicted = write_spk("predicted", -1002, 8000.0);
+        let updated = write_spk("updated", -1002, 9000.0);
+
+        let almanac = Almanac::default()
+            .load(&reference)
+            .unwrap()
+            .load_as(&predicted, "predicted")
+            .unwrap();
+
+        let kernels = almanac.loaded_kernels();
+        assert_eq!(kernels.len(), 2);
+        assert_eq!(kernels[0].kind, KernelKind::SPK);
+        assert_eq!(kernels[0].source.as_deref(), Some(reference.as_str()));
+        assert_eq!(kernels[0].alias, None);
+        assert_eq!(kernels[0].crc32, almanac.spk_data[0].crc32());
+        assert_eq!(kernels[1].alias.as_deref(), Some("predicted"));
+        assert_eq!(kernels[1].load_order, 1);
+        assert!(kernels[1].matches(&predicted) && kernels[1].matches("predicted"));
+        assert_eq!(x_km(&almanac, -1002), 8000.0);
+
+        // The replacement keeps the alias and the precedence of the kernel it replaces.
+        let swapped = almanac.replace("predicted", &updated).unwrap();
+        assert_eq!(x_km(&swapped, -1002), 9000.0);
+        assert_eq!(x_km(&swapped, -1001), 7000.0);
+        assert_eq!(
+            swapped.loaded_kernels()[1],
+            KernelInfo {
+                kind: KernelKind::SPK,
+                source: Some(updated.clone()),
+                uri: None,
+                alias: Some("predicted".to_string()),
+                crc32: swapped.spk_data[1].crc32(),
+                load_order: 1,
+            }
+        );
+        // The original Almanac is unchanged.
+        assert_eq!(x_km(&almanac, -1002), 8000.0);
+
+        // Unloading by path or alias removes the data.
+        for name in [predicted.as_str(), "predicted"] {
+            let unloaded = almanac.unload(name).unwrap();
+            assert_eq!(unloaded.num_loaded_spk(), 1);
+            assert_eq!(unloaded.loaded_kernels(), vec![kernels[0].clone()]);
+            assert!(unloaded
+                .translate(Frame::from_ephem_j2000(-1002), EARTH_J2000, epoch, None)
+                .is_err());
+        }
+
+        // A file loaded twice is unloaded entirely, and new kernels are loaded after all of the others.
+        let twice = almanac.load(&reference).unwrap();
+        assert_eq!(twice.loaded_kernels()[2].load_order, 2);
+        let unloaded = twice.unload(&reference).unwrap();
+        assert_eq!(unloaded.loaded_kernels(), vec![kernels[1].clone()]);
+        assert_eq!(x_km(&unloaded, -1002), 8000.0);
+        assert_eq!(unloaded.load(&reference).unwrap().kernels[1].load_order, 2);
+
+        assert!(almanac.unload("unknown").is_err());
+        assert!(almanac.replace("unknown", &updated).is_err());
+        assert!(almanac
+            .replace("predicted", "../data/moon_080317.txt")
+            .is_err());
+
+        let cleared = almanac.unload_all();
+        assert_eq!(cleared.num_loaded_spk(), 0);
+        assert!(cleared.loaded_kernels().is_empty());
+
+        // Kernels loaded from memory have no source until they are replaced from a file.
+        let in_memory = Almanac::default()
+            .with_spk(almanac.spk_data[1].clone())
+            .unwrap();
+        assert_eq!(in_memory.loaded_kernels()[0].source, None);
+        assert!(in_memory.replace(&predicted, &updated).is_err());
+    }
+}
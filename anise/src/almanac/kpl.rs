@@ -0,0 +1,169 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use log::info;
+use snafu::ResultExt;
+
+use super::Almanac;
+use crate::constants::orientations::J2000;
+use crate::errors::{AlmanacError, AlmanacResult, TLDataSetSnafu};
+use crate::naif::kpl::fk::FKItem;
+use crate::naif::kpl::parser::{fk_dataset, parse_bytes, sclk_dataset, update_planetary_data};
+use crate::naif::kpl::sclk::SCLKItem;
+use crate::naif::kpl::tpc::TPCItem;
+use crate::structure::dataset::{DataSetError, DataSetT, DataSetType, MergePolicy};
+use crate::structure::planetocentric::PlanetaryData;
+use crate::NaifId;
+
+/// Returns the kind of KPL text kernel held in these bytes, e.g. `PCK` for a kernel starting with `KPL/PCK`.
+pub(crate) fn kpl_kind(bytes: &[u8]) -> Option<&str> {
+    let start = bytes.iter().position(|c| !c.is_ascii_whitespace())?;
+    let id_word = bytes[start..].strip_prefix(b"KPL/")?;
+    let len = id_word
+        .iter()
+        .position(|c| c.is_ascii_whitespace())
+        .unwrap_or(id_word.len());
+    core::str::from_utf8(&id_word[..len]).ok()
+}
+
+impl Almanac {
+    /// Loads a KPL text kernel (a `.tpc` planetary constants kernel, a `.tf` frame kernel, or a `.tsc` spacecraft clock
+    /// kernel) into a clone of this Almanac, converting it in memory.
+    ///
+    /// The kernel is merged with the data already loaded, following the merge policy of this Almanac. For planetary
+    /// constants with the default [MergePolicy::LatestWins], only the parameters defined in the kernel are updated, so the
+    /// gravity parameters and the orientations of the bodies may be loaded from different kernels, in any order.
+    ///
+    /// # Note
+    /// Unlike [convert_tpc](crate::naif::kpl::parser::convert_tpc), which only keeps the bodies of the PCK that have a
+    /// gravity parameter, all of the bodies of the kernel are loaded.
+    pub fn load_text_kernel(&self, bytes: &[u8]) -> AlmanacResult<Self> {
+        let mut me = self.clone();
+        match kpl_kind(bytes) {
+            Some("PCK") => {
+                info!("Loading as KPL/PCK");
+                let items = parse_bytes::<TPCItem>(bytes, false).context(TLDataSetSnafu {
+                    action: "parsing KPL/PCK text kernel",
+                })?;
+
+                let mut object_ids: Vec<NaifId> = items.keys().copied().collect();
+                object_ids.sort();
+                for object_id in object_ids {
+                    let item = &items[&object_id];
+                    let existing = me.planetary_data.get_by_id(object_id).ok();
+                    if existing.is_some() {
+                        match me.merge_policy {
+                            MergePolicy::LatestWins => {}
+                            MergePolicy::KeepFirst => continue,
+                            MergePolicy::Error => {
+                                return Err(DataSetError::MergeConflict {
+                                    kind: PlanetaryData::NAME,
+                                    key: format!("ID {object_id}"),
+                                })
+                                .context(TLDataSetSnafu {
+                                    action: "adding KPL/PCK text kernel to planetary data",
+                                })
+                            }
+                        }
+                    }
+                    let mut constant = existing.unwrap_or(PlanetaryData {
+                        object_id,
+                        parent_id: J2000,
+                        ..Default::default()
+                    });
+                    update_planetary_data(&mut constant, item).context(
+                        TLDataSetSnafu {
+                            action: "converting KPL/PCK text kernel",
+                        },
+                    )?;
+
+                    if existing.is_some() {
+                        me.planetary_data.set_by_id(object_id, constant)
+                    } else {
+                        me.planetary_data.push(constant, Some(object_id), None)
+                    }
+                    .context(TLDataSetSnafu {
+                        action: "adding KPL/PCK text kernel to planetary data",
+                    })?;
+                }
+
+                me.planetary_data.metadata.dataset_type = DataSetType::PlanetaryData;
+                me.planetary_data.set_crc32();
+            }
+            Some("FK") => {
+                info!("Loading as KPL/FK");
+                let dataset = parse_bytes::<FKItem>(bytes, false)
+                    .and_then(fk_dataset)
+                    .context(TLDataSetSnafu {
+                        action: "converting KPL/FK text kernel",
+                    })?;
+
+                me = me.merge_euler_parameters(&dataset)?;
+            }
+            Some("SCLK") => {
+                info!("Loading as KPL/SCLK");
+                let dataset = parse_bytes::<SCLKItem>(bytes, false)
+                    .and_then(sclk_dataset)
+                    .context(TLDataSetSnafu {
+                        action: "converting KPL/SCLK text kernel",
+                    })?;
+
+                me = me.merge_sclk_data(&dataset)?;
+            }
+            Some(kind) => {
+                return Err(AlmanacError::GenericError {
+                    err: format!(
+                        "KPL/{kind} text kernels are not supported, only KPL/PCK, KPL/FK and KPL/SCLK are"
+                    ),
+                })
+            }
+            None => {
+                return Err(AlmanacError::GenericError {
+                    err: "not a KPL text kernel: the file must start with its KPL/ identification word"
+                        .to_string(),
+                })
+            }
+        }
+
+        Ok(me)
+    }
+}
+
+#[cfg(test)]
+mod ut_kpl {
+    use super::kpl_kind;
+    use crate::almanac::Almanac;
+    use crate::errors::AlmanacError;
+
+    #[test]
+    fn text_kernel_kind() {
+        assert_eq!(kpl_kind(b"KPL/PCK\n\nP_constants"), Some("PCK"));
+        assert_eq!(kpl_kind(b"\r\n  KPL/FK"), Some("FK"));
+        assert_eq!(kpl_kind(b"KPL/SCLK "), Some("SCLK"));
+        assert_eq!(kpl_kind(b"DAF/SPK "), None);
+        assert_eq!(kpl_kind(b""), None);
+    }
+
+    #[test]
+    fn unsupported_text_kernel() {
+        let almanac = Almanac::default();
+        assert_eq!(
+            almanac
+                .load_text_kernel(b"KPL/LSK\n\\begindata\nDELTET/K = 1.657D-3\n")
+                .err(),
+            Some(AlmanacError::GenericError {
+                err:
+                    "KPL/LSK text kernels are not supported, only KPL/PCK, KPL/FK and KPL/SCLK are"
+                        .to_string()
+            })
+        );
+        assert!(almanac.load_text_kernel(b"\\begindata\n").is_err());
+    }
+}
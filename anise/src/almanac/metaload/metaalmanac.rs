@@ -56,6 +56,8 @@ impl MetaAlmanac {
 
     /// Fetch all of the URIs and return a loaded Almanac
     pub(crate) fn _process(&mut self) -> AlmanacResult<Almanac> {
+        // Processing replaces the URIs by the local paths, so the kernels record the original URIs.
+        let uris: Vec<String> = self.files.iter().map(|file| file.uri.clone()).collect();
         for (fno, file) in self.files.iter_mut().enumerate() {
             file._process().context(MetaSnafu {
                 fno,
@@ -64,8 +66,8 @@ impl MetaAlmanac {
         }
         // At this stage, all of the files are local files, so we can load them as is.
         let mut ctx = Almanac::default();
-        for uri in &self.files {
-            ctx = ctx.load(&uri.uri)?;
+        for (file, uri) in self.files.iter().zip(&uris) {
+            ctx = ctx.load_from_uri(&file.uri, uri)?;
         }
         Ok(ctx)
     }
@@ -57,11 +57,12 @@ pub enum MetaAlmanacError {
 impl Almanac {
     /// Load from the provided MetaFile.
     fn _load_from_metafile(&self, mut metafile: MetaFile) -> AlmanacResult<Self> {
+        let uri = metafile.uri.clone();
         metafile._process().context(MetaSnafu {
             fno: 0_usize,
             file: metafile.clone(),
         })?;
-        self.load(&metafile.uri)
+        self.load_from_uri(&metafile.uri, &uri)
     }
 
     /// Load from the provided MetaFile, downloading it if necessary.
@@ -80,6 +81,11 @@ impl Almanac {
 #[cfg(test)]
 mod meta_test {
     use crate::almanac::metaload::MetaFile;
+    use crate::almanac::Almanac;
+    use crate::constants::frames::EARTH_J2000;
+    use crate::math::Vector3;
+    use crate::naif::spk::fixtures::{hermite_spk, sampled_states};
+    use hifitime::{Epoch, TimeUnits};
 
     use super::MetaAlmanac;
     use std::path::Path;
@@ -146,4 +152,43 @@ mod meta_test {
 
         assert_eq!(from_str, default);
     }
+
+    #[test]
+    fn kernels_record_uri() {
+        let start = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        let states = sampled_states(EARTH_J2000, start, 1.minutes(), 0..=10, |_| {
+            (Vector3::new(7000.0, 0.0, 0.0), Vector3::zeros())
+        });
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../target/ut-meta-uri.bsp");
+        hermite_spk("URI", [("URI", -1001, states, 4)])
+            .persist(&path)
+            .unwrap();
+        let path = path.to_str().unwrap().to_string();
+        let uri = format!("file://{path}");
+
+        // The kernels record both the local path they were loaded from and the URI of their MetaFile.
+        let mut meta = MetaAlmanac {
+            files: vec![MetaFile {
+                uri: uri.clone(),
+                crc32: None,
+            }],
+        };
+        let almanac = meta._process().unwrap();
+        let kernel = &almanac.loaded_kernels()[0];
+        assert_eq!(kernel.source.as_deref(), Some(path.as_str()));
+        assert_eq!(kernel.uri.as_deref(), Some(uri.as_str()));
+        assert!(kernel.matches(&uri));
+
+        let almanac = Almanac::default()
+            ._load_from_metafile(MetaFile {
+                uri: uri.clone(),
+                crc32: None,
+            })
+            .unwrap();
+        assert_eq!(
+            almanac.loaded_kernels()[0].uri.as_deref(),
+            Some(uri.as_str())
+        );
+        assert_eq!(almanac.unload(&uri).unwrap().num_loaded_spk(), 0);
+    }
 }
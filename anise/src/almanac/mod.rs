@@ -21,22 +21,26 @@ use crate::errors::{
 use crate::file2heap;
 use crate::naif::daf::{FileRecord, NAIFRecord};
 use crate::naif::pretty_print::NAIFPrettyPrint;
-use crate::naif::{BPC, SPK};
-use crate::orientations::BPCSnafu;
-use crate::structure::dataset::DataSetType;
+use crate::naif::{BPC, CK, SPK};
+use crate::orientations::{BPCSnafu, CKSnafu};
+use crate::structure::dataset::{DataSetType, MergePolicy};
 use crate::structure::metadata::Metadata;
-use crate::structure::{EulerParameterDataSet, PlanetaryDataSet, SpacecraftDataSet};
+use crate::structure::{EulerParameterDataSet, PlanetaryDataSet, SCLKDataSet, SpacecraftDataSet};
 use core::fmt;
-
-// TODO: Switch these to build constants so that it's configurable when building the library.
-pub const MAX_LOADED_SPKS: usize = 32;
-pub const MAX_LOADED_BPCS: usize = 8;
-pub const MAX_SPACECRAFT_DATA: usize = 16;
-pub const MAX_PLANETARY_DATA: usize = 64;
+use index::SegmentIndex;
+use kernels::KernelInfo;
 
 pub mod aer;
+pub mod batch;
 pub mod bpc;
+pub mod ck;
+pub mod eclipse;
+pub mod events;
+pub mod index;
+pub mod kernels;
+pub mod kpl;
 pub mod planetary;
+pub mod sclk;
 pub mod solar;
 pub mod spk;
 pub mod transform;
@@ -56,22 +60,34 @@ use pyo3::prelude::*;
 
 /// An Almanac contains all of the loaded SPICE and ANISE data.
 ///
-/// # Limitations
-/// The stack space required depends on the maximum number of each type that can be loaded.
+/// There is no limit on the number of files or data set entries an Almanac can hold: the NAIF files are shared
+/// pointers to their bytes, so cloning an Almanac to load more data only copies these pointers and the look up tables.
 #[derive(Clone, Default)]
 #[cfg_attr(feature = "python", pyclass)]
 #[cfg_attr(feature = "python", pyo3(module = "anise"))]
 pub struct Almanac {
-    /// NAIF SPK is kept unchanged
-    pub spk_data: [Option<SPK>; MAX_LOADED_SPKS],
-    /// NAIF BPC is kept unchanged
-    pub bpc_data: [Option<BPC>; MAX_LOADED_BPCS],
+    /// NAIF SPK is kept unchanged, in loading order
+    pub spk_data: Vec<SPK>,
+    /// NAIF BPC is kept unchanged, in loading order
+    pub bpc_data: Vec<BPC>,
+    /// NAIF CK is kept unchanged, in loading order
+    pub ck_data: Vec<CK>,
+    /// Source, alias and checksum of each of the SPK, BPC and CK, in loading order
+    pub kernels: Vec<KernelInfo>,
+    /// Index of the segments of the SPKs, kept up to date by the methods that load and unload them
+    pub spk_index: SegmentIndex,
+    /// Index of the segments of the BPCs, kept up to date by the methods that load and unload them
+    pub bpc_index: SegmentIndex,
+    /// Dataset of spacecraft clocks, used to convert clock readings like the time tags of the CK data
+    pub sclk_data: SCLKDataSet,
     /// Dataset of planetary data
     pub planetary_data: PlanetaryDataSet,
     /// Dataset of spacecraft data
     pub spacecraft_data: SpacecraftDataSet,
     /// Dataset of euler parameters
     pub euler_param_data: EulerParameterDataSet,
+    /// Policy to resolve the entries of loaded data sets that are already in this Almanac
+    pub merge_policy: MergePolicy,
 }
 
 impl fmt::Display for Almanac {
@@ -82,6 +98,9 @@ impl fmt::Display for Almanac {
             self.num_loaded_spk(),
             self.num_loaded_bpc()
         )?;
+        if self.num_loaded_ck() > 0 {
+            write!(f, "\t#CK = {}", self.num_loaded_ck())?;
+        }
         if !self.planetary_data.lut.by_id.is_empty() {
             write!(f, "\t{}", self.planetary_data)?;
         }
@@ -91,6 +110,9 @@ impl fmt::Display for Almanac {
         if !self.euler_param_data.lut.by_id.is_empty() {
             write!(f, "\t{}", self.euler_param_data)?;
         }
+        if !self.sclk_data.lut.by_id.is_empty() {
+            write!(f, "\t{}", self.sclk_data)?;
+        }
         Ok(())
     }
 }
@@ -101,20 +123,57 @@ impl Almanac {
         Self::default().load(path)
     }
 
-    /// Loads the provided spacecraft data into a clone of this original Almanac.
+    /// Returns a clone of this Almanac that resolves the entries of the data sets it loads with the provided policy.
+    pub fn with_merge_policy(&self, merge_policy: MergePolicy) -> Self {
+        let mut me = self.clone();
+        me.merge_policy = merge_policy;
+        me
+    }
+
+    /// Loads the provided spacecraft data into a clone of this original Almanac, replacing all of the loaded spacecraft data.
     pub fn with_spacecraft_data(&self, spacecraft_data: SpacecraftDataSet) -> Self {
         let mut me = self.clone();
         me.spacecraft_data = spacecraft_data;
         me
     }
 
-    /// Loads the provided Euler parameter data into a clone of this original Almanac.
+    /// Merges the provided spacecraft data into a clone of this original Almanac, resolving the entries that are
+    /// already loaded with the merge policy of this Almanac.
+    pub fn merge_spacecraft_data(
+        &self,
+        spacecraft_data: &SpacecraftDataSet,
+    ) -> AlmanacResult<Self> {
+        let mut me = self.clone();
+        me.spacecraft_data
+            .merge(spacecraft_data, self.merge_policy)
+            .context(TLDataSetSnafu {
+                action: "merging spacecraft data",
+            })?;
+        Ok(me)
+    }
+
+    /// Loads the provided Euler parameter data into a clone of this original Almanac, replacing all of the loaded Euler parameters.
     pub fn with_euler_parameters(&self, ep_dataset: EulerParameterDataSet) -> Self {
         let mut me = self.clone();
         me.euler_param_data = ep_dataset;
         me
     }
 
+    /// Merges the provided Euler parameter data into a clone of this original Almanac, resolving the entries that are
+    /// already loaded with the merge policy of this Almanac.
+    pub fn merge_euler_parameters(
+        &self,
+        ep_dataset: &EulerParameterDataSet,
+    ) -> AlmanacResult<Self> {
+        let mut me = self.clone();
+        me.euler_param_data
+            .merge(ep_dataset, self.merge_policy)
+            .context(TLDataSetSnafu {
+                action: "merging Euler parameters",
+            })?;
+        Ok(me)
+    }
+
     pub fn load_from_bytes(&self, bytes: Bytes) -> AlmanacResult<Self> {
         // Try to load as a SPICE DAF first (likely the most typical use case)
 
@@ -136,6 +195,19 @@ impl Almanac {
                             action: "adding BPC file to context",
                         })
                     }
+                    "CK" => {
+                        info!("Loading as DAF/CK");
+                        let ck = CK::parse(bytes)
+                            .context(CKSnafu {
+                                action: "parsing bytes",
+                            })
+                            .context(OrientationSnafu {
+                                action: "from generic loading",
+                            })?;
+                        self.with_ck(ck).context(OrientationSnafu {
+                            action: "adding CK file to context",
+                        })
+                    }
                     "SPK" => {
                         info!("Loading as DAF/SPK");
                         let spk = SPK::parse(bytes)
@@ -157,6 +229,10 @@ impl Almanac {
             // Fall through to try to load as an ANISE file
         }
 
+        if kpl::kpl_kind(&bytes).is_some() {
+            return self.load_text_kernel(&bytes);
+        }
+
         if let Ok(metadata) = Metadata::decode_header(&bytes) {
             // Now, we can load this depending on the kind of data that it is
             match metadata.dataset_type {
@@ -168,7 +244,7 @@ impl Almanac {
                             action: "loading as spacecraft data",
                         }
                     })?;
-                    Ok(self.with_spacecraft_data(dataset))
+                    self.merge_spacecraft_data(&dataset)
                 }
                 DataSetType::PlanetaryData => {
                     // Decode as planetary data
@@ -177,7 +253,7 @@ impl Almanac {
                             action: "loading as planetary data",
                         }
                     })?;
-                    Ok(self.with_planetary_data(dataset))
+                    self.merge_planetary_data(&dataset)
                 }
                 DataSetType::EulerParameterData => {
                     // Decode as euler parameter data
@@ -186,12 +262,22 @@ impl Almanac {
                             action: "loading Euler parameters",
                         }
                     })?;
-                    Ok(self.with_euler_parameters(dataset))
+                    self.merge_euler_parameters(&dataset)
+                }
+                DataSetType::SCLKData => {
+                    // Decode as spacecraft clock data
+                    let dataset = SCLKDataSet::try_from_bytes(bytes).context({
+                        TLDataSetSnafu {
+                            action: "loading spacecraft clocks",
+                        }
+                    })?;
+                    self.merge_sclk_data(&dataset)
                 }
             }
         } else {
             Err(AlmanacError::GenericError {
-                err: "Provided file cannot be inspected loaded directly in ANISE and may need a conversion first".to_string(),
+                err: "Provided file is neither a NAIF DAF, a KPL text kernel, nor an ANISE file"
+                    .to_string(),
             })
         }
     }
@@ -206,7 +292,7 @@ impl Almanac {
             path: path.to_string(),
         })?;
         info!("Loading almanac from {path}");
-        self.load_from_bytes(bytes).map_err(|e| match e {
+        let mut me = self.load_from_bytes(bytes).map_err(|e| match e {
             AlmanacError::GenericError { err } => {
                 // Add the path to the error
                 AlmanacError::GenericError {
@@ -214,7 +300,14 @@ impl Almanac {
                 }
             }
             _ => e,
-        })
+        })?;
+        // Record where the kernel comes from, so it can be unloaded or replaced by its path.
+        if me.kernels.len() > self.kernels.len() {
+            if let Some(kernel) = me.kernels.last_mut() {
+                kernel.source = Some(path.to_string());
+            }
+        }
+        Ok(me)
     }
 
     /// Initializes a new Almanac from the provided file path, guessing at the file type
@@ -247,14 +340,7 @@ impl Almanac {
         let print_any = spk.unwrap_or(false) || bpc.unwrap_or(false) || planetary.unwrap_or(false);
 
         if spk.unwrap_or(!print_any) {
-            for (spk_no, maybe_spk) in self
-                .spk_data
-                .iter()
-                .take(self.num_loaded_spk())
-                .rev()
-                .enumerate()
-            {
-                let spk = maybe_spk.as_ref().unwrap();
+            for (spk_no, spk) in self.spk_data.iter().rev().enumerate() {
                 println!(
                     "=== SPK #{spk_no} ===\n{}",
                     spk.describe_in(time_scale.unwrap_or(TimeScale::TDB), round_time)
@@ -263,14 +349,7 @@ impl Almanac {
         }
 
         if bpc.unwrap_or(!print_any) {
-            for (bpc_no, maybe_bpc) in self
-                .bpc_data
-                .iter()
-                .take(self.num_loaded_bpc())
-                .rev()
-                .enumerate()
-            {
-                let bpc = maybe_bpc.as_ref().unwrap();
+            for (bpc_no, bpc) in self.bpc_data.iter().rev().enumerate() {
                 println!(
                     "=== BPC #{bpc_no} ===\n{}",
                     bpc.describe_in(time_scale.unwrap_or(TimeScale::TDB), round_time)
@@ -12,6 +12,7 @@ use snafu::prelude::*;
 use tabled::{settings::Style, Table, Tabled};
 
 use crate::{
+    errors::{AlmanacResult, TLDataSetSnafu},
     prelude::{Frame, FrameUid},
     structure::{dataset::DataSetError, PlanetaryDataSet},
 };
@@ -39,12 +40,24 @@ impl Almanac {
             .to_frame(uid))
     }
 
-    /// Loads the provided planetary data into a clone of this original Almanac.
+    /// Loads the provided planetary data into a clone of this original Almanac, replacing all of the loaded planetary data.
     pub fn with_planetary_data(&self, planetary_data: PlanetaryDataSet) -> Self {
         let mut me = self.clone();
         me.planetary_data = planetary_data;
         me
     }
+
+    /// Merges the provided planetary data into a clone of this original Almanac, resolving the entries that are already
+    /// loaded with the merge policy of this Almanac.
+    pub fn merge_planetary_data(&self, planetary_data: &PlanetaryDataSet) -> AlmanacResult<Self> {
+        let mut me = self.clone();
+        me.planetary_data
+            .merge(planetary_data, self.merge_policy)
+            .context(TLDataSetSnafu {
+                action: "merging planetary data",
+            })?;
+        Ok(me)
+    }
 }
 
 #[derive(Tabled, Default)]
@@ -12,7 +12,14 @@ use super::{
     planetary::{PlanetaryDataError, PlanetaryDataSetSnafu},
     Almanac,
 };
-use crate::prelude::Frame;
+use crate::ephemerides::EphemerisError;
+use crate::errors::AlmanacResult;
+use crate::math::cartesian::CartesianState;
+use crate::orientations::OrientationError;
+use crate::prelude::{Aberration, Frame};
+use hifitime::Epoch;
+use numpy::ndarray::{Array2, Array3};
+use numpy::{IntoPyArray, PyArray2, PyArray3};
 use pyo3::prelude::*;
 use snafu::prelude::*;
 
@@ -27,4 +34,66 @@ impl Almanac {
             })?
             .to_frame(uid.into()))
     }
+
+    /// Returns the Cartesian states of the target frame as seen from the observer frame at each of these epochs, as a
+    /// NumPy array with one row per epoch of the position (km) and velocity (km/s), cf. `transform`.
+    #[pyo3(name = "transform_many")]
+    fn py_transform_many<'py>(
+        &self,
+        py: Python<'py>,
+        target_frame: Frame,
+        observer_frame: Frame,
+        epochs: Vec<Epoch>,
+        ab_corr: Option<Aberration>,
+    ) -> AlmanacResult<Bound<'py, PyArray2<f64>>> {
+        let states = py.allow_threads(|| {
+            self.transform_many(target_frame, observer_frame, &epochs, ab_corr)
+        })?;
+        Ok(states_to_array(&states).into_pyarray_bound(py))
+    }
+
+    /// Returns the states of the target frame as seen from the observer frame at each of these epochs without any
+    /// rotation, as a NumPy array with one row per epoch of the position (km) and velocity (km/s), cf. `translate`.
+    #[pyo3(name = "translate_many")]
+    fn py_translate_many<'py>(
+        &self,
+        py: Python<'py>,
+        target_frame: Frame,
+        observer_frame: Frame,
+        epochs: Vec<Epoch>,
+        ab_corr: Option<Aberration>,
+    ) -> Result<Bound<'py, PyArray2<f64>>, EphemerisError> {
+        let states = py.allow_threads(|| {
+            self.translate_many(target_frame, observer_frame, &epochs, ab_corr)
+        })?;
+        Ok(states_to_array(&states).into_pyarray_bound(py))
+    }
+
+    /// Returns the rotation matrices from the `from_frame` to the `to_frame` at each of these epochs, as a NumPy
+    /// array of shape (number of epochs, 3, 3), cf. `rotate_from_to`.
+    #[pyo3(name = "rotate_many")]
+    fn py_rotate_many<'py>(
+        &self,
+        py: Python<'py>,
+        from_frame: Frame,
+        to_frame: Frame,
+        epochs: Vec<Epoch>,
+    ) -> Result<Bound<'py, PyArray3<f64>>, OrientationError> {
+        let dcms = py.allow_threads(|| self.rotate_many(from_frame, to_frame, &epochs))?;
+        Ok(Array3::from_shape_fn((dcms.len(), 3, 3), |(i, row, col)| {
+            dcms[i].rot_mat[(row, col)]
+        })
+        .into_pyarray_bound(py))
+    }
+}
+
+/// Stacks the position and velocity of these states in an array with one row per state.
+fn states_to_array(states: &[CartesianState]) -> Array2<f64> {
+    Array2::from_shape_fn((states.len(), 6), |(i, j)| {
+        if j < 3 {
+            states[i].radius_km[j]
+        } else {
+            states[i].velocity_km_s[j - 3]
+        }
+    })
 }
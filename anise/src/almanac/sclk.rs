@@ -0,0 +1,178 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use hifitime::Epoch;
+use snafu::ResultExt;
+
+use super::Almanac;
+use crate::errors::{AlmanacResult, TLDataSetSnafu};
+use crate::naif::sclk::{SCLKDataSetSnafu, SCLKError, SCLK};
+use crate::structure::dataset::DataSetType;
+use crate::structure::SCLKDataSet;
+use crate::NaifId;
+
+impl Almanac {
+    /// Loads the provided spacecraft clock data into a clone of this original Almanac, replacing all of the loaded clocks.
+    pub fn with_sclk_data(&self, sclk_data: SCLKDataSet) -> Self {
+        let mut me = self.clone();
+        me.sclk_data = sclk_data;
+        me
+    }
+
+    /// Merges the provided spacecraft clock data into a clone of this original Almanac, resolving the clocks that are
+    /// already loaded with the merge policy of this Almanac.
+    pub fn merge_sclk_data(&self, sclk_data: &SCLKDataSet) -> AlmanacResult<Self> {
+        let mut me = self.clone();
+        me.sclk_data
+            .merge(sclk_data, self.merge_policy)
+            .context(TLDataSetSnafu {
+                action: "merging spacecraft clocks",
+            })?;
+        Ok(me)
+    }
+
+    /// Loads a spacecraft clock, replacing any clock with the same ID that was previously loaded.
+    pub fn with_sclk(&self, sclk: SCLK) -> Result<Self, SCLKError> {
+        let mut me = self.clone();
+        let clock_id = sclk.clock_id;
+        if me.sclk_data.lut.by_id.contains_key(&clock_id) {
+            me.sclk_data
+                .set_by_id(clock_id, sclk)
+                .context(SCLKDataSetSnafu {
+                    action: "replacing spacecraft clock",
+                })?;
+        } else {
+            me.sclk_data
+                .push(sclk, Some(clock_id), None)
+                .context(SCLKDataSetSnafu {
+                    action: "adding spacecraft clock",
+                })?;
+        }
+        me.sclk_data.metadata.dataset_type = DataSetType::SCLKData;
+        me.sclk_data.set_crc32();
+        Ok(me)
+    }
+
+    /// Returns the loaded spacecraft clock of the provided ID.
+    pub fn sclk(&self, clock_id: NaifId) -> Result<&SCLK, SCLKError> {
+        self.sclk_data
+            .lut
+            .by_id
+            .get(&clock_id)
+            .and_then(|idx| self.sclk_data.data.get(*idx as usize))
+            .ok_or(SCLKError::SCLKNotLoaded { clock_id })
+    }
+
+    /// Converts the encoded ticks of the spacecraft clock `clock_id` into an epoch.
+    pub fn sclk_ticks_to_epoch(&self, clock_id: NaifId, ticks: f64) -> Result<Epoch, SCLKError> {
+        self.sclk(clock_id)?.ticks_to_epoch(ticks)
+    }
+
+    /// Converts an epoch into encoded ticks of the spacecraft clock `clock_id`.
+    pub fn epoch_to_sclk_ticks(&self, clock_id: NaifId, epoch: Epoch) -> Result<f64, SCLKError> {
+        self.sclk(clock_id)?.epoch_to_ticks(epoch)
+    }
+
+    /// Converts a clock string of the spacecraft clock `clock_id` (e.g. `1/1465644281.165`) into an epoch.
+    ///
+    /// If the string does not specify the partition, the first partition which includes this clock reading is used.
+    pub fn sclk_string_to_epoch(&self, clock_id: NaifId, sclk: &str) -> Result<Epoch, SCLKError> {
+        let sclk_data = self.sclk(clock_id)?;
+        sclk_data.ticks_to_epoch(sclk_data.parse_ticks(sclk)?)
+    }
+
+    /// Converts an epoch into a clock string of the spacecraft clock `clock_id`, rounded to the nearest tick.
+    pub fn epoch_to_sclk_string(
+        &self,
+        clock_id: NaifId,
+        epoch: Epoch,
+    ) -> Result<String, SCLKError> {
+        let sclk_data = self.sclk(clock_id)?;
+        sclk_data.format_ticks(sclk_data.epoch_to_ticks(epoch)?)
+    }
+}
+
+#[cfg(test)]
+mod ut_almanac_sclk {
+    use hifitime::{Epoch, Unit};
+
+    use crate::almanac::Almanac;
+    use crate::naif::kpl::parser::convert_sclk;
+    use crate::naif::sclk::SCLKError;
+
+    const CLOCK: i32 = -999;
+
+    #[test]
+    fn sclk_conversions() {
+        let dataset = convert_sclk("../data/example.tsc", false).unwrap();
+        let mut buf = vec![];
+        der::Encode::encode_to_vec(&dataset, &mut buf).unwrap();
+
+        assert_eq!(
+            Almanac::default().sclk_string_to_epoch(CLOCK, "1/10.0"),
+            Err(SCLKError::SCLKNotLoaded { clock_id: CLOCK })
+        );
+
+        let almanac = Almanac::default().load_from_bytes(buf.into()).unwrap();
+
+        // First half of the first partition, at exactly one second per count.
+        let epoch = almanac
+            .sclk_string_to_epoch(CLOCK, "1/0000000010.128")
+            .unwrap();
+        assert_eq!(epoch, Epoch::from_et_seconds(750_000_010.5));
+        assert_eq!(
+            almanac.epoch_to_sclk_string(CLOCK, epoch).unwrap(),
+            "1/0000000010.128"
+        );
+        assert_eq!(almanac.epoch_to_sclk_ticks(CLOCK, epoch).unwrap(), 2_688.0);
+        assert_eq!(almanac.sclk_ticks_to_epoch(CLOCK, 2_688.0).unwrap(), epoch);
+
+        // Second half of the first partition, where the clock counts slightly slower than the parallel time.
+        let epoch = almanac
+            .sclk_string_to_epoch(CLOCK, "1/0000600000.000")
+            .unwrap();
+        let expected = Epoch::from_et_seconds(750_500_000.0 + 100_000.0 * 1.000001);
+        assert!((epoch - expected).abs() < Unit::Microsecond * 1);
+
+        // After the clock reset, the partition must be specified to not be mistaken for the first partition.
+        let epoch = almanac
+            .sclk_string_to_epoch(CLOCK, "2/0000000010.000")
+            .unwrap();
+        assert!((epoch - Epoch::from_et_seconds(751_000_010.5)).abs() < Unit::Microsecond * 1);
+        assert_eq!(
+            almanac.epoch_to_sclk_string(CLOCK, epoch).unwrap(),
+            "2/0000000010.000"
+        );
+        assert_eq!(
+            almanac
+                .sclk_string_to_epoch(CLOCK, "0000000010.000")
+                .unwrap(),
+            Epoch::from_et_seconds(750_000_010.0)
+        );
+
+        assert!(almanac.sclk_string_to_epoch(CLOCK, "3/10.0").is_err());
+        assert!(almanac.epoch_to_sclk_string(-82, epoch).is_err());
+    }
+
+    #[test]
+    fn sclk_replaced() {
+        let dataset = convert_sclk("../data/example.tsc", false).unwrap();
+        let almanac = Almanac::default().with_sclk_data(dataset);
+
+        let mut sclk = almanac.sclk(CLOCK).unwrap().clone();
+        sclk.coefficients[0].parallel_time_s += 1.0;
+        let almanac = almanac.with_sclk(sclk).unwrap();
+        assert_eq!(almanac.sclk_data.len(), 1);
+        assert_eq!(
+            almanac.sclk_string_to_epoch(CLOCK, "1/10.0").unwrap(),
+            Epoch::from_et_seconds(750_000_011.0)
+        );
+    }
+}
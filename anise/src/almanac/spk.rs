@@ -14,17 +14,20 @@ use hifitime::Epoch;
 
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
-use snafu::ensure;
+use snafu::{ensure, ResultExt};
 
-use crate::ephemerides::NoEphemerisLoadedSnafu;
+use crate::ephemerides::{NoEphemerisLoadedSnafu, SPKSnafu};
+use crate::math::window::Window;
 use crate::naif::daf::DAFError;
 use crate::naif::daf::NAIFSummaryRecord;
+use crate::naif::spk::fit::{ChebyshevFit, ChebyshevFitConfig, ChebyshevFitError};
 use crate::naif::spk::summary::SPKSummaryRecord;
 use crate::naif::SPK;
+use crate::prelude::Frame;
 use crate::{ephemerides::EphemerisError, NaifId};
 use log::error;
 
-use super::{Almanac, MAX_LOADED_SPKS};
+use super::{index::SegmentIndex, kernels::KernelKind, Almanac};
 
 impl Almanac {
     pub fn from_spk(spk: SPK) -> Result<Almanac, EphemerisError> {
@@ -33,40 +36,26 @@ impl Almanac {
     }
 
     /// Loads a new SPK file into a new context.
-    /// This new context is needed to satisfy the unloading of files. In fact, to unload a file, simply let the newly loaded context drop out of scope and Rust will clean it up.
+    /// This new context is needed to satisfy the unloading of files. In fact, to unload a file, simply let the newly loaded context drop out of scope and Rust will clean it up,
+    /// or call [Almanac::unload] with the path it was loaded from.
     pub fn with_spk(&self, spk: SPK) -> Result<Self, EphemerisError> {
         // This is just a bunch of pointers so it doesn't use much memory.
         let mut me = self.clone();
         // Parse as SPK and place into the SPK list if there is room
-        let mut data_idx = MAX_LOADED_SPKS;
-        for (idx, item) in self.spk_data.iter().enumerate() {
-            if item.is_none() {
-                data_idx = idx;
-                break;
-            }
-        }
-        if data_idx == MAX_LOADED_SPKS {
-            return Err(EphemerisError::StructureIsFull {
-                max_slots: MAX_LOADED_SPKS,
-            });
+        me.record_kernel(KernelKind::SPK, spk.crc32_checksum);
+        if me.spk_index.num_kernels() != me.spk_data.len() {
+            // The SPK data was modified directly, so the index is rebuilt.
+            me.spk_index = SegmentIndex::new(&me.spk_data);
         }
-        me.spk_data[data_idx] = Some(spk);
+        me.spk_index.push(&spk);
+        me.spk_data.push(spk);
         Ok(me)
     }
 }
 
 impl Almanac {
     pub fn num_loaded_spk(&self) -> usize {
-        let mut count = 0;
-        for maybe in &self.spk_data {
-            if maybe.is_none() {
-                break;
-            } else {
-                count += 1;
-            }
-        }
-
-        count
+        self.spk_data.len()
     }
 
     /// Returns the summary given the name of the summary record if that summary has data defined at the requested epoch and the SPK where this name was found to be valid at that epoch.
@@ -75,14 +64,7 @@ impl Almanac {
         name: &str,
         epoch: Epoch,
     ) -> Result<(&SPKSummaryRecord, usize, usize), EphemerisError> {
-        for (spk_no, maybe_spk) in self
-            .spk_data
-            .iter()
-            .take(self.num_loaded_spk())
-            .rev()
-            .enumerate()
-        {
-            let spk = maybe_spk.as_ref().unwrap();
+        for (spk_no, spk) in self.spk_data.iter().rev().enumerate() {
             if let Ok((summary, idx_in_spk)) = spk.summary_from_name_at_epoch(name, epoch) {
                 return Ok((summary, spk_no, idx_in_spk));
             }
@@ -106,17 +88,22 @@ impl Almanac {
         id: i32,
         epoch: Epoch,
     ) -> Result<(&SPKSummaryRecord, usize, usize), EphemerisError> {
-        for (spk_no, maybe_spk) in self
-            .spk_data
-            .iter()
-            .take(self.num_loaded_spk())
-            .rev()
-            .enumerate()
-        {
-            let spk = maybe_spk.as_ref().unwrap();
-            if let Ok((summary, idx_in_spk)) = spk.summary_from_id_at_epoch(id, epoch) {
-                // NOTE: We're iterating backward, so the correct SPK number is "total loaded" minus "current iteration".
-                return Ok((summary, self.num_loaded_spk() - spk_no - 1, idx_in_spk));
+        if self.spk_index.num_kernels() == self.num_loaded_spk() {
+            if let Some((spk_no, idx_in_spk)) = self.spk_index.segment_at_epoch(id, epoch) {
+                let summary = self.spk_data[spk_no]
+                    .nth_summary(idx_in_spk)
+                    .context(SPKSnafu {
+                        action: "fetching indexed SPK summary",
+                    })?;
+                return Ok((summary, spk_no, idx_in_spk));
+            }
+        } else {
+            // The SPK data was modified directly, so search all of the summaries.
+            for (spk_no, spk) in self.spk_data.iter().rev().enumerate() {
+                if let Ok((summary, idx_in_spk)) = spk.summary_from_id_at_epoch(id, epoch) {
+                    // NOTE: We're iterating backward, so the correct SPK number is "total loaded" minus "current iteration".
+                    return Ok((summary, self.num_loaded_spk() - spk_no - 1, idx_in_spk));
+                }
             }
         }
 
@@ -137,14 +124,7 @@ impl Almanac {
         &self,
         name: &str,
     ) -> Result<(&SPKSummaryRecord, usize, usize), EphemerisError> {
-        for (spk_no, maybe_spk) in self
-            .spk_data
-            .iter()
-            .take(self.num_loaded_spk())
-            .rev()
-            .enumerate()
-        {
-            let spk = maybe_spk.as_ref().unwrap();
+        for (spk_no, spk) in self.spk_data.iter().rev().enumerate() {
             if let Ok((summary, idx_in_spk)) = spk.summary_from_name(name) {
                 return Ok((summary, spk_no, idx_in_spk));
             }
@@ -167,14 +147,7 @@ impl Almanac {
         &self,
         id: i32,
     ) -> Result<(&SPKSummaryRecord, usize, usize), EphemerisError> {
-        for (spk_no, maybe_spk) in self
-            .spk_data
-            .iter()
-            .take(self.num_loaded_spk())
-            .rev()
-            .enumerate()
-        {
-            let spk = maybe_spk.as_ref().unwrap();
+        for (spk_no, spk) in self.spk_data.iter().rev().enumerate() {
             if let Ok((summary, idx_in_spk)) = spk.summary_from_id(id) {
                 // NOTE: We're iterating backward, so the correct SPK number is "total loaded" minus "current iteration".
                 return Ok((summary, self.num_loaded_spk() - spk_no - 1, idx_in_spk));
@@ -188,6 +161,29 @@ impl Almanac {
             source: DAFError::SummaryIdError { kind: "SPK", id },
         })
     }
+
+    /// Fits Chebyshev polynomials on the translation of `target_frame` with respect to `observer_frame` from `start` to
+    /// `end`, e.g. to recompress a high rate Hermite ephemeris or to rebuild a planetary ephemeris over a custom span.
+    ///
+    /// The fit can then be written to a new SPK with [crate::naif::spk::builder::SPKBuilder::add_chebyshev_segment].
+    pub fn fit_chebyshev(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        start: Epoch,
+        end: Epoch,
+        config: ChebyshevFitConfig,
+    ) -> Result<ChebyshevFit, ChebyshevFitError> {
+        ChebyshevFit::fit(
+            |epoch| {
+                let state = self.translate(target_frame, observer_frame, epoch, None)?;
+                Ok((state.radius_km, state.velocity_km_s))
+            },
+            start,
+            end,
+            config,
+        )
+    }
 }
 
 #[cfg_attr(feature = "python", pymethods)]
@@ -198,10 +194,9 @@ impl Almanac {
     /// This function performs a memory allocation.
     pub fn spk_summaries(&self, id: NaifId) -> Result<Vec<SPKSummaryRecord>, EphemerisError> {
         let mut summaries = vec![];
-        for maybe_spk in self.spk_data.iter().take(self.num_loaded_spk()).rev() {
-            let spk = maybe_spk.as_ref().unwrap();
-            if let Ok(these_summaries) = spk.data_summaries() {
-                for summary in these_summaries {
+        for spk in self.spk_data.iter().rev() {
+            if let Ok(these_summaries) = spk.summaries() {
+                for (_, summary) in these_summaries {
                     if summary.id() == id {
                         summaries.push(*summary);
                     }
@@ -249,10 +244,9 @@ impl Almanac {
         ensure!(self.num_loaded_spk() > 0, NoEphemerisLoadedSnafu);
 
         let mut domains = HashMap::new();
-        for maybe_spk in self.spk_data.iter().take(self.num_loaded_spk()).rev() {
-            let spk = maybe_spk.as_ref().unwrap();
-            if let Ok(these_summaries) = spk.data_summaries() {
-                for summary in these_summaries {
+        for spk in self.spk_data.iter().rev() {
+            if let Ok(these_summaries) = spk.summaries() {
+                for (_, summary) in these_summaries {
                     let this_id = summary.id();
                     match domains.get_mut(&this_id) {
                         Some((ref mut cur_start, ref mut cur_end)) => {
@@ -273,14 +267,60 @@ impl Almanac {
 
         Ok(domains)
     }
+
+    /// Returns the window of the epochs covered by the loaded SPK segments of this ID, including the gaps between
+    /// segments and across files, like SPICE's `spkcov`.
+    pub fn spk_coverage(&self, id: NaifId) -> Result<Window, EphemerisError> {
+        let mut coverage = Window::default();
+        for spk in &self.spk_data {
+            if let Ok(window) = spk.coverage(id) {
+                coverage = coverage.union(&window);
+            }
+        }
+
+        if coverage.is_empty() {
+            Err(EphemerisError::SPK {
+                action: "searching for SPK summary",
+                source: DAFError::SummaryIdError { kind: "SPK", id },
+            })
+        } else {
+            Ok(coverage)
+        }
+    }
+
+    /// Returns a map of each loaded SPK ID to its coverage window, cf. [Almanac::spk_coverage].
+    ///
+    /// # Warning
+    /// This function performs a memory allocation.
+    pub fn spk_coverages(&self) -> Result<HashMap<NaifId, Window>, EphemerisError> {
+        ensure!(self.num_loaded_spk() > 0, NoEphemerisLoadedSnafu);
+
+        let mut coverages: HashMap<NaifId, Window> = HashMap::new();
+        for spk in &self.spk_data {
+            if let Ok(windows) = spk.coverages() {
+                for (id, window) in windows {
+                    let coverage = coverages.entry(id).or_default();
+                    *coverage = coverage.union(&window);
+                }
+            }
+        }
+
+        Ok(coverages)
+    }
 }
 
 #[cfg(test)]
 mod ut_almanac_spk {
     use crate::{
         constants::frames::{EARTH_J2000, MOON_J2000},
+        math::{window::Window, Vector3},
+        naif::{
+            spk::fixtures::{hermite_spk, sampled_states},
+            SPK,
+        },
         prelude::{Almanac, Epoch},
     };
+    use hifitime::TimeUnits;
 
     #[test]
     fn summaries_nothing_loaded() {
@@ -329,4 +369,57 @@ mod ut_almanac_spk {
             "empty Almanac should report an error"
         );
     }
+
+    /// Builds an SPK of the Moon with a segment for each interval, in hours past the reference epoch.
+    fn build_spk(t0: Epoch, intervals: &[(i64, i64)]) -> SPK {
+        hermite_spk(
+            "COVERAGE",
+            intervals.iter().map(|(start, end)| {
+                let states = sampled_states(EARTH_J2000, t0, 1.hours(), *start..=*end, |hour| {
+                    (Vector3::new(384_400.0, hour as f64, 0.0), Vector3::zeros())
+                });
+                ("MOON", MOON_J2000.ephemeris_id, states, 2)
+            }),
+        )
+    }
+
+    #[test]
+    fn coverage_with_gaps() {
+        let almanac = Almanac::default();
+        assert!(almanac.spk_coverage(301).is_err());
+        assert!(almanac.spk_coverages().is_err());
+
+        let t0 = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        let almanac = almanac
+            .with_spk(build_spk(t0, &[(0, 10), (20, 30)]))
+            .unwrap()
+            .with_spk(build_spk(t0, &[(5, 12), (40, 50)]))
+            .unwrap();
+
+        let coverage = almanac.spk_coverage(301).unwrap();
+        // The summaries store the epochs in TDB seconds, so compare the windows loosely.
+        let expected = [(0, 12), (20, 30), (40, 50)];
+        assert_eq!(coverage.len(), expected.len());
+        for ((start, end), (exp_start, exp_end)) in coverage.intervals().iter().zip(expected) {
+            assert!((*start - (t0 + exp_start.hours())).abs() < 1.microseconds());
+            assert!((*end - (t0 + exp_end.hours())).abs() < 1.microseconds());
+        }
+        assert_eq!(coverage.gaps().len(), 2);
+        // Durations in TDB differ slightly from those in UTC.
+        assert!((coverage.total_duration() - 32.hours()).abs() < 1.milliseconds());
+
+        // The domain hides the gaps.
+        let (start, end) = almanac.spk_domain(301).unwrap();
+        assert_eq!(
+            Window::from_intervals([(start, end)])
+                .difference(&coverage)
+                .len(),
+            2
+        );
+
+        let coverages = almanac.spk_coverages().unwrap();
+        assert_eq!(coverages.len(), 1);
+        assert_eq!(coverages[&301], coverage);
+        assert!(almanac.spk_coverage(399).is_err());
+    }
 }
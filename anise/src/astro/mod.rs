@@ -26,8 +26,12 @@ pub mod utils;
 pub(crate) mod aberration;
 pub use aberration::Aberration;
 
+pub mod occultation;
+pub use occultation::{EclipseState, Occultation};
+
 pub mod orbit;
 pub mod orbit_geodetic;
+pub mod tle;
 
 pub type PhysicsResult<T> = Result<T, PhysicsError>;
 
@@ -0,0 +1,135 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+
+use hifitime::Epoch;
+
+use crate::prelude::Frame;
+
+#[cfg(feature = "python")]
+use pyo3::exceptions::PyTypeError;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use pyo3::pyclass::CompareOp;
+
+/// Lighting conditions of an observer with respect to the Sun and an eclipsing body.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "python", pyclass)]
+#[cfg_attr(feature = "python", pyo3(module = "anise.astro"))]
+pub enum EclipseState {
+    /// The whole solar disk is visible.
+    Sunlit,
+    /// Part of the solar disk is hidden, including when the eclipsing body is entirely within the solar disk.
+    Penumbra,
+    /// The solar disk is entirely hidden.
+    Umbra,
+}
+
+/// Occultation of a back body by a front body, as seen by an observer at an epoch.
+///
+/// The bodies are modeled as disks with the same apparent area as their ellipsoid shapes, and the visible fraction
+/// is the fraction of the area of the disk of the back body that is not covered by the disk of the front body.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "python", pyclass)]
+#[cfg_attr(feature = "python", pyo3(get_all, set_all))]
+#[cfg_attr(feature = "python", pyo3(module = "anise.astro"))]
+pub struct Occultation {
+    pub epoch: Epoch,
+    /// Fraction of the disk of the back body that is visible, between 0.0 and 1.0
+    pub visible_fraction: f64,
+    pub back_frame: Frame,
+    pub front_frame: Frame,
+}
+
+#[cfg_attr(feature = "python", pymethods)]
+impl Occultation {
+    /// Returns true if the back body is entirely visible.
+    pub fn is_visible(&self) -> bool {
+        self.visible_fraction >= 1.0
+    }
+
+    /// Returns true if the back body is entirely hidden by the front body.
+    pub fn is_obstructed(&self) -> bool {
+        self.visible_fraction <= 0.0
+    }
+
+    /// Returns true if the back body is partially hidden by the front body.
+    pub fn is_partial(&self) -> bool {
+        !self.is_visible() && !self.is_obstructed()
+    }
+
+    /// Returns the lighting conditions of the observer, assuming that the back body is the Sun.
+    pub fn eclipse_state(&self) -> EclipseState {
+        if self.is_visible() {
+            EclipseState::Sunlit
+        } else if self.is_obstructed() {
+            EclipseState::Umbra
+        } else {
+            EclipseState::Penumbra
+        }
+    }
+
+    #[cfg(feature = "python")]
+    fn __str__(&self) -> String {
+        format!("{self}")
+    }
+
+    #[cfg(feature = "python")]
+    fn __repr__(&self) -> String {
+        format!("{self} (@{self:p})")
+    }
+
+    #[cfg(feature = "python")]
+    fn __richcmp__(&self, other: &Self, op: CompareOp) -> Result<bool, PyErr> {
+        match op {
+            CompareOp::Eq => Ok(self == other),
+            CompareOp::Ne => Ok(self != other),
+            _ => Err(PyErr::new::<PyTypeError, _>(format!(
+                "{op:?} not available"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Occultation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {:.3} % of {:e} visible behind {:e}",
+            self.epoch,
+            self.visible_fraction * 100.0,
+            self.back_frame,
+            self.front_frame
+        )
+    }
+}
+
+/// Returns the fraction of the back disk that is not covered by the front disk, given their apparent radii and the
+/// angle between their centers, all in radians.
+///
+/// The disks are small enough to treat their overlap as that of two circles in a plane.
+pub(crate) fn visible_fraction(back_radius: f64, front_radius: f64, separation: f64) -> f64 {
+    if separation >= back_radius + front_radius {
+        1.0
+    } else if separation <= front_radius - back_radius {
+        0.0
+    } else if separation <= back_radius - front_radius {
+        // The front disk is entirely within the back disk.
+        1.0 - (front_radius / back_radius).powi(2)
+    } else {
+        let (d, r1, r2) = (separation, back_radius, front_radius);
+        let overlap = r1.powi(2) * ((d.powi(2) + r1.powi(2) - r2.powi(2)) / (2.0 * d * r1)).acos()
+            + r2.powi(2) * ((d.powi(2) + r2.powi(2) - r1.powi(2)) / (2.0 * d * r2)).acos()
+            - 0.5 * ((-d + r1 + r2) * (d + r1 - r2) * (d - r1 + r2) * (d + r1 + r2)).sqrt();
+        (1.0 - overlap / (core::f64::consts::PI * r1.powi(2))).clamp(0.0, 1.0)
+    }
+}
@@ -0,0 +1,402 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+use core::str::FromStr;
+
+use hifitime::{Epoch, Unit};
+use sgp4::chrono::{Datelike, Timelike};
+use sgp4::{Constants, Geopotential, MinutesSinceEpoch};
+
+use super::orbit::Orbit;
+use super::PhysicsResult;
+use crate::constants::celestial_objects::EARTH;
+use crate::constants::orientations::{J2000, TEME};
+use crate::errors::PhysicsError;
+use crate::math::rotation::{r1, r2, r3};
+use crate::math::{Matrix3, Vector3};
+use crate::prelude::Frame;
+
+/// Number of seconds in a Julian century, used as the time argument of the precession and nutation models.
+const SECONDS_PER_JULIAN_CENTURY: f64 = 36525.0 * 86400.0;
+/// Julian date of the J2000 reference epoch, used to compute the years since J2000 expected by SGP4.
+const J2000_JD: f64 = 2_451_545.0;
+/// Number of days per Julian year
+const DAYS_PER_JULIAN_YEAR: f64 = 365.25;
+
+/// Two-line element set (TLE) and its initialized SGP4/SDP4 propagator.
+///
+/// The states are computed in the True Equator Mean Equinox ([TEME]) frame of the SGP4 theory, centered on the Earth,
+/// and may be rotated into [J2000] using the IAU 1976 precession and IAU 1980 nutation models.
+///
+/// # Example
+/// ```
+/// use anise::astro::tle::TLE;
+/// use core::str::FromStr;
+///
+/// let tle = TLE::from_str(
+///     "ISS (ZARYA)
+/// 1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992
+/// 2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008",
+/// )
+/// .unwrap();
+///
+/// let state = tle.propagate_j2000(tle.epoch).unwrap();
+/// assert!((state.rmag_km() - 6790.0).abs() < 20.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct TLE {
+    /// Name of the object, if the TLE was provided in the three line format.
+    pub name: Option<String>,
+    /// NORAD catalog number of the object
+    pub norad_id: u64,
+    /// Reference epoch of the mean elements
+    pub epoch: Epoch,
+    constants: Constants,
+}
+
+impl TLE {
+    /// Parses the two lines of a TLE, optionally with the name of the object (i.e. the first line of the three line format).
+    pub fn from_lines(name: Option<&str>, line1: &str, line2: &str) -> PhysicsResult<Self> {
+        let elements = sgp4::Elements::from_tle(
+            name.map(|name| name.trim().to_string()),
+            line1.trim().as_bytes(),
+            line2.trim().as_bytes(),
+        )
+        .map_err(|_| PhysicsError::InvalidTLE {
+            reason: "could not parse the two lines",
+        })?;
+
+        let constants = Constants::from_elements(&elements).map_err(|e| match e {
+            sgp4::ElementsError::KozaiElementsError(_) => PhysicsError::InvalidTLE {
+                reason: "mean motion is negative",
+            },
+            sgp4::ElementsError::OutOfRangeEpochEccentricity(_) => PhysicsError::InvalidTLE {
+                reason: "eccentricity is outside [0, 1)",
+            },
+        })?;
+
+        let dt = elements.datetime;
+        let epoch = Epoch::maybe_from_gregorian_utc(
+            dt.year(),
+            dt.month() as u8,
+            dt.day() as u8,
+            dt.hour() as u8,
+            dt.minute() as u8,
+            dt.second() as u8,
+            dt.nanosecond(),
+        )
+        .map_err(|_| PhysicsError::InvalidTLE {
+            reason: "invalid epoch",
+        })?;
+
+        Ok(Self {
+            name: elements.object_name,
+            norad_id: elements.norad_id,
+            epoch,
+            constants,
+        })
+    }
+
+    /// Initializes the propagator from the Kozai mean elements (angles in radians, mean motion in radians per minute) and the provided geopotential,
+    /// as stored in the packets of SPK Type 10 segments.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn from_kozai_elements(
+        epoch: Epoch,
+        geopotential: Geopotential,
+        bstar: f64,
+        inclination_rad: f64,
+        raan_rad: f64,
+        eccentricity: f64,
+        aop_rad: f64,
+        mean_anomaly_rad: f64,
+        mean_motion_rad_min: f64,
+    ) -> PhysicsResult<Self> {
+        let orbit_0 = sgp4::Orbit::from_kozai_elements(
+            &geopotential,
+            inclination_rad,
+            raan_rad,
+            eccentricity,
+            aop_rad,
+            mean_anomaly_rad,
+            mean_motion_rad_min,
+        )
+        .map_err(|_| PhysicsError::InvalidTLE {
+            reason: "mean motion is negative",
+        })?;
+
+        let years_since_j2000 = (epoch.to_jde_utc_days() - J2000_JD) / DAYS_PER_JULIAN_YEAR;
+
+        let constants = Constants::new(
+            geopotential,
+            sgp4::iau_epoch_to_sidereal_time,
+            years_since_j2000,
+            bstar,
+            orbit_0,
+        )
+        .map_err(|_| PhysicsError::InvalidTLE {
+            reason: "eccentricity is outside [0, 1)",
+        })?;
+
+        Ok(Self {
+            name: None,
+            norad_id: 0,
+            epoch,
+            constants,
+        })
+    }
+
+    /// Returns the position (km) and velocity (km/s) in TEME at the provided epoch.
+    pub(crate) fn teme_parts(&self, epoch: Epoch) -> PhysicsResult<(Vector3, Vector3)> {
+        let minutes = (epoch - self.epoch).to_unit(Unit::Minute);
+
+        let prediction = self
+            .constants
+            .propagate(MinutesSinceEpoch(minutes))
+            .map_err(|_| PhysicsError::SGP4Divergence { minutes })?;
+
+        Ok((
+            Vector3::from(prediction.position),
+            Vector3::from(prediction.velocity),
+        ))
+    }
+
+    /// Propagates these elements with SGP4/SDP4 to the provided epoch and returns the state in the Earth centered TEME frame.
+    pub fn propagate_teme(&self, epoch: Epoch) -> PhysicsResult<Orbit> {
+        let (radius_km, velocity_km_s) = self.teme_parts(epoch)?;
+
+        Ok(Orbit {
+            radius_km,
+            velocity_km_s,
+            epoch,
+            frame: Frame::new(EARTH, TEME),
+        })
+    }
+
+    /// Propagates these elements with SGP4/SDP4 to the provided epoch and returns the state in the Earth centered J2000 frame.
+    ///
+    /// # Limitations
+    /// The rotation from TEME uses a truncated IAU 1980 nutation series, and the time derivative of the rotation is neglected.
+    /// Both effects are well below the accuracy of the SGP4 theory.
+    pub fn propagate_j2000(&self, epoch: Epoch) -> PhysicsResult<Orbit> {
+        let (radius_km, velocity_km_s) = self.teme_parts(epoch)?;
+        let rot_mat = teme_to_j2000(epoch);
+
+        Ok(Orbit {
+            radius_km: rot_mat * radius_km,
+            velocity_km_s: rot_mat * velocity_km_s,
+            epoch,
+            frame: Frame::new(EARTH, J2000),
+        })
+    }
+}
+
+impl FromStr for TLE {
+    type Err = PhysicsError;
+
+    /// Parses a TLE in the two or three line format.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = s
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<&str>>();
+
+        match lines.len() {
+            2 => Self::from_lines(None, lines[0], lines[1]),
+            3 => Self::from_lines(Some(lines[0].trim_start_matches("0 ")), lines[1], lines[2]),
+            _ => Err(PhysicsError::InvalidTLE {
+                reason: "expected two or three lines",
+            }),
+        }
+    }
+}
+
+impl fmt::Display for TLE {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "TLE {name} (#{}) @ {}", self.norad_id, self.epoch),
+            None => write!(f, "TLE #{} @ {}", self.norad_id, self.epoch),
+        }
+    }
+}
+
+/// Julian centuries past J2000 TDB of the provided epoch.
+fn centuries_since_j2000(epoch: Epoch) -> f64 {
+    epoch.to_et_seconds() / SECONDS_PER_JULIAN_CENTURY
+}
+
+/// Mean obliquity of the ecliptic of date, IAU 1980 model, in radians.
+pub(crate) fn mean_obliquity_rad(epoch: Epoch) -> f64 {
+    let t = centuries_since_j2000(epoch);
+    let arcsec = 84381.448 + t * (-46.8150 + t * (-0.00059 + t * 0.001813));
+    (arcsec / 3600.0).to_radians()
+}
+
+/// Terms of the IAU 1980 nutation series with an amplitude greater than 0.01 arcsecond.
+/// Each row is the multipliers of D, M, M', F, and Omega, followed by the sine coefficients of the nutation in longitude
+/// and the cosine coefficients of the nutation in obliquity, in units of 0.0001 arcsecond (and per Julian century).
+const NUTATION_TERMS: [[f64; 9]; 13] = [
+    [0.0, 0.0, 0.0, 0.0, 1.0, -171996.0, -174.2, 92025.0, 8.9],
+    [-2.0, 0.0, 0.0, 2.0, 2.0, -13187.0, -1.6, 5736.0, -3.1],
+    [0.0, 0.0, 0.0, 2.0, 2.0, -2274.0, -0.2, 977.0, -0.5],
+    [0.0, 0.0, 0.0, 0.0, 2.0, 2062.0, 0.2, -895.0, 0.5],
+    [0.0, 1.0, 0.0, 0.0, 0.0, 1426.0, -3.4, 54.0, -0.1],
+    [0.0, 0.0, 1.0, 0.0, 0.0, 712.0, 0.1, -7.0, 0.0],
+    [-2.0, 1.0, 0.0, 2.0, 2.0, -517.0, 1.2, 224.0, -0.6],
+    [0.0, 0.0, 0.0, 2.0, 1.0, -386.0, -0.4, 200.0, 0.0],
+    [0.0, 0.0, 1.0, 2.0, 2.0, -301.0, 0.0, 129.0, -0.1],
+    [-2.0, -1.0, 0.0, 2.0, 2.0, 217.0, -0.5, -95.0, 0.3],
+    [-2.0, 0.0, 1.0, 0.0, 0.0, -158.0, 0.0, 0.0, 0.0],
+    [-2.0, 0.0, 0.0, 2.0, 1.0, 129.0, 0.1, -70.0, 0.0],
+    [0.0, 0.0, -1.0, 2.0, 2.0, 123.0, 0.0, -53.0, 0.0],
+];
+
+/// Returns the nutation in longitude and in obliquity, in radians, using the largest terms of the IAU 1980 series.
+///
+/// # Algorithm
+/// The fundamental arguments of the Moon and Sun are those of Meeus, _Astronomical Algorithms_, chapter 22.
+/// The truncated series is accurate to a few hundredths of an arcsecond, i.e. about a meter at the altitude of LEO objects.
+pub(crate) fn nutation_iau1980(epoch: Epoch) -> (f64, f64) {
+    let t = centuries_since_j2000(epoch);
+    // Mean elongation of the Moon from the Sun
+    let d = 297.85036 + t * (445267.111480 + t * (-0.0019142 + t / 189474.0));
+    // Mean anomaly of the Sun
+    let m = 357.52772 + t * (35999.050340 + t * (-0.0001603 - t / 300000.0));
+    // Mean anomaly of the Moon
+    let m_prime = 134.96298 + t * (477198.867398 + t * (0.0086972 + t / 56250.0));
+    // Argument of latitude of the Moon
+    let f = 93.27191 + t * (483202.017538 + t * (-0.0036825 + t / 327270.0));
+    // Longitude of the ascending node of the mean lunar orbit on the ecliptic
+    let omega = 125.04452 + t * (-1934.136261 + t * (0.0020708 + t / 450000.0));
+
+    let (mut dpsi, mut deps) = (0.0, 0.0);
+    for term in NUTATION_TERMS {
+        let arg = (term[0] * d + term[1] * m + term[2] * m_prime + term[3] * f + term[4] * omega)
+            .to_radians();
+        dpsi += (term[5] + term[6] * t) * arg.sin();
+        deps += (term[7] + term[8] * t) * arg.cos();
+    }
+
+    (
+        (dpsi * 1e-4 / 3600.0).to_radians(),
+        (deps * 1e-4 / 3600.0).to_radians(),
+    )
+}
+
+/// Rotation matrix from the true equator and equinox of date into J2000 given the nutation angles (in radians),
+/// i.e. the transpose of the IAU 1980 nutation matrix followed by the transpose of the IAU 1976 precession matrix.
+pub(crate) fn true_of_date_to_j2000(epoch: Epoch, dpsi_rad: f64, deps_rad: f64) -> Matrix3 {
+    let t = centuries_since_j2000(epoch);
+    let zeta = (t * (2306.2181 + t * (0.30188 + t * 0.017998)) / 3600.0).to_radians();
+    let theta = (t * (2004.3109 + t * (-0.42665 - t * 0.041833)) / 3600.0).to_radians();
+    let z = (t * (2306.2181 + t * (1.09468 + t * 0.018203)) / 3600.0).to_radians();
+    // From J2000 to mean of date
+    let precession = r3(-z) * r2(theta) * r3(-zeta);
+
+    let mean_obliquity = mean_obliquity_rad(epoch);
+    // From mean of date to true of date
+    let nutation = r1(-mean_obliquity - deps_rad) * r3(-dpsi_rad) * r1(mean_obliquity);
+
+    (nutation * precession).transpose()
+}
+
+/// Rotation matrix from TEME into J2000 at the provided epoch.
+///
+/// # Algorithm
+/// TEME differs from the true of date frame by the equation of the equinoxes, computed here without its small periodic terms.
+pub fn teme_to_j2000(epoch: Epoch) -> Matrix3 {
+    let (dpsi, deps) = nutation_iau1980(epoch);
+    let equation_of_equinoxes = dpsi * mean_obliquity_rad(epoch).cos();
+
+    true_of_date_to_j2000(epoch, dpsi, deps) * r3(-equation_of_equinoxes)
+}
+
+#[cfg(test)]
+mod tle_ut {
+    use super::*;
+
+    const ISS_TLE: &str = "ISS (ZARYA)
+1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927
+2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537";
+
+    #[test]
+    fn parse_and_propagate() {
+        let tle = TLE::from_str(ISS_TLE).unwrap();
+        assert_eq!(tle.name.as_deref(), Some("ISS (ZARYA)"));
+        assert_eq!(tle.norad_id, 25544);
+        // Day 264.51782528 of 2008
+        let expected_epoch =
+            Epoch::from_gregorian_utc_at_midnight(2008, 9, 20) + 0.51782528 * Unit::Day;
+        assert!((tle.epoch - expected_epoch).abs() < 1 * Unit::Millisecond);
+
+        // Two line format yields the same elements
+        let lines = ISS_TLE.lines().skip(1).collect::<Vec<&str>>().join("\n");
+        let unnamed = TLE::from_str(&lines).unwrap();
+        assert!(unnamed.name.is_none());
+
+        let teme = tle.propagate_teme(tle.epoch).unwrap();
+        assert_eq!(teme.frame.orientation_id, TEME);
+        assert_eq!(teme.frame.ephemeris_id, EARTH);
+        assert_eq!(
+            teme.radius_km,
+            unnamed.propagate_teme(tle.epoch).unwrap().radius_km
+        );
+        // The ISS is on a near circular orbit at about 350 km altitude in 2008.
+        assert!((teme.rmag_km() - 6720.0).abs() < 30.0, "{teme}");
+        assert!((teme.vmag_km_s() - 7.7).abs() < 0.1, "{teme}");
+
+        // Rotating into J2000 preserves the norms, and the frames differ by a fraction of degree.
+        let j2k = tle.propagate_j2000(tle.epoch).unwrap();
+        assert_eq!(j2k.frame.orientation_id, J2000);
+        assert!((j2k.rmag_km() - teme.rmag_km()).abs() < 1e-9);
+        assert!((j2k.vmag_km_s() - teme.vmag_km_s()).abs() < 1e-12);
+        let angle_deg = j2k.radius_km.angle(&teme.radius_km).to_degrees();
+        assert!(angle_deg > 0.05 && angle_deg < 0.2, "{angle_deg}");
+
+        // Propagating a full day later still works
+        assert!(tle.propagate_j2000(tle.epoch + Unit::Day * 1).is_ok());
+    }
+
+    #[test]
+    fn invalid_tle() {
+        assert_eq!(
+            TLE::from_str("1 25544U 98067A").unwrap_err(),
+            PhysicsError::InvalidTLE {
+                reason: "expected two or three lines"
+            }
+        );
+        let mut lines = ISS_TLE.lines().skip(1);
+        let line1 = lines.next().unwrap();
+        assert!(TLE::from_lines(None, line1, line1).is_err());
+    }
+
+    #[test]
+    fn nutation_meeus_example() {
+        // Example 22.a of Meeus: 1987 April 10 at 0h TD, computed there with the full series
+        let epoch = Epoch::from_gregorian_at_midnight(1987, 4, 10, hifitime::TimeScale::TDB);
+        let (dpsi, deps) = nutation_iau1980(epoch);
+        assert!(((dpsi.to_degrees() * 3600.0) - -3.788).abs() < 0.03);
+        assert!(((deps.to_degrees() * 3600.0) - 9.443).abs() < 0.03);
+        let eps0_deg = mean_obliquity_rad(epoch).to_degrees();
+        assert!((eps0_deg - (23.0 + 26.0 / 60.0 + 27.407 / 3600.0)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn teme_rotation_is_orthonormal() {
+        let epoch = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        let rot_mat = teme_to_j2000(epoch);
+        assert!((rot_mat * rot_mat.transpose() - Matrix3::identity()).norm() < 1e-12);
+        assert!((rot_mat.determinant() - 1.0).abs() < 1e-12);
+        // At J2000, the only difference is the nutation.
+        let rot_j2k = teme_to_j2000(Epoch::from_et_seconds(0.0));
+        assert!((rot_j2k - Matrix3::identity()).norm() < 1e-4);
+    }
+}
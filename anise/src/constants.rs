@@ -213,6 +213,10 @@ pub mod orientations {
     /// The DE-403 frame is treated as equivalent to the J2000 frame.
     pub const DE143: NaifId = 21;
 
+    /// True Equator Mean Equinox of date, the frame of the states produced by the SGP4/SDP4 propagator of two-line elements.
+    /// This is not a SPICE built-in frame and it is not part of the orientation tree: use [crate::astro::tle::TLE::propagate_j2000] to rotate TLE states into J2000.
+    pub const TEME: NaifId = 22;
+
     /// Body fixed IAU rotation
     pub const IAU_MERCURY: NaifId = 199;
     pub const IAU_VENUS: NaifId = 299;
@@ -246,6 +250,7 @@ pub mod orientations {
             MARSIAU => Some("Mars IAU"),
             ECLIPJ2000 => Some("ECLIPJ2000"),
             ECLIPB1950 => Some("ECLIPB1950"),
+            TEME => Some("TEME"),
             IAU_MERCURY => Some("IAU_MERCURY"),
             IAU_VENUS => Some("IAU_VENUS"),
             IAU_EARTH => Some("IAU_EARTH"),
@@ -272,6 +277,7 @@ pub mod orientations {
             "Mars IAU" => Ok(MARSIAU),
             "ECLIPJ2000" => Ok(ECLIPJ2000),
             "ECLIPB1950" => Ok(ECLIPB1950),
+            "TEME" => Ok(TEME),
             "IAU_MERCURY" => Ok(IAU_MERCURY),
             "IAU_VENUS" => Ok(IAU_VENUS),
             "IAU_EARTH" => Ok(IAU_EARTH),
@@ -27,16 +27,20 @@ impl Almanac {
     ///
     /// 1. For each loaded SPK, iterated in reverse order (to mimic SPICE behavior)
     /// 2. For each summary record in each SPK, follow the ephemeris branch all the way up until the end of this SPK or until the SSB.
+    ///
+    /// The root is tracked by the SPK segment index as the SPKs are loaded, so this search only happens if the SPK data was modified directly.
     pub fn try_find_ephemeris_root(&self) -> Result<NaifId, EphemerisError> {
         ensure!(self.num_loaded_spk() > 0, NoEphemerisLoadedSnafu);
 
+        if self.spk_index.num_kernels() == self.num_loaded_spk() {
+            return Ok(self.spk_index.root().unwrap_or(i32::MAX));
+        }
+
         // The common center is the absolute minimum of all centers due to the NAIF numbering.
         let mut common_center = i32::MAX;
 
-        for maybe_spk in self.spk_data.iter().take(self.num_loaded_spk()).rev() {
-            let spk = maybe_spk.as_ref().unwrap();
-
-            for summary in spk.data_summaries().context(SPKSnafu {
+        for spk in self.spk_data.iter().rev() {
+            for (_, summary) in spk.summaries().context(SPKSnafu {
                 action: "finding ephemeris root",
             })? {
                 // This summary exists, so we need to follow the branch of centers up the tree.
@@ -17,7 +17,11 @@ use crate::ephemerides::EphemInterpolationSnafu;
 use crate::hifitime::Epoch;
 use crate::math::cartesian::CartesianState;
 use crate::math::Vector3;
-use crate::naif::daf::datatypes::{HermiteSetType13, LagrangeSetType9, Type2ChebyshevSet};
+use crate::naif::daf::datatypes::{
+    ESOCSetType18, ESOCSetType19, EquinoctialSetType17, HermiteSetType12, HermiteSetType13,
+    LagrangeSetType8, LagrangeSetType9, MDASetType1, MDASetType21, PrecessingConicSetType15,
+    TLESetType10, Type2ChebyshevSet, Type3ChebyshevSet,
+};
 use crate::naif::daf::{DAFError, DafDataType, NAIFDataSet, NAIFSummaryRecord};
 use crate::prelude::Frame;
 
@@ -47,13 +51,23 @@ impl Almanac {
         trace!("translate {source} wrt to {new_frame} @ {epoch:E}");
 
         // This should not fail because we've fetched the spk_no from above with the spk_summary_at_epoch call.
-        let spk_data = self.spk_data[spk_no]
-            .as_ref()
+        let spk_data = self
+            .spk_data
+            .get(spk_no)
             .ok_or(EphemerisError::Unreachable)?;
 
         // Now let's simply evaluate the data
 
         let (pos_km, vel_km_s) = match summary.data_type()? {
+            DafDataType::Type1ModifiedDifferenceArray => {
+                let data = spk_data
+                    .nth_data::<MDASetType1>(idx_in_spk)
+                    .context(SPKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
             DafDataType::Type2ChebyshevTriplet => {
                 let data =
                     spk_data
@@ -64,6 +78,25 @@ impl Almanac {
                 data.evaluate(epoch, summary)
                     .context(EphemInterpolationSnafu)?
             }
+            DafDataType::Type3ChebyshevSextuplet => {
+                let data =
+                    spk_data
+                        .nth_data::<Type3ChebyshevSet>(idx_in_spk)
+                        .context(SPKSnafu {
+                            action: "fetching data for interpolation",
+                        })?;
+                data.evaluate(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
+            DafDataType::Type8LagrangeEqualStep => {
+                let data = spk_data
+                    .nth_data::<LagrangeSetType8>(idx_in_spk)
+                    .context(SPKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
             DafDataType::Type9LagrangeUnequalStep => {
                 let data = spk_data
                     .nth_data::<LagrangeSetType9>(idx_in_spk)
@@ -73,6 +106,24 @@ impl Almanac {
                 data.evaluate(epoch, summary)
                     .context(EphemInterpolationSnafu)?
             }
+            DafDataType::Type10SpaceCommandTLE => {
+                let data = spk_data
+                    .nth_data::<TLESetType10>(idx_in_spk)
+                    .context(SPKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
+            DafDataType::Type12HermiteEqualStep => {
+                let data = spk_data
+                    .nth_data::<HermiteSetType12>(idx_in_spk)
+                    .context(SPKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
             DafDataType::Type13HermiteUnequalStep => {
                 let data = spk_data
                     .nth_data::<HermiteSetType13>(idx_in_spk)
@@ -82,6 +133,51 @@ impl Almanac {
                 data.evaluate(epoch, summary)
                     .context(EphemInterpolationSnafu)?
             }
+            DafDataType::Type15PrecessingConics => {
+                let data = spk_data
+                    .nth_data::<PrecessingConicSetType15>(idx_in_spk)
+                    .context(SPKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
+            DafDataType::Type17Equinoctial => {
+                let data = spk_data
+                    .nth_data::<EquinoctialSetType17>(idx_in_spk)
+                    .context(SPKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
+            DafDataType::Type18ESOCHermiteLagrange => {
+                let data = spk_data
+                    .nth_data::<ESOCSetType18>(idx_in_spk)
+                    .context(SPKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
+            DafDataType::Type19ESOCPiecewise => {
+                let data = spk_data
+                    .nth_data::<ESOCSetType19>(idx_in_spk)
+                    .context(SPKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
+            DafDataType::Type21ExtendedModifiedDifferenceArray => {
+                let data = spk_data
+                    .nth_data::<MDASetType21>(idx_in_spk)
+                    .context(SPKSnafu {
+                        action: "fetching data for interpolation",
+                    })?;
+                data.evaluate(epoch, summary)
+                    .context(EphemInterpolationSnafu)?
+            }
             dtype => {
                 return Err(EphemerisError::SPK {
                     action: "translation to parent",
@@ -22,6 +22,7 @@ use crate::math::cartesian::CartesianState;
 use crate::math::units::*;
 use crate::math::Vector3;
 use crate::prelude::Frame;
+use crate::NaifId;
 
 /// **Limitation:** no translation or rotation may have more than 8 nodes.
 pub const MAX_TREE_DEPTH: usize = 8;
@@ -66,56 +67,8 @@ impl Almanac {
 
         match ab_corr {
             None => {
-                let (node_count, path, common_node) =
-                    self.common_ephemeris_path(observer_frame, target_frame, epoch)?;
-
-                // The fwrd variables are the states from the `from frame` to the common node
-                let (mut pos_fwrd, mut vel_fwrd, mut frame_fwrd) =
-                    if observer_frame.ephem_origin_id_match(common_node) {
-                        (Vector3::zeros(), Vector3::zeros(), observer_frame)
-                    } else {
-                        self.translation_parts_to_parent(observer_frame, epoch)?
-                    };
-
-                // The bwrd variables are the states from the `to frame` back to the common node
-                let (mut pos_bwrd, mut vel_bwrd, mut frame_bwrd) =
-                    if target_frame.ephem_origin_id_match(common_node) {
-                        (Vector3::zeros(), Vector3::zeros(), target_frame)
-                    } else {
-                        self.translation_parts_to_parent(target_frame, epoch)?
-                    };
-
-                for cur_node_id in path.iter().take(node_count) {
-                    if !frame_fwrd.ephem_origin_id_match(common_node) {
-                        let (cur_pos_fwrd, cur_vel_fwrd, cur_frame_fwrd) =
-                            self.translation_parts_to_parent(frame_fwrd, epoch)?;
-
-                        pos_fwrd += cur_pos_fwrd;
-                        vel_fwrd += cur_vel_fwrd;
-                        frame_fwrd = cur_frame_fwrd;
-                    }
-
-                    if !frame_bwrd.ephem_origin_id_match(common_node) {
-                        let (cur_pos_bwrd, cur_vel_bwrd, cur_frame_bwrd) =
-                            self.translation_parts_to_parent(frame_bwrd, epoch)?;
-
-                        pos_bwrd += cur_pos_bwrd;
-                        vel_bwrd += cur_vel_bwrd;
-                        frame_bwrd = cur_frame_bwrd;
-                    }
-
-                    // We know this exist, so we can safely unwrap it
-                    if cur_node_id.unwrap() == common_node {
-                        break;
-                    }
-                }
-
-                Ok(CartesianState {
-                    radius_km: pos_bwrd - pos_fwrd,
-                    velocity_km_s: vel_bwrd - vel_fwrd,
-                    epoch,
-                    frame: observer_frame.with_orient(target_frame.orientation_id),
-                })
+                let path = self.common_ephemeris_path(observer_frame, target_frame, epoch)?;
+                self.translate_along_path(target_frame, observer_frame, epoch, path)
             }
             Some(ab_corr) => {
                 // This is a rewrite of NAIF SPICE's `spkapo`
@@ -241,3 +194,65 @@ impl Almanac {
         })
     }
 }
+
+impl Almanac {
+    /// Returns the geometric state of the target frame as seen from the observer frame, along an ephemeris path
+    /// computed by [Almanac::common_ephemeris_path] at an epoch where the same segments are used.
+    ///
+    /// The observer frame must already include its frame information, if any.
+    pub(crate) fn translate_along_path(
+        &self,
+        target_frame: Frame,
+        observer_frame: Frame,
+        epoch: Epoch,
+        (node_count, path, common_node): (usize, [Option<NaifId>; MAX_TREE_DEPTH], NaifId),
+    ) -> Result<CartesianState, EphemerisError> {
+        // The fwrd variables are the states from the `from frame` to the common node
+        let (mut pos_fwrd, mut vel_fwrd, mut frame_fwrd) =
+            if observer_frame.ephem_origin_id_match(common_node) {
+                (Vector3::zeros(), Vector3::zeros(), observer_frame)
+            } else {
+                self.translation_parts_to_parent(observer_frame, epoch)?
+            };
+
+        // The bwrd variables are the states from the `to frame` back to the common node
+        let (mut pos_bwrd, mut vel_bwrd, mut frame_bwrd) =
+            if target_frame.ephem_origin_id_match(common_node) {
+                (Vector3::zeros(), Vector3::zeros(), target_frame)
+            } else {
+                self.translation_parts_to_parent(target_frame, epoch)?
+            };
+
+        for cur_node_id in path.iter().take(node_count) {
+            if !frame_fwrd.ephem_origin_id_match(common_node) {
+                let (cur_pos_fwrd, cur_vel_fwrd, cur_frame_fwrd) =
+                    self.translation_parts_to_parent(frame_fwrd, epoch)?;
+
+                pos_fwrd += cur_pos_fwrd;
+                vel_fwrd += cur_vel_fwrd;
+                frame_fwrd = cur_frame_fwrd;
+            }
+
+            if !frame_bwrd.ephem_origin_id_match(common_node) {
+                let (cur_pos_bwrd, cur_vel_bwrd, cur_frame_bwrd) =
+                    self.translation_parts_to_parent(frame_bwrd, epoch)?;
+
+                pos_bwrd += cur_pos_bwrd;
+                vel_bwrd += cur_vel_bwrd;
+                frame_bwrd = cur_frame_bwrd;
+            }
+
+            // We know this exist, so we can safely unwrap it
+            if cur_node_id.unwrap() == common_node {
+                break;
+            }
+        }
+
+        Ok(CartesianState {
+            radius_km: pos_bwrd - pos_fwrd,
+            velocity_km_s: vel_bwrd - vel_fwrd,
+            epoch,
+            frame: observer_frame.with_orient(target_frame.orientation_id),
+        })
+    }
+}
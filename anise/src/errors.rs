@@ -207,6 +207,10 @@ pub enum PhysicsError {
     VelocityError { action: &'static str },
     #[snafu(display("invalid aberration: {action}"))]
     AberrationError { action: &'static str },
+    #[snafu(display("invalid two-line elements: {reason}"))]
+    InvalidTLE { reason: &'static str },
+    #[snafu(display("SGP4 propagation diverged {minutes} minutes from the elements epoch"))]
+    SGP4Divergence { minutes: f64 },
 }
 
 impl From<IOErrorKind> for InputOutputError {
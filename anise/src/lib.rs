@@ -52,7 +52,7 @@ pub mod prelude {
     pub use crate::frames::*;
     pub use crate::math::units::*;
     pub use crate::naif::daf::NAIFSummaryRecord;
-    pub use crate::naif::{BPC, SPK};
+    pub use crate::naif::{BPC, CK, SPK};
     pub use crate::time::*;
     pub use std::fs::File;
 }
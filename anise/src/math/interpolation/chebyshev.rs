@@ -8,6 +8,8 @@
  * Documentation: https://nyxspace.com/
  */
 
+use core::f64::consts::PI;
+
 use crate::errors::MathError;
 
 use hifitime::Epoch;
@@ -57,3 +59,29 @@ pub fn chebyshev_eval(
     let deriv = (w[0] + normalized_time * dw[0] - dw[1]) / spline_radius_s;
     Ok((val, deriv))
 }
+
+/// Returns the normalized times, in [-1, 1], of the nodes of a Chebyshev interpolation of the provided degree.
+pub fn chebyshev_nodes(degree: usize) -> impl Iterator<Item = f64> {
+    let num_nodes = degree + 1;
+    (0..num_nodes).map(move |k| (PI * (k as f64 + 0.5) / num_nodes as f64).cos())
+}
+
+/// Computes the coefficients of the Chebyshev polynomial interpolating the provided values, which must be sampled at the
+/// nodes returned by [chebyshev_nodes], in the same order.
+pub fn chebyshev_fit(values_at_nodes: &[f64]) -> Vec<f64> {
+    let num_nodes = values_at_nodes.len() as f64;
+    (0..values_at_nodes.len())
+        .map(|j| {
+            let sum: f64 = values_at_nodes
+                .iter()
+                .enumerate()
+                .map(|(k, val)| val * (PI * j as f64 * (k as f64 + 0.5) / num_nodes).cos())
+                .sum();
+            if j == 0 {
+                sum / num_nodes
+            } else {
+                2.0 * sum / num_nodes
+            }
+        })
+        .collect()
+}
@@ -12,13 +12,13 @@ mod chebyshev;
 mod hermite;
 mod lagrange;
 
-pub use chebyshev::chebyshev_eval;
+pub use chebyshev::{chebyshev_eval, chebyshev_fit, chebyshev_nodes};
 pub use hermite::hermite_eval;
 use hifitime::Epoch;
 pub use lagrange::lagrange_eval;
 use snafu::Snafu;
 
-use crate::errors::{DecodingError, MathError};
+use crate::errors::{DecodingError, MathError, PhysicsError};
 
 /// Defines the maximum degree for an interpolation.
 /// Until https://github.com/rust-lang/rust/issues/60551 , we cannot do operations on const generic, so we need some hack around it.
@@ -37,6 +37,11 @@ pub enum InterpolationError {
         #[snafu(backtrace)]
         source: MathError,
     },
+    #[snafu(display("physics error during interpolation: {source}"))]
+    InterpPhysics {
+        #[snafu(backtrace)]
+        source: PhysicsError,
+    },
     #[snafu(display("spline valid from {start} to {end} but requested {req}"))]
     NoInterpolationData {
         req: Epoch,
@@ -45,6 +50,10 @@ pub enum InterpolationError {
     },
     #[snafu(display("no interpolation data to {epoch}, but prior checks succeeded (check integrity of the data?)"))]
     MissingInterpolationData { epoch: Epoch },
+    #[snafu(display("data valid from {start} to {end} SCLK ticks but requested {req} ticks"))]
+    NoTickData { req: f64, start: f64, end: f64 },
+    #[snafu(display("no interpolation data at {ticks} SCLK ticks (in a gap between intervals?)"))]
+    MissingTickData { ticks: f64 },
     #[snafu(display("interpolation data corrupted: {what}"))]
     CorruptedData { what: &'static str },
     #[snafu(display("{op} is unsupported for {kind}"))]
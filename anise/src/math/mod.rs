@@ -22,6 +22,7 @@ mod cartesian_py;
 pub mod interpolation;
 pub mod rotation;
 pub mod units;
+pub mod window;
 
 use nalgebra::allocator::Allocator;
 use nalgebra::{DefaultAllocator, DimName, OVector};
@@ -287,15 +287,18 @@ impl From<DCM> for Quaternion {
             (1.0 + 2.0 * c[(2, 2)] - tr) / 4.0,
         );
         let (w, x, y, z) = match b2.imax() {
-            0 => (
-                b2[0].sqrt(),
-                (c[(1, 2)] - c[(2, 1)]) / 4.0 / b2[0],
-                (c[(2, 0)] - c[(0, 2)]) / 4.0 / b2[0],
-                (c[(0, 1)] - c[(1, 0)]) / 4.0 / b2[0],
-            ),
+            0 => {
+                let w = b2[0].sqrt();
+                (
+                    w,
+                    (c[(1, 2)] - c[(2, 1)]) / 4.0 / w,
+                    (c[(2, 0)] - c[(0, 2)]) / 4.0 / w,
+                    (c[(0, 1)] - c[(1, 0)]) / 4.0 / w,
+                )
+            }
             1 => {
                 let mut x = b2[1].sqrt();
-                let mut w = (c[(1, 2)] - c[(2, 1)]) / 4.0 / b2[1];
+                let mut w = (c[(1, 2)] - c[(2, 1)]) / 4.0 / x;
                 if w < 0.0 {
                     w = -w;
                     x = -x;
@@ -308,7 +311,7 @@ impl From<DCM> for Quaternion {
             }
             2 => {
                 let mut y = b2[2].sqrt();
-                let mut w = (c[(2, 0)] - c[(0, 2)]) / 4.0 / b2[2];
+                let mut w = (c[(2, 0)] - c[(0, 2)]) / 4.0 / y;
                 if w < 0.0 {
                     w = -w;
                     y = -y;
@@ -321,7 +324,7 @@ impl From<DCM> for Quaternion {
             }
             3 => {
                 let mut z = b2[3].sqrt();
-                let mut w = (c[(0, 1)] - c[(1, 0)]) / 4.0 / b2[3];
+                let mut w = (c[(0, 1)] - c[(1, 0)]) / 4.0 / z;
                 if w < 0.0 {
                     z = -z;
                     w = -w;
@@ -516,6 +516,27 @@ mod ut_quaternion {
         }
     }
 
+    #[test]
+    fn test_quat_from_dcm_recip() {
+        // Converting a DCM into a quaternion and back must return the same DCM, including near 180 degrees
+        for angle in generate_angles() {
+            for dcm in [
+                DCM::r1(angle, 0, 1),
+                DCM::r2(angle, 0, 1),
+                DCM::r3(angle, 0, 1),
+                (DCM::r1(angle, 1, 2) * DCM::r3(angle / 2.0, 0, 1)).unwrap(),
+            ] {
+                let q = Quaternion::from(dcm);
+                assert!((q.as_vector().norm() - 1.0).abs() < 1e-12, "{q} not unit");
+                assert!(
+                    (DCM::from(q).rot_mat - dcm.rot_mat).norm() < 1e-12,
+                    "{q} for {:.2} deg",
+                    angle.to_degrees()
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_single_axis_rotations() {
         let q_x = Quaternion::about_x(FRAC_PI_2, 0, 1);
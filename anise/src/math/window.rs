@@ -0,0 +1,321 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+
+use hifitime::{Duration, Epoch};
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+/// A window is a sorted list of disjoint closed time intervals, like the windows of SPICE.
+///
+/// Intervals that overlap or touch are merged, and an interval may be a single epoch. The set operations follow
+/// SPICE, e.g. the difference of two windows keeps the bounds of the intervals that are removed.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "python", pyclass)]
+#[cfg_attr(feature = "python", pyo3(module = "anise"))]
+pub struct Window {
+    intervals: Vec<(Epoch, Epoch)>,
+}
+
+impl Window {
+    /// Builds a window from these intervals, in any order. Intervals that start after they end are ignored.
+    pub fn from_intervals<I: IntoIterator<Item = (Epoch, Epoch)>>(intervals: I) -> Self {
+        let mut intervals: Vec<(Epoch, Epoch)> = intervals
+            .into_iter()
+            .filter(|(start, end)| start <= end)
+            .collect();
+        intervals.sort_by_key(|(start, _)| *start);
+
+        let mut merged: Vec<(Epoch, Epoch)> = Vec::with_capacity(intervals.len());
+        for (start, end) in intervals {
+            match merged.last_mut() {
+                Some((_, last_end)) if start <= *last_end => {
+                    if end > *last_end {
+                        *last_end = end;
+                    }
+                }
+                _ => merged.push((start, end)),
+            }
+        }
+
+        Self { intervals: merged }
+    }
+
+    /// Adds this interval to the window.
+    pub fn insert(&mut self, start: Epoch, end: Epoch) {
+        let intervals = core::mem::take(&mut self.intervals);
+        *self = Self::from_intervals(intervals.into_iter().chain([(start, end)]));
+    }
+
+    /// Returns the intervals of this window, sorted by start epoch.
+    pub fn intervals(&self) -> &[(Epoch, Epoch)] {
+        &self.intervals
+    }
+
+    /// Returns the number of intervals in this window.
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    /// Returns whether this window has no interval.
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Returns the start of the first interval, if any.
+    pub fn start(&self) -> Option<Epoch> {
+        self.intervals.first().map(|(start, _)| *start)
+    }
+
+    /// Returns the end of the last interval, if any.
+    pub fn end(&self) -> Option<Epoch> {
+        self.intervals.last().map(|(_, end)| *end)
+    }
+}
+
+#[cfg_attr(feature = "python", pymethods)]
+impl Window {
+    /// Returns whether this epoch is in one of the intervals of this window.
+    pub fn contains(&self, epoch: Epoch) -> bool {
+        // The epoch is on the left of the comparisons to compare it in its own time scale.
+        let idx = self.intervals.partition_point(|(start, _)| epoch >= *start);
+        idx > 0 && epoch <= self.intervals[idx - 1].1
+    }
+
+    /// Returns the sum of the durations of all of the intervals.
+    pub fn total_duration(&self) -> Duration {
+        self.intervals
+            .iter()
+            .fold(Duration::ZERO, |total, (start, end)| {
+                total + (*end - *start)
+            })
+    }
+
+    /// Returns the epochs that are in either window.
+    pub fn union(&self, other: &Self) -> Self {
+        Self::from_intervals(self.intervals.iter().chain(other.intervals.iter()).copied())
+    }
+
+    /// Returns the epochs that are in both windows.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (start_a, end_a) = self.intervals[i];
+            let (start_b, end_b) = other.intervals[j];
+
+            let start = start_a.max(start_b);
+            let end = end_a.min(end_b);
+            if start <= end {
+                intervals.push((start, end));
+            }
+
+            if end_a < end_b {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { intervals }
+    }
+
+    /// Returns the epochs of this window that are not in the other one, except for the bounds of its intervals.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut intervals = vec![];
+        let mut first = 0;
+        for (start, end) in self.intervals.iter().copied() {
+            while first < other.intervals.len() && other.intervals[first].1 < start {
+                first += 1;
+            }
+
+            let mut remaining_start = start;
+            let mut subtracted = false;
+            for (other_start, other_end) in other.intervals[first..].iter().copied() {
+                if other_start > end {
+                    break;
+                }
+                subtracted = true;
+                if other_start > remaining_start {
+                    intervals.push((remaining_start, other_start));
+                }
+                remaining_start = remaining_start.max(other_end);
+            }
+
+            if !subtracted {
+                intervals.push((start, end));
+            } else if remaining_start < end {
+                intervals.push((remaining_start, end));
+            }
+        }
+
+        Self { intervals }
+    }
+
+    /// Returns the gaps between the intervals of this window.
+    pub fn gaps(&self) -> Self {
+        Self {
+            intervals: self
+                .intervals
+                .windows(2)
+                .map(|pair| (pair[0].1, pair[1].0))
+                .collect(),
+        }
+    }
+
+    /// Returns this window with each interval starting `left` later and ending `right` earlier, removing the
+    /// intervals that become empty, like SPICE's `wncond`.
+    pub fn contract(&self, left: Duration, right: Duration) -> Self {
+        Self {
+            intervals: self
+                .intervals
+                .iter()
+                .map(|(start, end)| (*start + left, *end - right))
+                .filter(|(start, end)| start <= end)
+                .collect(),
+        }
+    }
+
+    /// Returns this window with each interval starting `left` earlier and ending `right` later, merging the
+    /// intervals that then overlap, like SPICE's `wnexpd`.
+    pub fn expand(&self, left: Duration, right: Duration) -> Self {
+        Self::from_intervals(
+            self.intervals
+                .iter()
+                .map(|(start, end)| (*start - left, *end + right)),
+        )
+    }
+
+    /// Initializes a new window from a list of (start, end) intervals
+    #[cfg(feature = "python")]
+    #[new]
+    fn py_new(intervals: Vec<(Epoch, Epoch)>) -> Self {
+        Self::from_intervals(intervals)
+    }
+
+    /// Returns the (start, end) intervals of this window
+    #[cfg(feature = "python")]
+    #[pyo3(name = "intervals")]
+    fn py_intervals(&self) -> Vec<(Epoch, Epoch)> {
+        self.intervals.clone()
+    }
+
+    #[cfg(feature = "python")]
+    fn __len__(&self) -> usize {
+        self.len()
+    }
+
+    #[cfg(feature = "python")]
+    fn __eq__(&self, other: &Self) -> bool {
+        self == other
+    }
+
+    #[cfg(feature = "python")]
+    fn __str__(&self) -> String {
+        format!("{self}")
+    }
+
+    #[cfg(feature = "python")]
+    fn __repr__(&self) -> String {
+        format!("{self} (@{self:p})")
+    }
+}
+
+impl fmt::Display for Window {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "empty window");
+        }
+        for (no, (start, end)) in self.intervals.iter().enumerate() {
+            if no > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "[{start}, {end}]")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod ut_window {
+    use super::Window;
+    use hifitime::{Epoch, TimeUnits};
+
+    /// Builds a window from intervals in hours past the reference epoch.
+    fn hours(intervals: &[(i64, i64)]) -> Window {
+        let t0 = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        Window::from_intervals(
+            intervals
+                .iter()
+                .map(|(start, end)| (t0 + start.hours(), t0 + end.hours())),
+        )
+    }
+
+    #[test]
+    fn normalized() {
+        let window = hours(&[(7, 11), (1, 3), (2, 5), (11, 12), (20, 20), (9, 8)]);
+        assert_eq!(window, hours(&[(1, 5), (7, 12), (20, 20)]));
+        assert_eq!(window.len(), 3);
+        assert_eq!(window.total_duration(), 9.hours());
+        assert_eq!(window.gaps(), hours(&[(5, 7), (12, 20)]));
+
+        let t0 = window.start().unwrap() - 1.hours();
+        assert!(!window.contains(t0));
+        assert!(window.contains(t0 + 1.hours()));
+        assert!(window.contains(t0 + 4.hours()));
+        assert!(!window.contains(t0 + 6.hours()));
+        assert!(window.contains(t0 + 7.hours()));
+        assert!(window.contains(t0 + 20.hours()));
+        assert!(!window.contains(t0 + 21.hours()));
+
+        let mut window = window;
+        window.insert(t0 + 5.hours(), t0 + 8.hours());
+        assert_eq!(window, hours(&[(1, 12), (20, 20)]));
+
+        assert!(Window::default().is_empty());
+        assert_eq!(format!("{}", Window::default()), "empty window");
+    }
+
+    #[test]
+    fn set_operations() {
+        // Example from the SPICE documentation of the window routines.
+        let a = hours(&[(1, 3), (7, 11), (23, 27)]);
+        let b = hours(&[(2, 6), (8, 10), (16, 18)]);
+
+        assert_eq!(a.union(&b), hours(&[(1, 6), (7, 11), (16, 18), (23, 27)]));
+        assert_eq!(a.intersection(&b), hours(&[(2, 3), (8, 10)]));
+        assert_eq!(
+            a.difference(&b),
+            hours(&[(1, 2), (7, 8), (10, 11), (23, 27)])
+        );
+        assert_eq!(b.difference(&a), hours(&[(3, 6), (16, 18)]));
+        assert!(a.difference(&a).is_empty());
+    }
+
+    #[test]
+    fn contract_and_expand() {
+        let window = hours(&[(1, 3), (7, 11), (23, 27)]);
+
+        assert_eq!(
+            window.contract(2.hours(), 1.hours()),
+            hours(&[(9, 10), (25, 26)])
+        );
+        assert_eq!(
+            window.expand(2.hours(), 1.hours()),
+            hours(&[(-1, 4), (5, 12), (21, 28)])
+        );
+        assert_eq!(
+            window.expand(2.hours(), 3.hours()),
+            hours(&[(-1, 14), (21, 30)])
+        );
+    }
+}
@@ -0,0 +1,119 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+//! C-matrix kernels (CK) store the attitude of spacecraft and instruments.
+//!
+//! CK data is time tagged in encoded spacecraft clock (SCLK) ticks instead of ephemeris time. The data sets are therefore
+//! evaluated at ticks, cf. `evaluate_ticks`, which the [crate::almanac::Almanac] computes from the requested epoch with the
+//! SCLK of the spacecraft.
+
+use crate::{
+    naif::daf::{NAIFRecord, NAIFSummaryRecord},
+    naif::sclk::SCLK,
+    orientations::OrientationError,
+    NaifId,
+};
+use hifitime::Epoch;
+use zerocopy::{AsBytes, FromBytes, FromZeroes};
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+use super::daf::DafDataType;
+
+#[cfg_attr(feature = "python", pyclass)]
+#[cfg_attr(feature = "python", pyo3(module = "anise.internals"))]
+#[derive(Clone, Copy, Debug, Default, AsBytes, FromZeroes, FromBytes)]
+#[repr(C)]
+pub struct CKSummaryRecord {
+    pub start_sclk_ticks: f64,
+    pub end_sclk_ticks: f64,
+    /// ID of the instrument (or spacecraft structure) whose attitude is stored
+    pub instrument_id: i32,
+    /// ID of the frame from which the attitude is defined (e.g. J2000)
+    pub reference_frame_id: i32,
+    pub data_type_i: i32,
+    /// Set to 1 if the segment includes angular velocity data, 0 otherwise
+    pub angular_velocity_flag: i32,
+    pub start_idx: i32,
+    pub end_idx: i32,
+}
+
+impl CKSummaryRecord {
+    /// Returns whether the segment of this summary includes angular velocity data
+    pub fn has_angular_velocity(&self) -> bool {
+        self.angular_velocity_flag == 1
+    }
+
+    /// Returns the ID of the spacecraft clock which time tags the data of this summary
+    pub fn clock_id(&self) -> NaifId {
+        SCLK::clock_id_of(self.instrument_id)
+    }
+
+    /// Returns whether these encoded SCLK ticks are within the bounds of this summary
+    pub fn covers_ticks(&self, ticks: f64) -> bool {
+        !self.is_empty() && ticks >= self.start_sclk_ticks && ticks <= self.end_sclk_ticks
+    }
+}
+
+impl NAIFRecord for CKSummaryRecord {}
+
+impl NAIFSummaryRecord for CKSummaryRecord {
+    const NAME: &'static str = "CKSummaryRecord";
+
+    type Error = OrientationError;
+
+    fn data_type(&self) -> Result<DafDataType, Self::Error> {
+        DafDataType::try_from(self.data_type_i).map_err(|source| OrientationError::CK {
+            action: "converting data type from i32",
+            source,
+        })
+    }
+
+    fn start_index(&self) -> usize {
+        self.start_idx as usize
+    }
+
+    fn end_index(&self) -> usize {
+        self.end_idx as usize
+    }
+
+    /// Returns the start ticks of this summary stored as ET seconds, **not** its start epoch: prefer `start_sclk_ticks`.
+    fn start_epoch(&self) -> Epoch {
+        Epoch::from_et_seconds(self.start_sclk_ticks)
+    }
+
+    /// Returns the end ticks of this summary stored as ET seconds, **not** its end epoch: prefer `end_sclk_ticks`.
+    fn end_epoch(&self) -> Epoch {
+        Epoch::from_et_seconds(self.end_sclk_ticks)
+    }
+
+    fn id(&self) -> i32 {
+        self.instrument_id
+    }
+
+    fn start_epoch_et_s(&self) -> f64 {
+        self.start_sclk_ticks
+    }
+
+    fn end_epoch_et_s(&self) -> f64 {
+        self.end_sclk_ticks
+    }
+
+    fn update_indexes(&mut self, start: usize, end: usize) {
+        self.start_idx = start as i32;
+        self.end_idx = end as i32;
+    }
+
+    fn update_epochs(&mut self, start_epoch: Epoch, end_epoch: Epoch) {
+        self.start_sclk_ticks = start_epoch.to_et_seconds();
+        self.end_sclk_ticks = end_epoch.to_et_seconds();
+    }
+}
@@ -8,14 +8,16 @@
  * Documentation: https://nyxspace.com/
  */
 
+use super::endianness::native_endian_bytes;
 use super::file_record::FileRecordError;
 use super::{
-    DAFError, DecodingNameSnafu, DecodingSummarySnafu, FileRecordSnafu, IOSnafu, NAIFDataSet,
-    NAIFRecord, NAIFSummaryRecord,
+    DAFError, DecodingCommentsSnafu, DecodingNameSnafu, DecodingSummarySnafu, FileRecordSnafu,
+    IOSnafu, NAIFDataSet, NAIFRecord, NAIFSummaryRecord, COMMENT_CHARS_PER_RCRD,
 };
 pub use super::{FileRecord, NameRecord, SummaryRecord};
 use crate::errors::DecodingError;
 use crate::file2heap;
+use crate::math::window::Window;
 use crate::naif::daf::DecodingDataSnafu;
 use crate::{errors::IntegrityError, DBL_SIZE};
 use bytes::{Bytes, BytesMut};
@@ -26,6 +28,7 @@ use core::ops::Deref;
 use hifitime::Epoch;
 use log::{debug, error, trace};
 use snafu::ResultExt;
+use std::collections::HashMap;
 
 use zerocopy::AsBytes;
 use zerocopy::{FromBytes, Ref};
@@ -128,51 +131,192 @@ impl<R: NAIFSummaryRecord, W: MutKind> GenericDAF<R, W> {
             .context(DecodingSummarySnafu { kind: R::NAME })
     }
 
-    /// Parses the data summaries on the fly.
-    pub fn data_summaries(&self) -> Result<&[R], DAFError> {
-        if self.file_record()?.is_empty() {
-            return Err(DAFError::FileRecord {
-                kind: R::NAME,
-                source: FileRecordError::EmptyRecord,
-            });
+    /// Returns the one-indexed numbers of the summary records, following their linked list from the first one.
+    pub fn summary_record_numbers(&self) -> Result<Vec<usize>, DAFError> {
+        Ok(self.summary_record_nos()?.collect())
+    }
+
+    /// Returns an iterator over the one-indexed numbers of the summary records, after checking that their linked list
+    /// ends, without allocating.
+    fn summary_record_nos(&self) -> Result<impl Iterator<Item = usize> + '_, DAFError> {
+        let first = self.file_record()?.fwrd_idx();
+
+        // A linked list longer than the number of records of the file necessarily loops.
+        let max_records = self.bytes.len() / RCRD_LEN;
+        let mut num_records = 0;
+        let mut rcrd_no = first;
+        while rcrd_no > 0 {
+            num_records += 1;
+            if num_records > max_records {
+                return Err(DAFError::DecodingSummary {
+                    kind: R::NAME,
+                    source: DecodingError::Integrity {
+                        source: IntegrityError::InvalidValue {
+                            dataset: R::NAME,
+                            variable: "next summary record",
+                            value: rcrd_no as f64,
+                            reason: "loops back to a previous summary record",
+                        },
+                    },
+                });
+            }
+            rcrd_no = self.summary_record(rcrd_no)?.0.next_record();
         }
 
-        // Move onto the next record, DAF indexes start at 1 ... =(
-        let rcrd_idx = (self.file_record()?.fwrd_idx() - 1) * RCRD_LEN;
-        let rcrd_bytes = match self
+        Ok(core::iter::successors(Some(first), move |rcrd_no| {
+            self.summary_record(*rcrd_no)
+                .ok()
+                .map(|(control, _)| control.next_record())
+        })
+        .take(num_records))
+    }
+
+    /// Returns the one-indexed number of the summary record at this position of the linked list, or None if the list
+    /// is shorter, only following the links before that position.
+    fn nth_summary_record_no(&self, position: usize) -> Result<Option<usize>, DAFError> {
+        let mut rcrd_no = self.file_record()?.fwrd_idx();
+        for _ in 0..position {
+            if rcrd_no == 0 {
+                break;
+            }
+            rcrd_no = self.summary_record(rcrd_no)?.0.next_record();
+        }
+        Ok(Some(rcrd_no).filter(|rcrd_no| *rcrd_no > 0))
+    }
+
+    /// Returns the control area and all of the summary slots, including empty ones, of the summary record of that
+    /// one-indexed number.
+    fn summary_record(&self, rcrd_no: usize) -> Result<(SummaryRecord, &[R]), DAFError> {
+        let rcrd_idx = (rcrd_no.max(1) - 1) * RCRD_LEN;
+        let rcrd_bytes = self
             .bytes
             .get(rcrd_idx..rcrd_idx + RCRD_LEN)
             .ok_or_else(|| DecodingError::InaccessibleBytes {
                 start: rcrd_idx,
                 end: rcrd_idx + RCRD_LEN,
                 size: self.bytes.len(),
-            }) {
-            Ok(it) => it,
-            Err(source) => {
-                return Err(DAFError::DecodingSummary {
-                    kind: R::NAME,
-                    source,
+            })
+            .context(DecodingSummarySnafu { kind: R::NAME })?;
+
+        let control = SummaryRecord::read_from(&rcrd_bytes[..SummaryRecord::SIZE]).unwrap();
+        let summaries_end = SummaryRecord::SIZE + Self::summaries_per_record() * R::SIZE;
+        let summaries = Ref::new_slice(&rcrd_bytes[SummaryRecord::SIZE..summaries_end])
+            .ok_or(DecodingError::Casting)
+            .context(DecodingSummarySnafu { kind: R::NAME })?
+            .into_slice();
+
+        Ok((control, summaries))
+    }
+
+    /// Returns the number of summaries that fit in a summary record, i.e. the number of slots of each record.
+    pub(crate) const fn summaries_per_record() -> usize {
+        (RCRD_LEN - SummaryRecord::SIZE) / R::SIZE
+    }
+
+    /// Parses the data summaries of the first summary record on the fly.
+    ///
+    /// DAFs with more segments than fit in a summary record store the others in the next summary records: use
+    /// [GenericDAF::summaries] to iterate through all of them.
+    pub fn data_summaries(&self) -> Result<&[R], DAFError> {
+        if self.file_record()?.is_empty() {
+            return Err(DAFError::FileRecord {
+                kind: R::NAME,
+                source: FileRecordError::EmptyRecord,
+            });
+        }
+
+        Ok(self.summary_record(self.file_record()?.fwrd_idx())?.1)
+    }
+
+    /// Returns an iterator over the data summaries of all of the summary records, with their index.
+    ///
+    /// Each summary record contributes all of its slots, including the empty ones, such that the index of a summary
+    /// is also the index of its data, e.g. in [GenericDAF::nth_data].
+    pub fn summaries(&self) -> Result<impl Iterator<Item = (usize, &R)> + '_, DAFError> {
+        if self.file_record()?.is_empty() {
+            return Err(DAFError::FileRecord {
+                kind: R::NAME,
+                source: FileRecordError::EmptyRecord,
+            });
+        }
+
+        let per_record = Self::summaries_per_record();
+        Ok(self
+            .summary_record_nos()?
+            .filter_map(|rcrd_no| self.summary_record(rcrd_no).ok())
+            .enumerate()
+            .flat_map(move |(position, (_, summaries))| {
+                summaries
+                    .iter()
+                    .enumerate()
+                    .map(move |(slot, summary)| (position * per_record + slot, summary))
+            }))
+    }
+
+    /// Returns the n-th data summary, cf. [GenericDAF::summaries], only reading the summary records up to its own.
+    pub fn nth_summary(&self, idx: usize) -> Result<&R, DAFError> {
+        if self.file_record()?.is_empty() {
+            return Err(DAFError::FileRecord {
+                kind: R::NAME,
+                source: FileRecordError::EmptyRecord,
+            });
+        }
+
+        let per_record = Self::summaries_per_record();
+        let rcrd_no = self
+            .nth_summary_record_no(idx / per_record)?
+            .ok_or(DAFError::InvalidIndex { idx, kind: R::NAME })?;
+        Ok(&self.summary_record(rcrd_no)?.1[idx % per_record])
+    }
+
+    /// Returns the name of the n-th data summary, stored in the name record following its summary record.
+    pub fn nth_name(&self, idx: usize) -> Result<&str, DAFError> {
+        let per_record = Self::summaries_per_record();
+        let rcrd_no = self
+            .nth_summary_record_no(idx / per_record)?
+            .ok_or(DAFError::InvalidIndex { idx, kind: R::NAME })?;
+        self.name_in_record(rcrd_no, idx % per_record)
+    }
+
+    /// Returns the name of this slot of the name record following the summary record of that one-indexed number.
+    fn name_in_record(&self, rcrd_no: usize, slot: usize) -> Result<&str, DAFError> {
+        let rcrd_idx = rcrd_no * RCRD_LEN;
+        let name_record = NameRecord::ref_from(
+            self.bytes
+                .get(rcrd_idx..rcrd_idx + RCRD_LEN)
+                .ok_or_else(|| DecodingError::InaccessibleBytes {
+                    start: rcrd_idx,
+                    end: rcrd_idx + RCRD_LEN,
+                    size: self.bytes.len(),
                 })
-            }
-        };
+                .context(DecodingNameSnafu { kind: R::NAME })?,
+        )
+        .unwrap();
 
-        // The summaries are defined in the same record as the DAF summary
-        Ok(match Ref::new_slice(&rcrd_bytes[SummaryRecord::SIZE..]) {
-            Some(data) => data.into_slice(),
-            None => &{
-                R::default();
-                [] as [R; 0]
-            },
+        Ok(name_record.nth_name(slot, self.file_record()?.summary_size()))
+    }
+
+    /// Returns the index of the first summary of that name, searching all of the name records.
+    fn index_from_name(&self, name: &str) -> Result<usize, DAFError> {
+        let per_record = Self::summaries_per_record();
+        for (position, rcrd_no) in self.summary_record_nos()?.enumerate() {
+            for slot in 0..per_record {
+                if self.name_in_record(rcrd_no, slot)? == name.trim() {
+                    return Ok(position * per_record + slot);
+                }
+            }
+        }
+        Err(DAFError::NameError {
+            kind: R::NAME,
+            name: name.to_string(),
         })
     }
 
     /// Returns the summary given the name of the summary record
     pub fn summary_from_name(&self, name: &str) -> Result<(&R, usize), DAFError> {
-        let idx = self
-            .name_record()?
-            .index_from_name::<R>(name, self.file_record()?.summary_size())?;
+        let idx = self.index_from_name(name)?;
 
-        Ok((&self.data_summaries()?[idx], idx))
+        Ok((self.nth_summary(idx)?, idx))
     }
 
     /// Returns the summary given the name of the summary record if that summary has data defined at the requested epoch
@@ -197,7 +341,7 @@ impl<R: NAIFSummaryRecord, W: MutKind> GenericDAF<R, W> {
 
     /// Returns the summary given the id of the summary record
     pub fn summary_from_id(&self, id: i32) -> Result<(&R, usize), DAFError> {
-        for (idx, summary) in self.data_summaries()?.iter().enumerate() {
+        for (idx, summary) in self.summaries()? {
             if summary.id() == id {
                 return Ok((summary, idx));
             }
@@ -210,11 +354,12 @@ impl<R: NAIFSummaryRecord, W: MutKind> GenericDAF<R, W> {
     pub fn summary_from_id_at_epoch(&self, id: i32, epoch: Epoch) -> Result<(&R, usize), DAFError> {
         // NOTE: We iterate through the whole summary because a specific NAIF ID may be repeated in the summary for different valid epochs
         // so we can't just call `summary_from_id`.
-        for (idx, summary) in self.data_summaries()?.iter().enumerate() {
+        // As in SPICE, the segments are searched from last to first, such that the last segment covering an epoch takes precedence.
+        let mut found = None;
+        for (idx, summary) in self.summaries()? {
             if summary.id() == id {
                 if epoch >= summary.start_epoch() && epoch <= summary.end_epoch() {
-                    trace!("Found {id} in position {idx}: {summary:?}");
-                    return Ok((summary, idx));
+                    found = Some((summary, idx));
                 } else {
                     debug!(
                         "Summary {id} not valid at {epoch:?} (only from {:?} to {:?}, offset of {} - {})",
@@ -226,6 +371,10 @@ impl<R: NAIFSummaryRecord, W: MutKind> GenericDAF<R, W> {
                 }
             }
         }
+        if let Some((summary, idx)) = found {
+            trace!("Found {id} in position {idx}: {summary:?}");
+            return Ok((summary, idx));
+        }
         Err(DAFError::InterpolationDataErrorFromId {
             kind: R::NAME,
             id,
@@ -233,33 +382,60 @@ impl<R: NAIFSummaryRecord, W: MutKind> GenericDAF<R, W> {
         })
     }
 
-    /// Provided a name that is in the summary, return its full data, if name is available.
-    pub fn data_from_name<'a, S: NAIFDataSet<'a>>(&'a self, name: &str) -> Result<S, DAFError> {
-        // O(N) search through the summaries
-        let name_rcrd = self.name_record()?;
-        for idx in 0..name_rcrd.num_entries(self.file_record()?.summary_size()) {
-            let this_name = name_rcrd.nth_name(idx, self.file_record()?.summary_size());
-
-            if name.trim() == this_name.trim() {
-                // Found it!
-                return self.nth_data(idx);
+    /// Returns the window of the epochs covered by the segments of this ID, with the gaps between them, like SPICE's
+    /// `spkcov` or `pckcov` for a single file.
+    ///
+    /// # Note
+    /// The intervals of a CK are in SCLK ticks, cf. [crate::naif::ck].
+    pub fn coverage(&self, id: i32) -> Result<Window, DAFError> {
+        let window = Window::from_intervals(
+            self.summaries()?
+                .filter(|(_, summary)| !summary.is_empty() && summary.id() == id)
+                .map(|(_, summary)| (summary.start_epoch(), summary.end_epoch())),
+        );
+
+        if window.is_empty() {
+            Err(DAFError::SummaryIdError { kind: R::NAME, id })
+        } else {
+            Ok(window)
+        }
+    }
+
+    /// Returns the coverage window of each ID of this DAF, cf. [GenericDAF::coverage].
+    pub fn coverages(&self) -> Result<HashMap<i32, Window>, DAFError> {
+        let mut intervals: HashMap<i32, Vec<(Epoch, Epoch)>> = HashMap::new();
+        for (_, summary) in self.summaries()? {
+            if !summary.is_empty() {
+                intervals
+                    .entry(summary.id())
+                    .or_default()
+                    .push((summary.start_epoch(), summary.end_epoch()));
             }
         }
-        Err(DAFError::NameError {
-            kind: R::NAME,
-            name: name.to_string(),
-        })
+
+        Ok(intervals
+            .into_iter()
+            .map(|(id, intervals)| (id, Window::from_intervals(intervals)))
+            .collect())
+    }
+
+    /// Provided a name that is in the summary, return its full data, if name is available.
+    pub fn data_from_name<'a, S: NAIFDataSet<'a>>(&'a self, name: &str) -> Result<S, DAFError> {
+        // O(N) search through the names
+        self.nth_data(self.index_from_name(name)?)
     }
 
     /// Provided a name that is in the summary, return its full data, if name is available.
     pub fn nth_data<'a, S: NAIFDataSet<'a>>(&'a self, idx: usize) -> Result<S, DAFError> {
-        let this_summary = self
-            .data_summaries()?
-            .get(idx)
-            .ok_or(DAFError::InvalidIndex {
-                idx,
-                kind: S::DATASET_NAME,
-            })?;
+        let data = self.nth_data_f64(idx)?;
+
+        // Convert it
+        S::from_f64_slice(data).context(DecodingDataSnafu { kind: R::NAME, idx })
+    }
+
+    /// Returns the raw data of the n-th segment of this DAF file, without decoding it into a data set.
+    pub fn nth_data_f64(&self, idx: usize) -> Result<&[f64], DAFError> {
+        let this_summary = self.nth_summary(idx)?;
         // Grab the data in native endianness (TODO: How to support both big and little endian?)
         trace!("{idx} -> {this_summary:?}");
         if self.file_record()?.is_empty() {
@@ -293,15 +469,14 @@ impl<R: NAIFSummaryRecord, W: MutKind> GenericDAF<R, W> {
         .unwrap()
         .into_slice();
 
-        // Convert it
-        S::from_f64_slice(data).context(DecodingDataSnafu { kind: R::NAME, idx })
+        Ok(data)
     }
 
     pub fn comments(&self) -> Result<Option<String>, DAFError> {
         // TODO: This can be cleaned up to avoid allocating a string. In my initial tests there were a bunch of additional spaces, so I canceled those changes.
         let mut rslt = String::new();
         // FWRD has the initial record of the summary. So we assume that all records between the second record and that one are comments
-        for rid in 1..self.file_record()?.fwrd_idx() {
+        for rid in 1..self.file_record()?.fwrd_idx().saturating_sub(1) {
             match core::str::from_utf8(
                 match self
                     .bytes
@@ -333,6 +508,12 @@ impl<R: NAIFSummaryRecord, W: MutKind> GenericDAF<R, W> {
             }
         }
 
+        // The comment area ends with an end-of-transmission character.
+        if let Some(eot) = rslt.find('\u{4}') {
+            rslt.truncate(eot);
+            rslt.truncate(rslt.trim_end().len());
+        }
+
         if rslt.is_empty() {
             Ok(None)
         } else {
@@ -340,32 +521,58 @@ impl<R: NAIFSummaryRecord, W: MutKind> GenericDAF<R, W> {
         }
     }
 
+    /// Returns the lines of the comment area, exactly as they are stored, i.e. terminated by a NULL character.
+    pub(crate) fn comment_lines(&self) -> Result<Vec<String>, DAFError> {
+        let mut comment_area = Vec::new();
+        for rid in 1..self.file_record()?.fwrd_idx().saturating_sub(1) {
+            let start = rid * RCRD_LEN;
+            let end = start + COMMENT_CHARS_PER_RCRD;
+            comment_area.extend_from_slice(
+                self.bytes
+                    .get(start..end)
+                    .ok_or_else(|| DecodingError::InaccessibleBytes {
+                        start,
+                        end,
+                        size: self.bytes.len(),
+                    })
+                    .context(DecodingCommentsSnafu { kind: R::NAME })?,
+            );
+        }
+
+        // The comment area ends with an end-of-transmission character.
+        if let Some(eot) = comment_area.iter().position(|c| *c == 0x4) {
+            comment_area.truncate(eot);
+        } else {
+            let len =
+                comment_area.len() - comment_area.iter().rev().take_while(|c| **c == 0x0).count();
+            comment_area.truncate(len);
+        }
+
+        if comment_area.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut lines: Vec<String> = comment_area
+            .split(|c| *c == 0x0)
+            .map(|line| String::from_utf8_lossy(line).into_owned())
+            .collect();
+        // Each line is terminated by a NULL, so the last one is empty.
+        if lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+
+        Ok(lines)
+    }
+
     /// Writes the contents of this DAF file to a new location.
     pub fn persist<P: AsRef<Path>>(&self, path: P) -> IoResult<()> {
         let mut fs = File::create(path)?;
 
         let mut file_rcrd = Vec::from(self.file_record().unwrap().as_bytes());
-        file_rcrd.extend(vec![
-            0x0;
-            (self.file_record().unwrap().fwrd_idx() - 1) * RCRD_LEN
-                - file_rcrd.len()
-        ]);
+        file_rcrd.extend(vec![0x0; RCRD_LEN - file_rcrd.len()]);
         fs.write_all(&file_rcrd)?;
-
-        let mut daf_summary = Vec::from(self.daf_summary().unwrap().as_bytes());
-        // Extend with the data summaries
-        for data_summary in self.data_summaries().unwrap() {
-            daf_summary.extend(data_summary.as_bytes());
-        }
-        // And pad with NULL
-        daf_summary.extend(vec![0x0; RCRD_LEN - daf_summary.len()]);
-        fs.write_all(&daf_summary)?;
-
-        let mut name_rcrd = Vec::from(self.name_record().unwrap().as_bytes());
-        name_rcrd.extend(vec![0x0; RCRD_LEN - name_rcrd.len()]);
-        fs.write_all(&name_rcrd)?;
-
-        fs.write_all(&self.bytes[self.file_record().unwrap().fwrd_idx() * (2 * RCRD_LEN)..])
+        // The comment area, the summary and name records, and the data are written as is.
+        fs.write_all(&self.bytes[RCRD_LEN..])
     }
 }
 
@@ -378,10 +585,16 @@ impl<R: NAIFSummaryRecord, W: MutKind> Hash for GenericDAF<R, W> {
 
 impl<R: NAIFSummaryRecord> DAF<R> {
     /// Parse the provided bytes as a SPICE Double Array File
+    ///
+    /// DAF files written in the opposite endianness of this platform are byte-swapped on load.
     pub fn parse<B: Deref<Target = [u8]>>(bytes: B) -> Result<Self, DAFError> {
+        let bytes = match native_endian_bytes(&bytes) {
+            Some(native) => Bytes::from(native),
+            None => Bytes::copy_from_slice(&bytes),
+        };
         let crc32_checksum = crc32fast::hash(&bytes);
         let me = Self {
-            bytes: Bytes::copy_from_slice(&bytes),
+            bytes,
             crc32_checksum,
             _daf_type: PhantomData,
         };
@@ -436,9 +649,16 @@ mod daf_ut {
     use crate::{
         errors::{InputOutputError, IntegrityError},
         file2heap,
-        naif::daf::{datatypes::HermiteSetType13, DAFError},
+        naif::daf::{
+            datatypes::HermiteSetType13, DAFError, NAIFRecord, NAIFSummaryRecord, SummaryRecord,
+            RCRD_LEN,
+        },
+        naif::spk::summary::SPKSummaryRecord,
         prelude::SPK,
     };
+    use zerocopy::AsBytes;
+
+    use super::MutDAF;
 
     use std::fs::File;
 
@@ -503,4 +723,33 @@ mod daf_ut {
             panic!("nth data test failed");
         }
     }
+
+    #[test]
+    fn looping_summary_records() {
+        let mut daf = MutDAF::<SPKSummaryRecord>::empty("DAF/SPK", 2, 6, "LOOP", &[]).unwrap();
+        let fwrd_idx = daf.file_record().unwrap().fwrd_idx();
+        assert_eq!(daf.summary_record_numbers().unwrap(), vec![fwrd_idx]);
+        assert_eq!(daf.summaries().unwrap().count(), 25);
+        assert!(daf.nth_summary(24).unwrap().is_empty());
+        assert_eq!(
+            daf.nth_summary(25),
+            Err(DAFError::InvalidIndex {
+                idx: 25,
+                kind: "SPKSummaryRecord"
+            })
+        );
+
+        // A summary record linked to itself is reported instead of being followed forever.
+        let rcrd_idx = (fwrd_idx - 1) * RCRD_LEN;
+        daf.bytes[rcrd_idx..rcrd_idx + SummaryRecord::SIZE]
+            .copy_from_slice(SummaryRecord::new(fwrd_idx, 0, 0).as_bytes());
+        assert!(matches!(
+            daf.summary_record_numbers(),
+            Err(DAFError::DecodingSummary { .. })
+        ));
+        assert!(daf.summaries().is_err());
+        // Only the links up to the requested record are followed.
+        assert!(daf.nth_summary(0).unwrap().is_empty());
+        assert!(daf.nth_summary(60).unwrap().is_empty());
+    }
 }
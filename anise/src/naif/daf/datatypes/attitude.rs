@@ -0,0 +1,977 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+use hifitime::Epoch;
+use nalgebra::Rotation3;
+use snafu::{ensure, ResultExt};
+
+use crate::{
+    errors::{DecodingError, IntegrityError, TooFewDoublesSnafu},
+    math::{
+        interpolation::{
+            hermite_eval, lagrange_eval, InterpDecodingSnafu, InterpolationError, MAX_SAMPLES,
+        },
+        rotation::{Quaternion, DCM},
+        Matrix3, Vector3, Vector4,
+    },
+    naif::daf::{NAIFDataRecord, NAIFDataSet, NAIFSummaryRecord},
+    NaifId,
+};
+
+/// The attitude computed from CK data: the quaternion from the reference frame to the instrument frame, and the angular
+/// velocity of the instrument with respect to the reference frame, expressed in the reference frame, in radians per second.
+///
+/// The reference frame is only known from the CK summary, so the `from` of the quaternion is left to zero by the data sets.
+pub type CKAttitude = (Quaternion, Option<Vector3>);
+
+/// Returns the number of entries in the directory of a CK list of `count` items (one every hundred items).
+const fn directory_len(count: usize) -> usize {
+    count.saturating_sub(1) / 100
+}
+
+/// Returns the quaternion of ANISE corresponding to the SPICE quaternion of a C-matrix.
+///
+/// SPICE quaternions are such that the C-matrix is their rotation matrix, whereas the DCM of an ANISE quaternion is the
+/// transpose of its rotation matrix: the ANISE quaternion of a C-matrix is the conjugate of the SPICE quaternion.
+fn from_spice_quaternion(q: &[f64; 4]) -> Vector4 {
+    Vector4::new(q[0], -q[1], -q[2], -q[3])
+}
+
+/// Returns the C-matrix of the provided ANISE quaternion components.
+fn c_matrix(q: &Vector4) -> Matrix3 {
+    DCM::from(Quaternion::new(q[0], q[1], q[2], q[3], 0, 0)).rot_mat
+}
+
+/// Builds the attitude from a C-matrix, its angular velocity, and the ID of the instrument.
+fn attitude_from_c_matrix(c_matrix: Matrix3, av: Option<Vector3>, id: NaifId) -> CKAttitude {
+    let q = Quaternion::from(DCM {
+        rot_mat: c_matrix,
+        rot_mat_dt: None,
+        from: 0,
+        to: id,
+    });
+    (q, av)
+}
+
+/// Pointing intervals of constant angular velocity of CK Type 2.
+///
+/// In each interval, the C-matrix at the start of the interval is rotated about the angular velocity vector.
+#[derive(PartialEq)]
+pub struct CKConstantRateSetType2<'a> {
+    pub num_records: usize,
+    pub record_data: &'a [f64],
+    /// Start of each interval, in encoded SCLK ticks
+    pub start_ticks: &'a [f64],
+    /// Stop of each interval, in encoded SCLK ticks
+    pub stop_ticks: &'a [f64],
+}
+
+impl<'a> fmt::Display for CKConstantRateSetType2<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "CK Type 2 with {} intervals from {} to {} ticks",
+            self.num_records,
+            self.start_ticks.first().unwrap_or(&0.0),
+            self.stop_ticks.last().unwrap_or(&0.0),
+        )
+    }
+}
+
+impl<'a> CKConstantRateSetType2<'a> {
+    /// Evaluates the attitude at these encoded SCLK ticks.
+    pub fn evaluate_ticks<S: NAIFSummaryRecord>(
+        &self,
+        ticks: f64,
+        summary: &S,
+    ) -> Result<CKAttitude, InterpolationError> {
+        let first = *self.start_ticks.first().unwrap_or(&0.0);
+        let last = *self.stop_ticks.last().unwrap_or(&0.0);
+        if ticks < first || ticks > last {
+            return Err(InterpolationError::NoTickData {
+                req: ticks,
+                start: first,
+                end: last,
+            });
+        }
+
+        // Find the last interval starting at or before these ticks, and ensure that it covers them.
+        let idx = self.start_ticks.partition_point(|start| *start <= ticks);
+        if idx == 0 || ticks > self.stop_ticks[idx - 1] {
+            return Err(InterpolationError::MissingTickData { ticks });
+        }
+
+        let record = self.nth_record(idx - 1).context(InterpDecodingSnafu)?;
+        let dt_s = (ticks - self.start_ticks[idx - 1]) * record.rate_s_per_tick;
+
+        // The instrument axes rotate about the angular velocity, which is expressed in the reference frame.
+        let rotation = Rotation3::from_scaled_axis(record.av_rad_s * dt_s);
+        let c_matrix =
+            c_matrix(&from_spice_quaternion(&record.quaternion)) * rotation.matrix().transpose();
+
+        Ok(attitude_from_c_matrix(
+            c_matrix,
+            Some(record.av_rad_s),
+            summary.id(),
+        ))
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for CKConstantRateSetType2<'a> {
+    type StateKind = CKAttitude;
+    type RecordKind = CKConstantRateRecord;
+    const DATASET_NAME: &'static str = "CK Type 2";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        // This type has no metadata: the segment is made of N records of 8 doubles, N start and N stop times, and the directory.
+        let mut num_records = slice.len() / 10;
+        while num_records > 0 && 10 * num_records + directory_len(num_records) > slice.len() {
+            num_records -= 1;
+        }
+        ensure!(
+            num_records > 0 && 10 * num_records + directory_len(num_records) == slice.len(),
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: 10 * (num_records + 1),
+                got: slice.len()
+            }
+        );
+
+        Ok(Self {
+            num_records,
+            record_data: &slice[..8 * num_records],
+            start_ticks: &slice[8 * num_records..9 * num_records],
+            stop_ticks: &slice[9 * num_records..10 * num_records],
+        })
+    }
+
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        Ok(Self::RecordKind::from_slice_f64(
+            self.record_data
+                .get(n * 8..(n + 1) * 8)
+                .ok_or(DecodingError::InaccessibleBytes {
+                    start: n * 8,
+                    end: (n + 1) * 8,
+                    size: self.record_data.len(),
+                })?,
+        ))
+    }
+
+    /// CK data is time tagged in SCLK ticks and cannot be evaluated at an epoch: use `evaluate_ticks` instead.
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        _epoch: Epoch,
+        _summary: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        Err(InterpolationError::UnsupportedOperation {
+            kind: Self::DATASET_NAME,
+            op: "evaluation at an epoch instead of SCLK ticks",
+        })
+    }
+
+    fn check_integrity(&self) -> Result<(), IntegrityError> {
+        for val in self
+            .record_data
+            .iter()
+            .chain(self.start_ticks)
+            .chain(self.stop_ticks)
+        {
+            if !val.is_finite() {
+                return Err(IntegrityError::SubNormal {
+                    dataset: Self::DATASET_NAME,
+                    variable: "one of the records or interval bounds",
+                });
+            }
+        }
+
+        for (start, stop) in self.start_ticks.iter().zip(self.stop_ticks) {
+            if stop < start {
+                return Err(IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "interval stop ticks",
+                    value: *stop,
+                    reason: "must be after the start of the interval",
+                });
+            }
+        }
+
+        if self.start_ticks.windows(2).any(|pair| pair[0] > pair[1]) {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "interval start ticks",
+                value: 0.0,
+                reason: "must be increasing",
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Discrete pointing instances of CK Type 3, linearly interpolated within each interpolation interval.
+#[derive(PartialEq)]
+pub struct CKDiscreteSetType3<'a> {
+    pub num_records: usize,
+    /// Either 4 (quaternion only) or 7 (quaternion and angular velocity)
+    pub record_size: usize,
+    pub record_data: &'a [f64],
+    /// Time tag of each pointing instance, in encoded SCLK ticks
+    pub tick_data: &'a [f64],
+    /// Start of each interpolation interval, in encoded SCLK ticks
+    pub interval_starts: &'a [f64],
+}
+
+impl<'a> fmt::Display for CKDiscreteSetType3<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "CK Type 3 with {} pointing instances in {} intervals from {} to {} ticks",
+            self.num_records,
+            self.interval_starts.len(),
+            self.tick_data.first().unwrap_or(&0.0),
+            self.tick_data.last().unwrap_or(&0.0),
+        )
+    }
+}
+
+impl<'a> CKDiscreteSetType3<'a> {
+    /// Evaluates the attitude at these encoded SCLK ticks.
+    pub fn evaluate_ticks<S: NAIFSummaryRecord>(
+        &self,
+        ticks: f64,
+        summary: &S,
+    ) -> Result<CKAttitude, InterpolationError> {
+        let first = *self.tick_data.first().unwrap_or(&0.0);
+        let last = *self.tick_data.last().unwrap_or(&0.0);
+        if ticks < first || ticks > last {
+            return Err(InterpolationError::NoTickData {
+                req: ticks,
+                start: first,
+                end: last,
+            });
+        }
+
+        match self.tick_data.binary_search_by(|t| t.total_cmp(&ticks)) {
+            Ok(idx) => {
+                let record = self.nth_record(idx).context(InterpDecodingSnafu)?;
+                let q = from_spice_quaternion(&record.quaternion);
+                Ok(attitude_from_c_matrix(
+                    c_matrix(&q),
+                    record.av_rad_s,
+                    summary.id(),
+                ))
+            }
+            Err(idx) => {
+                // Both surrounding pointing instances must be in the same interpolation interval.
+                let (t1, t2) = (self.tick_data[idx - 1], self.tick_data[idx]);
+                let interval_of = |t: f64| self.interval_starts.partition_point(|s| *s <= t);
+                if interval_of(t1) != interval_of(t2) {
+                    return Err(InterpolationError::MissingTickData { ticks });
+                }
+
+                let first = self.nth_record(idx - 1).context(InterpDecodingSnafu)?;
+                let second = self.nth_record(idx).context(InterpDecodingSnafu)?;
+
+                // As in SPICE, rotate the first C-matrix by a fraction of the rotation to the second one.
+                let frac = (ticks - t1) / (t2 - t1);
+                let c1 = c_matrix(&from_spice_quaternion(&first.quaternion));
+                let c2 = c_matrix(&from_spice_quaternion(&second.quaternion));
+                let delta = Rotation3::from_matrix_unchecked(c1.transpose() * c2).scaled_axis();
+                let c_matrix = c1 * Rotation3::from_scaled_axis(delta * frac).matrix();
+
+                let av = match (first.av_rad_s, second.av_rad_s) {
+                    (Some(av1), Some(av2)) => Some(av1 + frac * (av2 - av1)),
+                    _ => None,
+                };
+
+                Ok(attitude_from_c_matrix(c_matrix, av, summary.id()))
+            }
+        }
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for CKDiscreteSetType3<'a> {
+    type StateKind = CKAttitude;
+    type RecordKind = CKAttitudePacket;
+    const DATASET_NAME: &'static str = "CK Type 3";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        ensure!(
+            slice.len() >= 2,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: 2_usize,
+                got: slice.len()
+            }
+        );
+
+        let num_records = slice[slice.len() - 1] as usize;
+        let num_intervals = slice[slice.len() - 2] as usize;
+        let overhead = num_records
+            + directory_len(num_records)
+            + num_intervals
+            + directory_len(num_intervals)
+            + 2;
+        ensure!(
+            num_records > 0 && slice.len() >= overhead + 4 * num_records,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: overhead + 4 * num_records.max(1),
+                got: slice.len()
+            }
+        );
+
+        // The size of the records depends on whether the segment includes angular velocity, which is only known from the summary.
+        let record_size = (slice.len() - overhead) / num_records;
+        if (record_size != 4 && record_size != 7)
+            || record_size * num_records + overhead != slice.len()
+        {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "record size",
+                    value: record_size as f64,
+                    reason: "must be 4 or 7",
+                },
+            });
+        }
+
+        let tick_start = record_size * num_records;
+        let interval_start = tick_start + num_records + directory_len(num_records);
+
+        Ok(Self {
+            num_records,
+            record_size,
+            record_data: &slice[..tick_start],
+            tick_data: &slice[tick_start..tick_start + num_records],
+            interval_starts: &slice[interval_start..interval_start + num_intervals],
+        })
+    }
+
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        let (start, end) = (n * self.record_size, (n + 1) * self.record_size);
+        Ok(Self::RecordKind::from_slice_f64(
+            self.record_data
+                .get(start..end)
+                .ok_or(DecodingError::InaccessibleBytes {
+                    start,
+                    end,
+                    size: self.record_data.len(),
+                })?,
+        ))
+    }
+
+    /// CK data is time tagged in SCLK ticks and cannot be evaluated at an epoch: use `evaluate_ticks` instead.
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        _epoch: Epoch,
+        _summary: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        Err(InterpolationError::UnsupportedOperation {
+            kind: Self::DATASET_NAME,
+            op: "evaluation at an epoch instead of SCLK ticks",
+        })
+    }
+
+    fn check_integrity(&self) -> Result<(), IntegrityError> {
+        for val in self
+            .record_data
+            .iter()
+            .chain(self.tick_data)
+            .chain(self.interval_starts)
+        {
+            if !val.is_finite() {
+                return Err(IntegrityError::SubNormal {
+                    dataset: Self::DATASET_NAME,
+                    variable: "one of the records, time tags, or interval starts",
+                });
+            }
+        }
+
+        if self.tick_data.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "time tags",
+                value: 0.0,
+                reason: "must be strictly increasing",
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Packets of quaternions (and possibly their derivatives and angular velocities) of CK Type 5, interpolated with
+/// Hermite (subtypes 0 and 2) or Lagrange (subtypes 1 and 3) polynomials within each interpolation interval.
+#[derive(PartialEq)]
+pub struct CKInterpolatedSetType5<'a> {
+    pub subtype: usize,
+    /// Number of packets used in each interpolation
+    pub window_size: usize,
+    /// Seconds per tick, used to convert the ticks into seconds when interpolating
+    pub rate_s_per_tick: f64,
+    pub num_records: usize,
+    pub packet_size: usize,
+    pub packet_data: &'a [f64],
+    /// Time tag of each packet, in encoded SCLK ticks
+    pub tick_data: &'a [f64],
+    /// Start of each interpolation interval, in encoded SCLK ticks
+    pub interval_starts: &'a [f64],
+}
+
+impl<'a> CKInterpolatedSetType5<'a> {
+    /// Returns the size of the packets of the provided subtype
+    const fn packet_size_of(subtype: usize) -> Option<usize> {
+        match subtype {
+            0 => Some(8),
+            1 => Some(4),
+            2 => Some(14),
+            3 => Some(7),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> fmt::Display for CKInterpolatedSetType5<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "CK Type 5 subtype {} (window of {}) with {} packets in {} intervals from {} to {} ticks",
+            self.subtype,
+            self.window_size,
+            self.num_records,
+            self.interval_starts.len(),
+            self.tick_data.first().unwrap_or(&0.0),
+            self.tick_data.last().unwrap_or(&0.0),
+        )
+    }
+}
+
+impl<'a> CKInterpolatedSetType5<'a> {
+    /// Evaluates the attitude at these encoded SCLK ticks.
+    pub fn evaluate_ticks<S: NAIFSummaryRecord>(
+        &self,
+        ticks: f64,
+        summary: &S,
+    ) -> Result<CKAttitude, InterpolationError> {
+        let first = *self.tick_data.first().unwrap_or(&0.0);
+        let last = *self.tick_data.last().unwrap_or(&0.0);
+        if ticks < first || ticks > last {
+            return Err(InterpolationError::NoTickData {
+                req: ticks,
+                start: first,
+                end: last,
+            });
+        }
+
+        // Find the packets of the interpolation interval which contains these ticks.
+        let interval_idx = self.interval_starts.partition_point(|s| *s <= ticks);
+        if interval_idx == 0 {
+            return Err(InterpolationError::MissingTickData { ticks });
+        }
+        let first_pkt = self
+            .tick_data
+            .partition_point(|t| *t < self.interval_starts[interval_idx - 1]);
+        let end_pkt = match self.interval_starts.get(interval_idx) {
+            Some(next_start) => self.tick_data.partition_point(|t| t < next_start),
+            None => self.num_records,
+        };
+        if end_pkt <= first_pkt || ticks > self.tick_data[end_pkt - 1] {
+            // These ticks are in the gap between the last packet of this interval and the start of the next one.
+            return Err(InterpolationError::MissingTickData { ticks });
+        }
+
+        // As in SPICE, the window is reduced if the interval does not have enough packets.
+        let window = self.window_size.clamp(1, end_pkt - first_pkt);
+        if window > MAX_SAMPLES {
+            return Err(InterpolationError::CorruptedData {
+                what: "window size is larger than supported",
+            });
+        }
+        let upper = self.tick_data.partition_point(|t| *t <= ticks);
+        let first_idx = upper
+            .saturating_sub(window / 2)
+            .clamp(first_pkt, end_pkt - window);
+
+        // Statically allocated arrays of the maximum number of samples
+        let mut xs = [0.0; MAX_SAMPLES];
+        let mut qs = [[0.0; MAX_SAMPLES]; 4];
+        let mut dqs = [[0.0; MAX_SAMPLES]; 4];
+        let mut avs = [[0.0; MAX_SAMPLES]; 3];
+        let mut davs = [[0.0; MAX_SAMPLES]; 3];
+
+        let t0 = self.tick_data[first_idx];
+        let mut prev_q: Option<Vector4> = None;
+        for (cno, idx) in (first_idx..first_idx + window).enumerate() {
+            let packet = self.nth_record(idx).context(InterpDecodingSnafu)?;
+            // Interpolate in seconds from the first packet to preserve precision.
+            xs[cno] = (self.tick_data[idx] - t0) * self.rate_s_per_tick;
+
+            let mut q = from_spice_quaternion(&packet.quaternion);
+            let mut dq = from_spice_quaternion(&packet.quaternion_dt.unwrap_or_default());
+            // Quaternions q and -q represent the same attitude, so pick the sign closest to the previous quaternion.
+            if prev_q.is_some_and(|prev| prev.dot(&q) < 0.0) {
+                q = -q;
+                dq = -dq;
+            }
+            prev_q = Some(q);
+
+            for i in 0..4 {
+                qs[i][cno] = q[i];
+                dqs[i][cno] = dq[i];
+            }
+            let av = packet.av_rad_s.unwrap_or_default();
+            let dav = packet.av_dt_rad_s2.unwrap_or_default();
+            for i in 0..3 {
+                avs[i][cno] = av[i];
+                davs[i][cno] = dav[i];
+            }
+        }
+
+        let x_eval = (ticks - t0) * self.rate_s_per_tick;
+        let hermite = self.subtype == 0 || self.subtype == 2;
+
+        let mut q = Vector4::zeros();
+        let mut dq = Vector4::zeros();
+        for i in 0..4 {
+            let (val, deriv) = if hermite {
+                hermite_eval(&xs[..window], &qs[i][..window], &dqs[i][..window], x_eval)?
+            } else {
+                lagrange_eval(&xs[..window], &qs[i][..window], x_eval)?
+            };
+            q[i] = val;
+            dq[i] = deriv;
+        }
+
+        let norm = q.norm();
+        if norm < f64::EPSILON {
+            return Err(InterpolationError::CorruptedData {
+                what: "interpolated quaternion has zero norm",
+            });
+        }
+        let c_matrix = c_matrix(&(q / norm));
+
+        let av = if self.subtype == 2 || self.subtype == 3 {
+            let mut av = Vector3::zeros();
+            for i in 0..3 {
+                av[i] = if hermite {
+                    hermite_eval(&xs[..window], &avs[i][..window], &davs[i][..window], x_eval)?.0
+                } else {
+                    lagrange_eval(&xs[..window], &avs[i][..window], x_eval)?.0
+                };
+            }
+            av
+        } else {
+            // Derive the angular velocity from the quaternion rate: dQ/dt = 1/2 [B(Q)] w, where w is in the instrument frame.
+            let unit_q = Quaternion::new(q[0], q[1], q[2], q[3], 0, 0);
+            let body_av = 2.0 * unit_q.b_matrix().transpose() * (dq / norm);
+            c_matrix.transpose() * body_av
+        };
+
+        Ok(attitude_from_c_matrix(c_matrix, Some(av), summary.id()))
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for CKInterpolatedSetType5<'a> {
+    type StateKind = CKAttitude;
+    type RecordKind = CKAttitudePacket;
+    const DATASET_NAME: &'static str = "CK Type 5";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        ensure!(
+            slice.len() >= 5,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: 5_usize,
+                got: slice.len()
+            }
+        );
+
+        // The metadata is stored at the very end of the segment.
+        let num_records = slice[slice.len() - 1] as usize;
+        let num_intervals = slice[slice.len() - 2] as usize;
+        let window_size = slice[slice.len() - 3] as usize;
+        let subtype = slice[slice.len() - 4] as usize;
+        let rate_s_per_tick = slice[slice.len() - 5];
+
+        let packet_size = Self::packet_size_of(subtype).ok_or(DecodingError::Integrity {
+            source: IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "subtype",
+                value: subtype as f64,
+                reason: "must be 0, 1, 2, or 3",
+            },
+        })?;
+
+        let tick_start = packet_size * num_records;
+        let interval_start = tick_start + num_records + directory_len(num_records);
+        let expected = interval_start + num_intervals + directory_len(num_intervals) + 5;
+        ensure!(
+            slice.len() >= expected,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: expected,
+                got: slice.len()
+            }
+        );
+
+        Ok(Self {
+            subtype,
+            window_size,
+            rate_s_per_tick,
+            num_records,
+            packet_size,
+            packet_data: &slice[..tick_start],
+            tick_data: &slice[tick_start..tick_start + num_records],
+            interval_starts: &slice[interval_start..interval_start + num_intervals],
+        })
+    }
+
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        let (start, end) = (n * self.packet_size, (n + 1) * self.packet_size);
+        Ok(Self::RecordKind::from_slice_f64(
+            self.packet_data
+                .get(start..end)
+                .ok_or(DecodingError::InaccessibleBytes {
+                    start,
+                    end,
+                    size: self.packet_data.len(),
+                })?,
+        ))
+    }
+
+    /// CK data is time tagged in SCLK ticks and cannot be evaluated at an epoch: use `evaluate_ticks` instead.
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        _epoch: Epoch,
+        _summary: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        Err(InterpolationError::UnsupportedOperation {
+            kind: Self::DATASET_NAME,
+            op: "evaluation at an epoch instead of SCLK ticks",
+        })
+    }
+
+    fn check_integrity(&self) -> Result<(), IntegrityError> {
+        if !self.rate_s_per_tick.is_finite() || self.rate_s_per_tick <= 0.0 {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "seconds per tick",
+                value: self.rate_s_per_tick,
+                reason: "must be strictly positive",
+            });
+        }
+
+        if self.window_size == 0 {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "window size",
+                value: 0.0,
+                reason: "must be strictly positive",
+            });
+        }
+
+        for val in self
+            .packet_data
+            .iter()
+            .chain(self.tick_data)
+            .chain(self.interval_starts)
+        {
+            if !val.is_finite() {
+                return Err(IntegrityError::SubNormal {
+                    dataset: Self::DATASET_NAME,
+                    variable: "one of the packets, time tags, or interval starts",
+                });
+            }
+        }
+
+        if self.tick_data.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "time tags",
+                value: 0.0,
+                reason: "must be strictly increasing",
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// A record of CK Type 2: the SPICE quaternion at the start of the interval, the constant angular velocity, and the clock rate.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct CKConstantRateRecord {
+    pub quaternion: [f64; 4],
+    pub av_rad_s: Vector3,
+    pub rate_s_per_tick: f64,
+}
+
+impl fmt::Display for CKConstantRateRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl<'a> NAIFDataRecord<'a> for CKConstantRateRecord {
+    fn from_slice_f64(slice: &'a [f64]) -> Self {
+        let val = |idx: usize| slice.get(idx).copied().unwrap_or(0.0);
+        Self {
+            quaternion: [val(0), val(1), val(2), val(3)],
+            av_rad_s: Vector3::new(val(4), val(5), val(6)),
+            rate_s_per_tick: val(7),
+        }
+    }
+}
+
+/// A pointing record of CK Type 3 or a packet of CK Type 5, whose content depends on its size.
+///
+/// The quaternions are stored with the SPICE convention, i.e. the C-matrix is their rotation matrix.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct CKAttitudePacket {
+    pub quaternion: [f64; 4],
+    /// Time derivative of the quaternion, per second (CK Type 5 subtypes 0 and 2)
+    pub quaternion_dt: Option<[f64; 4]>,
+    /// Angular velocity, in the reference frame (CK Type 3 with angular velocity, CK Type 5 subtypes 2 and 3)
+    pub av_rad_s: Option<Vector3>,
+    /// Angular acceleration, in the reference frame (CK Type 5 subtype 2)
+    pub av_dt_rad_s2: Option<Vector3>,
+}
+
+impl fmt::Display for CKAttitudePacket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl<'a> NAIFDataRecord<'a> for CKAttitudePacket {
+    fn from_slice_f64(slice: &'a [f64]) -> Self {
+        let val = |idx: usize| slice.get(idx).copied().unwrap_or(0.0);
+        let vec3 = |idx: usize| Vector3::new(val(idx), val(idx + 1), val(idx + 2));
+        let quaternion = [val(0), val(1), val(2), val(3)];
+        let quaternion_dt = Some([val(4), val(5), val(6), val(7)]);
+
+        match slice.len() {
+            7 => Self {
+                quaternion,
+                av_rad_s: Some(vec3(4)),
+                ..Default::default()
+            },
+            8 => Self {
+                quaternion,
+                quaternion_dt,
+                ..Default::default()
+            },
+            14 => Self {
+                quaternion,
+                quaternion_dt,
+                av_rad_s: Some(vec3(8)),
+                av_dt_rad_s2: Some(vec3(11)),
+            },
+            _ => Self {
+                quaternion,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod attitude_ut {
+    use super::*;
+    use crate::math::rotation::r3;
+    use crate::naif::ck::CKSummaryRecord;
+
+    const RATE_RAD_S: f64 = 0.01;
+
+    fn summary() -> CKSummaryRecord {
+        CKSummaryRecord {
+            instrument_id: -82000,
+            reference_frame_id: 1,
+            ..Default::default()
+        }
+    }
+
+    /// SPICE quaternion of the C-matrix of a rotation about Z at constant rate, i.e. `r3(RATE_RAD_S * t_s)`
+    fn spice_q(t_s: f64) -> [f64; 4] {
+        let (s, c) = (RATE_RAD_S * t_s / 2.0).sin_cos();
+        [c, 0.0, 0.0, -s]
+    }
+
+    /// Time derivative of the SPICE quaternion of `spice_q`
+    fn spice_dq(t_s: f64) -> [f64; 4] {
+        let (s, c) = (RATE_RAD_S * t_s / 2.0).sin_cos();
+        [-s * RATE_RAD_S / 2.0, 0.0, 0.0, -c * RATE_RAD_S / 2.0]
+    }
+
+    fn check(attitude: CKAttitude, t_s: f64, expect_av: bool) {
+        let (q, av) = attitude;
+        assert_eq!(q.to, -82000);
+        let err = (DCM::from(q).rot_mat - r3(RATE_RAD_S * t_s)).norm();
+        assert!(err < 1e-9, "C-matrix error of {err:e} at {t_s} s");
+        if expect_av {
+            let err = (av.unwrap() - Vector3::new(0.0, 0.0, RATE_RAD_S)).norm();
+            assert!(err < 1e-9, "angular velocity error of {err:e} at {t_s} s");
+        } else {
+            assert!(av.is_none());
+        }
+    }
+
+    #[test]
+    fn ck_type2_constant_rate() {
+        // Two intervals of 100 s with a gap between them, with 1000 ticks per second.
+        let mut data = vec![];
+        for start_s in [0.0, 200.0] {
+            data.extend(spice_q(start_s));
+            data.extend([0.0, 0.0, RATE_RAD_S, 1e-3]);
+        }
+        data.extend([0.0, 200_000.0, 100_000.0, 300_000.0]);
+
+        let set = CKConstantRateSetType2::from_f64_slice(&data).unwrap();
+        assert_eq!(set.num_records, 2);
+        set.check_integrity().unwrap();
+
+        for t_s in [0.0, 12.5, 100.0, 200.0, 275.0] {
+            let attitude = set.evaluate_ticks(t_s * 1e3, &summary()).unwrap();
+            check(attitude, t_s, true);
+        }
+
+        assert_eq!(
+            set.evaluate_ticks(150e3, &summary()),
+            Err(InterpolationError::MissingTickData { ticks: 150e3 })
+        );
+        assert!(set.evaluate_ticks(-1.0, &summary()).is_err());
+    }
+
+    #[test]
+    fn ck_type3_discrete() {
+        for with_av in [false, true] {
+            // Pointing every 10 s (10 ticks per second), in two intervals.
+            let times_s = [0.0, 10.0, 20.0, 50.0, 60.0];
+            let mut data = vec![];
+            for t_s in times_s {
+                data.extend(spice_q(t_s));
+                if with_av {
+                    data.extend([0.0, 0.0, RATE_RAD_S]);
+                }
+            }
+            data.extend(times_s.iter().map(|t_s| t_s * 10.0));
+            data.extend([0.0, 500.0, 2.0, times_s.len() as f64]);
+
+            let set = CKDiscreteSetType3::from_f64_slice(&data).unwrap();
+            assert_eq!(set.record_size, if with_av { 7 } else { 4 });
+            set.check_integrity().unwrap();
+
+            for t_s in [0.0, 4.0, 10.0, 17.5, 50.0, 55.0, 60.0] {
+                let attitude = set.evaluate_ticks(t_s * 10.0, &summary()).unwrap();
+                check(attitude, t_s, with_av);
+            }
+
+            // No interpolation between intervals.
+            assert!(set.evaluate_ticks(300.0, &summary()).is_err());
+        }
+    }
+
+    #[test]
+    fn ck_evaluated_at_raw_ticks() {
+        // Encoded SCLK ticks may be far larger than any number of seconds an epoch can represent.
+        let t0 = 5e14;
+        let times_s = [0.0, 10.0, 20.0];
+        let mut data = vec![];
+        for t_s in times_s {
+            data.extend(spice_q(t_s));
+        }
+        data.extend(times_s.iter().map(|t_s| t0 + t_s));
+        data.extend([t0, 1.0, times_s.len() as f64]);
+
+        let set = CKDiscreteSetType3::from_f64_slice(&data).unwrap();
+        set.check_integrity().unwrap();
+
+        // The bounds are checked on the ticks themselves, without any round trip through an epoch.
+        for t_s in [0.0, 5.0, 20.0] {
+            check(
+                set.evaluate_ticks(t0 + t_s, &summary()).unwrap(),
+                t_s,
+                false,
+            );
+        }
+        assert_eq!(
+            set.evaluate_ticks(t0 + 21.0, &summary()),
+            Err(InterpolationError::NoTickData {
+                req: t0 + 21.0,
+                start: t0,
+                end: t0 + 20.0
+            })
+        );
+
+        assert!(matches!(
+            set.evaluate(Epoch::from_et_seconds(t0), &summary()),
+            Err(InterpolationError::UnsupportedOperation { .. })
+        ));
+    }
+
+    #[test]
+    fn ck_type5_subtypes() {
+        // Packets every 4 s (1000 ticks per second), over a single interval.
+        let times_s: Vec<f64> = (0..10).map(|i| 4.0 * f64::from(i)).collect();
+        for subtype in 0..4 {
+            let mut data = vec![];
+            for t_s in &times_s {
+                data.extend(spice_q(*t_s));
+                if subtype == 0 || subtype == 2 {
+                    data.extend(spice_dq(*t_s));
+                }
+                if subtype == 2 || subtype == 3 {
+                    data.extend([0.0, 0.0, RATE_RAD_S]);
+                }
+                if subtype == 2 {
+                    data.extend([0.0, 0.0, 0.0]);
+                }
+            }
+            data.extend(times_s.iter().map(|t_s| t_s * 1e3));
+            let window = if subtype % 2 == 0 { 4.0 } else { 8.0 };
+            data.extend([
+                0.0,
+                1e-3,
+                f64::from(subtype),
+                window,
+                1.0,
+                times_s.len() as f64,
+            ]);
+
+            let set = CKInterpolatedSetType5::from_f64_slice(&data).unwrap();
+            assert_eq!(set.subtype, subtype as usize);
+            set.check_integrity().unwrap();
+
+            for t_s in [0.0, 1.0, 6.3, 18.0, 33.9, 36.0] {
+                let (q, av) = set.evaluate_ticks(t_s * 1e3, &summary()).unwrap();
+                let err = (DCM::from(q).rot_mat - r3(RATE_RAD_S * t_s)).norm();
+                assert!(err < 1e-9, "subtype {subtype}: C-matrix error {err:e}");
+                let err = (av.unwrap() - Vector3::new(0.0, 0.0, RATE_RAD_S)).norm();
+                assert!(
+                    err < 1e-8,
+                    "subtype {subtype}: angular velocity error {err:e}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn ck_decoding_errors() {
+        assert!(CKConstantRateSetType2::from_f64_slice(&[0.0; 9]).is_err());
+        assert!(CKDiscreteSetType3::from_f64_slice(&[0.0; 1]).is_err());
+        // A record size of 5 is invalid for Type 3.
+        let mut data = vec![0.0; 5];
+        data.extend([0.0, 0.0, 1.0, 1.0]);
+        assert!(CKDiscreteSetType3::from_f64_slice(&data).is_err());
+        // Unknown Type 5 subtype
+        assert!(CKInterpolatedSetType5::from_f64_slice(&[1e-3, 4.0, 1.0, 0.0, 0.0]).is_err());
+    }
+}
@@ -53,7 +53,9 @@ impl<'a> Type2ChebyshevSet<'a> {
 
         let window_duration_s = self.interval_length.to_seconds();
 
-        let ephem_start_delta_s = epoch.to_et_seconds() - summary.start_epoch_et_s();
+        // The records are indexed from the initial epoch of the set, which may precede the start of the summary.
+        let ephem_start_delta_s =
+            (epoch.to_et_seconds() - self.init_epoch.to_et_seconds()).max(0.0);
 
         Ok(((ephem_start_delta_s / window_duration_s) as usize + 1).min(self.num_records))
     }
@@ -204,13 +206,13 @@ impl<'a> NAIFDataSet<'a> for Type2ChebyshevSet<'a> {
         };
 
         let end_idx = if let Some(end) = new_end {
-            self.spline_idx(end, summary)?
+            self.spline_idx(end, summary)? - 1
         } else {
             self.num_records - 1
         };
 
         self.record_data = &self.record_data[start_idx * self.rsize..(end_idx + 1) * self.rsize];
-        self.num_records = (self.record_data.len() / self.rsize) - 1;
+        self.num_records = self.record_data.len() / self.rsize;
         self.init_epoch = self.nth_record(0).unwrap().midpoint_epoch() - 0.5 * self.interval_length;
 
         Ok(self)
@@ -271,6 +273,256 @@ impl<'a> NAIFDataRecord<'a> for Type2ChebyshevRecord<'a> {
     }
 }
 
+/// Chebyshev polynomials of SPK Type 3 (position and velocity) and of BPC Type 3 (Euler angles and their rates).
+/// Unlike Type 2, the derivatives are stored as their own polynomials and are evaluated directly instead of being differentiated.
+#[derive(PartialEq)]
+pub struct Type3ChebyshevSet<'a> {
+    pub init_epoch: Epoch,
+    pub interval_length: Duration,
+    pub rsize: usize,
+    pub num_records: usize,
+    pub record_data: &'a [f64],
+}
+
+impl<'a> Type3ChebyshevSet<'a> {
+    /// Returns the degree of the polynomials, or an error if the record size cannot hold a single coefficient of each component.
+    pub fn degree(&self) -> Result<usize, DecodingError> {
+        self.rsize
+            .checked_sub(2)
+            .and_then(|num_coeffs| (num_coeffs / 6).checked_sub(1))
+            .ok_or(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "record size",
+                    value: self.rsize as f64,
+                    reason: "must be two plus a non-zero multiple of six",
+                },
+            })
+    }
+
+    fn spline_idx<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<usize, InterpolationError> {
+        if epoch < summary.start_epoch() - 1_i64.nanoseconds()
+            || epoch > summary.end_epoch() + 1_i64.nanoseconds()
+        {
+            // No need to go any further.
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: summary.start_epoch(),
+                end: summary.end_epoch(),
+            });
+        }
+
+        let window_duration_s = self.interval_length.to_seconds();
+
+        // The records are indexed from the initial epoch of the set, which may precede the start of the summary.
+        let ephem_start_delta_s = (epoch - self.init_epoch).to_seconds().max(0.0);
+
+        Ok(((ephem_start_delta_s / window_duration_s) as usize + 1).min(self.num_records))
+    }
+}
+
+impl<'a> fmt::Display for Type3ChebyshevSet<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "start: {:E}\tlength: {}\trsize: {}\tnum_records: {}\tlen data: {}",
+            self.init_epoch,
+            self.interval_length,
+            self.rsize,
+            self.num_records,
+            self.record_data.len()
+        )
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for Type3ChebyshevSet<'a> {
+    type StateKind = (Vector3, Vector3);
+    type RecordKind = Type3ChebyshevRecord<'a>;
+    const DATASET_NAME: &'static str = "Chebyshev Type 3";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        ensure!(
+            slice.len() >= 5,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: 5_usize,
+                got: slice.len()
+            }
+        );
+        // Like Type 2, the directory of this set is stored at the very end of the dataset
+        let seconds_since_j2000 = slice[slice.len() - 4];
+        if !seconds_since_j2000.is_finite() {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::SubNormal {
+                    dataset: Self::DATASET_NAME,
+                    variable: "seconds since J2000 ET",
+                },
+            });
+        }
+
+        let start_epoch = Epoch::from_et_seconds(seconds_since_j2000);
+
+        let interval_length_s = slice[slice.len() - 3];
+        if !interval_length_s.is_finite() {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::SubNormal {
+                    dataset: Self::DATASET_NAME,
+                    variable: "interval length in seconds",
+                },
+            });
+        } else if interval_length_s <= 0.0 {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "interval length in seconds",
+                    value: interval_length_s,
+                    reason: "must be strictly greater than zero",
+                },
+            });
+        }
+
+        let interval_length = interval_length_s.seconds();
+        let rsize = slice[slice.len() - 2] as usize;
+        let num_records = slice[slice.len() - 1] as usize;
+
+        Ok(Self {
+            init_epoch: start_epoch,
+            interval_length,
+            rsize,
+            num_records,
+            record_data: &slice[0..slice.len() - 4],
+        })
+    }
+
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        Ok(Self::RecordKind::from_slice_f64(
+            self.record_data
+                .get(n * self.rsize..(n + 1) * self.rsize)
+                .ok_or(DecodingError::InaccessibleBytes {
+                    start: n * self.rsize,
+                    end: (n + 1) * self.rsize,
+                    size: self.record_data.len(),
+                })?,
+        ))
+    }
+
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<(Vector3, Vector3), InterpolationError> {
+        // The record size is only validated by the integrity check, so it must not be trusted here.
+        let degree = self.degree().context(InterpDecodingSnafu)?;
+        let spline_idx = self.spline_idx(epoch, summary)?;
+
+        let window_duration_s = self.interval_length.to_seconds();
+        let radius_s = window_duration_s / 2.0;
+
+        let record = self
+            .nth_record(spline_idx - 1)
+            .context(InterpDecodingSnafu)?;
+
+        let normalized_time = (epoch - record.midpoint).to_seconds() / radius_s;
+
+        let mut state = Vector3::zeros();
+        let mut rate = Vector3::zeros();
+
+        // Unlike Type 2, the velocity has its own set of coefficients, so the derivative of the polynomials is not used.
+        for (cno, coeffs) in [record.x_coeffs, record.y_coeffs, record.z_coeffs]
+            .iter()
+            .enumerate()
+        {
+            let (val, _) = chebyshev_eval(normalized_time, coeffs, radius_s, epoch, degree)?;
+            state[cno] = val;
+        }
+
+        for (cno, coeffs) in [record.vx_coeffs, record.vy_coeffs, record.vz_coeffs]
+            .iter()
+            .enumerate()
+        {
+            let (val, _) = chebyshev_eval(normalized_time, coeffs, radius_s, epoch, degree)?;
+            rate[cno] = val;
+        }
+
+        Ok((state, rate))
+    }
+
+    fn check_integrity(&self) -> Result<(), IntegrityError> {
+        if !self
+            .degree()
+            .is_ok_and(|degree| self.rsize == 6 * (degree + 1) + 2)
+        {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "record size",
+                value: self.rsize as f64,
+                reason: "must be two plus a non-zero multiple of six",
+            });
+        }
+
+        if self.record_data.len() != self.rsize * self.num_records {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "number of records",
+                value: self.num_records as f64,
+                reason: "does not match the length of the record data",
+            });
+        }
+
+        // Verify that none of the data is invalid once when we load it.
+        for val in self.record_data {
+            if !val.is_finite() {
+                return Err(IntegrityError::SubNormal {
+                    dataset: Self::DATASET_NAME,
+                    variable: "one of the record data",
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn truncate<S: NAIFSummaryRecord>(
+        mut self,
+        summary: &S,
+        new_start: Option<Epoch>,
+        new_end: Option<Epoch>,
+    ) -> Result<Self, InterpolationError> {
+        let start_idx = if let Some(start) = new_start {
+            self.spline_idx(start, summary)? - 1
+        } else {
+            0
+        };
+
+        let end_idx = if let Some(end) = new_end {
+            self.spline_idx(end, summary)? - 1
+        } else {
+            self.num_records - 1
+        };
+
+        self.record_data = &self.record_data[start_idx * self.rsize..(end_idx + 1) * self.rsize];
+        self.num_records = self.record_data.len() / self.rsize;
+        self.init_epoch = self.nth_record(0).unwrap().midpoint - 0.5 * self.interval_length;
+
+        Ok(self)
+    }
+
+    /// Builds the DAF array representing a Chebyshev Type 3 interpolation set.
+    fn to_f64_daf_vec(&self) -> Result<Vec<f64>, InterpolationError> {
+        let mut data = self.record_data.to_vec();
+        data.push(self.init_epoch.to_et_seconds());
+        data.push(self.interval_length.to_seconds());
+        data.push(self.rsize as f64);
+        data.push(self.num_records as f64);
+
+        Ok(data)
+    }
+}
+
 #[derive(PartialEq)]
 pub struct Type3ChebyshevRecord<'a> {
     pub midpoint: Epoch,
@@ -306,12 +558,12 @@ impl<'a> NAIFDataRecord<'a> for Type3ChebyshevRecord<'a> {
         Self {
             midpoint: Epoch::from_et_seconds(slice[0]),
             radius: slice[1].seconds(),
-            x_coeffs: &slice[2..num_coeffs],
-            y_coeffs: &slice[2 + num_coeffs..num_coeffs * 2],
-            z_coeffs: &slice[2 + num_coeffs * 2..num_coeffs * 3],
-            vx_coeffs: &slice[2 + num_coeffs * 3..num_coeffs * 4],
-            vy_coeffs: &slice[2 + num_coeffs * 4..num_coeffs * 5],
-            vz_coeffs: &slice[2 + num_coeffs * 5..],
+            x_coeffs: &slice[2..2 + num_coeffs],
+            y_coeffs: &slice[2 + num_coeffs..2 + num_coeffs * 2],
+            z_coeffs: &slice[2 + num_coeffs * 2..2 + num_coeffs * 3],
+            vx_coeffs: &slice[2 + num_coeffs * 3..2 + num_coeffs * 4],
+            vy_coeffs: &slice[2 + num_coeffs * 4..2 + num_coeffs * 5],
+            vz_coeffs: &slice[2 + num_coeffs * 5..2 + num_coeffs * 6],
         }
     }
 }
@@ -323,7 +575,7 @@ mod chebyshev_ut {
         naif::daf::NAIFDataSet,
     };
 
-    use super::Type2ChebyshevSet;
+    use super::{Type2ChebyshevSet, Type3ChebyshevSet};
 
     #[test]
     fn too_small() {
@@ -403,4 +655,120 @@ mod chebyshev_ut {
             }
         }
     }
+
+    #[test]
+    fn type3_invalid_record_size() {
+        use crate::{
+            math::interpolation::InterpolationError, naif::spk::summary::SPKSummaryRecord,
+        };
+        use hifitime::Epoch;
+
+        // A record size too small for a single coefficient per component, evaluated without an integrity check.
+        let data = [0.0, 1.0, 2.0, 3.0, 4.0, 0.0, 86_400.0, 5.0, 1.0];
+        let dataset = Type3ChebyshevSet::from_f64_slice(&data).unwrap();
+        assert!(dataset.degree().is_err());
+        assert!(dataset.check_integrity().is_err());
+
+        let summary = SPKSummaryRecord {
+            end_epoch_et_s: 86_400.0,
+            ..Default::default()
+        };
+        assert!(matches!(
+            dataset.evaluate(Epoch::from_et_seconds(10.0), &summary),
+            Err(InterpolationError::InterpDecoding { .. })
+        ));
+    }
+
+    #[test]
+    fn type3_eval_truncate() {
+        use crate::{
+            math::interpolation::InterpolationError, naif::spk::summary::SPKSummaryRecord,
+        };
+        use hifitime::{Epoch, TimeUnits};
+
+        // Degree two polynomials on each of the six components, over two records of one day each.
+        let init_et_s = 0.0;
+        let interval_s = 86_400.0;
+        let coeffs = |rno: usize, cno: usize| {
+            let base = (rno * 6 + cno) as f64;
+            [base, 2.0 * base + 1.0, 0.5 - base]
+        };
+        // T0 = 1, T1 = t, T2 = 2t^2 - 1
+        let eval = |c: [f64; 3], t: f64| c[0] + c[1] * t + c[2] * (2.0 * t * t - 1.0);
+
+        let mut data = Vec::new();
+        for rno in 0..2 {
+            data.push(init_et_s + (rno as f64 + 0.5) * interval_s);
+            data.push(0.5 * interval_s);
+            for cno in 0..6 {
+                data.extend_from_slice(&coeffs(rno, cno));
+            }
+        }
+        data.extend_from_slice(&[init_et_s, interval_s, 20.0, 2.0]);
+
+        let dataset = Type3ChebyshevSet::from_f64_slice(&data).unwrap();
+        dataset.check_integrity().unwrap();
+        assert_eq!(dataset.degree(), Ok(2));
+
+        let record = dataset.nth_record(1).unwrap();
+        assert_eq!(record.x_coeffs, coeffs(1, 0));
+        assert_eq!(record.vz_coeffs, coeffs(1, 5));
+
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: init_et_s,
+            end_epoch_et_s: init_et_s + 2.0 * interval_s,
+            ..Default::default()
+        };
+
+        for (delta_s, rno, t) in [
+            (0.0, 0, -1.0),
+            (21_600.0, 0, -0.5),
+            (129_600.0, 1, 0.0),
+            (172_800.0, 1, 1.0),
+        ] {
+            let (pos, vel) = dataset
+                .evaluate(Epoch::from_et_seconds(init_et_s + delta_s), &summary)
+                .unwrap();
+            for cno in 0..3 {
+                assert!((pos[cno] - eval(coeffs(rno, cno), t)).abs() < 1e-12);
+                assert!((vel[cno] - eval(coeffs(rno, cno + 3), t)).abs() < 1e-12);
+            }
+        }
+
+        assert!(matches!(
+            dataset.evaluate(Epoch::from_et_seconds(-1.0), &summary),
+            Err(InterpolationError::NoInterpolationData { .. })
+        ));
+
+        // Truncating to the second day only keeps the second record
+        let truncated = Type3ChebyshevSet::from_f64_slice(&data)
+            .unwrap()
+            .truncate(
+                &summary,
+                Some(Epoch::from_et_seconds(init_et_s) + 1.5.days()),
+                None,
+            )
+            .unwrap();
+        assert_eq!(truncated.num_records, 1);
+        assert_eq!(truncated.init_epoch, Epoch::from_et_seconds(interval_s));
+        truncated.check_integrity().unwrap();
+
+        let daf_data = truncated.to_f64_daf_vec().unwrap();
+        assert_eq!(daf_data[..20], data[20..40]);
+        assert_eq!(daf_data[20..], [interval_s, interval_s, 20.0, 1.0]);
+
+        // The record size must match six sets of coefficients
+        data[42] = 21.0;
+        assert_eq!(
+            Type3ChebyshevSet::from_f64_slice(&data)
+                .unwrap()
+                .check_integrity(),
+            Err(IntegrityError::InvalidValue {
+                dataset: "Chebyshev Type 3",
+                variable: "record size",
+                value: 21.0,
+                reason: "must be two plus a non-zero multiple of six",
+            })
+        );
+    }
 }
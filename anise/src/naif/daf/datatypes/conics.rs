@@ -0,0 +1,752 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::f64::consts::{FRAC_PI_2, TAU};
+use core::fmt;
+use hifitime::Epoch;
+use nalgebra::{Rotation3, Unit};
+use snafu::{ensure, ResultExt};
+
+use crate::{
+    astro::orbit::{Orbit, ECC_EPSILON},
+    errors::{DecodingError, IntegrityError, TooFewDoublesSnafu},
+    math::{
+        interpolation::{InterpPhysicsSnafu, InterpolationError},
+        rotation::{r1, r3},
+        Matrix3, Vector3,
+    },
+    naif::daf::{NAIFDataRecord, NAIFDataSet, NAIFSummaryRecord},
+    prelude::Frame,
+};
+
+/// Returns the Keplerian state at the requested epoch of an orbit around a central body of the provided GM, in the frame of the elements.
+/// Angles are in radians, and the anomaly is the mean anomaly.
+#[allow(clippy::too_many_arguments)]
+fn keplerian_state(
+    gm_km3_s2: f64,
+    sma_km: f64,
+    ecc: f64,
+    inc_rad: f64,
+    raan_rad: f64,
+    aop_rad: f64,
+    ma_rad: f64,
+    epoch: Epoch,
+) -> Result<Orbit, InterpolationError> {
+    // Only the GM of the frame is used to compute the state, the identifiers are irrelevant.
+    let frame = Frame::new(0, 0).with_mu_km3_s2(gm_km3_s2);
+    let ma_rad = if ecc < 1.0 {
+        ma_rad.rem_euclid(TAU)
+    } else {
+        ma_rad
+    };
+
+    Orbit::try_keplerian_mean_anomaly(
+        sma_km,
+        ecc,
+        inc_rad.to_degrees(),
+        raan_rad.to_degrees(),
+        aop_rad.to_degrees(),
+        ma_rad.to_degrees(),
+        epoch,
+        frame,
+    )
+    .context(InterpPhysicsSnafu)
+}
+
+/// Returns the position and velocity in the perifocal frame of a parabolic orbit of the provided semi latus rectum, `dt_s`
+/// seconds after periapsis.
+fn parabolic_state(gm_km3_s2: f64, semi_latus_rectum_km: f64, dt_s: f64) -> (Vector3, Vector3) {
+    // Barker's equation, D + D^3 / 3 = 2 sqrt(GM / p^3) dt with D = tan(nu / 2), has a closed form solution.
+    let barker = 3.0 * (gm_km3_s2 / semi_latus_rectum_km.powi(3)).sqrt() * dt_s;
+    let d = 2.0 * (barker.asinh() / 3.0).sinh();
+    let speed_scale_km_s = (gm_km3_s2 / semi_latus_rectum_km).sqrt() * 2.0 / (1.0 + d.powi(2));
+
+    (
+        Vector3::new(
+            0.5 * semi_latus_rectum_km * (1.0 - d.powi(2)),
+            semi_latus_rectum_km * d,
+            0.0,
+        ),
+        Vector3::new(-speed_scale_km_s * d, speed_scale_km_s, 0.0),
+    )
+}
+
+/// Precessing conic of SPK Type 15: a two-body orbit whose line of apsides precesses and whose line of nodes regresses
+/// because of the oblateness (J2) of the central body.
+///
+/// # Limitations
+/// As in SPICE, the J2 effects are only applied to elliptical orbits.
+#[derive(PartialEq)]
+pub struct PrecessingConicSetType15 {
+    pub record: PrecessingConicRecord,
+}
+
+impl fmt::Display for PrecessingConicSetType15 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Precessing conic with periapsis at {}, p = {} km, e = {}",
+            Epoch::from_et_seconds(self.record.periapsis_epoch_et_s),
+            self.record.semi_latus_rectum_km,
+            self.record.ecc
+        )
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for PrecessingConicSetType15 {
+    type StateKind = (Vector3, Vector3);
+    type RecordKind = PrecessingConicRecord;
+    const DATASET_NAME: &'static str = "Precessing Conics Type 15";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        ensure!(
+            slice.len() >= PrecessingConicRecord::SIZE,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: PrecessingConicRecord::SIZE,
+                got: slice.len()
+            }
+        );
+
+        Ok(Self {
+            record: Self::RecordKind::from_slice_f64(&slice[..PrecessingConicRecord::SIZE]),
+        })
+    }
+
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        if n == 0 {
+            Ok(self.record)
+        } else {
+            Err(DecodingError::InaccessibleBytes {
+                start: n * PrecessingConicRecord::SIZE,
+                end: (n + 1) * PrecessingConicRecord::SIZE,
+                size: PrecessingConicRecord::SIZE,
+            })
+        }
+    }
+
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        _: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        let rec = &self.record;
+        let dt_s = epoch.to_et_seconds() - rec.periapsis_epoch_et_s;
+
+        // Build the perifocal basis, ensuring that the periapsis is orthogonal to the trajectory pole.
+        let z_hat = rec.trajectory_pole.normalize();
+        let x_hat = (rec.periapsis - rec.periapsis.dot(&z_hat) * z_hat).normalize();
+        let y_hat = z_hat.cross(&x_hat);
+        if !(z_hat.norm().is_finite() && x_hat.norm().is_finite()) {
+            return Err(InterpolationError::CorruptedData {
+                what: "trajectory pole or periapsis vectors are null or colinear",
+            });
+        }
+
+        let sma_km = rec.semi_latus_rectum_km / (1.0 - rec.ecc.powi(2));
+        let mean_motion_rad_s = (rec.gm_km3_s2 / sma_km.abs().powi(3)).sqrt();
+
+        // The Keplerian elements are singular for nearly parabolic orbits, which are hence treated as parabolic.
+        let (perifocal_pos_km, perifocal_vel_km_s) = if (rec.ecc - 1.0).abs() < ECC_EPSILON {
+            parabolic_state(rec.gm_km3_s2, rec.semi_latus_rectum_km, dt_s)
+        } else {
+            let perifocal = keplerian_state(
+                rec.gm_km3_s2,
+                sma_km,
+                rec.ecc,
+                0.0,
+                0.0,
+                0.0,
+                mean_motion_rad_s * dt_s,
+                epoch,
+            )?;
+            (perifocal.radius_km, perifocal.velocity_km_s)
+        };
+
+        let basis = Matrix3::from_columns(&[x_hat, y_hat, z_hat]);
+        let mut pos_km = basis * perifocal_pos_km;
+        let mut vel_km_s = basis * perifocal_vel_km_s;
+
+        let body_pole = rec.central_body_pole.normalize();
+        if rec.ecc < 1.0 && rec.applies_j2() && body_pole.norm().is_finite() {
+            let cos_inc = z_hat.dot(&body_pole);
+            let rate = 1.5
+                * mean_motion_rad_s
+                * rec.j2
+                * (rec.central_body_radius_km / rec.semi_latus_rectum_km).powi(2);
+
+            let node_rate_rad_s = if rec.regresses_nodes() {
+                -rate * cos_inc
+            } else {
+                0.0
+            };
+            let apsis_rate_rad_s = if rec.precesses_apsides() {
+                rate * (2.5 * cos_inc.powi(2) - 0.5)
+            } else {
+                0.0
+            };
+
+            // Precess the line of apsides about the trajectory pole, then regress the line of nodes about the pole of the central body.
+            let rot_apsides =
+                Rotation3::from_axis_angle(&Unit::new_unchecked(z_hat), apsis_rate_rad_s * dt_s);
+            let rot_nodes =
+                Rotation3::from_axis_angle(&Unit::new_unchecked(body_pole), node_rate_rad_s * dt_s);
+            let rot = rot_nodes * rot_apsides;
+
+            // As in SPICE, the velocity is rotated like the position, without the transport of the precessing frame.
+            pos_km = rot * pos_km;
+            vel_km_s = rot * vel_km_s;
+        }
+
+        Ok((pos_km, vel_km_s))
+    }
+
+    fn check_integrity(&self) -> Result<(), IntegrityError> {
+        self.record.check_integrity()
+    }
+}
+
+/// The sixteen doubles of a precessing conic segment.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct PrecessingConicRecord {
+    /// Epoch of periapsis, in TDB seconds past J2000
+    pub periapsis_epoch_et_s: f64,
+    /// Pole of the trajectory, i.e. direction of its angular momentum
+    pub trajectory_pole: Vector3,
+    /// Direction of the periapsis
+    pub periapsis: Vector3,
+    pub semi_latus_rectum_km: f64,
+    pub ecc: f64,
+    /// J2 processing flag: 1 only regresses the nodes, 2 only precesses the apsides, 3 disables J2 effects, any other value applies both.
+    pub j2_flag: f64,
+    pub central_body_pole: Vector3,
+    pub gm_km3_s2: f64,
+    pub j2: f64,
+    pub central_body_radius_km: f64,
+}
+
+impl PrecessingConicRecord {
+    /// Number of doubles in a Type 15 segment
+    pub const SIZE: usize = 16;
+
+    fn applies_j2(&self) -> bool {
+        self.j2_flag != 3.0 && self.j2 != 0.0
+    }
+
+    fn regresses_nodes(&self) -> bool {
+        self.j2_flag != 2.0
+    }
+
+    fn precesses_apsides(&self) -> bool {
+        self.j2_flag != 1.0
+    }
+
+    fn check_integrity(&self) -> Result<(), IntegrityError> {
+        for (variable, value) in [
+            ("periapsis epoch", self.periapsis_epoch_et_s),
+            ("semi latus rectum", self.semi_latus_rectum_km),
+            ("eccentricity", self.ecc),
+            ("GM", self.gm_km3_s2),
+            ("J2", self.j2),
+            ("central body radius", self.central_body_radius_km),
+        ] {
+            if !value.is_finite() {
+                return Err(IntegrityError::SubNormal {
+                    dataset: PrecessingConicSetType15::DATASET_NAME,
+                    variable,
+                });
+            }
+        }
+
+        for (variable, vector) in [
+            ("trajectory pole", self.trajectory_pole),
+            ("periapsis", self.periapsis),
+        ] {
+            if !vector.iter().all(|val| val.is_finite()) || vector.norm() == 0.0 {
+                return Err(IntegrityError::SubNormal {
+                    dataset: PrecessingConicSetType15::DATASET_NAME,
+                    variable,
+                });
+            }
+        }
+
+        if self.semi_latus_rectum_km <= 0.0 {
+            return Err(IntegrityError::InvalidValue {
+                dataset: PrecessingConicSetType15::DATASET_NAME,
+                variable: "semi latus rectum",
+                value: self.semi_latus_rectum_km,
+                reason: "must be strictly positive",
+            });
+        }
+
+        if self.ecc < 0.0 {
+            return Err(IntegrityError::InvalidValue {
+                dataset: PrecessingConicSetType15::DATASET_NAME,
+                variable: "eccentricity",
+                value: self.ecc,
+                reason: "must be positive",
+            });
+        }
+
+        if self.gm_km3_s2 <= 0.0 {
+            return Err(IntegrityError::InvalidValue {
+                dataset: PrecessingConicSetType15::DATASET_NAME,
+                variable: "GM",
+                value: self.gm_km3_s2,
+                reason: "must be strictly positive",
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for PrecessingConicRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl<'a> NAIFDataRecord<'a> for PrecessingConicRecord {
+    fn from_slice_f64(slice: &'a [f64]) -> Self {
+        let val = |idx: usize| slice.get(idx).copied().unwrap_or(0.0);
+        Self {
+            periapsis_epoch_et_s: val(0),
+            trajectory_pole: Vector3::new(val(1), val(2), val(3)),
+            periapsis: Vector3::new(val(4), val(5), val(6)),
+            semi_latus_rectum_km: val(7),
+            ecc: val(8),
+            j2_flag: val(9),
+            central_body_pole: Vector3::new(val(10), val(11), val(12)),
+            gm_km3_s2: val(13),
+            j2: val(14),
+            central_body_radius_km: val(15),
+        }
+    }
+}
+
+/// Equinoctial elements of SPK Type 17, with constant rates of the mean longitude, of the longitude of periapsis, and of the longitude of the node.
+///
+/// The elements are defined with respect to a reference plane whose pole is given by its right ascension and declination in the frame of the segment.
+#[derive(PartialEq)]
+pub struct EquinoctialSetType17 {
+    pub record: EquinoctialRecord,
+}
+
+impl fmt::Display for EquinoctialSetType17 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Equinoctial elements at {} with a = {} km",
+            Epoch::from_et_seconds(self.record.epoch_et_s),
+            self.record.sma_km,
+        )
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for EquinoctialSetType17 {
+    type StateKind = (Vector3, Vector3);
+    type RecordKind = EquinoctialRecord;
+    const DATASET_NAME: &'static str = "Equinoctial Type 17";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        ensure!(
+            slice.len() >= EquinoctialRecord::SIZE,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: EquinoctialRecord::SIZE,
+                got: slice.len()
+            }
+        );
+
+        Ok(Self {
+            record: Self::RecordKind::from_slice_f64(&slice[..EquinoctialRecord::SIZE]),
+        })
+    }
+
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        if n == 0 {
+            Ok(self.record)
+        } else {
+            Err(DecodingError::InaccessibleBytes {
+                start: n * EquinoctialRecord::SIZE,
+                end: (n + 1) * EquinoctialRecord::SIZE,
+                size: EquinoctialRecord::SIZE,
+            })
+        }
+    }
+
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        _: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        let rec = &self.record;
+        let dt_s = epoch.to_et_seconds() - rec.epoch_et_s;
+
+        // The anomalistic mean motion sets the size of the orbit, and hence the equivalent GM.
+        let mean_motion_rad_s = rec.mean_longitude_rate_rad_s - rec.periapsis_longitude_rate_rad_s;
+        if mean_motion_rad_s <= 0.0 {
+            return Err(InterpolationError::CorruptedData {
+                what: "mean longitude rate must be greater than the periapsis longitude rate",
+            });
+        }
+        let gm_km3_s2 = mean_motion_rad_s.powi(2) * rec.sma_km.powi(3);
+
+        let ecc = rec.h.hypot(rec.k);
+        let inc_rad = 2.0 * rec.p.hypot(rec.q).atan();
+        let periapsis_longitude_rad =
+            rec.h.atan2(rec.k) + rec.periapsis_longitude_rate_rad_s * dt_s;
+        let raan_rad = rec.p.atan2(rec.q) + rec.node_longitude_rate_rad_s * dt_s;
+        let mean_longitude_rad = rec.mean_longitude_rad + rec.mean_longitude_rate_rad_s * dt_s;
+
+        let state = keplerian_state(
+            gm_km3_s2,
+            rec.sma_km,
+            ecc,
+            inc_rad,
+            raan_rad,
+            periapsis_longitude_rad - raan_rad,
+            mean_longitude_rad - periapsis_longitude_rad,
+            epoch,
+        )?;
+
+        // Rotate from the reference plane into the frame of the segment. As in SPICE, the velocity is the two-body
+        // velocity of the elements at this epoch, without the transport of the precessing orbit plane.
+        let rot_mat =
+            (r1(FRAC_PI_2 - rec.pole_dec_rad) * r3(FRAC_PI_2 + rec.pole_ra_rad)).transpose();
+
+        Ok((rot_mat * state.radius_km, rot_mat * state.velocity_km_s))
+    }
+
+    fn check_integrity(&self) -> Result<(), IntegrityError> {
+        for (variable, value) in [
+            ("epoch", self.record.epoch_et_s),
+            ("semi major axis", self.record.sma_km),
+            ("h", self.record.h),
+            ("k", self.record.k),
+            ("mean longitude", self.record.mean_longitude_rad),
+            ("p", self.record.p),
+            ("q", self.record.q),
+            (
+                "periapsis longitude rate",
+                self.record.periapsis_longitude_rate_rad_s,
+            ),
+            ("mean longitude rate", self.record.mean_longitude_rate_rad_s),
+            ("node longitude rate", self.record.node_longitude_rate_rad_s),
+            ("pole right ascension", self.record.pole_ra_rad),
+            ("pole declination", self.record.pole_dec_rad),
+        ] {
+            if !value.is_finite() {
+                return Err(IntegrityError::SubNormal {
+                    dataset: Self::DATASET_NAME,
+                    variable,
+                });
+            }
+        }
+
+        if self.record.sma_km <= 0.0 {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "semi major axis",
+                value: self.record.sma_km,
+                reason: "must be strictly positive",
+            });
+        }
+
+        let ecc = self.record.h.hypot(self.record.k);
+        if ecc >= 1.0 {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "eccentricity",
+                value: ecc,
+                reason: "equinoctial elements must describe an elliptical orbit",
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// The twelve doubles of an equinoctial segment.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct EquinoctialRecord {
+    /// Epoch of the elements, in TDB seconds past J2000
+    pub epoch_et_s: f64,
+    pub sma_km: f64,
+    /// Eccentricity times the sine of the longitude of periapsis
+    pub h: f64,
+    /// Eccentricity times the cosine of the longitude of periapsis
+    pub k: f64,
+    pub mean_longitude_rad: f64,
+    /// Tangent of half the inclination times the sine of the longitude of the ascending node
+    pub p: f64,
+    /// Tangent of half the inclination times the cosine of the longitude of the ascending node
+    pub q: f64,
+    pub periapsis_longitude_rate_rad_s: f64,
+    pub mean_longitude_rate_rad_s: f64,
+    pub node_longitude_rate_rad_s: f64,
+    /// Right ascension of the pole of the reference plane
+    pub pole_ra_rad: f64,
+    /// Declination of the pole of the reference plane
+    pub pole_dec_rad: f64,
+}
+
+impl EquinoctialRecord {
+    /// Number of doubles in a Type 17 segment
+    pub const SIZE: usize = 12;
+}
+
+impl fmt::Display for EquinoctialRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl<'a> NAIFDataRecord<'a> for EquinoctialRecord {
+    fn from_slice_f64(slice: &'a [f64]) -> Self {
+        let val = |idx: usize| slice.get(idx).copied().unwrap_or(0.0);
+        Self {
+            epoch_et_s: val(0),
+            sma_km: val(1),
+            h: val(2),
+            k: val(3),
+            mean_longitude_rad: val(4),
+            p: val(5),
+            q: val(6),
+            periapsis_longitude_rate_rad_s: val(7),
+            mean_longitude_rate_rad_s: val(8),
+            node_longitude_rate_rad_s: val(9),
+            pole_ra_rad: val(10),
+            pole_dec_rad: val(11),
+        }
+    }
+}
+
+#[cfg(test)]
+mod conics_ut {
+    use super::*;
+    use crate::constants::frames::EARTH_J2000;
+    use crate::naif::spk::summary::SPKSummaryRecord;
+    use hifitime::Unit;
+
+    const GM_EARTH: f64 = 398_600.435_436;
+
+    /// Reference orbit and its periapsis epoch
+    fn reference_orbit() -> (Orbit, Epoch) {
+        let epoch = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        let orbit = Orbit::try_keplerian(
+            8000.0,
+            0.1,
+            35.0,
+            40.0,
+            60.0,
+            0.0,
+            epoch,
+            EARTH_J2000.with_mu_km3_s2(GM_EARTH),
+        )
+        .unwrap();
+        (orbit, epoch)
+    }
+
+    fn type15_data(orbit: &Orbit, epoch: Epoch, j2_flag: f64) -> Vec<f64> {
+        let pole = orbit.hvec().unwrap().normalize();
+        let periapsis = orbit.radius_km.normalize();
+        vec![
+            epoch.to_et_seconds(),
+            pole[0],
+            pole[1],
+            pole[2],
+            periapsis[0],
+            periapsis[1],
+            periapsis[2],
+            orbit.semi_parameter_km().unwrap(),
+            orbit.ecc().unwrap(),
+            j2_flag,
+            0.0,
+            0.0,
+            1.0,
+            GM_EARTH,
+            1.08262668e-3,
+            6378.1363,
+        ]
+    }
+
+    #[test]
+    fn type15_without_j2_is_keplerian() {
+        let (orbit, epoch) = reference_orbit();
+        let data = type15_data(&orbit, epoch, 3.0);
+        let dataset = PrecessingConicSetType15::from_f64_slice(&data).unwrap();
+        dataset.check_integrity().unwrap();
+
+        let summary = SPKSummaryRecord::default();
+        for hours in [-5, 0, 1, 7, 30] {
+            let eval_epoch = epoch + Unit::Hour * hours;
+            let (pos_km, vel_km_s) = dataset.evaluate(eval_epoch, &summary).unwrap();
+            let expected = orbit.at_epoch(eval_epoch).unwrap();
+            // The reference orbit recomputes its elements to propagate, hence the tolerance.
+            assert!((pos_km - expected.radius_km).norm() < 1e-3, "{hours} h");
+            assert!(
+                (vel_km_s - expected.velocity_km_s).norm() < 1e-6,
+                "{hours} h"
+            );
+        }
+    }
+
+    #[test]
+    fn type15_j2_precession() {
+        let (orbit, epoch) = reference_orbit();
+        let dataset =
+            PrecessingConicSetType15::from_f64_slice(&type15_data(&orbit, epoch, 0.0)).unwrap();
+        let summary = SPKSummaryRecord::default();
+
+        // After ten days, the node has regressed by about 3.8 degrees per day for this orbit, and the apsides have precessed.
+        let later = epoch + Unit::Day * 10;
+        let dt_s = (later - epoch).to_seconds();
+        let (pos_km, vel_km_s) = dataset.evaluate(later, &summary).unwrap();
+
+        let p = orbit.semi_parameter_km().unwrap();
+        let n = (GM_EARTH / 8000.0_f64.powi(3)).sqrt();
+        let rate = 1.5 * n * 1.08262668e-3 * (6378.1363 / p).powi(2);
+        let cos_inc = 35.0_f64.to_radians().cos();
+        let node_rate_rad_s = -rate * cos_inc;
+        let apsis_rate_rad_s = rate * (2.5 * cos_inc.powi(2) - 0.5);
+        assert!((node_rate_rad_s * 86400.0).to_degrees() + 3.77 < 0.01);
+
+        let keplerian =
+            PrecessingConicSetType15::from_f64_slice(&type15_data(&orbit, epoch, 3.0)).unwrap();
+        let (pos_kep_km, vel_kep_km_s) = keplerian.evaluate(later, &summary).unwrap();
+        let traj_pole = nalgebra::Unit::new_normalize(orbit.hvec().unwrap());
+        let rot = r3(-node_rate_rad_s * dt_s)
+            * Rotation3::from_axis_angle(&traj_pole, apsis_rate_rad_s * dt_s);
+        assert!((pos_km - rot * pos_kep_km).norm() < 1e-5);
+
+        // The velocity is rotated like the position, without the transport of the precessing frame.
+        assert!((vel_km_s - rot * vel_kep_km_s).norm() < 1e-8);
+
+        // Only regressing the nodes is a rotation of the Keplerian state about the pole of the central body.
+        let nodes_only =
+            PrecessingConicSetType15::from_f64_slice(&type15_data(&orbit, epoch, 1.0)).unwrap();
+        let (pos_nodes_km, _) = nodes_only.evaluate(later, &summary).unwrap();
+        assert!((r3(-node_rate_rad_s * dt_s) * pos_kep_km - pos_nodes_km).norm() < 1e-5);
+    }
+
+    #[test]
+    fn type15_parabolic() {
+        let (orbit, epoch) = reference_orbit();
+        let mut data = type15_data(&orbit, epoch, 0.0);
+        let p_km = data[7];
+        data[8] = 1.0;
+        let dataset = PrecessingConicSetType15::from_f64_slice(&data).unwrap();
+        dataset.check_integrity().unwrap();
+
+        let summary = SPKSummaryRecord::default();
+        let (pos_km, vel_km_s) = dataset.evaluate(epoch, &summary).unwrap();
+        assert!((pos_km - orbit.radius_km.normalize() * p_km / 2.0).norm() < 1e-6);
+
+        for hours in [-20, -1, 3, 50] {
+            let eval_epoch = epoch + Unit::Hour * hours;
+            let (pos_km, vel_km_s) = dataset.evaluate(eval_epoch, &summary).unwrap();
+            // The orbital energy is null and the motion remains in the plane of the trajectory.
+            assert!((vel_km_s.norm_squared() - 2.0 * GM_EARTH / pos_km.norm()).abs() < 1e-9);
+            assert!(pos_km.dot(&orbit.hvec().unwrap()).abs() < 1e-3);
+
+            // The velocity is the time derivative of the position, since the J2 effects do not apply.
+            let (pos_before_km, _) = dataset
+                .evaluate(eval_epoch - Unit::Millisecond * 500, &summary)
+                .unwrap();
+            let (pos_after_km, _) = dataset
+                .evaluate(eval_epoch + Unit::Millisecond * 500, &summary)
+                .unwrap();
+            assert!(
+                (pos_after_km - pos_before_km - vel_km_s).norm() < 1e-6,
+                "{hours} h"
+            );
+        }
+        assert!((vel_km_s.norm() - (4.0 * GM_EARTH / p_km).sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn type17_evaluation() {
+        let (orbit, epoch) = reference_orbit();
+        let sma_km: f64 = 8000.0;
+        let ecc = 0.1;
+        let (lp, node) = (100.0_f64.to_radians(), 40.0_f64.to_radians());
+        let tan_half_inc = (35.0_f64.to_radians() / 2.0).tan();
+        let n = (GM_EARTH / sma_km.powi(3)).sqrt();
+
+        let mut data = vec![
+            epoch.to_et_seconds(),
+            sma_km,
+            ecc * lp.sin(),
+            ecc * lp.cos(),
+            lp, // periapsis passage
+            tan_half_inc * node.sin(),
+            tan_half_inc * node.cos(),
+            0.0,
+            n,
+            0.0,
+            -FRAC_PI_2,
+            FRAC_PI_2,
+        ];
+
+        let summary = SPKSummaryRecord::default();
+
+        // Without rates and with the reference plane of the segment frame, the elements are Keplerian.
+        let dataset = EquinoctialSetType17::from_f64_slice(&data).unwrap();
+        dataset.check_integrity().unwrap();
+        for hours in [-3, 0, 2, 11] {
+            let eval_epoch = epoch + Unit::Hour * hours;
+            let (pos_km, vel_km_s) = dataset.evaluate(eval_epoch, &summary).unwrap();
+            let expected = orbit.at_epoch(eval_epoch).unwrap();
+            // The reference orbit recomputes its elements to propagate, hence the tolerance.
+            assert!((pos_km - expected.radius_km).norm() < 1e-3, "{hours} h");
+            assert!(
+                (vel_km_s - expected.velocity_km_s).norm() < 1e-6,
+                "{hours} h"
+            );
+        }
+
+        // With rates, the state is the two-body state of the elements propagated to that epoch
+        data[7] = 1e-6;
+        data[8] = n + 1e-6;
+        data[9] = -2e-6;
+        // And the reference plane is the Earth equator, seen from the ecliptic
+        data[10] = -FRAC_PI_2;
+        data[11] = FRAC_PI_2 - 23.4_f64.to_radians();
+        let dataset = EquinoctialSetType17::from_f64_slice(&data).unwrap();
+        let eval_epoch = epoch + Unit::Day * 3;
+        let (pos_km, vel_km_s) = dataset.evaluate(eval_epoch, &summary).unwrap();
+        assert!((pos_km.norm() - 8000.0).abs() < 800.0 + 1e-6);
+
+        let dt_s = eval_epoch.to_et_seconds() - epoch.to_et_seconds();
+        let (lp_later, node_later) = (lp + data[7] * dt_s, node + data[9] * dt_s);
+        let mut propagated = data.clone();
+        propagated[0] = eval_epoch.to_et_seconds();
+        propagated[2] = ecc * lp_later.sin();
+        propagated[3] = ecc * lp_later.cos();
+        propagated[4] = lp + data[8] * dt_s;
+        propagated[5] = tan_half_inc * node_later.sin();
+        propagated[6] = tan_half_inc * node_later.cos();
+        let (pos_prop_km, vel_prop_km_s) = EquinoctialSetType17::from_f64_slice(&propagated)
+            .unwrap()
+            .evaluate(eval_epoch, &summary)
+            .unwrap();
+        assert!((pos_km - pos_prop_km).norm() < 1e-6);
+        assert!((vel_km_s - vel_prop_km_s).norm() < 1e-9);
+
+        // Hyperbolic elements are rejected
+        data[2] = 2.0;
+        assert!(EquinoctialSetType17::from_f64_slice(&data)
+            .unwrap()
+            .check_integrity()
+            .is_err());
+    }
+}
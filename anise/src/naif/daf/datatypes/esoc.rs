@@ -0,0 +1,719 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+use hifitime::Epoch;
+use snafu::{ensure, ResultExt};
+
+use crate::{
+    errors::{DecodingError, IntegrityError, TooFewDoublesSnafu},
+    math::{
+        interpolation::{
+            hermite_eval, lagrange_eval, InterpDecodingSnafu, InterpolationError, MAX_SAMPLES,
+        },
+        Vector3,
+    },
+    naif::daf::{NAIFDataRecord, NAIFDataSet, NAIFSummaryRecord},
+};
+
+/// Interpolation subtype of the ESOC data sets (Types 18 and 19), which defines the content of each packet.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ESOCSubtype {
+    /// Hermite interpolation of twelve element packets: position and its derivative, velocity and its derivative.
+    Hermite12 = 0,
+    /// Lagrange interpolation of six element packets: position and velocity.
+    Lagrange6 = 1,
+    /// Hermite interpolation of six element packets: position and velocity, only valid for Type 19.
+    Hermite6 = 2,
+}
+
+impl ESOCSubtype {
+    /// Number of doubles in each packet of this subtype.
+    pub const fn packet_size(&self) -> usize {
+        match self {
+            Self::Hermite12 => 12,
+            Self::Lagrange6 | Self::Hermite6 => 6,
+        }
+    }
+}
+
+impl TryFrom<f64> for ESOCSubtype {
+    type Error = IntegrityError;
+
+    fn try_from(code: f64) -> Result<Self, Self::Error> {
+        match code as i32 {
+            0 => Ok(Self::Hermite12),
+            1 => Ok(Self::Lagrange6),
+            2 => Ok(Self::Hermite6),
+            _ => Err(IntegrityError::InvalidValue {
+                dataset: ESOCSetType18::DATASET_NAME,
+                variable: "subtype",
+                value: code,
+                reason: "must be 0, 1, or 2",
+            }),
+        }
+    }
+}
+
+/// ESOC Hermite or Lagrange set of SPK Type 18, which is also the format of each mini-segment of Type 19.
+#[derive(PartialEq)]
+pub struct ESOCSetType18<'a> {
+    pub subtype: ESOCSubtype,
+    /// Number of packets used to build the interpolation
+    pub window_size: usize,
+    pub num_records: usize,
+    pub packet_data: &'a [f64],
+    /// Epochs of each of the packets, ANISE expects this to be ordered chronologically!
+    pub epoch_data: &'a [f64],
+    pub epoch_registry: &'a [f64],
+}
+
+impl<'a> fmt::Display for ESOCSetType18<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ESOC Type 18 ({:?}) from {:E} to {:E} with window size {} ({} items, {} epoch directories)",
+            self.subtype,
+            Epoch::from_et_seconds(*self.epoch_data.first().unwrap_or(&0.0)),
+            Epoch::from_et_seconds(*self.epoch_data.last().unwrap_or(&0.0)),
+            self.window_size,
+            self.num_records,
+            self.epoch_registry.len()
+        )
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for ESOCSetType18<'a> {
+    type StateKind = (Vector3, Vector3);
+    type RecordKind = ESOCPacket;
+    const DATASET_NAME: &'static str = "ESOC Type 18";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        ensure!(
+            slice.len() >= 3,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: 3_usize,
+                got: slice.len()
+            }
+        );
+
+        // For this kind of record, the metadata is stored at the very end of the dataset
+        let num_records = slice[slice.len() - 1] as usize;
+        let window_size = slice[slice.len() - 2] as usize;
+        let subtype = ESOCSubtype::try_from(slice[slice.len() - 3])
+            .map_err(|source| DecodingError::Integrity { source })?;
+
+        let packet_data_end_idx = subtype.packet_size() * num_records;
+        let epoch_data_end_idx = packet_data_end_idx + num_records;
+        ensure!(
+            slice.len() >= epoch_data_end_idx + 3,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: epoch_data_end_idx + 3,
+                got: slice.len()
+            }
+        );
+
+        Ok(Self {
+            subtype,
+            window_size,
+            num_records,
+            packet_data: &slice[..packet_data_end_idx],
+            epoch_data: &slice[packet_data_end_idx..epoch_data_end_idx],
+            // And the epoch directory is whatever remains minus the metadata
+            epoch_registry: &slice[epoch_data_end_idx..slice.len() - 3],
+        })
+    }
+
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        let rcrd_len = self.subtype.packet_size();
+        Ok(Self::RecordKind::from_slice_f64(
+            self.packet_data
+                .get(n * rcrd_len..(n + 1) * rcrd_len)
+                .ok_or(DecodingError::InaccessibleBytes {
+                    start: n * rcrd_len,
+                    end: (n + 1) * rcrd_len,
+                    size: self.packet_data.len(),
+                })?,
+        ))
+    }
+
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        _: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        let epoch_et_s = epoch.to_et_seconds();
+        if self.epoch_data.is_empty()
+            || epoch_et_s + 1e-9 < self.epoch_data[0]
+            || epoch_et_s - 1e-9 > *self.epoch_data.last().unwrap()
+        {
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: Epoch::from_et_seconds(*self.epoch_data.first().unwrap_or(&0.0)),
+                end: Epoch::from_et_seconds(*self.epoch_data.last().unwrap_or(&0.0)),
+            });
+        }
+
+        // SPICE reduces the window to the number of packets if there are fewer of those.
+        let window_size = self.window_size.min(self.num_records);
+        if window_size == 0 || window_size > MAX_SAMPLES {
+            return Err(InterpolationError::CorruptedData {
+                what: "window size of ESOC data is zero or greater than MAX_SAMPLES (32)",
+            });
+        }
+
+        // Index of the last epoch before or at the requested epoch, and of the epoch nearest to it.
+        let low_idx = self
+            .epoch_data
+            .partition_point(|et_s| *et_s <= epoch_et_s)
+            .saturating_sub(1);
+        let near_idx = if low_idx + 1 < self.num_records
+            && self.epoch_data[low_idx + 1] - epoch_et_s < epoch_et_s - self.epoch_data[low_idx]
+        {
+            low_idx + 1
+        } else {
+            low_idx
+        };
+
+        // Odd windows are centered on the nearest epoch, even windows on the two epochs bracketing the requested one.
+        let first_idx = if window_size % 2 == 1 {
+            near_idx.saturating_sub((window_size - 1) / 2)
+        } else {
+            low_idx.saturating_sub(window_size / 2 - 1)
+        }
+        .min(self.num_records - window_size);
+
+        // Statically allocated arrays of the maximum number of samples
+        let mut epochs = [0.0; MAX_SAMPLES];
+        let mut states = [[0.0; MAX_SAMPLES]; 6];
+        let mut derivatives = [[0.0; MAX_SAMPLES]; 6];
+
+        for (cno, idx) in (first_idx..first_idx + window_size).enumerate() {
+            let packet = self.nth_record(idx).context(InterpDecodingSnafu)?;
+            epochs[cno] = self.epoch_data[idx];
+            for i in 0..3 {
+                states[i][cno] = packet.position_km[i];
+                states[i + 3][cno] = packet.velocity_km_s[i];
+                if let Some((pos_rate, vel_rate)) = packet.derivatives {
+                    derivatives[i][cno] = pos_rate[i];
+                    derivatives[i + 3][cno] = vel_rate[i];
+                }
+            }
+        }
+
+        let epochs = &epochs[..window_size];
+        let mut pos_km = Vector3::zeros();
+        let mut vel_km_s = Vector3::zeros();
+
+        for i in 0..3 {
+            let pos_samples = &states[i][..window_size];
+            let vel_samples = &states[i + 3][..window_size];
+            match self.subtype {
+                ESOCSubtype::Hermite12 => {
+                    // The position and the velocity are interpolated independently.
+                    (pos_km[i], _) = hermite_eval(
+                        epochs,
+                        pos_samples,
+                        &derivatives[i][..window_size],
+                        epoch_et_s,
+                    )?;
+                    (vel_km_s[i], _) = hermite_eval(
+                        epochs,
+                        vel_samples,
+                        &derivatives[i + 3][..window_size],
+                        epoch_et_s,
+                    )?;
+                }
+                ESOCSubtype::Lagrange6 => {
+                    (pos_km[i], _) = lagrange_eval(epochs, pos_samples, epoch_et_s)?;
+                    (vel_km_s[i], _) = lagrange_eval(epochs, vel_samples, epoch_et_s)?;
+                }
+                ESOCSubtype::Hermite6 => {
+                    (pos_km[i], vel_km_s[i]) =
+                        hermite_eval(epochs, pos_samples, vel_samples, epoch_et_s)?;
+                }
+            }
+        }
+
+        Ok((pos_km, vel_km_s))
+    }
+
+    fn check_integrity(&self) -> Result<(), IntegrityError> {
+        if self.window_size < 2 || self.window_size > MAX_SAMPLES {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "window size",
+                value: self.window_size as f64,
+                reason: "must be between 2 and 32",
+            });
+        }
+
+        // Verify that none of the data is invalid once when we load it.
+        for val in self.epoch_data {
+            if !val.is_finite() {
+                return Err(IntegrityError::SubNormal {
+                    dataset: Self::DATASET_NAME,
+                    variable: "one of the epoch data",
+                });
+            }
+        }
+
+        if self.epoch_data.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "epochs of the packets",
+                value: 0.0,
+                reason: "must be strictly increasing",
+            });
+        }
+
+        for val in self.epoch_registry {
+            if !val.is_finite() {
+                return Err(IntegrityError::SubNormal {
+                    dataset: Self::DATASET_NAME,
+                    variable: "one of the epoch registry data",
+                });
+            }
+        }
+
+        for val in self.packet_data {
+            if !val.is_finite() {
+                return Err(IntegrityError::SubNormal {
+                    dataset: Self::DATASET_NAME,
+                    variable: "one of the packet data",
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A packet of ESOC data, whose derivatives are only available in twelve element packets.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ESOCPacket {
+    pub position_km: Vector3,
+    pub velocity_km_s: Vector3,
+    /// Derivatives of the position and of the velocity used for the Hermite interpolation of twelve element packets.
+    pub derivatives: Option<(Vector3, Vector3)>,
+}
+
+impl fmt::Display for ESOCPacket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl<'a> NAIFDataRecord<'a> for ESOCPacket {
+    fn from_slice_f64(slice: &'a [f64]) -> Self {
+        if slice.len() == 12 {
+            Self {
+                position_km: Vector3::new(slice[0], slice[1], slice[2]),
+                velocity_km_s: Vector3::new(slice[6], slice[7], slice[8]),
+                derivatives: Some((
+                    Vector3::new(slice[3], slice[4], slice[5]),
+                    Vector3::new(slice[9], slice[10], slice[11]),
+                )),
+            }
+        } else {
+            Self {
+                position_km: Vector3::new(slice[0], slice[1], slice[2]),
+                velocity_km_s: Vector3::new(slice[3], slice[4], slice[5]),
+                derivatives: None,
+            }
+        }
+    }
+}
+
+/// ESOC piecewise set of SPK Type 19, made of mini-segments (each in the Type 18 format) which cover consecutive intervals.
+#[derive(PartialEq)]
+pub struct ESOCSetType19<'a> {
+    /// If true, the later interval is used when the requested epoch is on the boundary between two intervals.
+    pub select_last: bool,
+    pub num_intervals: usize,
+    /// Boundaries of each interval, i.e. the number of intervals plus one.
+    pub interval_bounds: &'a [f64],
+    pub interval_registry: &'a [f64],
+    /// Start pointer of each mini-segment, followed by the stop pointer of the last mini-segment plus one.
+    pub minisegment_ptrs: &'a [f64],
+    /// All of the data of the segment, which the pointers index.
+    pub data: &'a [f64],
+}
+
+impl<'a> ESOCSetType19<'a> {
+    /// Returns the index of the interval which covers the provided epoch, following the boundary selection flag.
+    fn interval_idx(&self, epoch: Epoch) -> Result<usize, InterpolationError> {
+        let epoch_et_s = epoch.to_et_seconds();
+        let start_et_s = *self.interval_bounds.first().unwrap_or(&0.0);
+        let end_et_s = *self.interval_bounds.last().unwrap_or(&0.0);
+
+        if self.num_intervals == 0 || epoch_et_s < start_et_s || epoch_et_s > end_et_s {
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: Epoch::from_et_seconds(start_et_s),
+                end: Epoch::from_et_seconds(end_et_s),
+            });
+        }
+
+        let idx = if self.select_last {
+            self.interval_bounds
+                .partition_point(|et_s| *et_s <= epoch_et_s)
+                .saturating_sub(1)
+        } else {
+            self.interval_bounds
+                .partition_point(|et_s| *et_s < epoch_et_s)
+                .saturating_sub(1)
+        };
+
+        Ok(idx.min(self.num_intervals - 1))
+    }
+}
+
+impl<'a> fmt::Display for ESOCSetType19<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ESOC Type 19 from {:E} to {:E} with {} intervals (select last: {})",
+            Epoch::from_et_seconds(*self.interval_bounds.first().unwrap_or(&0.0)),
+            Epoch::from_et_seconds(*self.interval_bounds.last().unwrap_or(&0.0)),
+            self.num_intervals,
+            self.select_last
+        )
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for ESOCSetType19<'a> {
+    type StateKind = (Vector3, Vector3);
+    type RecordKind = ESOCMiniSegment<'a>;
+    const DATASET_NAME: &'static str = "ESOC Type 19";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        ensure!(
+            slice.len() >= 2,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: 2_usize,
+                got: slice.len()
+            }
+        );
+
+        // For this kind of record, the metadata is stored at the very end of the dataset
+        let num_intervals = slice[slice.len() - 1] as usize;
+        let select_last = slice[slice.len() - 2] != 0.0;
+
+        let ptrs_start_idx = (slice.len() - 2).checked_sub(num_intervals + 1).ok_or(
+            DecodingError::TooFewDoubles {
+                dataset: Self::DATASET_NAME,
+                got: slice.len(),
+                need: num_intervals + 3,
+            },
+        )?;
+        let minisegment_ptrs = &slice[ptrs_start_idx..slice.len() - 2];
+
+        // The interval boundaries immediately follow the last mini-segment, whose stop pointer (plus one) is the last pointer.
+        // Pointers are one-indexed addresses relative to the start of the segment.
+        let bounds_start_idx = (*minisegment_ptrs.last().unwrap_or(&1.0) as usize).max(1) - 1;
+        let bounds_end_idx = bounds_start_idx + num_intervals + 1;
+        ensure!(
+            bounds_end_idx <= ptrs_start_idx,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: bounds_end_idx + num_intervals + 3,
+                got: slice.len()
+            }
+        );
+
+        Ok(Self {
+            select_last,
+            num_intervals,
+            interval_bounds: &slice[bounds_start_idx..bounds_end_idx],
+            // And the interval directory is whatever remains before the pointers
+            interval_registry: &slice[bounds_end_idx..ptrs_start_idx],
+            minisegment_ptrs,
+            data: slice,
+        })
+    }
+
+    /// Returns the n-th mini-segment of this set.
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        let start = (*self
+            .minisegment_ptrs
+            .get(n)
+            .ok_or(DecodingError::InaccessibleBytes {
+                start: n,
+                end: n + 1,
+                size: self.minisegment_ptrs.len(),
+            })? as usize)
+            .saturating_sub(1);
+        let end = (*self
+            .minisegment_ptrs
+            .get(n + 1)
+            .ok_or(DecodingError::InaccessibleBytes {
+                start: n + 1,
+                end: n + 2,
+                size: self.minisegment_ptrs.len(),
+            })? as usize)
+            .saturating_sub(1);
+
+        Ok(Self::RecordKind::from_slice_f64(
+            self.data
+                .get(start..end)
+                .ok_or(DecodingError::InaccessibleBytes {
+                    start,
+                    end,
+                    size: self.data.len(),
+                })?,
+        ))
+    }
+
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        let idx = self.interval_idx(epoch)?;
+
+        self.nth_record(idx)
+            .context(InterpDecodingSnafu)?
+            .to_set()
+            .context(InterpDecodingSnafu)?
+            .evaluate(epoch, summary)
+    }
+
+    fn check_integrity(&self) -> Result<(), IntegrityError> {
+        for val in self.interval_bounds.iter().chain(self.interval_registry) {
+            if !val.is_finite() {
+                return Err(IntegrityError::SubNormal {
+                    dataset: Self::DATASET_NAME,
+                    variable: "one of the interval boundaries",
+                });
+            }
+        }
+
+        if self
+            .interval_bounds
+            .windows(2)
+            .any(|pair| pair[0] >= pair[1])
+        {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "interval boundaries",
+                value: 0.0,
+                reason: "must be strictly increasing",
+            });
+        }
+
+        for n in 0..self.num_intervals {
+            self.nth_record(n)
+                .map_err(|_| IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "mini-segment pointer",
+                    value: self.minisegment_ptrs[n],
+                    reason: "points outside of the segment",
+                })?
+                .to_set()
+                .map_err(|_| IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "mini-segment pointer",
+                    value: self.minisegment_ptrs[n],
+                    reason: "points to data which is not a valid mini-segment",
+                })?
+                .check_integrity()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Raw data of a mini-segment of an ESOC Type 19 set, which is stored in the same format as an ESOC Type 18 set.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ESOCMiniSegment<'a> {
+    pub data: &'a [f64],
+}
+
+impl<'a> ESOCMiniSegment<'a> {
+    /// Decodes this mini-segment as its Type 18 set.
+    pub fn to_set(&self) -> Result<ESOCSetType18<'a>, DecodingError> {
+        ESOCSetType18::from_f64_slice(self.data)
+    }
+}
+
+impl<'a> fmt::Display for ESOCMiniSegment<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.to_set() {
+            Ok(set) => write!(f, "{set}"),
+            Err(e) => write!(
+                f,
+                "invalid mini-segment of {} doubles: {e}",
+                self.data.len()
+            ),
+        }
+    }
+}
+
+impl<'a> NAIFDataRecord<'a> for ESOCMiniSegment<'a> {
+    fn from_slice_f64(slice: &'a [f64]) -> Self {
+        Self { data: slice }
+    }
+}
+
+#[cfg(test)]
+mod esoc_ut {
+    use hifitime::Epoch;
+
+    use crate::{
+        math::interpolation::InterpolationError,
+        naif::{daf::NAIFDataSet, spk::summary::SPKSummaryRecord},
+    };
+
+    use super::{ESOCSetType18, ESOCSetType19, ESOCSubtype};
+
+    /// A cubic trajectory offset by a constant, with its velocity and acceleration.
+    fn cubic(t: f64, offset: f64) -> [f64; 9] {
+        let x = offset + 2.0 * t - 3e-3 * t.powi(2) + 4e-6 * t.powi(3);
+        let vx = 2.0 - 6e-3 * t + 12e-6 * t.powi(2);
+        let ax = -6e-3 + 24e-6 * t;
+        [x, -x, 2.0 * x, vx, -vx, 2.0 * vx, ax, -ax, 2.0 * ax]
+    }
+
+    /// Builds the data of a Type 18 set (or of a Type 19 mini-segment) of the cubic trajectory.
+    fn esoc_data(
+        subtype: ESOCSubtype,
+        window_size: usize,
+        epochs: &[f64],
+        offset: f64,
+    ) -> Vec<f64> {
+        let mut data = Vec::new();
+        for t in epochs {
+            let state = cubic(*t, offset);
+            match subtype {
+                ESOCSubtype::Hermite12 => {
+                    data.extend_from_slice(&state[..6]);
+                    data.extend_from_slice(&state[3..]);
+                }
+                _ => data.extend_from_slice(&state[..6]),
+            }
+        }
+        data.extend_from_slice(epochs);
+        data.extend_from_slice(&[
+            subtype as u8 as f64,
+            window_size as f64,
+            epochs.len() as f64,
+        ]);
+        data
+    }
+
+    fn assert_cubic(pos: crate::math::Vector3, vel: crate::math::Vector3, t: f64, offset: f64) {
+        let expected = cubic(t, offset);
+        for i in 0..3 {
+            assert!((pos[i] - expected[i]).abs() < 1e-9, "{t} s: pos error");
+            assert!((vel[i] - expected[i + 3]).abs() < 1e-11, "{t} s: vel error");
+        }
+    }
+
+    #[test]
+    fn type18_subtypes() {
+        let summary = SPKSummaryRecord::default();
+        // Unequal time steps
+        let epochs: Vec<f64> = (0..15).map(|i| (i * i) as f64 * 10.0).collect();
+
+        for (subtype, window_size) in [
+            (ESOCSubtype::Hermite12, 4),
+            (ESOCSubtype::Lagrange6, 6),
+            (ESOCSubtype::Lagrange6, 5),
+        ] {
+            let data = esoc_data(subtype, window_size, &epochs, 1.0);
+            let dataset = ESOCSetType18::from_f64_slice(&data).unwrap();
+            dataset.check_integrity().unwrap();
+            assert_eq!(dataset.subtype, subtype);
+            assert_eq!(dataset.window_size, window_size);
+            assert_eq!(dataset.num_records, epochs.len());
+            assert!(dataset.epoch_registry.is_empty());
+
+            for t in [0.0, 5.0, 40.0, 333.3, 1500.0, 1959.0, 1960.0] {
+                let (pos, vel) = dataset
+                    .evaluate(Epoch::from_et_seconds(t), &summary)
+                    .unwrap();
+                assert_cubic(pos, vel, t, 1.0);
+            }
+
+            assert!(matches!(
+                dataset.evaluate(Epoch::from_et_seconds(1960.1), &summary),
+                Err(InterpolationError::NoInterpolationData { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn type19_minisegments() {
+        let summary = SPKSummaryRecord::default();
+
+        let epochs1: Vec<f64> = (0..=10).map(|i| i as f64 * 60.0).collect();
+        let epochs2: Vec<f64> = (10..=20).map(|i| i as f64 * 60.0).collect();
+        // Both mini-segments are offset differently to know which one is used.
+        let minisegment1 = esoc_data(ESOCSubtype::Hermite6, 4, &epochs1, 1.0);
+        let minisegment2 = esoc_data(ESOCSubtype::Lagrange6, 6, &epochs2, 5.0);
+
+        let build = |select_last: bool| {
+            let mut data = minisegment1.clone();
+            data.extend_from_slice(&minisegment2);
+            // Interval boundaries, no interval directory, pointers and control words
+            data.extend_from_slice(&[0.0, 600.0, 1200.0]);
+            data.extend_from_slice(&[
+                1.0,
+                1.0 + minisegment1.len() as f64,
+                1.0 + (minisegment1.len() + minisegment2.len()) as f64,
+            ]);
+            data.extend_from_slice(&[if select_last { 1.0 } else { 0.0 }, 2.0]);
+            data
+        };
+
+        let data = build(false);
+        let dataset = ESOCSetType19::from_f64_slice(&data).unwrap();
+        dataset.check_integrity().unwrap();
+        assert_eq!(dataset.num_intervals, 2);
+        assert_eq!(dataset.interval_bounds, &[0.0, 600.0, 1200.0]);
+        assert!(dataset.interval_registry.is_empty());
+        assert_eq!(dataset.nth_record(1).unwrap().data, minisegment2);
+
+        for (t, offset) in [
+            (0.0, 1.0),
+            (300.5, 1.0),
+            (600.0, 1.0),
+            (600.5, 5.0),
+            (1200.0, 5.0),
+        ] {
+            let (pos, vel) = dataset
+                .evaluate(Epoch::from_et_seconds(t), &summary)
+                .unwrap();
+            assert_cubic(pos, vel, t, offset);
+        }
+
+        // When selecting the last interval, the boundary is evaluated with the second mini-segment
+        let data = build(true);
+        let dataset = ESOCSetType19::from_f64_slice(&data).unwrap();
+        assert!(dataset.select_last);
+        let (pos, vel) = dataset
+            .evaluate(Epoch::from_et_seconds(600.0), &summary)
+            .unwrap();
+        assert_cubic(pos, vel, 600.0, 5.0);
+        let (pos, vel) = dataset
+            .evaluate(Epoch::from_et_seconds(0.0), &summary)
+            .unwrap();
+        assert_cubic(pos, vel, 0.0, 1.0);
+
+        assert!(matches!(
+            dataset.evaluate(Epoch::from_et_seconds(-0.1), &summary),
+            Err(InterpolationError::NoInterpolationData { .. })
+        ));
+    }
+}
@@ -18,7 +18,7 @@ use crate::math::interpolation::{
 };
 use crate::naif::daf::NAIFSummaryRecord;
 use crate::{
-    math::{cartesian::CartesianState, Vector3},
+    math::Vector3,
     naif::daf::{NAIFDataRecord, NAIFDataSet, NAIFRecord},
     DBL_SIZE,
 };
@@ -48,8 +48,15 @@ impl<'a> fmt::Display for HermiteSetType12<'a> {
     }
 }
 
+impl<'a> HermiteSetType12<'a> {
+    /// Returns the epoch of the last state of this set
+    pub fn last_state_epoch(&self) -> Epoch {
+        self.first_state_epoch + self.step_size * (self.num_records.saturating_sub(1) as f64)
+    }
+}
+
 impl<'a> NAIFDataSet<'a> for HermiteSetType12<'a> {
-    type StateKind = CartesianState;
+    type StateKind = (Vector3, Vector3);
     type RecordKind = PositionVelocityRecord;
     const DATASET_NAME: &'static str = "Hermite Type 12";
 
@@ -85,7 +92,8 @@ impl<'a> NAIFDataSet<'a> for HermiteSetType12<'a> {
         }
 
         let step_size = step_size_s.seconds();
-        let window_size = slice[slice.len() - 2] as usize;
+        // NOTE: The Type 12 and 13 specify that the windows size minus one is stored!
+        let window_size = slice[slice.len() - 2] as usize + 1;
         let num_records = slice[slice.len() - 1] as usize;
 
         Ok(Self {
@@ -112,16 +120,97 @@ impl<'a> NAIFDataSet<'a> for HermiteSetType12<'a> {
 
     fn evaluate<S: NAIFSummaryRecord>(
         &self,
-        _epoch: Epoch,
+        epoch: Epoch,
         _: &S,
-    ) -> Result<CartesianState, InterpolationError> {
-        Err(InterpolationError::UnimplementedType {
-            dataset: Self::DATASET_NAME,
-            issue: 14,
-        })
+    ) -> Result<Self::StateKind, InterpolationError> {
+        let last_state_epoch = self.last_state_epoch();
+        // Check that we even have interpolation data for that time
+        if epoch.to_et_seconds() + 1e-9 < self.first_state_epoch.to_et_seconds()
+            || epoch.to_et_seconds() - 1e-9 > last_state_epoch.to_et_seconds()
+        {
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: self.first_state_epoch,
+                end: last_state_epoch,
+            });
+        }
+
+        let samples = self.window_size;
+        if samples > MAX_SAMPLES || samples > self.num_records {
+            return Err(InterpolationError::CorruptedData {
+                what: "window size is larger than the number of states or than supported",
+            });
+        }
+
+        // The states are equally spaced, so we can compute the index of the window directly instead of searching for it.
+        let step_s = self.step_size.to_seconds();
+        let offset = (epoch - self.first_state_epoch).to_seconds() / step_s;
+        // As in SPICE, odd window sizes are centered on the nearest state, and even window sizes have as many states before as after the epoch.
+        let first_idx = if samples % 2 == 1 {
+            (offset.round() as usize).saturating_sub((samples - 1) / 2)
+        } else {
+            (offset.floor() as usize).saturating_sub(samples / 2 - 1)
+        }
+        .min(self.num_records - samples);
+
+        // Statically allocated arrays of the maximum number of samples
+        let mut epochs = [0.0; MAX_SAMPLES];
+        let mut xs = [0.0; MAX_SAMPLES];
+        let mut ys = [0.0; MAX_SAMPLES];
+        let mut zs = [0.0; MAX_SAMPLES];
+        let mut vxs = [0.0; MAX_SAMPLES];
+        let mut vys = [0.0; MAX_SAMPLES];
+        let mut vzs = [0.0; MAX_SAMPLES];
+
+        for (cno, idx) in (first_idx..first_idx + samples).enumerate() {
+            let record = self.nth_record(idx).context(InterpDecodingSnafu)?;
+            xs[cno] = record.x_km;
+            ys[cno] = record.y_km;
+            zs[cno] = record.z_km;
+            vxs[cno] = record.vx_km_s;
+            vys[cno] = record.vy_km_s;
+            vzs[cno] = record.vz_km_s;
+            epochs[cno] = self.first_state_epoch.to_et_seconds() + (idx as f64) * step_s;
+        }
+
+        let (x_km, vx_km_s) = hermite_eval(
+            &epochs[..samples],
+            &xs[..samples],
+            &vxs[..samples],
+            epoch.to_et_seconds(),
+        )?;
+
+        let (y_km, vy_km_s) = hermite_eval(
+            &epochs[..samples],
+            &ys[..samples],
+            &vys[..samples],
+            epoch.to_et_seconds(),
+        )?;
+
+        let (z_km, vz_km_s) = hermite_eval(
+            &epochs[..samples],
+            &zs[..samples],
+            &vzs[..samples],
+            epoch.to_et_seconds(),
+        )?;
+
+        // And build the result
+        let pos_km = Vector3::new(x_km, y_km, z_km);
+        let vel_km_s = Vector3::new(vx_km_s, vy_km_s, vz_km_s);
+
+        Ok((pos_km, vel_km_s))
     }
 
     fn check_integrity(&self) -> Result<(), IntegrityError> {
+        if self.step_size <= Duration::ZERO {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "step size",
+                value: self.step_size.to_seconds(),
+                reason: "must be strictly positive",
+            });
+        }
+
         for val in self.record_data {
             if !val.is_finite() {
                 return Err(IntegrityError::SubNormal {
@@ -388,6 +477,25 @@ impl<'a> NAIFDataSet<'a> for HermiteSetType13<'a> {
 
         Ok(())
     }
+
+    /// Builds the DAF array representing a Hermite Type 13 set, regenerating its epoch directory.
+    fn to_f64_daf_vec(&self) -> Result<Vec<f64>, InterpolationError> {
+        let mut data = self.state_data.to_vec();
+        data.extend_from_slice(self.epoch_data);
+        // The directory holds every hundredth epoch, and never the last one.
+        data.extend(
+            self.epoch_data
+                .iter()
+                .skip(99)
+                .step_by(100)
+                .take(self.num_records.saturating_sub(1) / 100),
+        );
+        // Type 13 stores the window size minus one.
+        data.push((self.samples - 1) as f64);
+        data.push(self.num_records as f64);
+
+        Ok(data)
+    }
 }
 
 #[cfg(test)]
@@ -397,7 +505,7 @@ mod hermite_ut {
         naif::daf::NAIFDataSet,
     };
 
-    use super::HermiteSetType13;
+    use super::{HermiteSetType12, HermiteSetType13};
 
     #[test]
     fn too_small() {
@@ -491,4 +599,66 @@ mod hermite_ut {
             }
         }
     }
+
+    #[test]
+    fn type12_equal_step() {
+        use crate::math::interpolation::InterpolationError;
+        use crate::naif::spk::summary::SPKSummaryRecord;
+        use hifitime::Epoch;
+
+        // A cubic trajectory, which a Hermite polynomial over two or more states interpolates exactly.
+        let cubic = |t: f64| -> [f64; 6] {
+            let x = 1.0 + 2.0 * t - 3e-3 * t.powi(2) + 4e-6 * t.powi(3);
+            let vx = 2.0 - 6e-3 * t + 12e-6 * t.powi(2);
+            [x, -x, 2.0 * x, vx, -vx, 2.0 * vx]
+        };
+
+        let start_et_s = 1e6;
+        let step_s = 60.0;
+        let num_records = 20;
+        let mut data = Vec::new();
+        for i in 0..num_records {
+            data.extend_from_slice(&cubic(i as f64 * step_s));
+        }
+        // The window size minus one is stored
+        data.extend_from_slice(&[start_et_s, step_s, 3.0, num_records as f64]);
+
+        let dataset = HermiteSetType12::from_f64_slice(&data).unwrap();
+        dataset.check_integrity().unwrap();
+        assert_eq!(dataset.window_size, 4);
+        assert_eq!(dataset.num_records, num_records);
+
+        let summary = SPKSummaryRecord::default();
+        for t in [0.0, 1.0, 59.0, 90.0, 601.5, 1000.0, 1139.0, 1140.0] {
+            let (pos, vel) = dataset
+                .evaluate(Epoch::from_et_seconds(start_et_s + t), &summary)
+                .unwrap();
+            let expected = cubic(t);
+            for i in 0..3 {
+                assert!((pos[i] - expected[i]).abs() < 1e-9, "{t} s: pos error");
+                assert!((vel[i] - expected[i + 3]).abs() < 1e-12, "{t} s: vel error");
+            }
+        }
+
+        assert!(matches!(
+            dataset.evaluate(Epoch::from_et_seconds(start_et_s + 1140.1), &summary),
+            Err(InterpolationError::NoInterpolationData { .. })
+        ));
+
+        // A null step size is invalid
+        let mut invalid_step = data.clone();
+        let len = invalid_step.len();
+        invalid_step[len - 3] = 0.0;
+        assert_eq!(
+            HermiteSetType12::from_f64_slice(&invalid_step)
+                .unwrap()
+                .check_integrity(),
+            Err(IntegrityError::InvalidValue {
+                dataset: "Hermite Type 12",
+                variable: "step size",
+                value: 0.0,
+                reason: "must be strictly positive",
+            })
+        );
+    }
 }
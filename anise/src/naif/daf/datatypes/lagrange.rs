@@ -15,7 +15,6 @@ use snafu::{ensure, ResultExt};
 use crate::{
     errors::{DecodingError, IntegrityError, TooFewDoublesSnafu},
     math::{
-        cartesian::CartesianState,
         interpolation::{lagrange_eval, InterpDecodingSnafu, InterpolationError, MAX_SAMPLES},
         Vector3,
     },
@@ -38,7 +37,7 @@ impl<'a> fmt::Display for LagrangeSetType8<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Lagrange Type 8: start: {:E}\tstep: {}\twindow size: {}\tnum records: {}\tlen data: {}",
+            "Lagrange Type 8: start: {:E}\tstep: {}\tdegree: {}\tnum records: {}\tlen data: {}",
             self.first_state_epoch,
             self.step_size,
             self.degree,
@@ -48,8 +47,15 @@ impl<'a> fmt::Display for LagrangeSetType8<'a> {
     }
 }
 
+impl<'a> LagrangeSetType8<'a> {
+    /// Returns the epoch of the last state of this set
+    pub fn last_state_epoch(&self) -> Epoch {
+        self.first_state_epoch + self.step_size * (self.num_records.saturating_sub(1) as f64)
+    }
+}
+
 impl<'a> NAIFDataSet<'a> for LagrangeSetType8<'a> {
-    type StateKind = CartesianState;
+    type StateKind = (Vector3, Vector3);
     type RecordKind = PositionVelocityRecord;
     const DATASET_NAME: &'static str = "Lagrange Type 8";
 
@@ -79,7 +85,7 @@ impl<'a> NAIFDataSet<'a> for LagrangeSetType8<'a> {
         if !step_size_s.is_finite() {
             return Err(DecodingError::Integrity {
                 source: IntegrityError::SubNormal {
-                    dataset: "Hermite Type 12",
+                    dataset: Self::DATASET_NAME,
                     variable: "step size in seconds",
                 },
             });
@@ -113,16 +119,113 @@ impl<'a> NAIFDataSet<'a> for LagrangeSetType8<'a> {
 
     fn evaluate<S: NAIFSummaryRecord>(
         &self,
-        _epoch: Epoch,
+        epoch: Epoch,
         _: &S,
-    ) -> Result<CartesianState, InterpolationError> {
-        Err(InterpolationError::UnimplementedType {
-            dataset: Self::DATASET_NAME,
-            issue: 12,
-        })
+    ) -> Result<Self::StateKind, InterpolationError> {
+        let last_state_epoch = self.last_state_epoch();
+        // Check that we even have interpolation data for that time
+        if epoch.to_et_seconds() + 1e-9 < self.first_state_epoch.to_et_seconds()
+            || epoch.to_et_seconds() - 1e-9 > last_state_epoch.to_et_seconds()
+        {
+            return Err(InterpolationError::NoInterpolationData {
+                req: epoch,
+                start: self.first_state_epoch,
+                end: last_state_epoch,
+            });
+        }
+
+        let group_size = self.degree + 1;
+        if group_size > MAX_SAMPLES || group_size > self.num_records {
+            return Err(InterpolationError::CorruptedData {
+                what: "polynomial degree is larger than the number of states or than supported",
+            });
+        }
+
+        // The states are equally spaced, so we can compute the index of the window directly instead of searching for it.
+        let step_s = self.step_size.to_seconds();
+        let offset = (epoch - self.first_state_epoch).to_seconds() / step_s;
+        // As in SPICE, odd window sizes are centered on the nearest state, and even window sizes have as many states before as after the epoch.
+        let first_idx = if group_size % 2 == 1 {
+            (offset.round() as usize).saturating_sub(self.degree / 2)
+        } else {
+            (offset.floor() as usize).saturating_sub(self.degree / 2)
+        }
+        .min(self.num_records - group_size);
+
+        // Statically allocated arrays of the maximum number of samples
+        let mut epochs = [0.0; MAX_SAMPLES];
+        let mut xs = [0.0; MAX_SAMPLES];
+        let mut ys = [0.0; MAX_SAMPLES];
+        let mut zs = [0.0; MAX_SAMPLES];
+        let mut vxs = [0.0; MAX_SAMPLES];
+        let mut vys = [0.0; MAX_SAMPLES];
+        let mut vzs = [0.0; MAX_SAMPLES];
+
+        for (cno, idx) in (first_idx..first_idx + group_size).enumerate() {
+            let record = self.nth_record(idx).context(InterpDecodingSnafu)?;
+            xs[cno] = record.x_km;
+            ys[cno] = record.y_km;
+            zs[cno] = record.z_km;
+            vxs[cno] = record.vx_km_s;
+            vys[cno] = record.vy_km_s;
+            vzs[cno] = record.vz_km_s;
+            epochs[cno] = self.first_state_epoch.to_et_seconds() + (idx as f64) * step_s;
+        }
+
+        // Each component is interpolated independently, including the velocity, like in SPICE.
+        let (x_km, _) = lagrange_eval(
+            &epochs[..group_size],
+            &xs[..group_size],
+            epoch.to_et_seconds(),
+        )?;
+
+        let (y_km, _) = lagrange_eval(
+            &epochs[..group_size],
+            &ys[..group_size],
+            epoch.to_et_seconds(),
+        )?;
+
+        let (z_km, _) = lagrange_eval(
+            &epochs[..group_size],
+            &zs[..group_size],
+            epoch.to_et_seconds(),
+        )?;
+
+        let (vx_km_s, _) = lagrange_eval(
+            &epochs[..group_size],
+            &vxs[..group_size],
+            epoch.to_et_seconds(),
+        )?;
+
+        let (vy_km_s, _) = lagrange_eval(
+            &epochs[..group_size],
+            &vys[..group_size],
+            epoch.to_et_seconds(),
+        )?;
+
+        let (vz_km_s, _) = lagrange_eval(
+            &epochs[..group_size],
+            &vzs[..group_size],
+            epoch.to_et_seconds(),
+        )?;
+
+        // And build the result
+        let pos_km = Vector3::new(x_km, y_km, z_km);
+        let vel_km_s = Vector3::new(vx_km_s, vy_km_s, vz_km_s);
+
+        Ok((pos_km, vel_km_s))
     }
 
     fn check_integrity(&self) -> Result<(), IntegrityError> {
+        if self.step_size <= Duration::ZERO {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "step size",
+                value: self.step_size.to_seconds(),
+                reason: "must be strictly positive",
+            });
+        }
+
         for val in self.record_data {
             if !val.is_finite() {
                 return Err(IntegrityError::SubNormal {
@@ -351,4 +454,81 @@ impl<'a> NAIFDataSet<'a> for LagrangeSetType9<'a> {
 
         Ok(())
     }
+
+    /// Builds the DAF array representing a Lagrange Type 9 set, regenerating its epoch directory.
+    fn to_f64_daf_vec(&self) -> Result<Vec<f64>, InterpolationError> {
+        let mut data = self.state_data.to_vec();
+        data.extend_from_slice(self.epoch_data);
+        // The directory holds every hundredth epoch, and never the last one.
+        data.extend(
+            self.epoch_data
+                .iter()
+                .skip(99)
+                .step_by(100)
+                .take(self.num_records.saturating_sub(1) / 100),
+        );
+        data.push(self.degree as f64);
+        data.push(self.num_records as f64);
+
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod lagrange_ut {
+    use hifitime::Epoch;
+
+    use crate::{
+        math::interpolation::InterpolationError, naif::daf::NAIFDataSet,
+        naif::spk::summary::SPKSummaryRecord,
+    };
+
+    use super::LagrangeSetType8;
+
+    /// A cubic trajectory, which a degree 3 (or more) Lagrange polynomial interpolates exactly.
+    fn cubic(t: f64) -> [f64; 6] {
+        let x = 1.0 + 2.0 * t - 3e-3 * t.powi(2) + 4e-6 * t.powi(3);
+        let vx = 2.0 - 6e-3 * t + 12e-6 * t.powi(2);
+        [x, -x, 2.0 * x, vx, -vx, 2.0 * vx]
+    }
+
+    #[test]
+    fn type8_equal_step() {
+        let start_et_s = 1e6;
+        let step_s = 60.0;
+        let num_records = 20;
+        let mut data = Vec::new();
+        for i in 0..num_records {
+            data.extend_from_slice(&cubic(i as f64 * step_s));
+        }
+        data.extend_from_slice(&[start_et_s, step_s, 5.0, num_records as f64]);
+
+        let dataset = LagrangeSetType8::from_f64_slice(&data).unwrap();
+        dataset.check_integrity().unwrap();
+        assert_eq!(dataset.degree, 5);
+        assert_eq!(dataset.num_records, num_records);
+
+        let summary = SPKSummaryRecord::default();
+        // Check at the nodes, in between them, and at both edges of the data
+        for t in [0.0, 1.0, 59.0, 90.0, 601.5, 1000.0, 1139.0, 1140.0] {
+            let (pos, vel) = dataset
+                .evaluate(Epoch::from_et_seconds(start_et_s + t), &summary)
+                .unwrap();
+            let expected = cubic(t);
+            for i in 0..3 {
+                assert!((pos[i] - expected[i]).abs() < 1e-9, "{t} s: pos error");
+                assert!((vel[i] - expected[i + 3]).abs() < 1e-12, "{t} s: vel error");
+            }
+        }
+
+        // And outside of the domain
+        assert!(matches!(
+            dataset.evaluate(Epoch::from_et_seconds(start_et_s + 1140.1), &summary),
+            Err(InterpolationError::NoInterpolationData { .. })
+        ));
+        assert!(matches!(
+            dataset.evaluate(Epoch::from_et_seconds(start_et_s - 0.1), &summary),
+            Err(InterpolationError::NoInterpolationData { .. })
+        ));
+    }
 }
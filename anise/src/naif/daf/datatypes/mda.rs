@@ -0,0 +1,745 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+use hifitime::{Epoch, TimeUnits};
+use snafu::{ensure, ResultExt};
+
+use crate::{
+    errors::{DecodingError, IntegrityError, MathError, TooFewDoublesSnafu},
+    math::{
+        interpolation::{InterpDecodingSnafu, InterpolationError, MAX_SAMPLES},
+        Vector3,
+    },
+    naif::daf::{NAIFDataRecord, NAIFDataSet, NAIFSummaryRecord},
+};
+
+/// Maximum dimension of the difference arrays of SPK Type 1 records.
+pub const TYPE1_MAX_DIM: usize = 15;
+
+/// Number of final epochs between two consecutive entries of the epoch directory.
+const DIRECTORY_STEP: usize = 100;
+
+/// Size of an MDA record, in doubles, given the maximum dimension of its difference arrays.
+const fn mda_record_size(max_dim: usize) -> usize {
+    4 * max_dim + 11
+}
+
+/// Modified Difference Array set of SPK Type 1, as produced by the JPL small body integrators.
+#[derive(PartialEq)]
+pub struct MDASetType1<'a> {
+    pub num_records: usize,
+    pub record_data: &'a [f64],
+    pub epoch_data: &'a [f64],
+    pub epoch_registry: &'a [f64],
+}
+
+impl<'a> fmt::Display for MDASetType1<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "MDA Type 1 until {:E} ({} records, {} epoch directories)",
+            Epoch::from_et_seconds(*self.epoch_data.last().unwrap_or(&0.0)),
+            self.num_records,
+            self.epoch_registry.len()
+        )
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for MDASetType1<'a> {
+    type StateKind = (Vector3, Vector3);
+    type RecordKind = MDARecord<'a>;
+    const DATASET_NAME: &'static str = "MDA Type 1";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        // At least one record, its final epoch, and the number of records.
+        let min_len = mda_record_size(TYPE1_MAX_DIM) + 2;
+        ensure!(
+            slice.len() >= min_len,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: min_len,
+                got: slice.len()
+            }
+        );
+
+        // The number of records is the very last item of the dataset
+        let num_records = slice[slice.len() - 1] as usize;
+        let (record_data, epoch_data, epoch_registry) = split_mda_data(
+            Self::DATASET_NAME,
+            &slice[..slice.len() - 1],
+            mda_record_size(TYPE1_MAX_DIM),
+            num_records,
+        )?;
+
+        Ok(Self {
+            num_records,
+            record_data,
+            epoch_data,
+            epoch_registry,
+        })
+    }
+
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        nth_mda_record(self.record_data, mda_record_size(TYPE1_MAX_DIM), n)
+    }
+
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        let idx = mda_record_index(self.epoch_data, epoch, summary)?;
+        self.nth_record(idx)
+            .context(InterpDecodingSnafu)?
+            .evaluate(epoch)
+    }
+
+    fn check_integrity(&self) -> Result<(), IntegrityError> {
+        check_mda_integrity(
+            Self::DATASET_NAME,
+            self.record_data,
+            self.epoch_data,
+            self.epoch_registry,
+        )
+    }
+
+    fn truncate<S: NAIFSummaryRecord>(
+        mut self,
+        summary: &S,
+        new_start: Option<Epoch>,
+        new_end: Option<Epoch>,
+    ) -> Result<Self, InterpolationError> {
+        let (first_idx, last_idx) =
+            mda_truncation_indexes(self.epoch_data, summary, new_start, new_end)?;
+        let rsize = mda_record_size(TYPE1_MAX_DIM);
+
+        self.record_data = &self.record_data[first_idx * rsize..(last_idx + 1) * rsize];
+        self.epoch_data = &self.epoch_data[first_idx..=last_idx];
+        // The directory is rebuilt from the epochs when exporting the data.
+        self.epoch_registry = &[];
+        self.num_records = last_idx - first_idx + 1;
+
+        Ok(self)
+    }
+
+    /// Builds the DAF array representing an MDA Type 1 set, regenerating its epoch directory.
+    fn to_f64_daf_vec(&self) -> Result<Vec<f64>, InterpolationError> {
+        let mut data = mda_daf_vec(self.record_data, self.epoch_data);
+        data.push(self.num_records as f64);
+
+        Ok(data)
+    }
+}
+
+/// Extended Modified Difference Array set of SPK Type 21, whose difference arrays may be larger than those of Type 1.
+#[derive(PartialEq)]
+pub struct MDASetType21<'a> {
+    pub max_dim: usize,
+    pub num_records: usize,
+    pub record_data: &'a [f64],
+    pub epoch_data: &'a [f64],
+    pub epoch_registry: &'a [f64],
+}
+
+impl<'a> MDASetType21<'a> {
+    fn record_size(&self) -> usize {
+        mda_record_size(self.max_dim)
+    }
+}
+
+impl<'a> fmt::Display for MDASetType21<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "MDA Type 21 until {:E} with max dimension {} ({} records, {} epoch directories)",
+            Epoch::from_et_seconds(*self.epoch_data.last().unwrap_or(&0.0)),
+            self.max_dim,
+            self.num_records,
+            self.epoch_registry.len()
+        )
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for MDASetType21<'a> {
+    type StateKind = (Vector3, Vector3);
+    type RecordKind = MDARecord<'a>;
+    const DATASET_NAME: &'static str = "MDA Type 21";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        ensure!(
+            slice.len() >= 2,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: 2_usize,
+                got: slice.len()
+            }
+        );
+
+        // For this kind of record, the metadata is stored at the very end of the dataset
+        let num_records = slice[slice.len() - 1] as usize;
+        let max_dim = slice[slice.len() - 2] as usize;
+
+        if max_dim == 0 || max_dim + 2 > MAX_SAMPLES {
+            return Err(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "maximum difference array dimension",
+                    value: slice[slice.len() - 2],
+                    reason: "must be strictly positive and no greater than 30",
+                },
+            });
+        }
+
+        let (record_data, epoch_data, epoch_registry) = split_mda_data(
+            Self::DATASET_NAME,
+            &slice[..slice.len() - 2],
+            mda_record_size(max_dim),
+            num_records,
+        )?;
+
+        Ok(Self {
+            max_dim,
+            num_records,
+            record_data,
+            epoch_data,
+            epoch_registry,
+        })
+    }
+
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        nth_mda_record(self.record_data, self.record_size(), n)
+    }
+
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        summary: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        let idx = mda_record_index(self.epoch_data, epoch, summary)?;
+        self.nth_record(idx)
+            .context(InterpDecodingSnafu)?
+            .evaluate(epoch)
+    }
+
+    fn check_integrity(&self) -> Result<(), IntegrityError> {
+        check_mda_integrity(
+            Self::DATASET_NAME,
+            self.record_data,
+            self.epoch_data,
+            self.epoch_registry,
+        )
+    }
+
+    fn truncate<S: NAIFSummaryRecord>(
+        mut self,
+        summary: &S,
+        new_start: Option<Epoch>,
+        new_end: Option<Epoch>,
+    ) -> Result<Self, InterpolationError> {
+        let (first_idx, last_idx) =
+            mda_truncation_indexes(self.epoch_data, summary, new_start, new_end)?;
+        let rsize = self.record_size();
+
+        self.record_data = &self.record_data[first_idx * rsize..(last_idx + 1) * rsize];
+        self.epoch_data = &self.epoch_data[first_idx..=last_idx];
+        // The directory is rebuilt from the epochs when exporting the data.
+        self.epoch_registry = &[];
+        self.num_records = last_idx - first_idx + 1;
+
+        Ok(self)
+    }
+
+    /// Builds the DAF array representing an MDA Type 21 set, regenerating its epoch directory.
+    fn to_f64_daf_vec(&self) -> Result<Vec<f64>, InterpolationError> {
+        let mut data = mda_daf_vec(self.record_data, self.epoch_data);
+        data.push(self.max_dim as f64);
+        data.push(self.num_records as f64);
+
+        Ok(data)
+    }
+}
+
+/// A single record of a modified difference array, valid up to its final epoch stored in the epoch data of the set.
+#[derive(PartialEq)]
+pub struct MDARecord<'a> {
+    /// Reference epoch of the difference line, in seconds past J2000 ET.
+    pub ref_epoch_et_s: f64,
+    /// Step size function vector.
+    pub step_sizes: &'a [f64],
+    pub ref_pos_km: Vector3,
+    pub ref_vel_km_s: Vector3,
+    /// Modified divided difference arrays of the X, Y and Z components, in this order.
+    pub diff_table: &'a [f64],
+    /// Maximum integration order plus one.
+    pub kqmax1: usize,
+    /// Integration order of each component.
+    pub kq: [usize; 3],
+}
+
+impl<'a> MDARecord<'a> {
+    /// Maximum dimension of the difference arrays of this record.
+    pub fn max_dim(&self) -> usize {
+        self.step_sizes.len()
+    }
+
+    /// Evaluates the position and velocity at the provided epoch, following the algorithm of SPICE's SPKE21.
+    pub fn evaluate(&self, epoch: Epoch) -> Result<(Vector3, Vector3), InterpolationError> {
+        let max_dim = self.max_dim();
+        if self.kqmax1 < 2 || self.kqmax1 > max_dim + 1 || self.kqmax1 > MAX_SAMPLES {
+            return Err(InterpolationError::CorruptedData {
+                what: "maximum integration order of MDA record is invalid",
+            });
+        }
+        if self.kq.iter().any(|kq| *kq >= self.kqmax1) {
+            return Err(InterpolationError::CorruptedData {
+                what: "integration order of MDA record exceeds its maximum",
+            });
+        }
+
+        let delta_s = epoch.to_et_seconds() - self.ref_epoch_et_s;
+
+        // Workspace arrays: the indexing here is zero based, unlike the FORTRAN implementation.
+        let mut fc = [0.0_f64; MAX_SAMPLES];
+        let mut wc = [0.0_f64; MAX_SAMPLES];
+        let mut w = [0.0_f64; MAX_SAMPLES];
+
+        fc[0] = 1.0;
+        let mut tp = delta_s;
+        for j in 1..=self.kqmax1 - 2 {
+            let step_s = self.step_sizes[j - 1];
+            if step_s == 0.0 {
+                return Err(InterpolationError::InterpMath {
+                    source: MathError::DivisionByZero {
+                        action: "step size in MDA record is zero",
+                    },
+                });
+            }
+            fc[j] = tp / step_s;
+            wc[j - 1] = delta_s / step_s;
+            tp = delta_s + step_s;
+        }
+
+        for (j, wj) in w.iter_mut().enumerate().take(self.kqmax1) {
+            *wj = 1.0 / (j + 1) as f64;
+        }
+
+        // Compute the W(K) terms needed for the position interpolation.
+        let mut jx = 0;
+        let mut ks = self.kqmax1 - 1;
+        let mut ks1 = ks - 1;
+        while ks >= 2 {
+            jx += 1;
+            for j in 1..=jx {
+                w[j + ks - 1] = fc[j] * w[j + ks1 - 1] - wc[j - 1] * w[j + ks - 1];
+            }
+            ks = ks1;
+            ks1 -= 1;
+        }
+
+        let mut pos_km = Vector3::zeros();
+        for i in 0..3 {
+            let sum: f64 = (1..=self.kq[i])
+                .rev()
+                .map(|j| self.diff_table[i * max_dim + j - 1] * w[j])
+                .sum();
+            pos_km[i] = self.ref_pos_km[i] + delta_s * (self.ref_vel_km_s[i] + delta_s * sum);
+        }
+
+        // Update the W(K) terms once more for the velocity interpolation.
+        for j in 1..=jx {
+            w[j] = fc[j] * w[j - 1] - wc[j - 1] * w[j];
+        }
+
+        let mut vel_km_s = Vector3::zeros();
+        for i in 0..3 {
+            let sum: f64 = (1..=self.kq[i])
+                .rev()
+                .map(|j| self.diff_table[i * max_dim + j - 1] * w[j - 1])
+                .sum();
+            vel_km_s[i] = self.ref_vel_km_s[i] + delta_s * sum;
+        }
+
+        Ok((pos_km, vel_km_s))
+    }
+}
+
+impl<'a> fmt::Display for MDARecord<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "reference: {:E}\tposition: {}\tvelocity: {}\tkqmax1: {}\tkq: {:?}",
+            Epoch::from_et_seconds(self.ref_epoch_et_s),
+            self.ref_pos_km,
+            self.ref_vel_km_s,
+            self.kqmax1,
+            self.kq
+        )
+    }
+}
+
+impl<'a> NAIFDataRecord<'a> for MDARecord<'a> {
+    fn from_slice_f64(slice: &'a [f64]) -> Self {
+        let max_dim = (slice.len() - 11) / 4;
+        let refs = &slice[max_dim + 1..max_dim + 7];
+        Self {
+            ref_epoch_et_s: slice[0],
+            step_sizes: &slice[1..max_dim + 1],
+            // The reference position and velocity are interleaved.
+            ref_pos_km: Vector3::new(refs[0], refs[2], refs[4]),
+            ref_vel_km_s: Vector3::new(refs[1], refs[3], refs[5]),
+            diff_table: &slice[max_dim + 7..4 * max_dim + 7],
+            kqmax1: slice[4 * max_dim + 7] as usize,
+            kq: [
+                slice[4 * max_dim + 8] as usize,
+                slice[4 * max_dim + 9] as usize,
+                slice[4 * max_dim + 10] as usize,
+            ],
+        }
+    }
+}
+
+/// Records, final epochs of each record, and epoch directory of an MDA set.
+type MDAData<'a> = (&'a [f64], &'a [f64], &'a [f64]);
+
+/// Splits the MDA data, without its trailing metadata, into the records, their final epochs, and the epoch directory.
+fn split_mda_data<'a>(
+    dataset: &'static str,
+    slice: &'a [f64],
+    rsize: usize,
+    num_records: usize,
+) -> Result<MDAData<'a>, DecodingError> {
+    let epoch_data_end_idx = (rsize + 1) * num_records;
+    let epoch_registry_end_idx = epoch_data_end_idx + num_records / DIRECTORY_STEP;
+    ensure!(
+        slice.len() >= epoch_registry_end_idx,
+        TooFewDoublesSnafu {
+            dataset,
+            need: epoch_registry_end_idx,
+            got: slice.len()
+        }
+    );
+
+    Ok((
+        &slice[..rsize * num_records],
+        &slice[rsize * num_records..epoch_data_end_idx],
+        &slice[epoch_data_end_idx..epoch_registry_end_idx],
+    ))
+}
+
+fn nth_mda_record<'a>(
+    record_data: &'a [f64],
+    rsize: usize,
+    n: usize,
+) -> Result<MDARecord<'a>, DecodingError> {
+    Ok(MDARecord::from_slice_f64(
+        record_data
+            .get(n * rsize..(n + 1) * rsize)
+            .ok_or(DecodingError::InaccessibleBytes {
+                start: n * rsize,
+                end: (n + 1) * rsize,
+                size: record_data.len(),
+            })?,
+    ))
+}
+
+/// Returns the index of the record to use at the provided epoch, i.e. the first one whose final epoch is not before it.
+fn mda_record_index<S: NAIFSummaryRecord>(
+    epoch_data: &[f64],
+    epoch: Epoch,
+    summary: &S,
+) -> Result<usize, InterpolationError> {
+    if epoch < summary.start_epoch() - 1_i64.nanoseconds()
+        || epoch > summary.end_epoch() + 1_i64.nanoseconds()
+        || epoch_data.is_empty()
+    {
+        return Err(InterpolationError::NoInterpolationData {
+            req: epoch,
+            start: summary.start_epoch(),
+            end: summary.end_epoch(),
+        });
+    }
+
+    let epoch_et_s = epoch.to_et_seconds();
+    let idx = epoch_data.partition_point(|final_et_s| *final_et_s < epoch_et_s);
+
+    Ok(idx.min(epoch_data.len() - 1))
+}
+
+fn mda_truncation_indexes<S: NAIFSummaryRecord>(
+    epoch_data: &[f64],
+    summary: &S,
+    new_start: Option<Epoch>,
+    new_end: Option<Epoch>,
+) -> Result<(usize, usize), InterpolationError> {
+    let first_idx = match new_start {
+        Some(start) => mda_record_index(epoch_data, start, summary)?,
+        None => 0,
+    };
+
+    let last_idx = match new_end {
+        Some(end) => mda_record_index(epoch_data, end, summary)?,
+        None => epoch_data.len().saturating_sub(1),
+    };
+
+    if first_idx > last_idx {
+        return Err(InterpolationError::CorruptedData {
+            what: "truncated MDA data would have no record",
+        });
+    }
+
+    Ok((first_idx, last_idx))
+}
+
+fn check_mda_integrity(
+    dataset: &'static str,
+    record_data: &[f64],
+    epoch_data: &[f64],
+    epoch_registry: &[f64],
+) -> Result<(), IntegrityError> {
+    for val in epoch_data {
+        if !val.is_finite() {
+            return Err(IntegrityError::SubNormal {
+                dataset,
+                variable: "one of the epoch data",
+            });
+        }
+    }
+
+    if epoch_data.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err(IntegrityError::InvalidValue {
+            dataset,
+            variable: "final epochs of the records",
+            value: 0.0,
+            reason: "must be strictly increasing",
+        });
+    }
+
+    for val in epoch_registry {
+        if !val.is_finite() {
+            return Err(IntegrityError::SubNormal {
+                dataset,
+                variable: "one of the epoch registry data",
+            });
+        }
+    }
+
+    for val in record_data {
+        if !val.is_finite() {
+            return Err(IntegrityError::SubNormal {
+                dataset,
+                variable: "one of the record data",
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Rebuilds the records, final epochs, and epoch directory of an MDA set, without its trailing metadata.
+fn mda_daf_vec(record_data: &[f64], epoch_data: &[f64]) -> Vec<f64> {
+    let mut data = record_data.to_vec();
+    data.extend_from_slice(epoch_data);
+    data.extend(
+        epoch_data
+            .iter()
+            .skip(DIRECTORY_STEP - 1)
+            .step_by(DIRECTORY_STEP),
+    );
+    data
+}
+
+#[cfg(test)]
+mod mda_ut {
+    use hifitime::Epoch;
+
+    use crate::{
+        errors::{DecodingError, IntegrityError},
+        naif::{daf::NAIFDataSet, spk::summary::SPKSummaryRecord},
+    };
+
+    use super::{MDASetType1, MDASetType21, TYPE1_MAX_DIM};
+
+    /// Builds an MDA record of a motion with a constant jerk, i.e. of integration order two.
+    fn cubic_record(max_dim: usize, ref_et_s: f64, acc: [f64; 3], jerk: [f64; 3]) -> Vec<f64> {
+        let step_s = 100.0;
+        let mut record = vec![ref_et_s];
+        record.extend((0..max_dim).map(|i| step_s * (i + 1) as f64));
+        record.extend_from_slice(&[1.0, 0.1, 2.0, 0.2, 3.0, 0.3]);
+        for i in 0..3 {
+            let mut diffs = vec![0.0; max_dim];
+            diffs[0] = acc[i];
+            diffs[1] = jerk[i] * step_s;
+            record.extend(diffs);
+        }
+        record.extend_from_slice(&[3.0, 2.0, 2.0, 2.0]);
+        record
+    }
+
+    fn expected(delta_s: f64, acc: [f64; 3], jerk: [f64; 3]) -> [f64; 6] {
+        let (pos, vel) = ([1.0, 2.0, 3.0], [0.1, 0.2, 0.3]);
+        let mut state = [0.0; 6];
+        for i in 0..3 {
+            state[i] = pos[i]
+                + vel[i] * delta_s
+                + acc[i] * delta_s.powi(2) / 2.0
+                + jerk[i] * delta_s.powi(3) / 6.0;
+            state[i + 3] = vel[i] + acc[i] * delta_s + jerk[i] * delta_s.powi(2) / 2.0;
+        }
+        state
+    }
+
+    #[test]
+    fn type1_eval() {
+        let (acc0, jerk0) = ([1e-3, -2e-3, 3e-4], [1e-6, 2e-7, -3e-6]);
+        let (acc1, jerk1) = ([-1e-3, 5e-4, 0.0], [0.0, -1e-6, 4e-7]);
+
+        let mut data = cubic_record(TYPE1_MAX_DIM, 0.0, acc0, jerk0);
+        data.extend(cubic_record(TYPE1_MAX_DIM, 1000.0, acc1, jerk1));
+        // Final epochs of each record, no directory, and the number of records.
+        data.extend_from_slice(&[1000.0, 2000.0, 2.0]);
+
+        let dataset = MDASetType1::from_f64_slice(&data).unwrap();
+        dataset.check_integrity().unwrap();
+        assert_eq!(dataset.num_records, 2);
+
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 2000.0,
+            ..Default::default()
+        };
+
+        for (et_s, delta_s, acc, jerk) in [
+            (0.0, 0.0, acc0, jerk0),
+            (500.0, 500.0, acc0, jerk0),
+            // The final epoch of a record is still evaluated with that record
+            (1000.0, 1000.0, acc0, jerk0),
+            (1500.5, 500.5, acc1, jerk1),
+            (2000.0, 1000.0, acc1, jerk1),
+        ] {
+            let (pos, vel) = dataset
+                .evaluate(Epoch::from_et_seconds(et_s), &summary)
+                .unwrap();
+            let expected = expected(delta_s, acc, jerk);
+            for i in 0..3 {
+                assert!((pos[i] - expected[i]).abs() < 1e-9, "{et_s} s: pos error");
+                assert!(
+                    (vel[i] - expected[i + 3]).abs() < 1e-12,
+                    "{et_s} s: vel error"
+                );
+            }
+        }
+
+        assert!(dataset
+            .evaluate(Epoch::from_et_seconds(2000.1), &summary)
+            .is_err());
+
+        assert_eq!(dataset.to_f64_daf_vec().unwrap(), data);
+
+        // Truncation only keeps the records needed for the new bounds
+        let truncated = MDASetType1::from_f64_slice(&data)
+            .unwrap()
+            .truncate(&summary, Some(Epoch::from_et_seconds(1200.0)), None)
+            .unwrap();
+        assert_eq!(truncated.num_records, 1);
+        assert_eq!(truncated.epoch_data, &[2000.0]);
+        assert_eq!(
+            truncated.nth_record(0).unwrap().ref_epoch_et_s,
+            1000.0,
+            "wrong record kept"
+        );
+    }
+
+    #[test]
+    fn type21_eval() {
+        let max_dim = 4;
+        let (acc, jerk) = ([2e-3, 1e-4, -5e-4], [-1e-6, 3e-6, 1e-7]);
+
+        let mut data = cubic_record(max_dim, 500.0, acc, jerk);
+        data.extend_from_slice(&[1000.0, max_dim as f64, 1.0]);
+
+        let dataset = MDASetType21::from_f64_slice(&data).unwrap();
+        dataset.check_integrity().unwrap();
+        assert_eq!(dataset.max_dim, max_dim);
+
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 1000.0,
+            ..Default::default()
+        };
+
+        // Difference lines can be evaluated on either side of their reference epoch
+        for et_s in [0.0, 250.0, 500.0, 999.0] {
+            let (pos, vel) = dataset
+                .evaluate(Epoch::from_et_seconds(et_s), &summary)
+                .unwrap();
+            let expected = expected(et_s - 500.0, acc, jerk);
+            for i in 0..3 {
+                assert!((pos[i] - expected[i]).abs() < 1e-9, "{et_s} s: pos error");
+                assert!(
+                    (vel[i] - expected[i + 3]).abs() < 1e-12,
+                    "{et_s} s: vel error"
+                );
+            }
+        }
+
+        assert_eq!(dataset.to_f64_daf_vec().unwrap(), data);
+
+        // Unsupported dimensions of the difference arrays
+        assert_eq!(
+            MDASetType21::from_f64_slice(&[0.0, 1.0]).err(),
+            Some(DecodingError::Integrity {
+                source: IntegrityError::InvalidValue {
+                    dataset: "MDA Type 21",
+                    variable: "maximum difference array dimension",
+                    value: 0.0,
+                    reason: "must be strictly positive and no greater than 30",
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn epoch_directory() {
+        let max_dim = 4;
+        let num_records = 250;
+        let mut data = Vec::new();
+        for _ in 0..num_records {
+            data.extend(cubic_record(max_dim, 0.0, [0.0; 3], [0.0; 3]));
+        }
+        let epochs: Vec<f64> = (1..=num_records).map(|i| i as f64).collect();
+        data.extend_from_slice(&epochs);
+        // Every 100th final epoch is in the directory
+        data.extend_from_slice(&[100.0, 200.0]);
+        data.extend_from_slice(&[max_dim as f64, num_records as f64]);
+
+        let dataset = MDASetType21::from_f64_slice(&data).unwrap();
+        dataset.check_integrity().unwrap();
+        assert_eq!(dataset.epoch_registry, &[100.0, 200.0]);
+        assert_eq!(dataset.to_f64_daf_vec().unwrap(), data);
+
+        // Truncated data rebuilds its directory
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: num_records as f64,
+            ..Default::default()
+        };
+        let truncated = dataset
+            .truncate(&summary, Some(Epoch::from_et_seconds(50.5)), None)
+            .unwrap();
+        assert_eq!(truncated.num_records, 200);
+        let truncated_data = truncated.to_f64_daf_vec().unwrap();
+        let epoch_registry_idx = truncated_data.len() - 4;
+        assert_eq!(
+            truncated_data[epoch_registry_idx..],
+            [150.0, 250.0, max_dim as f64, 200.0]
+        );
+    }
+}
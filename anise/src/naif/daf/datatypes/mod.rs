@@ -8,11 +8,21 @@
  * Documentation: https://nyxspace.com/
  */
 
+pub mod attitude;
 pub mod chebyshev;
+pub mod conics;
+pub mod esoc;
 pub mod hermite;
 pub mod lagrange;
+pub mod mda;
 pub mod posvel;
+pub mod tle;
 
+pub use attitude::*;
 pub use chebyshev::*;
+pub use conics::*;
+pub use esoc::*;
 pub use hermite::*;
 pub use lagrange::*;
+pub use mda::*;
+pub use tle::*;
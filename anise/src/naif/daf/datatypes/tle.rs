@@ -0,0 +1,496 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::f64::consts::PI;
+use core::fmt;
+use hifitime::Epoch;
+use snafu::{ensure, ResultExt};
+
+use crate::{
+    astro::tle::{true_of_date_to_j2000, TLE},
+    errors::{DecodingError, IntegrityError, TooFewDoublesSnafu},
+    math::{
+        interpolation::{
+            hermite_eval, InterpDecodingSnafu, InterpPhysicsSnafu, InterpolationError,
+        },
+        Vector3,
+    },
+    naif::daf::{NAIFDataRecord, NAIFDataSet, NAIFSummaryRecord},
+};
+
+/// Number of metadata items of the generic segments written by SPICE
+const GENERIC_SEGMENT_NMETA: usize = 17;
+/// Number of geophysical constants stored in each Type 10 segment: J2, J3, J4, KE, QO, SO, ER, AE.
+const TYPE10_NUM_CONSTANTS: usize = 8;
+/// Number of doubles in each packet: the ten elements and the four nutation angles and rates.
+const TYPE10_PACKET_SIZE: usize = 14;
+
+/// Space Command two-line elements of SPK Type 10, propagated with SGP4/SDP4.
+///
+/// Type 10 segments are stored as SPICE generic segments, whose metadata is found at the end of the segment.
+/// The packets store the mean elements, which are propagated in the TEME frame before being rotated into J2000
+/// with the nutation angles stored alongside the elements. As in SPICE, the equation of the equinoxes is not applied.
+#[derive(PartialEq)]
+pub struct TLESetType10<'a> {
+    /// Geophysical constants of the segment: J2, J3, J4, KE, QO, SO, ER, AE
+    pub constants: &'a [f64],
+    pub num_records: usize,
+    pub packet_size: usize,
+    pub packet_data: &'a [f64],
+    /// Epochs of each of the element sets, in TDB seconds past J2000, in increasing order.
+    pub epoch_data: &'a [f64],
+}
+
+impl<'a> TLESetType10<'a> {
+    /// Returns the SGP4 propagator of the provided element set, initialized with the constants of this segment.
+    fn propagator(&self, record: &TLERecord) -> Result<TLE, InterpolationError> {
+        if self.constants.len() < TYPE10_NUM_CONSTANTS {
+            return Err(InterpolationError::CorruptedData {
+                what: "fewer than eight geophysical constants",
+            });
+        }
+
+        TLE::from_kozai_elements(
+            Epoch::from_et_seconds(record.epoch_et_s),
+            sgp4::Geopotential {
+                ae: self.constants[6],
+                ke: self.constants[3],
+                j2: self.constants[0],
+                j3: self.constants[1],
+                j4: self.constants[2],
+            },
+            record.bstar,
+            record.inclination_rad,
+            record.raan_rad,
+            record.eccentricity,
+            record.aop_rad,
+            record.mean_anomaly_rad,
+            record.mean_motion_rad_min,
+        )
+        .context(InterpPhysicsSnafu)
+    }
+
+    /// Returns the TEME state from the provided element set at the requested epoch.
+    fn teme_state(
+        &self,
+        record: &TLERecord,
+        epoch: Epoch,
+    ) -> Result<(Vector3, Vector3), InterpolationError> {
+        self.propagator(record)?
+            .teme_parts(epoch)
+            .context(InterpPhysicsSnafu)
+    }
+}
+
+impl<'a> fmt::Display for TLESetType10<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Type 10 TLE set with {} element sets from {} to {}",
+            self.num_records,
+            Epoch::from_et_seconds(*self.epoch_data.first().unwrap_or(&0.0)),
+            Epoch::from_et_seconds(*self.epoch_data.last().unwrap_or(&0.0)),
+        )
+    }
+}
+
+impl<'a> NAIFDataSet<'a> for TLESetType10<'a> {
+    type StateKind = (Vector3, Vector3);
+    type RecordKind = TLERecord;
+    const DATASET_NAME: &'static str = "Space Command TLE Type 10";
+
+    fn from_f64_slice(slice: &'a [f64]) -> Result<Self, DecodingError> {
+        ensure!(
+            slice.len() >= GENERIC_SEGMENT_NMETA,
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: GENERIC_SEGMENT_NMETA,
+                got: slice.len()
+            }
+        );
+
+        // The number of metadata items is the last value of the segment, and the metadata immediately precedes it.
+        let nmeta = slice[slice.len() - 1] as usize;
+        ensure!(
+            (GENERIC_SEGMENT_NMETA..=slice.len()).contains(&nmeta),
+            TooFewDoublesSnafu {
+                dataset: Self::DATASET_NAME,
+                need: nmeta.max(GENERIC_SEGMENT_NMETA),
+                got: slice.len()
+            }
+        );
+        let meta = &slice[slice.len() - nmeta..];
+
+        let (const_base, num_constants) = (meta[0] as usize, meta[1] as usize);
+        let (ref_base, num_refs) = (meta[5] as usize, meta[6] as usize);
+        let (pkt_base, num_records) = (meta[10] as usize, meta[11] as usize);
+        let (packet_size, pkt_offset) = (meta[14] as usize, meta[15] as usize);
+
+        let get = |start: usize, len: usize| {
+            slice
+                .get(start..start + len)
+                .ok_or(DecodingError::InaccessibleBytes {
+                    start,
+                    end: start + len,
+                    size: slice.len(),
+                })
+        };
+
+        Ok(Self {
+            constants: get(const_base, num_constants)?,
+            num_records,
+            packet_size,
+            packet_data: get(pkt_base + pkt_offset, num_records * packet_size)?,
+            epoch_data: get(ref_base, num_refs)?,
+        })
+    }
+
+    fn nth_record(&self, n: usize) -> Result<Self::RecordKind, DecodingError> {
+        let start = n * self.packet_size;
+        let end = start + self.packet_size;
+        Ok(Self::RecordKind::from_slice_f64(
+            self.packet_data
+                .get(start..end)
+                .ok_or(DecodingError::InaccessibleBytes {
+                    start,
+                    end,
+                    size: self.packet_data.len(),
+                })?,
+        ))
+    }
+
+    fn evaluate<S: NAIFSummaryRecord>(
+        &self,
+        epoch: Epoch,
+        _: &S,
+    ) -> Result<Self::StateKind, InterpolationError> {
+        if self.epoch_data.is_empty() {
+            return Err(InterpolationError::MissingInterpolationData { epoch });
+        }
+
+        let et_s = epoch.to_et_seconds();
+
+        // Use a single element set before the first epoch, after the last one, and exactly on any epoch.
+        // Otherwise, the states of the two surrounding sets are blended.
+        let (first_idx, second_idx) = match self.epoch_data.binary_search_by(|e| e.total_cmp(&et_s))
+        {
+            Ok(idx) => (idx, None),
+            Err(0) => (0, None),
+            Err(idx) if idx == self.epoch_data.len() => (idx - 1, None),
+            Err(idx) => (idx - 1, Some(idx)),
+        };
+
+        let first = self.nth_record(first_idx).context(InterpDecodingSnafu)?;
+        let (mut pos_km, mut vel_km_s) = self.teme_state(&first, epoch)?;
+
+        let (dpsi_rad, deps_rad) = match second_idx {
+            None => {
+                let dt_s = et_s - first.epoch_et_s;
+                (
+                    first.dpsi_rad + first.dpsi_dot_rad_s * dt_s,
+                    first.deps_rad + first.deps_dot_rad_s * dt_s,
+                )
+            }
+            Some(second_idx) => {
+                let second = self.nth_record(second_idx).context(InterpDecodingSnafu)?;
+                let (pos2_km, vel2_km_s) = self.teme_state(&second, epoch)?;
+
+                // Cosine weighting of both states, whose derivative contributes to the velocity.
+                let span_s = second.epoch_et_s - first.epoch_et_s;
+                let arg = PI * (et_s - first.epoch_et_s) / span_s;
+                let w = 0.5 + 0.5 * arg.cos();
+                let dw_dt = -0.5 * arg.sin() * PI / span_s;
+
+                vel_km_s = w * vel_km_s + (1.0 - w) * vel2_km_s + dw_dt * (pos_km - pos2_km);
+                pos_km = w * pos_km + (1.0 - w) * pos2_km;
+
+                let epochs = [first.epoch_et_s, second.epoch_et_s];
+                let (dpsi_rad, _) = hermite_eval(
+                    &epochs,
+                    &[first.dpsi_rad, second.dpsi_rad],
+                    &[first.dpsi_dot_rad_s, second.dpsi_dot_rad_s],
+                    et_s,
+                )?;
+                let (deps_rad, _) = hermite_eval(
+                    &epochs,
+                    &[first.deps_rad, second.deps_rad],
+                    &[first.deps_dot_rad_s, second.deps_dot_rad_s],
+                    et_s,
+                )?;
+                (dpsi_rad, deps_rad)
+            }
+        };
+
+        let rot_mat = true_of_date_to_j2000(epoch, dpsi_rad, deps_rad);
+
+        Ok((rot_mat * pos_km, rot_mat * vel_km_s))
+    }
+
+    fn check_integrity(&self) -> Result<(), IntegrityError> {
+        if self.constants.len() != TYPE10_NUM_CONSTANTS {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "number of geophysical constants",
+                value: self.constants.len() as f64,
+                reason: "must be 8",
+            });
+        }
+
+        if self.packet_size != TYPE10_PACKET_SIZE {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "packet size",
+                value: self.packet_size as f64,
+                reason: "must be 14",
+            });
+        }
+
+        if self.epoch_data.len() != self.num_records {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "number of epochs",
+                value: self.epoch_data.len() as f64,
+                reason: "must match the number of element sets",
+            });
+        }
+
+        for val in self
+            .constants
+            .iter()
+            .chain(self.packet_data)
+            .chain(self.epoch_data)
+        {
+            if !val.is_finite() {
+                return Err(IntegrityError::SubNormal {
+                    dataset: Self::DATASET_NAME,
+                    variable: "one of the constants, elements, or epochs",
+                });
+            }
+        }
+
+        if self.epoch_data.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(IntegrityError::InvalidValue {
+                dataset: Self::DATASET_NAME,
+                variable: "epochs",
+                value: 0.0,
+                reason: "must be strictly increasing",
+            });
+        }
+
+        for n in 0..self.num_records {
+            let record = self
+                .nth_record(n)
+                .map_err(|_| IntegrityError::DataMissing)?;
+            if !(0.0..1.0).contains(&record.eccentricity) {
+                return Err(IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "eccentricity",
+                    value: record.eccentricity,
+                    reason: "must be within [0, 1)",
+                });
+            }
+            if record.mean_motion_rad_min <= 0.0 {
+                return Err(IntegrityError::InvalidValue {
+                    dataset: Self::DATASET_NAME,
+                    variable: "mean motion",
+                    value: record.mean_motion_rad_min,
+                    reason: "must be strictly positive",
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A packet of a Type 10 segment: the mean elements of a TLE, followed by the nutation angles at the epoch of the elements.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct TLERecord {
+    /// First derivative of the mean motion divided by two, in radians per minute squared (unused by SGP4)
+    pub ndt20: f64,
+    /// Second derivative of the mean motion divided by six, in radians per minute cubed (unused by SGP4)
+    pub ndd60: f64,
+    /// Drag term, in inverse Earth radii
+    pub bstar: f64,
+    pub inclination_rad: f64,
+    pub raan_rad: f64,
+    pub eccentricity: f64,
+    pub aop_rad: f64,
+    pub mean_anomaly_rad: f64,
+    pub mean_motion_rad_min: f64,
+    /// Epoch of the elements, in TDB seconds past J2000
+    pub epoch_et_s: f64,
+    pub dpsi_rad: f64,
+    pub deps_rad: f64,
+    pub dpsi_dot_rad_s: f64,
+    pub deps_dot_rad_s: f64,
+}
+
+impl fmt::Display for TLERecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl<'a> NAIFDataRecord<'a> for TLERecord {
+    fn from_slice_f64(slice: &'a [f64]) -> Self {
+        let val = |idx: usize| slice.get(idx).copied().unwrap_or(0.0);
+        Self {
+            ndt20: val(0),
+            ndd60: val(1),
+            bstar: val(2),
+            inclination_rad: val(3),
+            raan_rad: val(4),
+            eccentricity: val(5),
+            aop_rad: val(6),
+            mean_anomaly_rad: val(7),
+            mean_motion_rad_min: val(8),
+            epoch_et_s: val(9),
+            dpsi_rad: val(10),
+            deps_rad: val(11),
+            dpsi_dot_rad_s: val(12),
+            deps_dot_rad_s: val(13),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tle_type10_ut {
+    use super::*;
+    use crate::astro::tle::nutation_iau1980;
+    use crate::naif::spk::summary::SPKSummaryRecord;
+    use hifitime::Unit;
+
+    /// WGS72 constants as written by SPICE: J2, J3, J4, KE, QO, SO, ER, AE
+    const GEOPHS: [f64; 8] = [
+        1.082616e-3,
+        -2.53881e-6,
+        -1.65597e-6,
+        7.43669161e-2,
+        120.0,
+        78.0,
+        6378.135,
+        1.0,
+    ];
+
+    /// Builds the packet of the ISS elements at the provided epoch (mean anomaly shifted to remain on the same orbit)
+    fn iss_packet(epoch: Epoch, mean_anomaly_deg: f64) -> Vec<f64> {
+        let (dpsi, deps) = nutation_iau1980(epoch);
+        vec![
+            0.0,
+            0.0,
+            -0.11606e-4,
+            51.6416_f64.to_radians(),
+            247.4627_f64.to_radians(),
+            0.0006703,
+            130.5360_f64.to_radians(),
+            mean_anomaly_deg.to_radians(),
+            15.72125391 * 2.0 * PI / 1440.0,
+            epoch.to_et_seconds(),
+            dpsi,
+            deps,
+            0.0,
+            0.0,
+        ]
+    }
+
+    /// Builds a generic segment with the constants, the packets, the epochs, and the metadata.
+    fn segment(packets: &[Vec<f64>]) -> Vec<f64> {
+        let mut data = GEOPHS.to_vec();
+        let pkt_base = data.len();
+        for packet in packets {
+            data.extend(packet);
+        }
+        let ref_base = data.len();
+        for packet in packets {
+            data.push(packet[9]);
+        }
+        let end = data.len() as f64;
+        let num = packets.len() as f64;
+        data.extend([
+            0.0,
+            8.0,
+            end,
+            0.0,
+            1.0,
+            ref_base as f64,
+            num,
+            end,
+            0.0,
+            1.0,
+            pkt_base as f64,
+            num,
+            end,
+            0.0,
+            14.0,
+            0.0,
+            17.0,
+        ]);
+        data
+    }
+
+    #[test]
+    fn type10_evaluation() {
+        let epoch1 = Epoch::from_gregorian_utc_at_midnight(2008, 9, 20);
+        let epoch2 = epoch1 + Unit::Hour * 6;
+        let data = segment(&[iss_packet(epoch1, 325.0288), iss_packet(epoch2, 100.0)]);
+
+        let dataset = TLESetType10::from_f64_slice(&data).unwrap();
+        dataset.check_integrity().unwrap();
+        assert_eq!(dataset.num_records, 2);
+        assert_eq!(dataset.constants, &GEOPHS);
+        assert_eq!(dataset.epoch_data.len(), 2);
+
+        let summary = SPKSummaryRecord::default();
+
+        // Exactly on the first epoch, only the first element set is used.
+        let (pos_km, vel_km_s) = dataset.evaluate(epoch1, &summary).unwrap();
+        let first = dataset.nth_record(0).unwrap();
+        let (teme_pos_km, teme_vel_km_s) = dataset.teme_state(&first, epoch1).unwrap();
+        assert!((pos_km.norm() - teme_pos_km.norm()).abs() < 1e-9);
+        assert!((vel_km_s.norm() - teme_vel_km_s.norm()).abs() < 1e-12);
+        assert!((pos_km.norm() - 6720.0).abs() < 30.0);
+
+        // Between both sets, the state is continuous with the one computed at the boundaries.
+        let (pos_km, _) = dataset
+            .evaluate(epoch2 - Unit::Second * 1, &summary)
+            .unwrap();
+        let (pos2_km, _) = dataset.evaluate(epoch2, &summary).unwrap();
+        assert!((pos_km - pos2_km).norm() < 10.0);
+
+        // And the blended velocity matches the finite difference of the positions.
+        let mid = epoch1 + Unit::Hour * 2;
+        let (pos_a_km, _) = dataset.evaluate(mid - Unit::Second * 1, &summary).unwrap();
+        let (pos_b_km, vel_km_s) = dataset.evaluate(mid, &summary).unwrap();
+        assert!((pos_b_km - pos_a_km - vel_km_s).norm() < 1e-2);
+
+        // Before and after the coverage, the closest set is propagated.
+        assert!(dataset.evaluate(epoch1 - Unit::Hour * 1, &summary).is_ok());
+        assert!(dataset.evaluate(epoch2 + Unit::Hour * 1, &summary).is_ok());
+    }
+
+    #[test]
+    fn type10_integrity() {
+        let epoch1 = Epoch::from_gregorian_utc_at_midnight(2008, 9, 20);
+        let mut packet = iss_packet(epoch1, 325.0288);
+        packet[5] = 1.5;
+        let data = segment(&[packet]);
+        let dataset = TLESetType10::from_f64_slice(&data).unwrap();
+        assert!(matches!(
+            dataset.check_integrity(),
+            Err(IntegrityError::InvalidValue {
+                variable: "eccentricity",
+                ..
+            })
+        ));
+
+        assert!(TLESetType10::from_f64_slice(&[0.0; 10]).is_err());
+    }
+}
@@ -0,0 +1,175 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use std::collections::HashSet;
+
+use log::info;
+use zerocopy::FromBytes;
+
+use super::{FileRecord, NAIFRecord, SummaryRecord, RCRD_LEN};
+use crate::{naif::Endian, parse_bytes_as, DBL_SIZE};
+
+/// Offsets of the integers of the file record: ND, NI, FWARD, BWARD and FREE.
+const FILE_RECORD_INTS: [usize; 5] = [8, 12, 76, 80, 84];
+/// Offset of the endian flag in the file record.
+const ENDIAN_STR_OFFSET: usize = 88;
+
+/// Returns a copy of these DAF bytes in the endianness of this platform, or None if they already are (or are not a valid DAF).
+pub(crate) fn native_endian_bytes(bytes: &[u8]) -> Option<Vec<u8>> {
+    let file_record = FileRecord::read_from(bytes.get(..FileRecord::SIZE)?)?;
+    let file_endian = file_record.file_endianness().ok()?;
+
+    if file_endian == Endian::f64_native() && file_endian == Endian::u64_native() {
+        None
+    } else {
+        info!("converting {file_endian:?} endian DAF to the endianness of this platform");
+        Some(swap_endianness(bytes, file_endian))
+    }
+}
+
+/// Byte-swaps all of the binary data of DAF bytes written in the `source` endianness, returning the same DAF in the
+/// opposite endianness.
+///
+/// The DAF is not decoded: the integers of the file record, the doubles and integers of the summary records, and every
+/// double of the data records are swapped, whereas the comments and the names are left untouched.
+pub(crate) fn swap_endianness(bytes: &[u8], source: Endian) -> Vec<u8> {
+    let mut swapped = bytes.to_vec();
+
+    let read_u32 = |offset: usize| parse_bytes_as!(u32, &bytes[offset..], source) as usize;
+    let (nd, ni, fwrd_idx) = (read_u32(8), read_u32(12), read_u32(76));
+
+    for offset in FILE_RECORD_INTS {
+        swapped[offset..offset + 4].reverse();
+    }
+    swapped[ENDIAN_STR_OFFSET..ENDIAN_STR_OFFSET + 8].copy_from_slice(match source {
+        Endian::Little => b"BIG-IEEE",
+        Endian::Big => b"LTL-IEEE",
+    });
+
+    // Follow the linked list of summary records, each of which is followed by its name record.
+    let summary_size = nd + ni.div_ceil(2);
+    let mut not_data = HashSet::new();
+    let mut rcrd_idx = fwrd_idx;
+    while rcrd_idx > 0 && rcrd_idx * RCRD_LEN <= bytes.len() && not_data.insert(rcrd_idx) {
+        not_data.insert(rcrd_idx + 1);
+
+        let start = (rcrd_idx - 1) * RCRD_LEN;
+        let rcrd = &mut swapped[start..start + RCRD_LEN];
+        for word in rcrd[..SummaryRecord::SIZE].chunks_exact_mut(DBL_SIZE) {
+            word.reverse();
+        }
+
+        if summary_size > 0 {
+            for summary in rcrd[SummaryRecord::SIZE..].chunks_exact_mut(summary_size * DBL_SIZE) {
+                let (doubles, ints) = summary.split_at_mut(nd * DBL_SIZE);
+                for double in doubles.chunks_exact_mut(DBL_SIZE) {
+                    double.reverse();
+                }
+                for int in ints.chunks_exact_mut(4) {
+                    int.reverse();
+                }
+            }
+        }
+
+        rcrd_idx = parse_bytes_as!(f64, &bytes[start..], source) as usize;
+    }
+
+    // All of the other records after the first summary record hold the data arrays.
+    for (idx, rcrd) in swapped.chunks_mut(RCRD_LEN).enumerate() {
+        if idx + 1 >= fwrd_idx && !not_data.contains(&(idx + 1)) {
+            for double in rcrd.chunks_exact_mut(DBL_SIZE) {
+                double.reverse();
+            }
+        }
+    }
+
+    swapped
+}
+
+#[cfg(test)]
+mod ut_endianness {
+    use super::{native_endian_bytes, swap_endianness};
+    use crate::almanac::Almanac;
+    use crate::constants::frames::EARTH_J2000;
+    use crate::math::Vector3;
+    use crate::naif::daf::daf::MutDAF;
+    use crate::naif::daf::file_record::FileRecordError;
+    use crate::naif::daf::{DAFError, FileRecord, NAIFRecord};
+    use crate::naif::spk::builder::SPKBuilder;
+    use crate::naif::spk::fixtures::sampled_states;
+    use crate::naif::spk::summary::SPKSummaryRecord;
+    use crate::naif::{Endian, SPK};
+    use crate::prelude::Frame;
+    use hifitime::{Epoch, TimeUnits};
+    use zerocopy::FromBytes;
+
+    #[test]
+    fn foreign_endian_spk() {
+        let start = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        let states = sampled_states(EARTH_J2000, start, 1.minutes(), 0..=60, |i| {
+            (
+                Vector3::new(7000.0, i as f64, -(i as f64)),
+                Vector3::new(0.0, 1.0, -1.0),
+            )
+        });
+
+        let mut builder = SPKBuilder::new("ENDIANNESS").with_comments("Comments are text.");
+        builder
+            .add_hermite_segment("HERMITE", -1001, &states, 4)
+            .unwrap();
+        builder
+            .add_lagrange_segment("LAGRANGE", -1002, &states, 5)
+            .unwrap();
+        let spk = builder.build().unwrap();
+
+        // Byte-swap the SPK, as if it were written on a platform of the opposite endianness.
+        let native = Endian::f64_native();
+        let foreign_bytes = swap_endianness(&spk.bytes, native);
+        assert_ne!(foreign_bytes, spk.bytes.to_vec());
+        let foreign_record = FileRecord::read_from(&foreign_bytes[..FileRecord::SIZE]).unwrap();
+        assert_ne!(foreign_record.file_endianness().unwrap(), native);
+        assert_eq!(
+            foreign_record.endianness(),
+            Err(FileRecordError::WrongEndian)
+        );
+
+        // Parsing converts it back to the exact same bytes.
+        assert!(native_endian_bytes(&spk.bytes).is_none());
+        let converted = SPK::parse(foreign_bytes.clone()).unwrap();
+        assert_eq!(converted.bytes, spk.bytes);
+        assert_eq!(converted.crc32_checksum, spk.crc32_checksum);
+        assert_eq!(converted.comments().unwrap().unwrap(), "Comments are text.");
+        let mutable = MutDAF::<SPKSummaryRecord>::parse(foreign_bytes).unwrap();
+        assert_eq!(mutable.bytes, spk.bytes);
+
+        let almanac = Almanac::default().with_spk(converted).unwrap();
+        let state = almanac
+            .translate(
+                Frame::from_ephem_j2000(-1002),
+                EARTH_J2000,
+                start + 30.minutes(),
+                None,
+            )
+            .unwrap();
+        assert!((state.radius_km - Vector3::new(7000.0, 30.0, -30.0)).norm() < 1e-6);
+
+        // Files with an invalid endian flag are not converted.
+        let mut invalid = spk.bytes.to_vec();
+        invalid[88..96].copy_from_slice(b"MID-IEEE");
+        assert!(native_endian_bytes(&invalid).is_none());
+        assert!(matches!(
+            SPK::parse(invalid),
+            Err(DAFError::FileRecord {
+                source: FileRecordError::InvalidEndian { .. },
+                ..
+            })
+        ));
+    }
+}
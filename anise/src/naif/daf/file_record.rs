@@ -103,6 +103,7 @@ impl FileRecord {
             match loci {
                 "SPK" => Ok("SPK"),
                 "PCK" => Ok("PCK"),
+                "CK" => Ok("CK"),
                 _ => {
                     error!("DAF of type `{}` is not yet supported", &str_locidw[4..]);
                     Err(FileRecordError::UnsupportedIdentifier {
@@ -113,18 +114,24 @@ impl FileRecord {
         }
     }
 
-    pub fn endianness(&self) -> Result<Endian, FileRecordError> {
+    /// Returns the endianness in which the binary data of this file was written, whether or not it matches this platform.
+    pub fn file_endianness(&self) -> Result<Endian, FileRecordError> {
         let str_endianness = core::str::from_utf8(&self.endian_str).context(ParsingSnafu)?;
 
-        let file_endian = if str_endianness == "LTL-IEEE" {
-            Endian::Little
+        if str_endianness == "LTL-IEEE" {
+            Ok(Endian::Little)
         } else if str_endianness == "BIG-IEEE" {
-            Endian::Big
+            Ok(Endian::Big)
         } else {
-            return Err(FileRecordError::InvalidEndian {
+            Err(FileRecordError::InvalidEndian {
                 read: str_endianness.to_string(),
-            });
-        };
+            })
+        }
+    }
+
+    /// Returns the endianness of this file, or an error if it does not match the endianness of this platform.
+    pub fn endianness(&self) -> Result<Endian, FileRecordError> {
+        let file_endian = self.file_endianness()?;
         if file_endian != Endian::f64_native() || file_endian != Endian::u64_native() {
             Err(FileRecordError::WrongEndian)
         } else {
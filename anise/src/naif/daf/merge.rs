@@ -0,0 +1,454 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use hifitime::Epoch;
+use log::{debug, warn};
+
+use super::{
+    daf::{GenericDAF, MutDAF, MutKind},
+    datatypes::{MDASetType1, MDASetType21, Type2ChebyshevSet, Type3ChebyshevSet},
+    DAFError, DafDataType, NAIFDataSet, NAIFSummaryRecord,
+};
+use crate::math::interpolation::{InterpDecodingSnafu, InterpolationError};
+use snafu::ResultExt;
+
+/// A segment to be copied into the merged DAF.
+struct MergedSegment<'a, R: NAIFSummaryRecord> {
+    summary: R,
+    name: String,
+    data: &'a [f64],
+}
+
+impl<R: NAIFSummaryRecord> MutDAF<R> {
+    /// Merges the segments of all of the provided DAF files into a single DAF file, keeping the file record and comments of the first file.
+    ///
+    /// The segments are copied in the order of the files, and in their order within each file. As SPICE searches the last
+    /// loaded file first, and the last segment of a file first, the merged file resolves overlapping segments of the same ID
+    /// as if all of the files were loaded in the provided order.
+    ///
+    /// If `winning_coverage_only` is set, each segment only keeps the time windows where it would be used: segments that
+    /// are fully overridden by later segments are dropped, and the others are split into their winning windows. Chebyshev
+    /// (Type 2 and 3) and MDA (Type 1 and 21) data are truncated to these windows, whereas other data types are copied
+    /// in full with updated summary epochs.
+    pub fn merge<W: MutKind>(
+        dafs: &[GenericDAF<R, W>],
+        winning_coverage_only: bool,
+    ) -> Result<Self, DAFError> {
+        let first = dafs
+            .first()
+            .ok_or(DAFError::NoDAFLoaded { kind: R::NAME })?;
+        let file_record = first.file_record()?;
+        // Start from the file record and the comment area of the first file.
+        let mut merged = first.without_segments()?;
+
+        let mut segments = Vec::new();
+        for (idx, daf) in dafs.iter().enumerate() {
+            let this_file_record = daf.file_record()?;
+            if this_file_record.nd != file_record.nd || this_file_record.ni != file_record.ni {
+                return Err(DAFError::IncompatibleDAF {
+                    kind: R::NAME,
+                    idx,
+                    nd: this_file_record.nd(),
+                    ni: this_file_record.ni(),
+                });
+            }
+
+            for (sno, summary) in daf.summaries()? {
+                if summary.is_empty() {
+                    continue;
+                }
+
+                segments.push(MergedSegment {
+                    summary: *summary,
+                    name: daf.nth_name(sno)?.to_string(),
+                    data: daf.nth_data_f64(sno)?,
+                });
+            }
+        }
+
+        for (idx, segment) in segments.iter().enumerate() {
+            if !winning_coverage_only {
+                merged.append_data(segment.summary, &segment.name, segment.data)?;
+                continue;
+            }
+
+            let windows = winning_windows(&segments, idx);
+            if windows.is_empty() {
+                debug!(
+                    "segment `{}` of {} is fully overridden by later segments",
+                    segment.name,
+                    segment.summary.id()
+                );
+            }
+
+            for (start_et_s, end_et_s) in windows {
+                if start_et_s == segment.summary.start_epoch_et_s()
+                    && end_et_s == segment.summary.end_epoch_et_s()
+                {
+                    merged.append_data(segment.summary, &segment.name, segment.data)?;
+                    continue;
+                }
+
+                let (start, end) = (
+                    Epoch::from_et_seconds(start_et_s),
+                    Epoch::from_et_seconds(end_et_s),
+                );
+                let mut summary = segment.summary;
+                summary.update_epochs(start, end);
+
+                match truncated_data(segment, start, end) {
+                    Some(data) => merged.append_data(summary, &segment.name, &data)?,
+                    None => merged.append_data(summary, &segment.name, segment.data)?,
+                }
+            }
+        }
+
+        merged.crc32_checksum = merged.crc32();
+
+        Ok(merged)
+    }
+}
+
+/// Returns the time windows, in ET seconds, where the segment `idx` is not overridden by any later segment of the same ID.
+fn winning_windows<R: NAIFSummaryRecord>(
+    segments: &[MergedSegment<R>],
+    idx: usize,
+) -> Vec<(f64, f64)> {
+    let this = &segments[idx].summary;
+    let mut windows = vec![(this.start_epoch_et_s(), this.end_epoch_et_s())];
+
+    for later in segments[idx + 1..]
+        .iter()
+        .map(|segment| &segment.summary)
+        .filter(|summary| summary.id() == this.id())
+    {
+        let (later_start, later_end) = (later.start_epoch_et_s(), later.end_epoch_et_s());
+        windows = windows
+            .into_iter()
+            .flat_map(|(start, end)| [(start, end.min(later_start)), (start.max(later_end), end)])
+            .filter(|(start, end)| start < end)
+            .collect();
+    }
+
+    windows
+}
+
+/// Truncates the data of this segment to the provided epochs, if its data type supports truncation.
+fn truncated_data<R: NAIFSummaryRecord>(
+    segment: &MergedSegment<R>,
+    start: Epoch,
+    end: Epoch,
+) -> Option<Vec<f64>> {
+    let truncated = match segment.summary.data_type() {
+        Ok(DafDataType::Type1ModifiedDifferenceArray) => {
+            truncate::<MDASetType1, R>(segment.data, &segment.summary, start, end)
+        }
+        Ok(DafDataType::Type2ChebyshevTriplet) => {
+            truncate::<Type2ChebyshevSet, R>(segment.data, &segment.summary, start, end)
+        }
+        Ok(DafDataType::Type3ChebyshevSextuplet) => {
+            truncate::<Type3ChebyshevSet, R>(segment.data, &segment.summary, start, end)
+        }
+        Ok(DafDataType::Type21ExtendedModifiedDifferenceArray) => {
+            truncate::<MDASetType21, R>(segment.data, &segment.summary, start, end)
+        }
+        _ => return None,
+    };
+
+    match truncated {
+        Ok(data) => Some(data),
+        Err(e) => {
+            warn!(
+                "segment `{}` copied in full, could not truncate it: {e}",
+                segment.name
+            );
+            None
+        }
+    }
+}
+
+fn truncate<'a, S: NAIFDataSet<'a>, R: NAIFSummaryRecord>(
+    data: &'a [f64],
+    summary: &R,
+    start: Epoch,
+    end: Epoch,
+) -> Result<Vec<f64>, InterpolationError> {
+    S::from_f64_slice(data)
+        .context(InterpDecodingSnafu)?
+        .truncate(summary, Some(start), Some(end))?
+        .to_f64_daf_vec()
+}
+
+#[cfg(test)]
+mod ut_merge {
+    use hifitime::{Epoch, TimeScale, TimeUnits};
+
+    use crate::almanac::Almanac;
+    use crate::constants::frames::EARTH_J2000;
+    use crate::math::cartesian::CartesianState;
+    use crate::naif::daf::{
+        daf::MutDAF, DAFError, NAIFRecord, NAIFSummaryRecord, SummaryRecord, RCRD_LEN,
+    };
+    use crate::naif::spk::builder::SPKBuilder;
+    use crate::naif::spk::fit::{ChebyshevFit, ChebyshevFitConfig};
+    use crate::naif::spk::fixtures::{circular, hermite_spk, sampled_states};
+    use crate::naif::spk::summary::SPKSummaryRecord;
+    use crate::naif::SPK;
+    use crate::DBL_SIZE;
+    use zerocopy::FromBytes;
+
+    type MutSPK = MutDAF<SPKSummaryRecord>;
+    use crate::prelude::Frame;
+
+    const SC_ID: i32 = -1001;
+    const OTHER_ID: i32 = -1002;
+
+    /// Samples the circular orbit every minute, from `first_min` to `last_min` minutes after `start`.
+    fn sampled(start: Epoch, radius_km: f64, first_min: i64, last_min: i64) -> Vec<CartesianState> {
+        sampled_states(
+            EARTH_J2000,
+            start,
+            1.minutes(),
+            first_min..=last_min,
+            |minutes| circular(radius_km, minutes as f64 * 60.0),
+        )
+    }
+
+    /// Builds a first SPK with a Chebyshev fit of six hours, and a second one with a different orbit of the same spacecraft
+    /// from the second to the fourth hour, followed by another spacecraft.
+    fn overlapping_spks(start: Epoch) -> (SPK, SPK) {
+        let fit = ChebyshevFit::fit(
+            |epoch| Ok(circular(7000.0, (epoch - start).to_seconds())),
+            start,
+            start + 6.hours(),
+            ChebyshevFitConfig::default(),
+        )
+        .unwrap();
+        let mut builder = SPKBuilder::new("FIRST");
+        builder
+            .add_chebyshev_segment("CHEBYSHEV", SC_ID, EARTH_J2000, &fit)
+            .unwrap();
+        let first = builder.build().unwrap();
+
+        let second = hermite_spk(
+            "SECOND",
+            [
+                ("UPDATE", SC_ID, sampled(start, 7100.0, 120, 240), 8),
+                ("OTHER", OTHER_ID, sampled(start, 8000.0, 0, 360), 8),
+            ],
+        );
+
+        (first, second)
+    }
+
+    /// Checks that the merged SPK returns the same states as both SPKs loaded in order, away from the segment boundaries.
+    fn assert_same_states(merged: SPK, first: &SPK, second: &SPK, start: Epoch) {
+        let merged = Almanac::default().with_spk(merged).unwrap();
+        let loaded = Almanac::default()
+            .with_spk(first.clone())
+            .unwrap()
+            .with_spk(second.clone())
+            .unwrap();
+
+        for id in [SC_ID, OTHER_ID] {
+            let mut epoch = start + 10.seconds();
+            while epoch < start + 6.hours() {
+                let frame = Frame::from_ephem_j2000(id);
+                let expected = loaded.translate(frame, EARTH_J2000, epoch, None).unwrap();
+                let state = merged.translate(frame, EARTH_J2000, epoch, None).unwrap();
+                assert!(
+                    (state.radius_km - expected.radius_km).norm() < 1e-9,
+                    "{id} at {epoch}"
+                );
+                assert!((state.velocity_km_s - expected.velocity_km_s).norm() < 1e-12);
+                epoch += 7.minutes() + 13.seconds();
+            }
+        }
+    }
+
+    #[test]
+    fn merge_all_segments() {
+        let start = Epoch::from_gregorian_at_midnight(2024, 1, 1, TimeScale::ET);
+        let (first, second) = overlapping_spks(start);
+
+        let merged = MutSPK::merge(&[first.clone(), second.clone()], false).unwrap();
+        assert_eq!(merged.daf_summary().unwrap().num_summaries(), 3);
+        assert_eq!(
+            merged.file_record().unwrap().internal_filename().unwrap(),
+            "FIRST"
+        );
+        let summaries = merged.data_summaries().unwrap();
+        assert_eq!(
+            merged.file_record().unwrap().free_addr as usize,
+            summaries[2].end_index() + 1
+        );
+        let name_rcrd = merged.name_record().unwrap();
+        let summary_size = merged.file_record().unwrap().summary_size();
+        assert_eq!(name_rcrd.nth_name(0, summary_size), "CHEBYSHEV");
+        assert_eq!(name_rcrd.nth_name(2, summary_size), "OTHER");
+        assert!(summaries[3].is_empty());
+
+        // The merged data is the same as the original data.
+        assert_eq!(
+            merged.nth_data_f64(1).unwrap(),
+            second.nth_data_f64(0).unwrap()
+        );
+
+        // Persisting the merged SPK preserves its content.
+        let path = "../target/merged-spk-test.bsp";
+        merged.persist(path).unwrap();
+        let reloaded = SPK::load(path).unwrap();
+        assert_eq!(reloaded.bytes, merged.bytes);
+
+        assert_same_states(reloaded, &first, &second, start);
+    }
+
+    #[test]
+    fn merge_winning_coverage() {
+        let start = Epoch::from_gregorian_at_midnight(2024, 1, 1, TimeScale::ET);
+        let (first, second) = overlapping_spks(start);
+
+        let merged = MutSPK::merge(&[first.clone(), second.clone()], true).unwrap();
+        let summaries = merged.data_summaries().unwrap();
+        assert_eq!(merged.daf_summary().unwrap().num_summaries(), 4);
+
+        // The Chebyshev segment is split around the updated orbit, and truncated on each side.
+        let first_len = first.nth_data_f64(0).unwrap().len();
+        for (idx, (start_h, end_h)) in [(0, 2), (4, 6)].into_iter().enumerate() {
+            assert_eq!(summaries[idx].id(), SC_ID);
+            assert!(
+                (summaries[idx].start_epoch() - (start + start_h.hours())).abs() < 1.microseconds()
+            );
+            assert!(
+                (summaries[idx].end_epoch() - (start + end_h.hours())).abs() < 1.microseconds()
+            );
+            assert!(merged.nth_data_f64(idx).unwrap().len() < first_len / 2);
+        }
+
+        // None of the segments of the same ID overlap anymore.
+        for (idx, summary) in summaries.iter().enumerate() {
+            for other in summaries[idx + 1..]
+                .iter()
+                .filter(|other| !other.is_empty() && other.id() == summary.id())
+            {
+                assert!(
+                    summary.end_epoch_et_s() <= other.start_epoch_et_s()
+                        || other.end_epoch_et_s() <= summary.start_epoch_et_s()
+                );
+            }
+        }
+
+        assert_same_states(SPK::parse(merged.bytes).unwrap(), &first, &second, start);
+
+        // Merging a file with itself only keeps the last copy of each segment.
+        let merged = MutSPK::merge(&[second.clone(), second.clone()], true).unwrap();
+        assert_eq!(merged.daf_summary().unwrap().num_summaries(), 2);
+    }
+
+    #[test]
+    fn merge_errors() {
+        let start = Epoch::from_gregorian_at_midnight(2024, 1, 1, TimeScale::ET);
+        let (first, second) = overlapping_spks(start);
+
+        assert_eq!(
+            MutSPK::merge::<bytes::Bytes>(&[], false).unwrap_err(),
+            DAFError::NoDAFLoaded {
+                kind: "SPKSummaryRecord"
+            }
+        );
+
+        // Files with a different summary format cannot be merged.
+        let mut incompatible = second.to_mutable();
+        incompatible.bytes[12..16].copy_from_slice(&5_u32.to_ne_bytes());
+        assert_eq!(
+            MutSPK::merge(&[first.to_mutable(), incompatible], false).unwrap_err(),
+            DAFError::IncompatibleDAF {
+                kind: "SPKSummaryRecord",
+                idx: 1,
+                nd: 2,
+                ni: 5
+            }
+        );
+    }
+
+    #[test]
+    fn merge_many_segments() {
+        let start = Epoch::from_gregorian_at_midnight(2024, 1, 1, TimeScale::ET);
+
+        // Twenty segments of 18 minutes per spacecraft, which do not fit in a single summary record once merged.
+        let build = |name: &str, id: i32, radius_km: f64| {
+            hermite_spk(
+                name,
+                (0..20).map(|i| {
+                    let states = sampled(start, radius_km, 18 * i, 18 * (i + 1));
+                    (format!("{name} {i}"), id, states, 4)
+                }),
+            )
+        };
+        let first = build("FIRST", SC_ID, 7000.0);
+        let second = build("SECOND", OTHER_ID, 8000.0);
+
+        let merged = MutSPK::merge(&[first.clone(), second.clone()], false).unwrap();
+
+        // The second summary record follows the data of the first 25 segments, and is linked to the first one.
+        let rcrd_nos = merged.summary_record_numbers().unwrap();
+        assert_eq!(rcrd_nos.len(), 2);
+        let file_record = merged.file_record().unwrap();
+        assert_eq!(file_record.fwrd_idx(), rcrd_nos[0]);
+        assert_eq!(file_record.backward as usize, rcrd_nos[1]);
+        let daf_summary = merged.daf_summary().unwrap();
+        assert_eq!(daf_summary.next_record(), rcrd_nos[1]);
+        assert_eq!(daf_summary.num_summaries(), 25);
+        let rcrd_idx = (rcrd_nos[1] - 1) * RCRD_LEN;
+        let last_summary =
+            SummaryRecord::read_from(&merged.bytes[rcrd_idx..rcrd_idx + SummaryRecord::SIZE])
+                .unwrap();
+        assert_eq!(last_summary.prev_record(), rcrd_nos[0]);
+        assert_eq!(last_summary.num_summaries(), 15);
+        assert!(last_summary.is_final_record());
+
+        let summaries: Vec<SPKSummaryRecord> =
+            merged.summaries().unwrap().map(|(_, s)| *s).collect();
+        assert_eq!(summaries.len(), 50);
+        assert_eq!(summaries.iter().filter(|s| !s.is_empty()).count(), 40);
+        assert!(summaries[24].end_index() * DBL_SIZE <= rcrd_idx);
+        assert_eq!(
+            summaries[25].start_index(),
+            (rcrd_nos[1] + 1) * RCRD_LEN / DBL_SIZE + 1
+        );
+        assert_eq!(
+            file_record.free_addr as usize,
+            summaries[39].end_index() + 1
+        );
+
+        for (idx, summary) in summaries.iter().take(40).enumerate() {
+            let (daf, sno) = if idx < 20 {
+                (&first, idx)
+            } else {
+                (&second, idx - 20)
+            };
+            assert_eq!(summary.id(), daf.nth_summary(sno).unwrap().id());
+            assert_eq!(merged.nth_name(idx).unwrap(), daf.nth_name(sno).unwrap());
+            assert_eq!(
+                merged.nth_data_f64(idx).unwrap(),
+                daf.nth_data_f64(sno).unwrap()
+            );
+        }
+        assert_eq!(merged.summary_from_name("SECOND 19").unwrap().1, 39);
+
+        // Persisting the merged SPK preserves all of the summary records.
+        let path = "../target/merged-many-spk-test.bsp";
+        merged.persist(path).unwrap();
+        let reloaded = SPK::load(path).unwrap();
+        assert_eq!(reloaded.bytes, merged.bytes);
+
+        assert_same_states(reloaded, &first, &second, start);
+    }
+}
@@ -18,10 +18,17 @@ use snafu::prelude::*;
 use zerocopy::{AsBytes, FromBytes};
 
 pub(crate) const RCRD_LEN: usize = 1024;
+/// Number of characters of the comment area stored in each comment record, as done by SPICE.
+pub(crate) const COMMENT_CHARS_PER_RCRD: usize = 1000;
+/// Validation string of the file record, used to detect files corrupted by an ASCII FTP transfer.
+pub(crate) const FTP_STR: &[u8; 28] = b"FTPSTR:\r:\n:\r\n:\r\x00:\x81:\x10\xce:ENDFTP";
 #[allow(clippy::module_inception)]
 pub mod daf;
 mod data_types;
+mod endianness;
+mod merge;
 pub mod mut_daf;
+mod transfer;
 pub use data_types::DataType as DafDataType;
 pub mod file_record;
 pub mod name_record;
@@ -59,6 +66,10 @@ pub trait NAIFSummaryRecord: NAIFRecord + Copy {
     fn end_epoch_et_s(&self) -> f64;
     /// Returns whatever is the ID of this summary record.
     fn id(&self) -> i32;
+    /// Returns the ID of the center of this summary record, if its data is relative to a center.
+    fn center(&self) -> Option<i32> {
+        None
+    }
     fn is_empty(&self) -> bool {
         self.start_index() == self.end_index()
     }
@@ -228,6 +239,23 @@ pub enum DAFError {
     InvalidIndex { kind: &'static str, idx: usize },
     #[snafu(display("could not build data vector of type DAF/{kind}"))]
     DataBuildError { kind: &'static str },
+    #[snafu(display("DAF/{kind}: summary record is full, cannot store more than {max} segments"))]
+    SummaryRecordFull { kind: &'static str, max: usize },
+    #[snafu(display(
+        "DAF/{kind}: file #{idx} has a different summary format (ND = {nd}, NI = {ni}) than the first file"
+    ))]
+    IncompatibleDAF {
+        kind: &'static str,
+        idx: usize,
+        nd: usize,
+        ni: usize,
+    },
+    #[snafu(display(
+        "DAF/{kind}: comments must be ASCII and cannot contain NULL or end-of-transmission characters"
+    ))]
+    InvalidComments { kind: &'static str },
+    #[snafu(display("DAF/{kind}: transfer file: {reason}"))]
+    TransferFile { kind: &'static str, reason: String },
 }
 
 // Manual implementation of PartialEq because IOError does not derive it, sadly.
@@ -9,28 +9,37 @@
  */
 
 use core::{marker::PhantomData, ops::Deref};
+use std::collections::HashSet;
 
 use super::{
-    daf::MutDAF, DAFError, DecodingNameSnafu, IOSnafu, NAIFDataSet, NAIFSummaryRecord, NameRecord,
-    RCRD_LEN,
+    daf::{GenericDAF, MutDAF, MutKind},
+    endianness::native_endian_bytes,
+    DAFError, DecodingNameSnafu, DecodingSummarySnafu, IOSnafu, InvalidCommentsSnafu, NAIFDataSet,
+    NAIFSummaryRecord, NameRecord, COMMENT_CHARS_PER_RCRD, FTP_STR, RCRD_LEN,
 };
 use crate::{
     errors::DecodingError,
     file2heap,
-    naif::daf::{file_record::FileRecordError, NAIFRecord, SummaryRecord},
+    naif::{
+        daf::{file_record::FileRecordError, FileRecord, NAIFRecord, SummaryRecord},
+        Endian,
+    },
     DBL_SIZE,
 };
 use bytes::BytesMut;
 use hifitime::Epoch;
-use snafu::ResultExt;
-use zerocopy::AsBytes;
+use snafu::{ensure, ResultExt};
+use zerocopy::{AsBytes, FromBytes};
 
 impl<R: NAIFSummaryRecord> MutDAF<R> {
-    /// Parse the provided bytes as a SPICE Double Array File
+    /// Parse the provided bytes as a SPICE Double Array File, byte-swapping them if they are not in the native endianness.
     pub fn parse<B: Deref<Target = [u8]>>(bytes: B) -> Result<Self, DAFError> {
-        let crc32_checksum = crc32fast::hash(&bytes);
         let mut buf = BytesMut::with_capacity(0);
-        buf.extend(bytes.iter());
+        match native_endian_bytes(&bytes) {
+            Some(native) => buf.extend(native),
+            None => buf.extend(bytes.iter()),
+        }
+        let crc32_checksum = crc32fast::hash(&buf);
         let me = Self {
             bytes: buf,
             crc32_checksum,
@@ -42,6 +51,60 @@ impl<R: NAIFSummaryRecord> MutDAF<R> {
         Ok(me)
     }
 
+    /// Initializes a DAF file without any segment: the file record, the comment area, and empty summary and name records.
+    ///
+    /// As in SPICE, each line of the comments is terminated by a NULL character in the comment area, which ends with an
+    /// end-of-transmission character. The internal file name is truncated to 60 characters.
+    pub(crate) fn empty(
+        id_word: &str,
+        nd: usize,
+        ni: usize,
+        internal_filename: &str,
+        comment_lines: &[&str],
+    ) -> Result<Self, DAFError> {
+        let comment_rcrds = comment_records(comment_lines);
+
+        // Records are one-indexed: the file record, the comments, the summaries, and then the names.
+        let fwrd_idx = 2 + comment_rcrds.len() / RCRD_LEN;
+
+        let mut id_str = [b' '; 8];
+        let id_len = id_word.len().min(8);
+        id_str[..id_len].copy_from_slice(&id_word.as_bytes()[..id_len]);
+
+        let mut filename = [b' '; 60];
+        let filename_len = internal_filename.len().min(60);
+        filename[..filename_len].copy_from_slice(&internal_filename.as_bytes()[..filename_len]);
+
+        let file_rcrd = FileRecord {
+            id_str,
+            nd: nd as u32,
+            ni: ni as u32,
+            internal_filename: filename,
+            forward: fwrd_idx as u32,
+            backward: fwrd_idx as u32,
+            // Addresses are one-indexed too, and the data starts right after the name record.
+            free_addr: ((fwrd_idx + 1) * RCRD_LEN / DBL_SIZE + 1) as u32,
+            endian_str: match Endian::f64_native() {
+                Endian::Little => *b"LTL-IEEE",
+                Endian::Big => *b"BIG-IEEE",
+            },
+            ftp_str: *FTP_STR,
+            ..Default::default()
+        };
+
+        let mut bytes = Vec::with_capacity((fwrd_idx + 1) * RCRD_LEN);
+        bytes.extend_from_slice(file_rcrd.as_bytes());
+        bytes.resize(RCRD_LEN, 0x0);
+
+        bytes.extend_from_slice(&comment_rcrds);
+
+        bytes.extend_from_slice(SummaryRecord::new(0, 0, 0).as_bytes());
+        bytes.resize(fwrd_idx * RCRD_LEN, 0x0);
+        bytes.extend_from_slice(NameRecord::default().as_bytes());
+
+        Self::parse(bytes)
+    }
+
     pub fn load(path: &str) -> Result<Self, DAFError> {
         let bytes = file2heap!(path).context(IOSnafu {
             action: format!("loading {path:?}"),
@@ -50,6 +113,121 @@ impl<R: NAIFSummaryRecord> MutDAF<R> {
         Self::parse(bytes)
     }
 
+    /// Replaces the comments of this DAF file, where each line of the comments is stored as a line of the comment area.
+    ///
+    /// The comment area is resized to fit the new comments, so the summary, name and data records are moved accordingly.
+    /// Empty comments remove the comment area altogether.
+    pub fn set_comments(&mut self, comments: &str) -> Result<(), DAFError> {
+        let lines: Vec<&str> = comments.lines().collect();
+        self.set_comment_lines(&lines)
+    }
+
+    /// Appends these comments after the existing comments of this DAF file, growing the comment area if needed.
+    pub fn append_comments(&mut self, comments: &str) -> Result<(), DAFError> {
+        let mut lines = self.comment_lines()?;
+        lines.extend(comments.lines().map(|line| line.to_string()));
+        let lines: Vec<&str> = lines.iter().map(|line| line.as_str()).collect();
+        self.set_comment_lines(&lines)
+    }
+
+    /// Removes all of the comments of this DAF file.
+    pub fn clear_comments(&mut self) -> Result<(), DAFError> {
+        self.set_comment_lines(&[])
+    }
+
+    /// Rewrites the comment area with these lines, and shifts all of the records after it, and the record numbers and
+    /// data addresses that point to them, by the change in the number of comment records.
+    fn set_comment_lines(&mut self, lines: &[&str]) -> Result<(), DAFError> {
+        // The NULL and end-of-transmission characters delimit the lines and the comment area.
+        ensure!(
+            lines
+                .iter()
+                .all(|line| line.is_ascii() && !line.contains(['\0', '\u{4}'])),
+            InvalidCommentsSnafu { kind: R::NAME }
+        );
+
+        let mut file_record = self.file_record()?;
+        if file_record.is_empty() {
+            return Err(DAFError::FileRecord {
+                kind: R::NAME,
+                source: FileRecordError::EmptyRecord,
+            });
+        }
+
+        let comment_rcrds = comment_records(lines);
+        let prev_fwrd_idx = file_record.fwrd_idx();
+        let fwrd_idx = 2 + comment_rcrds.len() / RCRD_LEN;
+        let size = self.bytes.len();
+        let records = self
+            .bytes
+            .get((prev_fwrd_idx - 1) * RCRD_LEN..)
+            .ok_or(DecodingError::InaccessibleBytes {
+                start: (prev_fwrd_idx - 1) * RCRD_LEN,
+                end: size,
+                size,
+            })
+            .context(DecodingSummarySnafu { kind: R::NAME })?;
+
+        // Shift of the record numbers and of the data addresses, which are both one-indexed.
+        let shift = fwrd_idx as isize - prev_fwrd_idx as isize;
+        let shift_rcrd = |idx: usize| {
+            if idx == 0 {
+                0
+            } else {
+                (idx as isize + shift) as usize
+            }
+        };
+        let shift_addr =
+            |addr: usize| (addr as isize + shift * (RCRD_LEN / DBL_SIZE) as isize) as usize;
+
+        let mut new_bytes = Vec::with_capacity(comment_rcrds.len() + RCRD_LEN + records.len());
+        new_bytes.extend_from_slice(&self.bytes[..RCRD_LEN]);
+        new_bytes.extend_from_slice(&comment_rcrds);
+        new_bytes.extend_from_slice(records);
+
+        file_record.forward = fwrd_idx as u32;
+        file_record.backward = shift_rcrd(file_record.backward as usize) as u32;
+        file_record.free_addr = shift_addr(file_record.free_addr as usize) as u32;
+        new_bytes[..FileRecord::SIZE].copy_from_slice(file_record.as_bytes());
+
+        // Follow the linked list of summary records, shifting the data addresses of all of their summaries.
+        let mut visited = HashSet::new();
+        let mut rcrd_idx = fwrd_idx;
+        while rcrd_idx > 0 && rcrd_idx * RCRD_LEN <= new_bytes.len() && visited.insert(rcrd_idx) {
+            let start = (rcrd_idx - 1) * RCRD_LEN;
+            let daf_summary =
+                SummaryRecord::read_from(&new_bytes[start..start + SummaryRecord::SIZE]).unwrap();
+            let daf_summary = SummaryRecord::new(
+                shift_rcrd(daf_summary.next_record()),
+                shift_rcrd(daf_summary.prev_record()),
+                daf_summary.num_summaries(),
+            );
+            new_bytes[start..start + SummaryRecord::SIZE].copy_from_slice(daf_summary.as_bytes());
+
+            let num_summaries = daf_summary
+                .num_summaries()
+                .min((RCRD_LEN - SummaryRecord::SIZE) / R::SIZE);
+            for sno in 0..num_summaries {
+                let summary_idx = start + SummaryRecord::SIZE + sno * R::SIZE;
+                let summary_bytes = &mut new_bytes[summary_idx..summary_idx + R::SIZE];
+                let mut summary = R::read_from(summary_bytes).unwrap();
+                if !summary.is_empty() {
+                    summary.update_indexes(
+                        shift_addr(summary.start_index()),
+                        shift_addr(summary.end_index()),
+                    );
+                    summary_bytes.copy_from_slice(summary.as_bytes());
+                }
+            }
+
+            rcrd_idx = daf_summary.next_record();
+        }
+
+        self.bytes = BytesMut::from_iter(new_bytes);
+
+        Ok(())
+    }
+
     /// Sets the name record of this mutable DAF file to the one provided as a parameter.
     pub fn set_name_record(&mut self, new_name_record: NameRecord) -> Result<(), DAFError> {
         let rcrd_idx = self.file_record()?.fwrd_idx() * RCRD_LEN;
@@ -68,6 +246,8 @@ impl<R: NAIFSummaryRecord> MutDAF<R> {
     }
 
     /// Sets the data for the n-th segment of this DAF file.
+    ///
+    /// The segments are rewritten in order after the summary and name records, so empty summaries are dropped.
     pub fn set_nth_data<'a, S: NAIFDataSet<'a>>(
         &mut self,
         idx: usize,
@@ -75,70 +255,116 @@ impl<R: NAIFSummaryRecord> MutDAF<R> {
         new_start_epoch: Epoch,
         new_end_epoch: Epoch,
     ) -> Result<(), DAFError> {
-        let summaries = self.data_summaries()?;
-        let this_summary = summaries
-            .get(idx)
-            .ok_or(DAFError::InvalidIndex { idx, kind: R::NAME })?;
+        let new_data = new_data
+            .to_f64_daf_vec()
+            .or(Err(DAFError::DataBuildError { kind: R::NAME }))?;
 
-        if self.file_record()?.is_empty() {
+        self.rewrite_segments(
+            idx,
+            Some((new_data.as_slice(), new_start_epoch, new_end_epoch)),
+        )
+    }
+
+    /// Appends a segment after the last data of this DAF file, in the slot following the last segment.
+    ///
+    /// As in SPICE, once the last summary record is full, a new summary record and its name record are written after
+    /// the data and linked to the previous summary record. The start and end indexes of the provided summary are
+    /// overwritten to point to the new data, and the number of summaries and the first free address of the DAF are
+    /// updated accordingly.
+    pub fn append_data(
+        &mut self,
+        mut summary: R,
+        name: &str,
+        data: &[f64],
+    ) -> Result<(), DAFError> {
+        let mut file_record = self.file_record()?;
+        if file_record.is_empty() {
             return Err(DAFError::FileRecord {
                 kind: R::NAME,
                 source: FileRecordError::EmptyRecord,
             });
         }
 
-        let orig_index_start = this_summary.start_index() - 1;
-        let orig_index_end = this_summary.end_index();
-        let orig_data_start = orig_index_start * DBL_SIZE;
-        let orig_data_end = orig_index_end * DBL_SIZE;
+        let rcrd_nos = self.summary_record_numbers()?;
+        let summaries: Vec<R> = self.summaries()?.map(|(_, summary)| *summary).collect();
+        let per_record = Self::summaries_per_record();
+        // The new segment follows the last one, such that it takes precedence over all of them.
+        let idx = summaries
+            .iter()
+            .rposition(|summary| !summary.is_empty())
+            .map_or(0, |last| last + 1);
 
-        let original_size = ((orig_data_end - orig_data_start) / DBL_SIZE) as isize;
+        // New data is written after the last segment, and after the last summary and name records.
+        let last_rcrd_no = rcrd_nos.iter().copied().max().unwrap_or(0);
+        let mut last_word = summaries
+            .iter()
+            .filter(|summary| !summary.is_empty())
+            .map(|summary| summary.end_index())
+            .max()
+            .unwrap_or(0)
+            .max((last_rcrd_no + 1) * RCRD_LEN / DBL_SIZE);
 
-        let new_data_bytes = new_data
-            .to_f64_daf_vec()
-            .or(Err(DAFError::DataBuildError { kind: R::NAME }))?;
+        let mut new_bytes = self.bytes.to_vec();
 
-        let new_size = new_data_bytes.len() as isize;
+        let rcrd_no = match rcrd_nos.get(idx / per_record) {
+            Some(rcrd_no) => *rcrd_no,
+            None => {
+                // All of the summary records are full, so link a new one to the last one.
+                let prev_rcrd_no = *rcrd_nos.last().unwrap();
+                let new_rcrd_no = (last_word * DBL_SIZE).div_ceil(RCRD_LEN) + 1;
 
-        // Size change will be positive if the new data is _smaller_ than the previous one, and vice versa.
-        let size_change = original_size - new_size;
+                let prev_idx = (prev_rcrd_no - 1) * RCRD_LEN;
+                let prev_summary =
+                    SummaryRecord::read_from(&new_bytes[prev_idx..prev_idx + SummaryRecord::SIZE])
+                        .unwrap();
+                let prev_summary = SummaryRecord::new(
+                    new_rcrd_no,
+                    prev_summary.prev_record(),
+                    prev_summary.num_summaries(),
+                );
+                new_bytes[prev_idx..prev_idx + SummaryRecord::SIZE]
+                    .copy_from_slice(prev_summary.as_bytes());
 
-        // Update the bytes
-        let mut new_bytes = self.bytes.to_vec();
-        new_bytes.splice(
-            orig_data_start..orig_data_end,
-            new_data_bytes.as_bytes().iter().cloned(),
-        );
+                new_bytes.resize((new_rcrd_no - 1) * RCRD_LEN, 0x0);
+                new_bytes.extend_from_slice(SummaryRecord::new(0, prev_rcrd_no, 0).as_bytes());
+                new_bytes.resize(new_rcrd_no * RCRD_LEN, 0x0);
+                new_bytes.extend_from_slice(NameRecord::default().as_bytes());
 
-        let mut new_summaries: Vec<R> = summaries.to_vec();
-        for (sno, summary) in new_summaries.iter_mut().enumerate() {
-            if sno < idx {
-                continue;
-            } else if sno == idx {
-                // Only update the end index for the data we modified
-                summary.update_indexes(
-                    orig_index_start + 1,
-                    (orig_index_end as isize - size_change) as usize,
-                );
-                summary.update_epochs(new_start_epoch, new_end_epoch);
-            } else if !summary.is_empty() {
-                // Shift all of the indexes.
-                let prev_start = summary.start_index();
-                let prev_end = summary.end_index();
-                summary.update_indexes(
-                    (prev_start as isize - size_change) as usize,
-                    (prev_end as isize - size_change) as usize,
-                );
+                file_record.backward = new_rcrd_no as u32;
+                last_word = (new_rcrd_no + 1) * RCRD_LEN / DBL_SIZE;
+                new_rcrd_no
             }
-        }
+        };
+        let slot = idx % per_record;
+
+        summary.update_indexes(last_word + 1, last_word + data.len());
+        file_record.free_addr = (last_word + data.len() + 1) as u32;
+
+        // Update the bytes, padding the last record with zeros.
+        new_bytes.resize(last_word * DBL_SIZE, 0x0);
+        new_bytes.extend_from_slice(data.as_bytes());
+        new_bytes.resize(new_bytes.len().div_ceil(RCRD_LEN) * RCRD_LEN, 0x0);
+
+        new_bytes[..FileRecord::SIZE].copy_from_slice(file_record.as_bytes());
 
-        let summary_bytes: Vec<u8> = new_summaries.as_bytes().to_vec();
+        let rcrd_idx = (rcrd_no - 1) * RCRD_LEN;
+        let daf_summary =
+            SummaryRecord::read_from(&new_bytes[rcrd_idx..rcrd_idx + SummaryRecord::SIZE]).unwrap();
+        let daf_summary = SummaryRecord::new(
+            daf_summary.next_record(),
+            daf_summary.prev_record(),
+            daf_summary.num_summaries().max(slot + 1),
+        );
+        new_bytes[rcrd_idx..rcrd_idx + SummaryRecord::SIZE].copy_from_slice(daf_summary.as_bytes());
+
+        let summary_idx = rcrd_idx + SummaryRecord::SIZE + slot * R::SIZE;
+        new_bytes[summary_idx..summary_idx + R::SIZE].copy_from_slice(summary.as_bytes());
 
-        let rcrd_idx = (self.file_record()?.fwrd_idx() - 1) * RCRD_LEN;
-        // Note: we use copy_from_slice here because we have the guarantee that the summary bytes are the same length as the original version.
-        let orig_summary_bytes =
-            &mut new_bytes[rcrd_idx..rcrd_idx + RCRD_LEN][SummaryRecord::SIZE..];
-        orig_summary_bytes.copy_from_slice(&summary_bytes);
+        let name_idx = rcrd_no * RCRD_LEN;
+        let mut name_record =
+            NameRecord::read_from(&new_bytes[name_idx..name_idx + RCRD_LEN]).unwrap();
+        name_record.set_nth_name(slot, file_record.summary_size(), name);
+        new_bytes[name_idx..name_idx + RCRD_LEN].copy_from_slice(name_record.as_bytes());
 
         self.bytes = BytesMut::from_iter(new_bytes);
 
@@ -146,70 +372,282 @@ impl<R: NAIFSummaryRecord> MutDAF<R> {
     }
 
     /// Deletes the data for the n-th segment of this DAF file.
+    ///
+    /// The other segments are rewritten in order after the summary and name records, so empty summaries are dropped.
     pub fn delete_nth_data(&mut self, idx: usize) -> Result<(), DAFError> {
-        let summaries = self.data_summaries()?;
-        let this_summary = summaries
-            .get(idx)
-            .ok_or(DAFError::InvalidIndex { idx, kind: R::NAME })?;
+        self.rewrite_segments(idx, None)
+    }
 
-        if self.file_record()?.is_empty() {
-            return Err(DAFError::FileRecord {
-                kind: R::NAME,
-                source: FileRecordError::EmptyRecord,
-            });
+    /// Rewrites all of the segments of this DAF file after its comment area, replacing the data and the epochs of the
+    /// n-th segment with the provided ones, or removing it if none are provided.
+    fn rewrite_segments(
+        &mut self,
+        idx: usize,
+        replacement: Option<(&[f64], Epoch, Epoch)>,
+    ) -> Result<(), DAFError> {
+        let summaries: Vec<R> = self.summaries()?.map(|(_, summary)| *summary).collect();
+        if summaries.get(idx).is_none_or(|summary| summary.is_empty()) {
+            return Err(DAFError::InvalidIndex { idx, kind: R::NAME });
         }
 
-        let orig_index_start = this_summary.start_index() - 1;
-        let orig_index_end = this_summary.end_index();
-        let orig_data_start = orig_index_start * DBL_SIZE;
-        let orig_data_end = orig_index_end * DBL_SIZE;
+        let mut rewritten = self.without_segments()?;
+        for (sno, summary) in summaries.iter().enumerate() {
+            if summary.is_empty() {
+                continue;
+            }
 
-        let original_size = ((orig_data_end - orig_data_start) / DBL_SIZE) as isize;
+            let name = self.nth_name(sno)?;
+            if sno != idx {
+                rewritten.append_data(*summary, name, self.nth_data_f64(sno)?)?;
+            } else if let Some((data, start_epoch, end_epoch)) = replacement {
+                let mut summary = *summary;
+                summary.update_epochs(start_epoch, end_epoch);
+                rewritten.append_data(summary, name, data)?;
+            }
+        }
 
-        // Size change will be positive if the new data is _smaller_ than the previous one, and vice versa.
-        let size_change = original_size;
+        self.bytes = rewritten.bytes;
 
-        // Update the bytes
-        let mut new_bytes = self.bytes.to_vec();
-        new_bytes.drain(orig_data_start..orig_data_end);
+        Ok(())
+    }
+}
+
+impl<R: NAIFSummaryRecord, W: MutKind> GenericDAF<R, W> {
+    /// Returns a mutable copy of this DAF file without any segment: its file record and comment area, followed by empty
+    /// summary and name records.
+    pub(crate) fn without_segments(&self) -> Result<MutDAF<R>, DAFError> {
+        let mut file_record = self.file_record()?;
+        let fwrd_idx = file_record.fwrd_idx();
+
+        let comments_end = (fwrd_idx - 1) * RCRD_LEN;
+        let mut bytes = self
+            .bytes
+            .get(..comments_end)
+            .ok_or_else(|| DecodingError::InaccessibleBytes {
+                start: 0,
+                end: comments_end,
+                size: self.bytes.len(),
+            })
+            .context(DecodingSummarySnafu { kind: R::NAME })?
+            .to_vec();
+
+        file_record.backward = file_record.forward;
+        file_record.free_addr = ((fwrd_idx + 1) * RCRD_LEN / DBL_SIZE + 1) as u32;
+        bytes[..FileRecord::SIZE].copy_from_slice(file_record.as_bytes());
+
+        bytes.extend_from_slice(SummaryRecord::default().as_bytes());
+        bytes.resize(fwrd_idx * RCRD_LEN, 0x0);
+        bytes.extend_from_slice(NameRecord::default().as_bytes());
+
+        MutDAF::parse(bytes)
+    }
+}
+
+/// Builds the records of the comment area: as in SPICE, each line is terminated by a NULL character, the comment area
+/// ends with an end-of-transmission character, and only the first 1000 characters of each record are used.
+fn comment_records(lines: &[&str]) -> Vec<u8> {
+    let mut comment_area = Vec::new();
+    if !lines.is_empty() {
+        for line in lines {
+            comment_area.extend_from_slice(line.as_bytes());
+            comment_area.push(0x0);
+        }
+        comment_area.push(0x4);
+    }
+
+    let mut records =
+        Vec::with_capacity(comment_area.len().div_ceil(COMMENT_CHARS_PER_RCRD) * RCRD_LEN);
+    for chunk in comment_area.chunks(COMMENT_CHARS_PER_RCRD) {
+        records.extend_from_slice(chunk);
+        records.resize(records.len().next_multiple_of(RCRD_LEN), 0x0);
+    }
+    records
+}
+
+#[cfg(test)]
+mod ut_mut_daf {
+    use crate::constants::frames::EARTH_J2000;
+    use crate::math::Vector3;
+    use crate::naif::daf::daf::MutDAF;
+    use crate::naif::daf::datatypes::HermiteSetType13;
+    use crate::naif::daf::{DAFError, NAIFSummaryRecord};
+    use crate::naif::spk::builder::SPKBuilder;
+    use crate::naif::spk::fixtures::{hermite_spk, sampled_states};
+    use crate::naif::spk::summary::SPKSummaryRecord;
+    use crate::naif::SPK;
+    use hifitime::{Epoch, TimeUnits};
 
-        let mut new_summaries: Vec<R> = summaries.to_vec();
-        for (sno, summary) in new_summaries.iter_mut().enumerate() {
-            if sno < idx {
+    #[test]
+    fn edit_comments() {
+        let start = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        let states = sampled_states(EARTH_J2000, start, 1.minutes(), 0..=60, |i| {
+            (
+                Vector3::new(7000.0, i as f64, -(i as f64)),
+                Vector3::new(0.0, 1.0, -1.0),
+            )
+        });
+
+        let build = |comments: &str| {
+            let mut builder = SPKBuilder::new("COMMENTS").with_comments(comments);
+            builder
+                .add_hermite_segment("HERMITE", -1001, &states, 4)
+                .unwrap();
+            builder
+                .add_lagrange_segment("LAGRANGE", -1002, &states, 5)
+                .unwrap();
+            builder.build().unwrap()
+        };
+
+        let spk = build("Original comments.");
+        let mut daf = spk.to_mutable();
+
+        // Three records of comments
+        let provenance: String = (0..50)
+            .map(|i| {
+                format!("Line {i:02} of the provenance of this SPK, which spans several records.\n")
+            })
+            .collect();
+        daf.set_comments(&provenance).unwrap();
+        assert_eq!(daf.file_record().unwrap().fwrd_idx(), 6);
+        assert_eq!(daf.bytes, build(&provenance).bytes);
+
+        let edited = SPK::parse(daf.bytes.clone()).unwrap();
+        assert_eq!(
+            edited.comments().unwrap().unwrap().lines().count(),
+            provenance.lines().count()
+        );
+        for (idx, summary) in spk.data_summaries().unwrap().iter().enumerate() {
+            if summary.is_empty() {
                 continue;
-            } else if sno == idx {
-                // We've removed this data, so clear the summary.
-                *summary = R::default()
-            } else if !summary.is_empty() {
-                // Shift all of the indexes.
-                let prev_start = summary.start_index();
-                let prev_end = summary.end_index();
-                summary.update_indexes(
-                    (prev_start as isize - size_change) as usize,
-                    (prev_end as isize - size_change) as usize,
-                );
             }
+            let edited_summary = edited.data_summaries().unwrap()[idx];
+            assert_eq!(edited_summary.id(), summary.id());
+            assert_eq!(
+                edited.nth_data_f64(idx).unwrap(),
+                spk.nth_data_f64(idx).unwrap()
+            );
         }
 
-        // Remove empty entries from the summary all together
-        let cleaned_summaries: Vec<R> = new_summaries
-            .iter()
-            .filter(|summary| !summary.is_empty())
-            .cloned()
-            .collect();
+        daf.append_comments("Appended line.").unwrap();
+        assert_eq!(
+            daf.comment_lines().unwrap().last().unwrap(),
+            "Appended line."
+        );
+        assert_eq!(daf.bytes, build(&(provenance + "Appended line.")).bytes);
 
-        let mut summary_bytes: Vec<u8> = cleaned_summaries.as_bytes().to_vec();
-        // We need to pad with zeros all of the summaries we've removed.
-        summary_bytes.extend(vec![0x0; 1000 - summary_bytes.len()]);
+        // Shrinking the comment area, down to no comments at all
+        daf.set_comments("Original comments.").unwrap();
+        assert_eq!(daf.bytes, spk.bytes);
+        daf.clear_comments().unwrap();
+        assert_eq!(daf.file_record().unwrap().fwrd_idx(), 2);
+        assert_eq!(daf.comments().unwrap(), None);
+        assert_eq!(daf.bytes, build("").bytes);
 
-        let rcrd_idx = (self.file_record()?.fwrd_idx() - 1) * RCRD_LEN;
-        // Note: we use copy_from_slice here because we have the guarantee that the summary bytes are the same length as the original version.
-        let orig_summary_bytes =
-            &mut new_bytes[rcrd_idx..rcrd_idx + RCRD_LEN][SummaryRecord::SIZE..];
-        orig_summary_bytes.copy_from_slice(&summary_bytes);
+        assert_eq!(
+            daf.set_comments("Not \u{4} valid"),
+            Err(DAFError::InvalidComments {
+                kind: "SPKSummaryRecord"
+            })
+        );
+        assert_eq!(
+            daf.append_comments("Not ASCII: ∆v"),
+            Err(DAFError::InvalidComments {
+                kind: "SPKSummaryRecord"
+            })
+        );
+    }
 
-        self.bytes = BytesMut::from_iter(new_bytes);
+    #[test]
+    fn edit_many_segments() {
+        let start = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        let spk = hermite_spk(
+            "MANY",
+            (0..20).map(|sno| {
+                let segment_start = start + (10 * sno).minutes();
+                let states = sampled_states(EARTH_J2000, segment_start, 1.minutes(), 0..=10, |i| {
+                    (
+                        Vector3::new(7000.0 + sno as f64, i as f64, 0.0),
+                        Vector3::new(0.0, 1.0, 0.0),
+                    )
+                });
+                (format!("SEGMENT {sno}"), -1001, states, 4)
+            }),
+        );
 
-        Ok(())
+        // Copies of all of the segments need a second summary record.
+        let mut daf = spk.to_mutable();
+        for sno in 0..20 {
+            daf.append_data(
+                *spk.nth_summary(sno).unwrap(),
+                &format!("COPY {sno}"),
+                spk.nth_data_f64(sno).unwrap(),
+            )
+            .unwrap();
+        }
+        assert_eq!(daf.summary_record_numbers().unwrap().len(), 2);
+
+        let check = |daf: &MutDAF<SPKSummaryRecord>, expected: &[usize]| {
+            let summaries: Vec<SPKSummaryRecord> =
+                daf.summaries().unwrap().map(|(_, s)| *s).collect();
+            assert_eq!(
+                summaries.iter().filter(|s| !s.is_empty()).count(),
+                expected.len()
+            );
+            for (idx, sno) in expected.iter().copied().enumerate() {
+                let name = if sno < 20 {
+                    format!("SEGMENT {sno}")
+                } else {
+                    format!("COPY {}", sno - 20)
+                };
+                assert_eq!(daf.nth_name(idx).unwrap(), name);
+                assert_eq!(
+                    daf.nth_data_f64(idx).unwrap(),
+                    spk.nth_data_f64(sno % 20).unwrap()
+                );
+                assert_eq!(
+                    summaries[idx].start_epoch_et_s,
+                    spk.data_summaries().unwrap()[sno % 20].start_epoch_et_s
+                );
+            }
+        };
+        let all: Vec<usize> = (0..40).collect();
+        check(&daf, &all);
+
+        // Growing the comment area shifts all of the summary records.
+        let comments = "Many segments.\n".repeat(200);
+        daf.set_comments(&comments).unwrap();
+        assert_eq!(daf.comments().unwrap().unwrap().lines().count(), 200);
+        check(&daf, &all);
+        let reparsed = SPK::parse(daf.bytes.clone()).unwrap();
+        assert_eq!(reparsed.summary_record_numbers().unwrap().len(), 2);
+
+        // Deleting a segment moves the following ones, and their names, to the previous slot.
+        daf.delete_nth_data(3).unwrap();
+        let remaining: Vec<usize> = (0..40).filter(|sno| *sno != 3).collect();
+        check(&daf, &remaining);
+        assert_eq!(daf.comments().unwrap().unwrap().lines().count(), 200);
+
+        for _ in 0..14 {
+            daf.delete_nth_data(0).unwrap();
+        }
+        assert_eq!(daf.summary_record_numbers().unwrap().len(), 1);
+        check(&daf, &remaining[14..]);
+
+        // Replacing the data of a segment keeps its slot.
+        let segment = spk.nth_data::<HermiteSetType13>(5).unwrap();
+        let new_start = spk.nth_summary(5).unwrap().start_epoch();
+        let new_end = spk.nth_summary(5).unwrap().end_epoch();
+        daf.set_nth_data(24, segment, new_start, new_end).unwrap();
+        assert_eq!(daf.nth_name(24).unwrap(), "COPY 19");
+        assert_eq!(daf.nth_data_f64(24).unwrap(), spk.nth_data_f64(5).unwrap());
+        assert_eq!(daf.nth_summary(24).unwrap().start_epoch(), new_start);
+
+        assert_eq!(
+            daf.delete_nth_data(25),
+            Err(DAFError::InvalidIndex {
+                kind: "SPKSummaryRecord",
+                idx: 25
+            })
+        );
     }
 }
@@ -23,6 +23,15 @@ pub struct SummaryRecord {
 impl NAIFRecord for SummaryRecord {}
 
 impl SummaryRecord {
+    /// Builds a summary record, where the next and previous records are one-indexed and zero marks the end of the list.
+    pub fn new(next_record: usize, prev_record: usize, num_summaries: usize) -> Self {
+        Self {
+            next_record: next_record as f64,
+            prev_record: prev_record as f64,
+            num_summaries: num_summaries as f64,
+        }
+    }
+
     pub fn next_record(&self) -> usize {
         self.next_record as usize
     }
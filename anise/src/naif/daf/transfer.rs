@@ -0,0 +1,590 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use super::{
+    daf::{GenericDAF, MutDAF, MutKind, DAF},
+    DAFError, NAIFSummaryRecord,
+};
+use crate::DBL_SIZE;
+
+/// First line of a DAF encoded transfer file.
+const ETF_HEADER: &str = "DAFETF NAIF DAF ENCODED TRANSFER FILE";
+const BEGIN_COMMENTS: &str = "~NAIF/SPC BEGIN COMMENTS~";
+const END_COMMENTS: &str = "~NAIF/SPC END COMMENTS~";
+/// Number of doubles written in each block of the data of an array, as done by SPICE.
+const BLOCK_LEN: usize = 1024;
+
+impl<R: NAIFSummaryRecord, W: MutKind> GenericDAF<R, W> {
+    /// Exports this DAF to the SPICE DAF transfer format, i.e. the text of a `.xsp` or `.xc` file.
+    ///
+    /// Doubles and integers are written in the hexadecimal encoding of SPICE, which preserves all of their bits: parsing
+    /// the exported text with [DAF::from_transfer] rebuilds this exact DAF if it is laid out as SPICE writes it.
+    pub fn to_transfer(&self) -> Result<String, DAFError> {
+        let file_record = self.file_record()?;
+        let (nd, ni) = (file_record.nd(), file_record.ni());
+        let name_len = file_record.summary_size() * DBL_SIZE;
+
+        let mut lines = vec![
+            ETF_HEADER.to_string(),
+            format!("'{}'", String::from_utf8_lossy(&file_record.id_str)),
+            format!("'{}'", encode_int(nd as i64)),
+            format!("'{}'", encode_int(ni as i64)),
+            format!(
+                "'{:<60}'",
+                quote(file_record.internal_filename().unwrap_or_default())
+            ),
+        ];
+
+        let mut num_arrays = 0;
+        for (idx, summary) in self.summaries()? {
+            if summary.is_empty() {
+                continue;
+            }
+            num_arrays += 1;
+
+            let data = self.nth_data_f64(idx)?;
+            lines.push(format!("BEGIN_ARRAY {num_arrays} {}", data.len()));
+            lines.push(format!("'{:<name_len$}'", quote(self.nth_name(idx)?)));
+
+            // The summary holds ND doubles followed by NI integers, the last two of which are the data addresses.
+            let summary_bytes = summary.as_bytes();
+            for dbl_bytes in summary_bytes[..nd * DBL_SIZE].chunks_exact(DBL_SIZE) {
+                let value = f64::from_ne_bytes(dbl_bytes.try_into().unwrap());
+                lines.push(format!("'{}'", encode_f64(value)));
+            }
+            for int_bytes in summary_bytes[nd * DBL_SIZE..].chunks_exact(4).take(ni - 2) {
+                let value = i32::from_ne_bytes(int_bytes.try_into().unwrap());
+                lines.push(format!("'{}'", encode_int(value.into())));
+            }
+
+            for block in data.chunks(BLOCK_LEN) {
+                lines.push(block.len().to_string());
+                lines.extend(
+                    block
+                        .iter()
+                        .map(|value| format!("'{}'", encode_f64(*value))),
+                );
+            }
+            lines.push(format!("END_ARRAY {num_arrays} {}", data.len()));
+        }
+        lines.push(format!("TOTAL_ARRAYS {num_arrays}"));
+
+        lines.push(format!(" {BEGIN_COMMENTS}"));
+        lines.extend(self.comment_lines()?);
+        lines.push(format!(" {END_COMMENTS}"));
+
+        Ok(lines.join("\n") + "\n")
+    }
+}
+
+impl<R: NAIFSummaryRecord> DAF<R> {
+    /// Parses a DAF written in the SPICE DAF transfer format, i.e. the text of a `.xsp` or `.xc` file.
+    ///
+    /// The DAF is laid out as SPICE writes it: the file record, the comment area, the summary and name records, and the
+    /// data of each array, in the order of the transfer file.
+    pub fn from_transfer(transfer: &str) -> Result<Self, DAFError> {
+        let transfer_err = |reason: String| DAFError::TransferFile {
+            kind: R::NAME,
+            reason,
+        };
+
+        let mut lines = transfer.lines();
+        if lines.next().map(|line| line.trim()) != Some(ETF_HEADER) {
+            return Err(transfer_err(format!(
+                "the first line must be `{ETF_HEADER}`"
+            )));
+        }
+
+        // The comments are the last part of the file, after the arrays.
+        let arrays: Vec<&str> = lines
+            .by_ref()
+            .take_while(|line| line.trim() != BEGIN_COMMENTS)
+            .collect();
+        let comment_lines: Vec<&str> = lines
+            .take_while(|line| line.trim() != END_COMMENTS)
+            .collect();
+
+        let mut reader = TransferReader::new(&arrays).map_err(transfer_err)?;
+
+        let id_word = reader
+            .quoted("the file identification")
+            .map_err(transfer_err)?;
+        let nd = reader.integer("ND").map_err(transfer_err)?;
+        let ni = reader.integer("NI").map_err(transfer_err)?;
+        let (nd, ni) = match (usize::try_from(nd), usize::try_from(ni)) {
+            (Ok(nd), Ok(ni))
+                if ni >= 2
+                    && nd
+                        .checked_add(ni.div_ceil(2))
+                        .and_then(|summary_size| summary_size.checked_mul(DBL_SIZE))
+                        == Some(R::SIZE) =>
+            {
+                (nd, ni)
+            }
+            _ => {
+                return Err(transfer_err(format!(
+                    "summaries of ND = {nd} and NI = {ni} are not {}",
+                    R::NAME
+                )))
+            }
+        };
+        let internal_filename = reader
+            .quoted("the internal file name")
+            .map_err(transfer_err)?;
+
+        let mut daf = MutDAF::<R>::empty(
+            &id_word,
+            nd,
+            ni,
+            internal_filename.trim_end(),
+            &comment_lines,
+        )?;
+
+        let mut num_arrays = 0;
+        loop {
+            let (line, keyword) = reader.word("an array or the total").map_err(transfer_err)?;
+            match keyword.as_str() {
+                "BEGIN_ARRAY" => {
+                    num_arrays += 1;
+                    let (summary, name, data) = reader
+                        .array::<R>(num_arrays, nd, ni)
+                        .map_err(transfer_err)?;
+                    daf.append_data(summary, name.trim_end(), &data)?;
+                }
+                "TOTAL_ARRAYS" => {
+                    let total = reader
+                        .decimal("the number of arrays")
+                        .map_err(transfer_err)?;
+                    if total != num_arrays {
+                        return Err(transfer_err(format!(
+                            "line {line}: expected {total} arrays, found {num_arrays}"
+                        )));
+                    }
+                    break;
+                }
+                _ => {
+                    return Err(transfer_err(format!(
+                        "line {line}: expected `BEGIN_ARRAY` or `TOTAL_ARRAYS`, found `{keyword}`"
+                    )))
+                }
+            }
+        }
+
+        Self::parse(daf.bytes)
+    }
+}
+
+/// Reads the tokens of the arrays of a transfer file, i.e. quoted strings and bare words, keeping track of their line.
+struct TransferReader {
+    /// Line number, whether the token was quoted, and the token.
+    tokens: Vec<(usize, bool, String)>,
+    next: usize,
+}
+
+impl TransferReader {
+    fn new(lines: &[&str]) -> Result<Self, String> {
+        let mut tokens = Vec::new();
+        for (lno, line) in lines.iter().enumerate() {
+            // The header is the first line.
+            let lno = lno + 2;
+            let mut chars = line.chars().peekable();
+            while let Some(c) = chars.next() {
+                if c.is_whitespace() {
+                    continue;
+                } else if c == '\'' {
+                    // Quotes in a quoted string are doubled.
+                    let mut token = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('\'') if chars.peek() == Some(&'\'') => {
+                                chars.next();
+                                token.push('\'');
+                            }
+                            Some('\'') => break,
+                            Some(c) => token.push(c),
+                            None => return Err(format!("line {lno}: unterminated string")),
+                        }
+                    }
+                    tokens.push((lno, true, token));
+                } else {
+                    let mut token = c.to_string();
+                    while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                        token.push(c);
+                    }
+                    tokens.push((lno, false, token));
+                }
+            }
+        }
+
+        Ok(Self { tokens, next: 0 })
+    }
+
+    fn token(&mut self, what: &str, quoted: bool) -> Result<(usize, String), String> {
+        let (line, is_quoted, token) = self
+            .tokens
+            .get(self.next)
+            .ok_or_else(|| format!("unexpected end of file, expected {what}"))?;
+        self.next += 1;
+        if *is_quoted != quoted {
+            Err(format!("line {line}: expected {what}, found `{token}`"))
+        } else {
+            Ok((*line, token.clone()))
+        }
+    }
+
+    fn quoted(&mut self, what: &str) -> Result<String, String> {
+        Ok(self.token(what, true)?.1)
+    }
+
+    fn word(&mut self, what: &str) -> Result<(usize, String), String> {
+        self.token(what, false)
+    }
+
+    fn decimal(&mut self, what: &str) -> Result<usize, String> {
+        let (line, token) = self.word(what)?;
+        token
+            .parse()
+            .map_err(|_| format!("line {line}: expected {what}, found `{token}`"))
+    }
+
+    fn integer(&mut self, what: &str) -> Result<i64, String> {
+        let (line, token) = self.token(what, true)?;
+        decode_int(&token).ok_or_else(|| format!("line {line}: invalid encoded integer `{token}`"))
+    }
+
+    fn double(&mut self, what: &str) -> Result<f64, String> {
+        let (line, token) = self.token(what, true)?;
+        decode_f64(&token).ok_or_else(|| format!("line {line}: invalid encoded double `{token}`"))
+    }
+
+    /// Reads an array after its `BEGIN_ARRAY` keyword, returning its summary (without addresses), name and data.
+    fn array<R: NAIFSummaryRecord>(
+        &mut self,
+        num: usize,
+        nd: usize,
+        ni: usize,
+    ) -> Result<(R, String, Vec<f64>), String> {
+        let begin = (
+            self.decimal("the array number")?,
+            self.decimal("the array size")?,
+        );
+        if begin.0 != num {
+            return Err(format!("expected array {num}, found array {}", begin.0));
+        }
+        let name = self.quoted("the array name")?;
+
+        let mut summary_bytes = Vec::with_capacity(R::SIZE);
+        for _ in 0..nd {
+            summary_bytes.extend_from_slice(&self.double("a summary double")?.to_ne_bytes());
+        }
+        for _ in 0..ni - 2 {
+            let (line, token) = self.token("a summary integer", true)?;
+            let integer = decode_int(&token)
+                .and_then(|integer| i32::try_from(integer).ok())
+                .ok_or_else(|| format!("line {line}: invalid summary integer `{token}`"))?;
+            summary_bytes.extend_from_slice(&integer.to_ne_bytes());
+        }
+        // The addresses of the data are set when appending it to the DAF.
+        summary_bytes.resize(R::SIZE, 0x0);
+        let summary = R::read_from(&summary_bytes).unwrap();
+
+        // The announced size is only trusted as far as the remaining tokens can fill it.
+        let mut data = Vec::with_capacity(begin.1.min(self.tokens.len() - self.next));
+        loop {
+            let (line, keyword) = self.word("a block of data or the end of the array")?;
+            if keyword == "END_ARRAY" {
+                let end = (
+                    self.decimal("the array number")?,
+                    self.decimal("the array size")?,
+                );
+                if end != begin || data.len() != begin.1 {
+                    return Err(format!(
+                        "line {line}: array {num} of {} doubles ends as array {} of {} doubles, with {} doubles read",
+                        begin.1,
+                        end.0,
+                        end.1,
+                        data.len()
+                    ));
+                }
+                return Ok((summary, name, data));
+            }
+
+            let count: usize = keyword
+                .parse()
+                .map_err(|_| format!("line {line}: expected a block size, found `{keyword}`"))?;
+            for _ in 0..count {
+                data.push(self.double("array data")?);
+            }
+        }
+    }
+}
+
+/// Doubles the quotes of a string to be written in a quoted string.
+fn quote(s: &str) -> String {
+    s.replace('\'', "''")
+}
+
+/// Encodes an integer in the hexadecimal representation of SPICE, e.g. `-52` for -82.
+fn encode_int(value: i64) -> String {
+    if value < 0 {
+        format!("-{:X}", value.unsigned_abs())
+    } else {
+        format!("{value:X}")
+    }
+}
+
+fn decode_int(encoded: &str) -> Option<i64> {
+    match encoded.strip_prefix('-') {
+        Some(abs) => i64::from_str_radix(abs, 16).ok().map(|value| -value),
+        None => i64::from_str_radix(encoded, 16).ok(),
+    }
+}
+
+/// Encodes a double in the hexadecimal representation of SPICE, `MANTISSA^EXPONENT`, where the value is the hexadecimal
+/// fraction `0.MANTISSA` times 16 to the power `EXPONENT` (itself in hexadecimal), e.g. `A8C^4` for 43200.
+///
+/// All of the bits of the mantissa are kept, so that decoding the string returns the exact same double.
+fn encode_f64(value: f64) -> String {
+    let sign = if value.is_sign_negative() { "-" } else { "" };
+    if value == 0.0 {
+        return format!("{sign}0^0");
+    }
+
+    let bits = value.to_bits();
+    let biased_exp = ((bits >> 52) & 0x7ff) as i64;
+    let fraction = bits & ((1 << 52) - 1);
+    // The value is the integer mantissa times two to the power exp2.
+    let (mantissa, exp2) = if biased_exp == 0 {
+        (fraction, -1074)
+    } else {
+        (fraction | (1 << 52), biased_exp - 1075)
+    };
+
+    // Shift the mantissa such that the exponent is a multiple of four, i.e. a power of 16.
+    let shift = exp2.rem_euclid(4);
+    let digits = format!("{:X}", mantissa << shift);
+    let exp16 = (exp2 - shift) / 4 + digits.len() as i64;
+
+    format!(
+        "{sign}{}^{}",
+        digits.trim_end_matches('0'),
+        encode_int(exp16)
+    )
+}
+
+fn decode_f64(encoded: &str) -> Option<f64> {
+    let (negative, encoded) = match encoded.strip_prefix('-') {
+        Some(abs) => (true, abs),
+        None => (false, encoded),
+    };
+    let (digits, exponent) = encoded.split_once('^')?;
+    if digits.is_empty() || digits.len() > 16 {
+        return None;
+    }
+
+    let mantissa = u64::from_str_radix(digits, 16).ok()?;
+    let exp2 = 4 * (decode_int(exponent)? - digits.len() as i64);
+    let value = scale_by_pow2(mantissa as f64, exp2);
+
+    Some(if negative { -value } else { value })
+}
+
+/// Multiplies the value by two to the provided power, in steps that are exact whenever the result is representable.
+fn scale_by_pow2(mut value: f64, mut exp2: i64) -> f64 {
+    let pow2 = |exp2: i64| f64::from_bits(((exp2 + 1023) as u64) << 52);
+    while exp2 > 1023 && value.is_finite() {
+        value *= pow2(1023);
+        exp2 -= 1023;
+    }
+    while exp2 < -1022 && value != 0.0 {
+        value *= pow2(-1022);
+        exp2 += 1022;
+    }
+    value * pow2(exp2.clamp(-1022, 1023))
+}
+
+#[cfg(test)]
+mod ut_transfer {
+    use super::{decode_f64, encode_f64, encode_int};
+    use crate::constants::frames::EARTH_J2000;
+    use crate::math::Vector3;
+    use crate::naif::daf::{DAFError, DafDataType};
+    use crate::naif::spk::builder::SPKBuilder;
+    use crate::naif::spk::fit::{ChebyshevFit, ChebyshevFitConfig};
+    use crate::naif::spk::fixtures::{hermite_spk, sampled_states};
+    use crate::naif::SPK;
+    use hifitime::{Epoch, TimeUnits};
+
+    #[test]
+    fn hex_encoding() {
+        assert_eq!(encode_f64(1.0), "1^1");
+        assert_eq!(encode_f64(-1.0), "-1^1");
+        assert_eq!(encode_f64(0.5), "8^0");
+        assert_eq!(encode_f64(43200.0), "A8C^4");
+        assert_eq!(encode_f64(0.0), "0^0");
+        assert_eq!(encode_f64(1.0 / 4096.0), "1^-2");
+        assert_eq!(encode_int(-82), "-52");
+        assert_eq!(decode_f64("A8C^4"), Some(43200.0));
+        assert_eq!(decode_f64("-3A1AD8D8^9"), Some(-0x3A1AD8D8 as f64 * 16.0));
+
+        for value in [
+            -0.0,
+            core::f64::consts::PI,
+            -1e-3,
+            7.5e8 + 1e-6,
+            f64::MAX,
+            f64::MIN_POSITIVE,
+            f64::MIN_POSITIVE / 3.0,
+            f64::from_bits(1),
+            -f64::EPSILON,
+        ] {
+            let decoded = decode_f64(&encode_f64(value)).unwrap();
+            assert_eq!(
+                decoded.to_bits(),
+                value.to_bits(),
+                "{value:e} -> {}",
+                encode_f64(value)
+            );
+        }
+
+        for invalid in ["", "^1", "1^", "G^1", "1.5^2", "12345678901234567^1"] {
+            assert_eq!(decode_f64(invalid), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn spk_round_trip() {
+        let start = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        let states = sampled_states(EARTH_J2000, start, 1.minutes(), 0..=1500, |i| {
+            (
+                Vector3::new(7000.0 + i as f64 / 3.0, -(i as f64).sqrt(), 1e-20),
+                Vector3::new(1.0 / 3.0, 0.0, -7.5),
+            )
+        });
+        let fit = ChebyshevFit::fit(
+            |epoch| {
+                let t_s = (epoch - start).to_seconds();
+                Ok((
+                    Vector3::new(t_s, t_s.sin(), 0.0),
+                    Vector3::new(1.0, t_s.cos(), 0.0),
+                ))
+            },
+            start,
+            start + 1.hours(),
+            ChebyshevFitConfig {
+                data_type: DafDataType::Type3ChebyshevSextuplet,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let mut builder = SPKBuilder::new("TRANSFER 'FORMAT'").with_comments(
+            "First line\n\n  Indented line after an empty line, with 'quotes'\n\nLast line",
+        );
+        // The Hermite data is written in two blocks.
+        builder
+            .add_hermite_segment("HERMITE", -1001, &states, 6)
+            .unwrap();
+        builder
+            .add_chebyshev_segment("CHEBYSHEV", -82, EARTH_J2000, &fit)
+            .unwrap();
+        let spk = builder.build().unwrap();
+
+        let transfer = spk.to_transfer().unwrap();
+        let mut lines = transfer.lines();
+        assert_eq!(lines.next(), Some("DAFETF NAIF DAF ENCODED TRANSFER FILE"));
+        assert_eq!(lines.next(), Some("'DAF/SPK '"));
+        assert_eq!(lines.next(), Some("'2'"));
+        assert_eq!(lines.next(), Some("'6'"));
+        assert!(transfer.contains("BEGIN_ARRAY 1 10524\n'HERMITE"));
+        assert!(transfer.contains("'-52'\n'18F'\n'1'\n'3'\n"));
+        assert!(transfer.contains("\n1024\n"));
+        assert!(transfer.contains("\nTOTAL_ARRAYS 2\n"));
+        assert!(transfer.ends_with(
+            " ~NAIF/SPC BEGIN COMMENTS~\nFirst line\n\n  Indented line after an empty line, with 'quotes'\n\nLast line\n ~NAIF/SPC END COMMENTS~\n"
+        ));
+
+        // The binary DAF is rebuilt bit for bit.
+        let parsed = SPK::from_transfer(&transfer).unwrap();
+        assert_eq!(parsed.crc32(), spk.crc32());
+        assert_eq!(parsed.bytes, spk.bytes);
+        assert_eq!(parsed.to_transfer().unwrap(), transfer);
+        assert_eq!(
+            parsed.file_record().unwrap().internal_filename().unwrap(),
+            "TRANSFER 'FORMAT'"
+        );
+
+        // Without comments
+        let spk = hermite_spk(
+            "NO COMMENTS",
+            [("HERMITE", -1001, states[..10].to_vec(), 6)],
+        );
+        let transfer = spk.to_transfer().unwrap();
+        assert!(transfer.ends_with("BEGIN COMMENTS~\n ~NAIF/SPC END COMMENTS~\n"));
+        assert_eq!(SPK::from_transfer(&transfer).unwrap().bytes, spk.bytes);
+    }
+
+    #[test]
+    fn invalid_transfer() {
+        let start = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        let states = sampled_states(EARTH_J2000, start, 1.minutes(), 0..10, |i| {
+            (
+                Vector3::new(7000.0, i as f64, 0.0),
+                Vector3::new(0.0, 1.0, 0.0),
+            )
+        });
+        let transfer = hermite_spk("INVALID", [("HERMITE", -1001, states, 6)])
+            .to_transfer()
+            .unwrap();
+
+        let reason = |transfer: &str| match SPK::from_transfer(transfer) {
+            Err(DAFError::TransferFile { reason, .. }) => reason,
+            Err(e) => panic!("unexpected error {e}"),
+            Ok(_) => panic!("invalid transfer file parsed"),
+        };
+
+        assert_eq!(
+            reason("NAIF DAF ENCODED TRANSFER FILE"),
+            "the first line must be `DAFETF NAIF DAF ENCODED TRANSFER FILE`"
+        );
+        assert_eq!(
+            reason(&transfer.replacen("'2'\n'6'\n", "'2'\n'4'\n", 1)),
+            "summaries of ND = 2 and NI = 4 are not SPKSummaryRecord"
+        );
+        // The sizes of the summaries are checked before being used.
+        assert_eq!(
+            reason(&transfer.replacen("'2'\n'6'\n", "'-2'\n'6'\n", 1)),
+            "summaries of ND = -2 and NI = 6 are not SPKSummaryRecord"
+        );
+        assert_eq!(
+            reason(&transfer.replacen("'2'\n'6'\n", "'1FFFFFFFFFFFFFFF'\n'6'\n", 1)),
+            format!(
+                "summaries of ND = {} and NI = 6 are not SPKSummaryRecord",
+                0x1FFF_FFFF_FFFF_FFFF_i64
+            )
+        );
+        assert!(reason(&transfer.replacen("'-3E9'", "'-1000000000'", 1))
+            .contains("invalid summary integer `-1000000000`"));
+        assert_eq!(
+            reason(&transfer.replace("TOTAL_ARRAYS 1", "TOTAL_ARRAYS 2")),
+            "line 88: expected 2 arrays, found 1"
+        );
+        assert!(reason(&transfer.replacen("'1^1'", "'1.0'", 1))
+            .contains("invalid encoded double `1.0`"));
+        assert!(reason(&transfer.replace("'INVALID", "'INVALID\n")).contains("unterminated string"));
+        // Missing data
+        let truncated: String = transfer
+            .lines()
+            .filter(|line| *line != "'0^0'")
+            .collect::<Vec<&str>>()
+            .join("\n");
+        assert!(reason(&truncated).starts_with("line "));
+    }
+}
@@ -198,6 +198,7 @@ mod fk_ut {
         use std::str::FromStr;
 
         use crate::math::rotation::{r1, r2, r3, DCM};
+        use crate::math::Matrix3;
         let dataset = convert_fk("../data/moon_080317.txt", false).unwrap();
 
         assert_eq!(dataset.len(), 3, "expected three items");
@@ -207,11 +208,23 @@ mod fk_ut {
         // From the file:
         // TKFRAME_31007_ANGLES = (67.92   78.56   0.30 )
         // TKFRAME_31007_AXES   = (3,      2,      1    )
-        // These angles are in arcseconds.
-        let expected = r3((67.92 / 3600.0_f64).to_radians())
+        // These angles are in arcseconds, and define the rotation from MOON_PA_DE421 into MOON_ME_DE421.
+        let expected = r1((0.30 / 3600.0_f64).to_radians())
             * r2((78.56 / 3600.0_f64).to_radians())
-            * r1((0.30 / 3600.0_f64).to_radians());
+            * r3((67.92 / 3600.0_f64).to_radians());
         assert!((DCM::from(moon_me).rot_mat - expected).norm() < 1e-10);
+        assert_eq!(
+            moon_me.from, 31006,
+            "MOON_ME_DE421 is relative to MOON_PA_DE421"
+        );
+        assert_eq!(moon_me.to, 31007);
+
+        // The MOON_ME and MOON_PA frames are identity rotations from their DE421 counterparts
+        let moon_me = dataset.get_by_name("MOON_ME").unwrap();
+        assert_eq!((moon_me.from, moon_me.to), (31007, 31001));
+        let moon_pa = dataset.get_by_name("MOON_PA").unwrap();
+        assert_eq!((moon_pa.from, moon_pa.to), (31006, 31000));
+        assert!((DCM::from(moon_pa).rot_mat - Matrix3::identity()).norm() < 1e-12);
         println!("{}", dataset.crc32());
         dataset
             .save_as(&PathBuf::from_str("../data/moon_fk.epa").unwrap(), true)
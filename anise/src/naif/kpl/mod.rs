@@ -19,6 +19,7 @@ use self::parser::Assignment;
 pub mod fk;
 
 pub mod parser;
+pub mod sclk;
 pub mod tpc;
 
 pub trait KPLItem: Debug + Default {
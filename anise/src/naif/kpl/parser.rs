@@ -16,20 +16,23 @@ use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
 
+use hifitime::TimeScale;
 use log::{error, info, warn};
 
-use crate::constants::orientations::J2000;
+use crate::constants::orientations::{id_to_orientation_name, J2000};
 use crate::math::rotation::{r1, r2, r3, DCM};
 use crate::math::Matrix3;
 use crate::naif::kpl::fk::FKItem;
+use crate::naif::kpl::sclk::{SCLKItem, SCLKParameter};
 use crate::naif::kpl::tpc::TPCItem;
 use crate::naif::kpl::Parameter;
+use crate::naif::sclk::{SCLKCoefficient, SCLKPartition, SCLK};
 use crate::structure::dataset::{DataSetError, DataSetType};
 use crate::structure::metadata::Metadata;
 use crate::structure::planetocentric::ellipsoid::Ellipsoid;
 use crate::structure::planetocentric::phaseangle::PhaseAngle;
 use crate::structure::planetocentric::{PlanetaryData, MAX_NUT_PREC_ANGLES};
-use crate::structure::{EulerParameterDataSet, PlanetaryDataSet};
+use crate::structure::{EulerParameterDataSet, PlanetaryDataSet, SCLKDataSet};
 
 use super::{KPLItem, KPLValue};
 
@@ -86,13 +89,28 @@ pub fn parse_file<P: AsRef<Path> + fmt::Debug, I: KPLItem>(
 ) -> Result<HashMap<i32, I>, DataSetError> {
     let file =
         File::open(&file_path).unwrap_or_else(|_| panic!("Failed to open file {file_path:?}"));
-    let reader = BufReader::new(file);
+    parse_reader(BufReader::new(file), show_comments)
+}
+
+/// Parses the KPL text kernel held in these bytes, e.g. a text kernel that was read in memory.
+pub fn parse_bytes<I: KPLItem>(
+    bytes: &[u8],
+    show_comments: bool,
+) -> Result<HashMap<i32, I>, DataSetError> {
+    parse_reader(bytes, show_comments)
+}
 
+fn parse_reader<B: BufRead, I: KPLItem>(
+    reader: B,
+    show_comments: bool,
+) -> Result<HashMap<i32, I>, DataSetError> {
     let mut block_type = BlockType::Comment;
     let mut assignments = vec![];
 
-    for line in reader.lines() {
-        let line = line.expect("Failed to read line");
+    for (lno, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| DataSetError::Conversion {
+            action: format!("reading line {} of KPL text: {e}", lno + 1),
+        })?;
         let tline = line.trim();
 
         if tline.starts_with("\\begintext") {
@@ -161,139 +179,20 @@ pub fn convert_tpc<P: AsRef<Path> + fmt::Debug>(
 
     for (object_id, planetary_data) in planetary_data {
         match planetary_data.data.get(&Parameter::GravitationalParameter) {
+            Some(KPLValue::Float(_)) => {
+                let mut constant = PlanetaryData {
+                    object_id,
+                    parent_id: J2000,
+                    ..Default::default()
+                };
+                update_planetary_data(&mut constant, &planetary_data)?;
+
+                // Skip the DER serialization in full.
+                dataset.push(constant, Some(object_id), None)?;
+                info!("Added {object_id}");
+            }
             Some(mu_km3_s2_value) => {
-                match mu_km3_s2_value {
-                    KPLValue::Float(mu_km3_s2) => {
-                        // Build the ellipsoid
-                        let ellipsoid = match planetary_data.data.get(&Parameter::Radii) {
-                            Some(radii_km) => match radii_km {
-                                KPLValue::Float(radius_km) => {
-                                    Some(Ellipsoid::from_sphere(*radius_km))
-                                }
-                                KPLValue::Matrix(radii_km) => match radii_km.len() {
-                                    2 => Some(Ellipsoid::from_spheroid(radii_km[0], radii_km[1])),
-                                    3 => Some(Ellipsoid {
-                                        semi_major_equatorial_radius_km: radii_km[0],
-                                        semi_minor_equatorial_radius_km: radii_km[1],
-                                        polar_radius_km: radii_km[2],
-                                    }),
-                                    _ => unreachable!(),
-                                },
-                                _ => panic!("radii_km should be float or matrix, got {radii_km:?}"),
-                            },
-                            None => None,
-                        };
-
-                        let mut constant = match planetary_data.data.get(&Parameter::PoleRa) {
-                            Some(data) => match data {
-                                KPLValue::Matrix(pole_ra_data) => {
-                                    let mut pole_ra_data = pole_ra_data.clone();
-                                    if let Some(coeffs) =
-                                        planetary_data.data.get(&Parameter::NutPrecRa)
-                                    {
-                                        pole_ra_data.extend(coeffs.to_vec_f64().unwrap());
-                                    }
-                                    let pola_ra = PhaseAngle::maybe_new(&pole_ra_data);
-
-                                    let mut pola_dec_data: Vec<f64> = planetary_data.data
-                                        [&Parameter::PoleDec]
-                                        .to_vec_f64()
-                                        .unwrap();
-                                    if let Some(coeffs) =
-                                        planetary_data.data.get(&Parameter::NutPrecDec)
-                                    {
-                                        pola_dec_data.extend(coeffs.to_vec_f64().unwrap());
-                                    }
-                                    let pola_dec = PhaseAngle::maybe_new(&pola_dec_data);
-
-                                    let mut prime_mer_data: Vec<f64> = planetary_data.data
-                                        [&Parameter::PrimeMeridian]
-                                        .to_vec_f64()
-                                        .unwrap();
-                                    if let Some(coeffs) =
-                                        planetary_data.data.get(&Parameter::NutPrecPm)
-                                    {
-                                        prime_mer_data.extend(coeffs.to_vec_f64().unwrap());
-                                    }
-                                    let prime_mer = PhaseAngle::maybe_new(&prime_mer_data);
-
-                                    let long_axis =
-                                        match planetary_data.data.get(&Parameter::LongAxis) {
-                                            Some(val) => match val {
-                                                KPLValue::Float(data) => Some(*data),
-                                                KPLValue::Matrix(data) => Some(data[0]),
-                                                _ => panic!(
-                                                "long axis must be float or matrix, got {val:?}"
-                                            ),
-                                            },
-                                            None => None,
-                                        };
-
-                                    PlanetaryData {
-                                        object_id,
-                                        parent_id: if [199, 299].contains(&object_id) {
-                                            J2000
-                                        } else if object_id > 100 {
-                                            object_id / 100
-                                        } else {
-                                            J2000
-                                        },
-                                        mu_km3_s2: *mu_km3_s2,
-                                        shape: ellipsoid,
-                                        pole_right_ascension: pola_ra,
-                                        pole_declination: pola_dec,
-                                        prime_meridian: prime_mer,
-                                        long_axis,
-                                        ..Default::default()
-                                    }
-                                }
-                                _ => unreachable!(),
-                            },
-                            None => {
-                                // Assume not rotation data available
-                                PlanetaryData {
-                                    object_id,
-                                    mu_km3_s2: *mu_km3_s2,
-                                    shape: ellipsoid,
-                                    parent_id: J2000,
-                                    ..Default::default()
-                                }
-                            }
-                        };
-
-                        // Add the nutation precession angles, which are defined for the system
-                        if let Some(nut_prec_val) =
-                            planetary_data.data.get(&Parameter::NutPrecAngles)
-                        {
-                            let phase_deg =
-                                match planetary_data.data.get(&Parameter::MaxPhaseDegree) {
-                                    Some(val) => (val.to_i32().unwrap() + 1) as usize,
-                                    None => 2,
-                                };
-                            let nut_prec_data = nut_prec_val.to_vec_f64().unwrap();
-                            let mut coeffs = [PhaseAngle::<0>::default(); MAX_NUT_PREC_ANGLES];
-                            let mut num = 0;
-                            for (i, nut_prec) in nut_prec_data.chunks(phase_deg).enumerate() {
-                                coeffs[i] = PhaseAngle::<0> {
-                                    offset_deg: nut_prec[0],
-                                    rate_deg: nut_prec[1],
-                                    ..Default::default()
-                                };
-                                num += 1;
-                            }
-
-                            constant.num_nut_prec_angles = num;
-                            constant.nut_prec_angles = coeffs;
-                        };
-
-                        // Skip the DER serialization in full.
-                        dataset.push(constant, Some(object_id), None)?;
-                        info!("Added {object_id}");
-                    }
-                    _ => error!(
-                        "expected gravity parameter to be a float but got {mu_km3_s2_value:?}"
-                    ),
-                }
+                error!("expected gravity parameter to be a float but got {mu_km3_s2_value:?}")
             }
             None => {
                 warn!("Skipping {object_id}: no gravity data")
@@ -310,18 +209,158 @@ pub fn convert_tpc<P: AsRef<Path> + fmt::Debug>(
     Ok(dataset)
 }
 
+/// Updates the planetary data of a body with the parameters defined for it in a KPL/TPC file.
+///
+/// Only the parameters defined in the TPC item are updated, so that the gravity parameter and the orientation of a body
+/// may be defined in different files.
+pub fn update_planetary_data(
+    constant: &mut PlanetaryData,
+    planetary_data: &TPCItem,
+) -> Result<(), DataSetError> {
+    let object_id = constant.object_id;
+    let conversion_err = |what: &str| DataSetError::Conversion {
+        action: format!("{what} for TPC body {object_id}"),
+    };
+    let values = |param: Parameter| -> Result<Option<Vec<f64>>, DataSetError> {
+        planetary_data
+            .data
+            .get(&param)
+            .map(|value| match value {
+                KPLValue::Float(data) => Ok(vec![*data]),
+                KPLValue::Integer(data) => Ok(vec![f64::from(*data)]),
+                _ => value
+                    .to_vec_f64()
+                    .map_err(|_| conversion_err(&format!("invalid {param:?} {value:?}"))),
+            })
+            .transpose()
+    };
+
+    if let Some(mu_km3_s2_value) = planetary_data.data.get(&Parameter::GravitationalParameter) {
+        match mu_km3_s2_value {
+            KPLValue::Float(mu_km3_s2) => constant.mu_km3_s2 = *mu_km3_s2,
+            _ => {
+                return Err(conversion_err(&format!(
+                    "expected gravity parameter to be a float but got {mu_km3_s2_value:?}"
+                )))
+            }
+        }
+    }
+
+    // Build the ellipsoid
+    if let Some(radii_km) = values(Parameter::Radii)? {
+        constant.shape = Some(match radii_km.as_slice() {
+            [radius_km] => Ellipsoid::from_sphere(*radius_km),
+            [equatorial_km, polar_km] => Ellipsoid::from_spheroid(*equatorial_km, *polar_km),
+            [semi_major_km, semi_minor_km, polar_km] => Ellipsoid {
+                semi_major_equatorial_radius_km: *semi_major_km,
+                semi_minor_equatorial_radius_km: *semi_minor_km,
+                polar_radius_km: *polar_km,
+            },
+            _ => return Err(conversion_err(&format!("invalid radii {radii_km:?}"))),
+        });
+    }
+
+    if let Some(mut pole_ra_data) = values(Parameter::PoleRa)? {
+        if let Some(coeffs) = values(Parameter::NutPrecRa)? {
+            pole_ra_data.extend(coeffs);
+        }
+
+        let mut pola_dec_data =
+            values(Parameter::PoleDec)?.ok_or_else(|| conversion_err("no pole declination"))?;
+        if let Some(coeffs) = values(Parameter::NutPrecDec)? {
+            pola_dec_data.extend(coeffs);
+        }
+
+        let mut prime_mer_data =
+            values(Parameter::PrimeMeridian)?.ok_or_else(|| conversion_err("no prime meridian"))?;
+        if let Some(coeffs) = values(Parameter::NutPrecPm)? {
+            prime_mer_data.extend(coeffs);
+        }
+
+        constant.parent_id = if [199, 299].contains(&object_id) {
+            J2000
+        } else if object_id > 100 {
+            object_id / 100
+        } else {
+            J2000
+        };
+        constant.pole_right_ascension = PhaseAngle::maybe_new(&pole_ra_data);
+        constant.pole_declination = PhaseAngle::maybe_new(&pola_dec_data);
+        constant.prime_meridian = PhaseAngle::maybe_new(&prime_mer_data);
+        constant.long_axis = values(Parameter::LongAxis)?.and_then(|data| data.first().copied());
+    }
+
+    // Add the nutation precession angles, which are defined for the system
+    if let Some(nut_prec_data) = values(Parameter::NutPrecAngles)? {
+        let phase_deg = match planetary_data.data.get(&Parameter::MaxPhaseDegree) {
+            Some(val) => {
+                (val.to_i32()
+                    .map_err(|_| conversion_err(&format!("invalid max phase degree {val:?}")))?
+                    + 1) as usize
+            }
+            None => 2,
+        };
+        if nut_prec_data.len() > phase_deg * MAX_NUT_PREC_ANGLES {
+            return Err(conversion_err(&format!(
+                "more than {MAX_NUT_PREC_ANGLES} nutation precession angles"
+            )));
+        }
+        let mut coeffs = [PhaseAngle::<0>::default(); MAX_NUT_PREC_ANGLES];
+        let mut num = 0;
+        for (i, nut_prec) in nut_prec_data.chunks(phase_deg).enumerate() {
+            coeffs[i] = PhaseAngle::<0> {
+                offset_deg: nut_prec[0],
+                rate_deg: nut_prec.get(1).copied().unwrap_or_default(),
+                ..Default::default()
+            };
+            num += 1;
+        }
+
+        constant.num_nut_prec_angles = num;
+        constant.nut_prec_angles = coeffs;
+    }
+
+    Ok(())
+}
+
 /// Converts a KPL/FK file, that defines frame constants like fixed rotations, and frame name to ID mappings into the EulerParameterDataSet equivalent ANISE file.
 /// KPL/FK files must be converted into "PCA" (Planetary Constant ANISE) files before being loaded into ANISE.
 pub fn convert_fk<P: AsRef<Path> + fmt::Debug>(
     fk_file_path: P,
     show_comments: bool,
+) -> Result<EulerParameterDataSet, DataSetError> {
+    fk_dataset(parse_file(fk_file_path, show_comments)?)
+}
+
+/// Builds the EulerParameterDataSet of the frames defined in a parsed KPL/FK file.
+pub fn fk_dataset(
+    assignments: HashMap<i32, FKItem>,
 ) -> Result<EulerParameterDataSet, DataSetError> {
     let mut dataset = EulerParameterDataSet::default();
 
-    let assignments = parse_file::<_, FKItem>(fk_file_path, show_comments)?;
+    // The RELATIVE frame of a TK frame is specified by name, which may be defined in this FK or be a built-in frame.
+    let ids_by_name: HashMap<String, i32> = assignments
+        .iter()
+        .filter_map(|(id, item)| item.name.as_ref().map(|name| (name.clone(), *id)))
+        .collect();
+
+    let relative_id = |id: i32, item: &FKItem| -> Result<i32, DataSetError> {
+        match item.data.get(&Parameter::Relative) {
+            Some(KPLValue::String(name)) => match ids_by_name.get(name) {
+                Some(relative_id) => Ok(*relative_id),
+                None => id_to_orientation_name(name).map_err(|_| DataSetError::Conversion {
+                    action: format!("unknown RELATIVE frame `{name}` for FK ID {id}"),
+                }),
+            },
+            _ => Err(DataSetError::Conversion {
+                action: format!("no RELATIVE frame for FK ID {id}"),
+            }),
+        }
+    };
 
     // Add all of the data into the data set
-    for (id, item) in assignments {
+    for (id, item) in &assignments {
+        let id = *id;
         if !item.data.contains_key(&Parameter::Angles)
             && !item.data.contains_key(&Parameter::Matrix)
         {
@@ -341,9 +380,11 @@ pub fn convert_fk<P: AsRef<Path> + fmt::Debug>(
                     *item /= 3600.0;
                 }
             }
-            // Build the quaternion from the Euler matrices
-            let from = id;
-            let to = item.data[&Parameter::Center].to_i32().unwrap();
+            // Build the quaternion from the Euler matrices.
+            // As in SPICE, the rotation from the RELATIVE frame to this frame is
+            // [angle_3]_axis_3 * [angle_2]_axis_2 * [angle_1]_axis_1
+            let from = relative_id(id, item)?;
+            let to = id;
 
             let mut dcm = Matrix3::identity();
 
@@ -360,7 +401,7 @@ pub fn convert_fk<P: AsRef<Path> + fmt::Debug>(
                 } else {
                     r3(angle_data[i].to_radians())
                 };
-                dcm *= this_dcm;
+                dcm = this_dcm * dcm;
             }
             // Convert to quaternion
             let q = DCM {
@@ -374,6 +415,8 @@ pub fn convert_fk<P: AsRef<Path> + fmt::Debug>(
             dataset.push(q, Some(id), item.name.as_deref())?;
         } else if let Some(matrix) = item.data.get(&Parameter::Matrix) {
             let mat_data = matrix.to_vec_f64().unwrap();
+            // SPICE stores the rotation from this frame to the RELATIVE frame in column-major order,
+            // so reading it row by row yields the rotation from the RELATIVE frame to this frame.
             let rot_mat = Matrix3::new(
                 mat_data[0],
                 mat_data[1],
@@ -386,8 +429,8 @@ pub fn convert_fk<P: AsRef<Path> + fmt::Debug>(
                 mat_data[8],
             );
             let dcm = DCM {
-                from: id,
-                to: item.data[&Parameter::Center].to_i32().unwrap(),
+                from: relative_id(id, item)?,
+                to: id,
                 rot_mat,
                 rot_mat_dt: None,
             };
@@ -401,3 +444,116 @@ pub fn convert_fk<P: AsRef<Path> + fmt::Debug>(
 
     Ok(dataset)
 }
+
+/// Converts a KPL/SCLK file, that defines spacecraft clocks, into the SCLKDataSet equivalent ANISE file.
+/// Only the type 1 clocks (the only type defined by SPICE) are supported, others are skipped.
+pub fn convert_sclk<P: AsRef<Path> + fmt::Debug>(
+    sclk_file_path: P,
+    show_comments: bool,
+) -> Result<SCLKDataSet, DataSetError> {
+    sclk_dataset(parse_file(sclk_file_path, show_comments)?)
+}
+
+/// Builds the SCLKDataSet of the clocks defined in a parsed KPL/SCLK file.
+pub fn sclk_dataset(assignments: HashMap<i32, SCLKItem>) -> Result<SCLKDataSet, DataSetError> {
+    let mut dataset = SCLKDataSet::default();
+
+    for (clock_id, item) in assignments {
+        let values = |param: SCLKParameter| -> Result<Vec<f64>, DataSetError> {
+            item.values(param).ok_or(DataSetError::Conversion {
+                action: format!("no {param:?} for SCLK {clock_id}"),
+            })
+        };
+
+        let data_type = values(SCLKParameter::DataType)?;
+        if data_type != [1.0] {
+            warn!("Skipping SCLK {clock_id}: unsupported data type {data_type:?}");
+            continue;
+        }
+
+        let time_scale = match item.values(SCLKParameter::TimeSystem).as_deref() {
+            None | Some([1.0]) => TimeScale::TDB,
+            Some([2.0]) => TimeScale::TT,
+            Some(time_system) => {
+                return Err(DataSetError::Conversion {
+                    action: format!("unknown time system {time_system:?} for SCLK {clock_id}"),
+                })
+            }
+        };
+
+        let moduli = values(SCLKParameter::Moduli)?;
+        let offsets = values(SCLKParameter::Offsets)?;
+        if moduli.len() != offsets.len() {
+            return Err(DataSetError::Conversion {
+                action: format!("SCLK {clock_id} has a different number of moduli and offsets"),
+            });
+        }
+
+        let delimiter = match values(SCLKParameter::OutputDelimiter)?.as_slice() {
+            [1.0] => '.',
+            [2.0] => ':',
+            [3.0] => '-',
+            [4.0] => ',',
+            [5.0] => ' ',
+            delim => {
+                return Err(DataSetError::Conversion {
+                    action: format!("unknown output delimiter {delim:?} for SCLK {clock_id}"),
+                })
+            }
+        };
+
+        let starts = values(SCLKParameter::PartitionStart)?;
+        let ends = values(SCLKParameter::PartitionEnd)?;
+        if starts.len() != ends.len() {
+            return Err(DataSetError::Conversion {
+                action: format!(
+                    "SCLK {clock_id} has a different number of partition starts and ends"
+                ),
+            });
+        }
+        let partitions = starts
+            .iter()
+            .zip(&ends)
+            .map(|(start_ticks, end_ticks)| SCLKPartition {
+                start_ticks: *start_ticks,
+                end_ticks: *end_ticks,
+            })
+            .collect();
+
+        let mut sclk = SCLK {
+            clock_id,
+            time_scale,
+            moduli,
+            offsets,
+            delimiter,
+            partitions,
+            coefficients: Vec::new(),
+        };
+
+        // The rates of the kernel are in seconds of parallel time per count of the most significant field.
+        let ticks_per_count = sclk.ticks_per_count();
+        let coefficients = values(SCLKParameter::Coefficients)?;
+        if coefficients.len() % 3 != 0 {
+            return Err(DataSetError::Conversion {
+                action: format!("SCLK {clock_id} coefficients are not triplets"),
+            });
+        }
+        sclk.coefficients = coefficients
+            .chunks_exact(3)
+            .map(|coeff| SCLKCoefficient {
+                ticks: coeff[0],
+                parallel_time_s: coeff[1],
+                rate_s_per_tick: coeff[2] / ticks_per_count,
+            })
+            .collect();
+
+        dataset.push(sclk, Some(clock_id), None)?;
+        info!("Added SCLK {clock_id}");
+    }
+
+    dataset.set_crc32();
+    dataset.metadata = Metadata::default();
+    dataset.metadata.dataset_type = DataSetType::SCLKData;
+
+    Ok(dataset)
+}
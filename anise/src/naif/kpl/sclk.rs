@@ -0,0 +1,163 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use std::{collections::HashMap, str::FromStr};
+
+use log::warn;
+use snafu::{whatever, Whatever};
+
+use super::{parser::Assignment, KPLItem, KPLValue};
+
+/// Known parameters of the spacecraft clock kernels (KPL/SCLK)
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum SCLKParameter {
+    DataType,
+    TimeSystem,
+    NumFields,
+    Moduli,
+    Offsets,
+    OutputDelimiter,
+    PartitionStart,
+    PartitionEnd,
+    Coefficients,
+}
+
+impl FromStr for SCLKParameter {
+    type Err = Whatever;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "SCLK_DATA_TYPE" => Ok(Self::DataType),
+            "SCLK01_TIME_SYSTEM" => Ok(Self::TimeSystem),
+            "SCLK01_N_FIELDS" => Ok(Self::NumFields),
+            "SCLK01_MODULI" => Ok(Self::Moduli),
+            "SCLK01_OFFSETS" => Ok(Self::Offsets),
+            "SCLK01_OUTPUT_DELIM" => Ok(Self::OutputDelimiter),
+            "SCLK_PARTITION_START" => Ok(Self::PartitionStart),
+            "SCLK_PARTITION_END" => Ok(Self::PartitionEnd),
+            "SCLK01_COEFFICIENTS" => Ok(Self::Coefficients),
+            _ => {
+                whatever!("unknown parameter `{s}`")
+            }
+        }
+    }
+}
+
+/// The data of one spacecraft clock in an SCLK kernel.
+///
+/// The keywords of the clock end with the negative of the clock ID, e.g. `SCLK01_MODULI_82` for the clock of Cassini (-82).
+#[derive(Debug, Default)]
+pub struct SCLKItem {
+    pub clock_id: Option<i32>,
+    pub data: HashMap<SCLKParameter, KPLValue>,
+}
+
+impl SCLKItem {
+    /// Returns the numerical values of this parameter, whether it was specified as a single number or as a list.
+    pub fn values(&self, param: SCLKParameter) -> Option<Vec<f64>> {
+        match self.data.get(&param)? {
+            KPLValue::Float(data) => Some(vec![*data]),
+            KPLValue::Integer(data) => Some(vec![*data as f64]),
+            KPLValue::Matrix(data) => Some(data.clone()),
+            KPLValue::String(_) => None,
+        }
+    }
+}
+
+impl KPLItem for SCLKItem {
+    type Parameter = SCLKParameter;
+
+    /// Returns the clock ID, or -1 for the kernel metadata (e.g. SCLK_KERNEL_ID).
+    fn extract_key(data: &Assignment) -> i32 {
+        if data.keyword.starts_with("SCLK") {
+            match data.keyword.rsplit_once('_') {
+                Some((_, id)) => match id.parse::<i32>() {
+                    Ok(id) => -id,
+                    Err(_) => -1,
+                },
+                None => -1,
+            }
+        } else {
+            -1
+        }
+    }
+
+    fn data(&self) -> &HashMap<Self::Parameter, KPLValue> {
+        &self.data
+    }
+
+    fn parse(&mut self, data: Assignment) {
+        if let Some((param, id)) = data.keyword.rsplit_once('_') {
+            if let Ok(id) = id.parse::<i32>() {
+                self.clock_id = Some(-id);
+                if let Ok(param) = SCLKParameter::from_str(param) {
+                    self.data.insert(param, data.to_value());
+                } else {
+                    warn!("Unknown parameter `{param}` -- ignoring");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod sclk_ut {
+    use super::{KPLValue, SCLKItem, SCLKParameter};
+    use crate::naif::kpl::parser::{convert_sclk, parse_file};
+    use crate::naif::sclk::SCLKPartition;
+    use crate::structure::SCLKDataSet;
+    use hifitime::TimeScale;
+
+    #[test]
+    fn test_parse_sclk() {
+        let assignments = parse_file::<_, SCLKItem>("../data/example.tsc", false).unwrap();
+
+        // The kernel ID is not a clock.
+        assert_eq!(assignments.len(), 1);
+        let clock = &assignments[&-999];
+        assert_eq!(clock.clock_id, Some(-999));
+        assert_eq!(clock.data[&SCLKParameter::DataType], KPLValue::Integer(1));
+        assert_eq!(
+            clock.data[&SCLKParameter::Moduli],
+            KPLValue::Matrix(vec![4294967296.0, 256.0])
+        );
+        assert_eq!(
+            clock.values(SCLKParameter::PartitionStart).unwrap(),
+            vec![0.0, 0.0]
+        );
+        assert_eq!(clock.values(SCLKParameter::Coefficients).unwrap().len(), 9);
+    }
+
+    #[test]
+    fn test_convert_sclk() {
+        let dataset = convert_sclk("../data/example.tsc", false).unwrap();
+        assert_eq!(dataset.len(), 1);
+
+        let sclk = dataset.get_by_id(-999).unwrap();
+        assert_eq!(sclk.time_scale, TimeScale::TDB);
+        assert_eq!(sclk.delimiter, '.');
+        assert_eq!(sclk.ticks_per_count(), 256.0);
+        assert_eq!(
+            sclk.partitions[1],
+            SCLKPartition {
+                start_ticks: 0.0,
+                end_ticks: 1099511627775.0
+            }
+        );
+        // The rates are converted to seconds per tick.
+        assert_eq!(sclk.coefficients[1].rate_s_per_tick, 1.000001 / 256.0);
+
+        // Check that the dataset round trips through its encoding.
+        let mut buf = vec![];
+        der::Encode::encode_to_vec(&dataset, &mut buf).unwrap();
+        let reloaded = SCLKDataSet::try_from_bytes(buf).unwrap();
+        assert_eq!(reloaded.get_by_id(-999).unwrap(), sclk);
+    }
+}
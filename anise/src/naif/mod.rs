@@ -8,15 +8,18 @@
  * Documentation: https://nyxspace.com/
  */
 
+pub mod ck;
 pub mod daf;
 
 pub mod kpl;
 pub mod pck;
+pub mod sclk;
 pub mod spk;
 
 pub mod pretty_print;
 
 use self::{
+    ck::CKSummaryRecord,
     daf::{daf::MutDAF, DAF},
     pck::BPCSummaryRecord,
     spk::summary::SPKSummaryRecord,
@@ -30,6 +33,10 @@ pub type MutSPK = MutDAF<SPKSummaryRecord>;
 pub type BPC = DAF<BPCSummaryRecord>;
 /// Binary Planetary Constant, mutable, for editing DAF/PCK files
 pub type MutBPC = MutDAF<BPCSummaryRecord>;
+/// C-matrix Kernel, i.e. spacecraft and instrument attitude
+pub type CK = DAF<CKSummaryRecord>;
+/// C-matrix Kernel, mutable, for editing DAF/CK files
+pub type MutCK = MutDAF<CKSummaryRecord>;
 
 #[macro_export]
 macro_rules! parse_bytes_as {
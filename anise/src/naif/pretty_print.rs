@@ -3,7 +3,7 @@ use tabled::{settings::Style, Table, Tabled};
 
 use crate::naif::daf::NAIFSummaryRecord;
 
-use super::{BPC, SPK};
+use super::{BPC, CK, SPK};
 
 #[derive(Tabled)]
 pub struct BpcRow {
@@ -41,6 +41,24 @@ pub struct SpkRow {
     pub interpolation_kind: String,
 }
 
+#[derive(Tabled)]
+pub struct CkRow {
+    #[tabled(rename = "Name")]
+    pub name: String,
+    #[tabled(rename = "Instrument")]
+    pub instrument: String,
+    #[tabled(rename = "Reference frame")]
+    pub reference_frame: String,
+    #[tabled(rename = "Start SCLK ticks")]
+    pub start_ticks: f64,
+    #[tabled(rename = "End SCLK ticks")]
+    pub end_ticks: f64,
+    #[tabled(rename = "Interpolation kind")]
+    pub interpolation_kind: String,
+    #[tabled(rename = "Angular velocity")]
+    pub angular_velocity: bool,
+}
+
 pub trait NAIFPrettyPrint {
     fn describe(&self) -> String {
         self.describe_in(TimeScale::TDB, None)
@@ -62,9 +80,8 @@ impl NAIFPrettyPrint for BPC {
             Unit::Second * 0
         };
 
-        for (sno, summary) in self.data_summaries().unwrap().iter().enumerate() {
-            let name_rcrd = self.name_record().unwrap();
-            let name = name_rcrd.nth_name(sno, self.file_record().unwrap().summary_size());
+        for (sno, summary) in self.summaries().unwrap() {
+            let name = self.nth_name(sno).unwrap();
             if summary.is_empty() {
                 continue;
             }
@@ -101,9 +118,8 @@ impl NAIFPrettyPrint for SPK {
             Unit::Second * 0
         };
 
-        for (sno, summary) in self.data_summaries().unwrap().iter().enumerate() {
-            let name_rcrd = self.name_record().unwrap();
-            let name = name_rcrd.nth_name(sno, self.file_record().unwrap().summary_size());
+        for (sno, summary) in self.summaries().unwrap() {
+            let name = self.nth_name(sno).unwrap();
             if summary.is_empty() {
                 continue;
             }
@@ -127,3 +143,35 @@ impl NAIFPrettyPrint for SPK {
         format!("{tbl}")
     }
 }
+
+impl NAIFPrettyPrint for CK {
+    /// Returns a string of a table representing this CK.
+    ///
+    /// CK data is time tagged in spacecraft clock ticks, so the bounds of each segment are printed as encoded SCLK ticks:
+    /// the time scale and rounding arguments are unused.
+    fn describe_in(&self, _time_scale: TimeScale, _round: Option<bool>) -> String {
+        // Build the rows of the table
+        let mut rows = Vec::new();
+
+        for (sno, summary) in self.summaries().unwrap() {
+            let name = self.nth_name(sno).unwrap();
+            if summary.is_empty() {
+                continue;
+            }
+
+            rows.push(CkRow {
+                name: name.to_string(),
+                instrument: format!("{}", summary.instrument_id),
+                reference_frame: format!("{}", summary.reference_frame_id),
+                start_ticks: summary.start_sclk_ticks,
+                end_ticks: summary.end_sclk_ticks,
+                interpolation_kind: summary.data_type().unwrap().to_string(),
+                angular_velocity: summary.has_angular_velocity(),
+            });
+        }
+
+        let mut tbl = Table::new(rows);
+        tbl.with(Style::modern());
+        format!("{tbl}")
+    }
+}
@@ -0,0 +1,580 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use core::fmt;
+use der::{Decode, Encode, Reader, Writer};
+use hifitime::{Epoch, TimeScale, Unit, ET_EPOCH_S};
+use snafu::prelude::*;
+
+use crate::{
+    structure::dataset::{DataSetError, DataSetT},
+    NaifId,
+};
+
+#[derive(Debug, Snafu, PartialEq)]
+#[snafu(visibility(pub(crate)))]
+pub enum SCLKError {
+    #[snafu(display("no spacecraft clock {clock_id} loaded (must call with_sclk)"))]
+    SCLKNotLoaded { clock_id: NaifId },
+    #[snafu(display("spacecraft clock {clock_id} has no coefficients"))]
+    NoCoefficients { clock_id: NaifId },
+    #[snafu(display("invalid clock string `{sclk}` for spacecraft clock {clock_id}: {reason}"))]
+    InvalidSCLKString {
+        clock_id: NaifId,
+        sclk: String,
+        reason: &'static str,
+    },
+    #[snafu(display(
+        "{ticks} ticks are outside of the partitions of spacecraft clock {clock_id}"
+    ))]
+    OutsidePartitions { clock_id: NaifId, ticks: f64 },
+    #[snafu(display("when {action}, {source}"))]
+    SCLKDataSet {
+        action: &'static str,
+        source: DataSetError,
+    },
+}
+
+/// One record of the coefficients table of a spacecraft clock: from the encoded clock `ticks` onward,
+/// the parallel time is `parallel_time_s + (ticks - self.ticks) * rate_s_per_tick`.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct SCLKCoefficient {
+    /// Encoded SCLK, i.e. the number of ticks since the start of the first partition of the clock
+    pub ticks: f64,
+    /// Parallel time at these ticks, in seconds past J2000 in the time scale of the clock
+    pub parallel_time_s: f64,
+    /// Clock rate, in seconds of parallel time per tick
+    pub rate_s_per_tick: f64,
+}
+
+/// A partition of a spacecraft clock, i.e. a period between two resets of the clock, in ticks of the clock counter.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct SCLKPartition {
+    pub start_ticks: f64,
+    pub end_ticks: f64,
+}
+
+impl SCLKPartition {
+    /// Returns the number of ticks in this partition
+    pub fn len_ticks(&self) -> f64 {
+        self.end_ticks - self.start_ticks
+    }
+}
+
+/// A spacecraft clock (SCLK), which maps the encoded ticks used to time tag CK data and telemetry to ephemeris time.
+///
+/// Between two coefficient records, the clock is modeled as a linear function of the ticks, exactly like the
+/// type 1 spacecraft clocks of SPICE. Before the first record, the first rate is used.
+///
+/// # Clock strings
+/// A clock string, e.g. `1/1465644281.165`, is made of an optional partition number followed by a slash, and of
+/// the clock fields from the most significant to the least significant one. Fields may be separated by any of
+/// `.`, `:`, `-`, `,`, or a space. Missing trailing fields are set to their offset value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SCLK {
+    /// ID of this clock, which is the NAIF ID of the spacecraft (e.g. -82 for Cassini)
+    pub clock_id: NaifId,
+    /// Time scale of the parallel time of the coefficients, either TDB or TT
+    pub time_scale: TimeScale,
+    /// Modulus of each field of the clock string, from the most significant field to the least significant one
+    pub moduli: Vec<f64>,
+    /// Offset of each field of the clock string, i.e. the value of the field when its count is zero
+    pub offsets: Vec<f64>,
+    /// Delimiter between fields when formatting clock strings
+    pub delimiter: char,
+    /// Partitions of the clock, in order
+    pub partitions: Vec<SCLKPartition>,
+    /// Coefficient records, sorted in increasing number of ticks
+    pub coefficients: Vec<SCLKCoefficient>,
+}
+
+impl Default for SCLK {
+    fn default() -> Self {
+        Self {
+            clock_id: 0,
+            time_scale: TimeScale::TDB,
+            moduli: Vec::new(),
+            offsets: Vec::new(),
+            delimiter: '.',
+            partitions: Vec::new(),
+            coefficients: Vec::new(),
+        }
+    }
+}
+
+impl DataSetT for SCLK {
+    const NAME: &'static str = "spacecraft clock";
+}
+
+impl SCLK {
+    /// Builds a new spacecraft clock from its coefficients, sorting them by their number of ticks.
+    ///
+    /// The clock has a single partition starting at zero ticks and a single field counting ticks, with its parallel time in TDB.
+    pub fn new(clock_id: NaifId, mut coefficients: Vec<SCLKCoefficient>) -> Self {
+        coefficients.sort_by(|a, b| a.ticks.total_cmp(&b.ticks));
+        Self {
+            clock_id,
+            coefficients,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the ID of the clock used to time tag the data of the provided CK instrument (or spacecraft).
+    ///
+    /// As in SPICE, the spacecraft of an instrument is the instrument ID divided by 1000, e.g. -82000 is on -82.
+    pub const fn clock_id_of(id: NaifId) -> NaifId {
+        if id <= -1000 {
+            id / 1000
+        } else {
+            id
+        }
+    }
+
+    /// Returns the number of ticks in one count of the most significant field of the clock.
+    pub fn ticks_per_count(&self) -> f64 {
+        self.moduli.iter().skip(1).product()
+    }
+
+    /// Converts the encoded ticks of this clock into the parallel time of the clock, in seconds past J2000.
+    pub fn ticks_to_parallel_time_s(&self, ticks: f64) -> Result<f64, SCLKError> {
+        // Find the last record starting at or before these ticks, defaulting to the first one.
+        let idx = self
+            .coefficients
+            .partition_point(|coeff| coeff.ticks <= ticks)
+            .saturating_sub(1);
+        let coeff = self
+            .coefficients
+            .get(idx)
+            .ok_or(SCLKError::NoCoefficients {
+                clock_id: self.clock_id,
+            })?;
+
+        Ok(coeff.parallel_time_s + (ticks - coeff.ticks) * coeff.rate_s_per_tick)
+    }
+
+    /// Converts the parallel time of the clock, in seconds past J2000, into encoded ticks of this clock.
+    pub fn parallel_time_s_to_ticks(&self, parallel_time_s: f64) -> Result<f64, SCLKError> {
+        let idx = self
+            .coefficients
+            .partition_point(|coeff| coeff.parallel_time_s <= parallel_time_s)
+            .saturating_sub(1);
+        let coeff = self
+            .coefficients
+            .get(idx)
+            .ok_or(SCLKError::NoCoefficients {
+                clock_id: self.clock_id,
+            })?;
+
+        Ok(coeff.ticks + (parallel_time_s - coeff.parallel_time_s) / coeff.rate_s_per_tick)
+    }
+
+    /// Returns the epoch corresponding to the encoded ticks of this clock.
+    pub fn ticks_to_epoch(&self, ticks: f64) -> Result<Epoch, SCLKError> {
+        let parallel_time_s = self.ticks_to_parallel_time_s(ticks)?;
+        Ok(match self.time_scale {
+            TimeScale::TT => {
+                Epoch::from_tt_duration(Unit::Second * ET_EPOCH_S + Unit::Second * parallel_time_s)
+            }
+            _ => Epoch::from_et_seconds(parallel_time_s),
+        })
+    }
+
+    /// Returns the encoded ticks of this clock at the provided epoch.
+    pub fn epoch_to_ticks(&self, epoch: Epoch) -> Result<f64, SCLKError> {
+        let parallel_time_s = match self.time_scale {
+            TimeScale::TT => epoch.to_tt_since_j2k().to_seconds(),
+            _ => epoch.to_et_seconds(),
+        };
+        self.parallel_time_s_to_ticks(parallel_time_s)
+    }
+
+    /// Returns the partitions of this clock, or a single unbounded partition if none are defined.
+    fn partitions_or_default(&self) -> Vec<SCLKPartition> {
+        if self.partitions.is_empty() {
+            vec![SCLKPartition {
+                start_ticks: 0.0,
+                end_ticks: f64::INFINITY,
+            }]
+        } else {
+            self.partitions.clone()
+        }
+    }
+
+    /// Returns the number of ticks of one count of each field, from the most significant one.
+    fn field_weights(&self) -> Vec<f64> {
+        if self.moduli.is_empty() {
+            return vec![1.0];
+        }
+        (0..self.moduli.len())
+            .map(|i| self.moduli.iter().skip(i + 1).product())
+            .collect()
+    }
+
+    /// Parses a clock string (e.g. `1/1465644281.165`) into encoded ticks of this clock.
+    ///
+    /// If the partition is not specified, the first partition which includes this clock reading is used.
+    pub fn parse_ticks(&self, sclk: &str) -> Result<f64, SCLKError> {
+        let invalid = |reason| SCLKError::InvalidSCLKString {
+            clock_id: self.clock_id,
+            sclk: sclk.to_string(),
+            reason,
+        };
+
+        let (partition, counter) = match sclk.split_once('/') {
+            Some((partition, counter)) => {
+                let partition = partition
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| invalid("partition is not a positive integer"))?;
+                (Some(partition), counter)
+            }
+            None => (None, sclk),
+        };
+
+        let fields = counter
+            .split(['.', ':', '-', ',', ' '])
+            .filter(|field| !field.is_empty())
+            .map(|field| field.parse::<f64>())
+            .collect::<Result<Vec<f64>, _>>()
+            .map_err(|_| invalid("fields must be numbers"))?;
+
+        let weights = self.field_weights();
+        ensure!(
+            !fields.is_empty(),
+            InvalidSCLKStringSnafu {
+                clock_id: self.clock_id,
+                sclk,
+                reason: "no clock fields"
+            }
+        );
+        ensure!(
+            fields.len() <= weights.len(),
+            InvalidSCLKStringSnafu {
+                clock_id: self.clock_id,
+                sclk,
+                reason: "too many clock fields"
+            }
+        );
+
+        let ticks: f64 = fields
+            .iter()
+            .zip(&weights)
+            .enumerate()
+            .map(|(i, (field, weight))| (field - self.offsets.get(i).unwrap_or(&0.0)) * weight)
+            .sum();
+
+        let partitions = self.partitions_or_default();
+        let p_idx = match partition {
+            Some(0) => return Err(invalid("partitions start at one")),
+            Some(partition) => {
+                let p_idx = partition - 1;
+                ensure!(
+                    p_idx < partitions.len(),
+                    InvalidSCLKStringSnafu {
+                        clock_id: self.clock_id,
+                        sclk,
+                        reason: "no such partition"
+                    }
+                );
+                ensure!(
+                    ticks >= partitions[p_idx].start_ticks && ticks <= partitions[p_idx].end_ticks,
+                    InvalidSCLKStringSnafu {
+                        clock_id: self.clock_id,
+                        sclk,
+                        reason: "clock reading is outside of its partition"
+                    }
+                );
+                p_idx
+            }
+            None => partitions
+                .iter()
+                .position(|p| ticks >= p.start_ticks && ticks <= p.end_ticks)
+                .ok_or_else(|| invalid("clock reading is outside of all partitions"))?,
+        };
+
+        let prior_ticks: f64 = partitions[..p_idx].iter().map(|p| p.len_ticks()).sum();
+
+        Ok(prior_ticks + ticks - partitions[p_idx].start_ticks)
+    }
+
+    /// Formats the encoded ticks of this clock into a clock string, rounding to the nearest tick.
+    pub fn format_ticks(&self, ticks: f64) -> Result<String, SCLKError> {
+        let ticks = ticks.round();
+
+        // Find the partition of these ticks
+        let mut prior_ticks = 0.0;
+        let mut found = None;
+        for (p_idx, partition) in self.partitions_or_default().iter().enumerate() {
+            if ticks >= prior_ticks && ticks <= prior_ticks + partition.len_ticks() {
+                found = Some((p_idx + 1, ticks - prior_ticks + partition.start_ticks));
+                break;
+            }
+            prior_ticks += partition.len_ticks();
+        }
+
+        let (partition, mut counter) = found.ok_or(SCLKError::OutsidePartitions {
+            clock_id: self.clock_id,
+            ticks,
+        })?;
+
+        let mut fields = Vec::new();
+        for (i, weight) in self.field_weights().iter().enumerate() {
+            let count = (counter / weight).floor();
+            counter -= count * weight;
+            let offset = self.offsets.get(i).unwrap_or(&0.0);
+            let field = count + offset;
+            // Pad the field with zeros to the width of its largest value
+            let width = match self.moduli.get(i) {
+                Some(modulus) => format!("{}", modulus - 1.0 + offset).len(),
+                None => 1,
+            };
+            fields.push(format!("{field:0width$}"));
+        }
+
+        Ok(format!(
+            "{partition}/{}",
+            fields.join(&self.delimiter.to_string())
+        ))
+    }
+}
+
+impl fmt::Display for SCLK {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "SCLK {} ({:?}) with {} partitions and {} coefficient records",
+            self.clock_id,
+            self.time_scale,
+            self.partitions.len(),
+            self.coefficients.len()
+        )
+    }
+}
+
+impl Encode for SCLK {
+    fn encoded_len(&self) -> der::Result<der::Length> {
+        let (partitions, coefficients) = self.flat_data();
+        self.clock_id.encoded_len()?
+            + u8::from(self.time_scale == TimeScale::TT).encoded_len()?
+            + (self.delimiter as u8).encoded_len()?
+            + self.moduli.encoded_len()?
+            + self.offsets.encoded_len()?
+            + partitions.encoded_len()?
+            + coefficients.encoded_len()?
+    }
+
+    fn encode(&self, encoder: &mut impl Writer) -> der::Result<()> {
+        let (partitions, coefficients) = self.flat_data();
+        self.clock_id.encode(encoder)?;
+        u8::from(self.time_scale == TimeScale::TT).encode(encoder)?;
+        (self.delimiter as u8).encode(encoder)?;
+        self.moduli.encode(encoder)?;
+        self.offsets.encode(encoder)?;
+        partitions.encode(encoder)?;
+        coefficients.encode(encoder)
+    }
+}
+
+impl<'a> Decode<'a> for SCLK {
+    fn decode<R: Reader<'a>>(decoder: &mut R) -> der::Result<Self> {
+        let clock_id = decoder.decode()?;
+        let is_tt: u8 = decoder.decode()?;
+        let delimiter: u8 = decoder.decode()?;
+        let moduli = decoder.decode()?;
+        let offsets = decoder.decode()?;
+        let partitions: Vec<f64> = decoder.decode()?;
+        let coefficients: Vec<f64> = decoder.decode()?;
+
+        Ok(Self {
+            clock_id,
+            time_scale: if is_tt == 1 {
+                TimeScale::TT
+            } else {
+                TimeScale::TDB
+            },
+            moduli,
+            offsets,
+            delimiter: delimiter as char,
+            partitions: partitions
+                .chunks_exact(2)
+                .map(|p| SCLKPartition {
+                    start_ticks: p[0],
+                    end_ticks: p[1],
+                })
+                .collect(),
+            coefficients: coefficients
+                .chunks_exact(3)
+                .map(|c| SCLKCoefficient {
+                    ticks: c[0],
+                    parallel_time_s: c[1],
+                    rate_s_per_tick: c[2],
+                })
+                .collect(),
+        })
+    }
+}
+
+impl SCLK {
+    /// Returns the partitions and the coefficients as flat lists of doubles, as they are encoded.
+    fn flat_data(&self) -> (Vec<f64>, Vec<f64>) {
+        let partitions = self
+            .partitions
+            .iter()
+            .flat_map(|p| [p.start_ticks, p.end_ticks])
+            .collect();
+        let coefficients = self
+            .coefficients
+            .iter()
+            .flat_map(|c| [c.ticks, c.parallel_time_s, c.rate_s_per_tick])
+            .collect();
+        (partitions, coefficients)
+    }
+}
+
+#[cfg(test)]
+mod sclk_ut {
+    use super::*;
+
+    #[test]
+    fn ticks_round_trip() {
+        // Two rates, 1/256th of a second per tick, and then slightly faster.
+        let sclk = SCLK::new(
+            -82,
+            vec![
+                SCLKCoefficient {
+                    ticks: 256_000.0,
+                    parallel_time_s: 1_000.0 + 1_000.0 * (1.0 + 1e-6),
+                    rate_s_per_tick: (1.0 + 1e-7) / 256.0,
+                },
+                SCLKCoefficient {
+                    ticks: 0.0,
+                    parallel_time_s: 1_000.0,
+                    rate_s_per_tick: (1.0 + 1e-6) / 256.0,
+                },
+            ],
+        );
+
+        assert_eq!(sclk.coefficients[0].ticks, 0.0, "coefficients not sorted");
+
+        // Before the first record, the first rate applies.
+        assert!((sclk.ticks_to_parallel_time_s(-256.0).unwrap() - (999.0 - 1e-6)).abs() < 1e-9);
+        // Exactly on a record.
+        assert!((sclk.ticks_to_parallel_time_s(256_000.0).unwrap() - 2_000.001).abs() < 1e-9);
+
+        for ticks in [-1e4, 0.0, 1.5e5, 256_000.0, 2e6] {
+            let parallel_time_s = sclk.ticks_to_parallel_time_s(ticks).unwrap();
+            assert!((sclk.parallel_time_s_to_ticks(parallel_time_s).unwrap() - ticks).abs() < 1e-6);
+            let epoch = sclk.ticks_to_epoch(ticks).unwrap();
+            assert!((sclk.epoch_to_ticks(epoch).unwrap() - ticks).abs() < 1e-6);
+        }
+
+        // The same clock in TT differs by the TDB - TT periodic terms.
+        let sclk_tt = SCLK {
+            time_scale: TimeScale::TT,
+            ..sclk.clone()
+        };
+        let epoch_tt = sclk_tt.ticks_to_epoch(256_000.0).unwrap();
+        assert!((epoch_tt.to_tt_since_j2k().to_seconds() - 2_000.001).abs() < 1e-6);
+        assert!((sclk_tt.epoch_to_ticks(epoch_tt).unwrap() - 256_000.0).abs() < 1e-6);
+        let delta_s = (epoch_tt - sclk.ticks_to_epoch(256_000.0).unwrap()).to_seconds();
+        assert!(delta_s.abs() > 0.0 && delta_s.abs() < 2e-3);
+
+        assert_eq!(SCLK::clock_id_of(-82000), -82);
+        assert_eq!(SCLK::clock_id_of(-82), -82);
+
+        assert_eq!(
+            SCLK::new(-82, vec![]).ticks_to_parallel_time_s(0.0),
+            Err(SCLKError::NoCoefficients { clock_id: -82 })
+        );
+    }
+
+    #[test]
+    fn clock_strings() {
+        // Two fields of 256 ticks per count, and a clock reset after 1000 counts.
+        let sclk = SCLK {
+            clock_id: -82,
+            moduli: vec![4294967296.0, 256.0],
+            offsets: vec![0.0, 0.0],
+            partitions: vec![
+                SCLKPartition {
+                    start_ticks: 0.0,
+                    end_ticks: 256_000.0,
+                },
+                SCLKPartition {
+                    start_ticks: 2_560.0,
+                    end_ticks: 4294967296.0 * 256.0 - 1.0,
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(sclk.ticks_per_count(), 256.0);
+        assert_eq!(sclk.parse_ticks("1/500.128").unwrap(), 128_128.0);
+        assert_eq!(sclk.parse_ticks("1/500:128").unwrap(), 128_128.0);
+        assert_eq!(sclk.parse_ticks("1/500").unwrap(), 128_000.0);
+        // Second partition starts at count 10
+        assert_eq!(sclk.parse_ticks("2/10.0").unwrap(), 256_000.0);
+        assert_eq!(sclk.parse_ticks("2/0000000011.128").unwrap(), 256_384.0);
+        // Without the partition, the first partition which includes this reading is used.
+        assert_eq!(sclk.parse_ticks("11.128").unwrap(), 2_944.0);
+        assert_eq!(sclk.parse_ticks("2000.0").unwrap(), 256_000.0 + 509_440.0);
+
+        assert_eq!(sclk.format_ticks(128_128.0).unwrap(), "1/0000000500.128");
+        assert_eq!(sclk.format_ticks(256_384.4).unwrap(), "2/0000000011.128");
+        // The end of a partition is formatted in that partition.
+        assert_eq!(sclk.format_ticks(256_000.0).unwrap(), "1/0000001000.000");
+
+        for ticks in [0.0, 1.0, 255_999.0, 256_001.0, 1e9] {
+            let sclk_str = sclk.format_ticks(ticks).unwrap();
+            assert_eq!(sclk.parse_ticks(&sclk_str).unwrap(), ticks, "{sclk_str}");
+        }
+
+        for bad in ["", "0/1.0", "3/1.0", "1/2000.0", "1/1.2.3", "a/1", "1/x"] {
+            assert!(
+                matches!(
+                    sclk.parse_ticks(bad),
+                    Err(SCLKError::InvalidSCLKString { .. })
+                ),
+                "{bad}"
+            );
+        }
+        assert_eq!(
+            sclk.format_ticks(-1.0),
+            Err(SCLKError::OutsidePartitions {
+                clock_id: -82,
+                ticks: -1.0
+            })
+        );
+    }
+
+    #[test]
+    fn encoding_round_trip() {
+        let sclk = SCLK {
+            clock_id: -82,
+            time_scale: TimeScale::TT,
+            moduli: vec![4294967296.0, 256.0],
+            offsets: vec![0.0, 0.0],
+            delimiter: ':',
+            partitions: vec![SCLKPartition {
+                start_ticks: 0.0,
+                end_ticks: 256_000.0,
+            }],
+            coefficients: vec![SCLKCoefficient {
+                ticks: 0.0,
+                parallel_time_s: 1_000.0,
+                rate_s_per_tick: 1.0 / 256.0,
+            }],
+        };
+
+        let mut buf = vec![];
+        sclk.encode_to_vec(&mut buf).unwrap();
+        assert_eq!(SCLK::from_der(&buf).unwrap(), sclk);
+    }
+}
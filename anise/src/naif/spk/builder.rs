@@ -0,0 +1,569 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use snafu::prelude::*;
+
+use super::{fit::ChebyshevFit, summary::SPKSummaryRecord};
+use crate::{
+    math::{cartesian::CartesianState, interpolation::InterpolationError},
+    naif::{
+        daf::{
+            daf::MutDAF,
+            datatypes::{HermiteSetType13, LagrangeSetType9},
+            DAFError, DafDataType, NAIFDataSet,
+        },
+        SPK,
+    },
+    prelude::Frame,
+    NaifId,
+};
+
+/// Number of doubles in an SPK summary: two epochs, then six integers packed in three doubles.
+const SPK_ND: usize = 2;
+const SPK_NI: usize = 6;
+/// Maximum degree of the Lagrange polynomials of Type 9 segments supported by SPICE.
+pub const MAX_LAGRANGE_DEGREE: usize = 27;
+/// Maximum window size of the Hermite polynomials of Type 13 segments supported by SPICE.
+pub const MAX_HERMITE_WINDOW_SIZE: usize = 14;
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+pub enum SPKBuilderError {
+    #[snafu(display("segment `{name}` is invalid: {reason}"))]
+    InvalidSegment { name: String, reason: String },
+    #[snafu(display("segment `{name}`: {source}"))]
+    SegmentData {
+        name: String,
+        #[snafu(backtrace)]
+        source: InterpolationError,
+    },
+    #[snafu(display("comments must be ASCII"))]
+    NonAsciiComments,
+    #[snafu(display("built SPK is invalid: {source}"))]
+    BuiltSPK {
+        #[snafu(backtrace)]
+        source: DAFError,
+    },
+}
+
+/// A segment of an SPK being built: its summary (whose data indexes are only known when building), name, and DAF array.
+#[derive(Clone, Debug)]
+struct SegmentToWrite {
+    summary: SPKSummaryRecord,
+    name: String,
+    data: Vec<f64>,
+}
+
+/// Builds a new SPK from scratch, e.g. from the states of a trajectory propagated outside of ANISE.
+///
+/// The segments are written in the order they were added: as for any SPK, the last segment covering an epoch takes
+/// precedence over the previous ones.
+///
+/// # Example
+/// ```
+/// use anise::constants::frames::EARTH_J2000;
+/// use anise::math::{cartesian::CartesianState, Vector3};
+/// use anise::naif::spk::builder::SPKBuilder;
+/// use hifitime::{Epoch, TimeUnits};
+///
+/// let start = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+/// let states: Vec<CartesianState> = (0..100)
+///     .map(|i| {
+///         let t_s = i as f64 * 60.0;
+///         CartesianState {
+///             radius_km: Vector3::new(7000.0 + t_s, 0.0, 0.0),
+///             velocity_km_s: Vector3::new(1.0, 0.0, 0.0),
+///             epoch: start + t_s.seconds(),
+///             frame: EARTH_J2000,
+///         }
+///     })
+///     .collect();
+///
+/// let mut builder = SPKBuilder::new("MY TRAJECTORY").with_comments("Propagated with my own tool.");
+/// builder
+///     .add_hermite_segment("MY SPACECRAFT", -1001, &states, 8)
+///     .unwrap();
+/// let spk = builder.build().unwrap();
+/// assert_eq!(spk.data_summaries().unwrap()[0].target_id, -1001);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SPKBuilder {
+    internal_filename: String,
+    comments: String,
+    segments: Vec<SegmentToWrite>,
+}
+
+impl SPKBuilder {
+    /// Initializes a new builder of an SPK with the provided internal file name, truncated to 60 characters.
+    pub fn new(internal_filename: &str) -> Self {
+        Self {
+            internal_filename: internal_filename.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the comments of the SPK, where each line of the comments is stored as a line of the comment area.
+    pub fn with_comments(mut self, comments: &str) -> Self {
+        self.comments = comments.to_string();
+        self
+    }
+
+    /// Returns the number of segments added so far.
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Returns whether no segment has been added yet.
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Adds a segment of any data type supporting the export to a DAF array.
+    ///
+    /// The start and end indexes of the summary are ignored and set when building the SPK.
+    pub fn add_segment<'a, S: NAIFDataSet<'a>>(
+        &mut self,
+        name: &str,
+        summary: SPKSummaryRecord,
+        data: &S,
+    ) -> Result<(), SPKBuilderError> {
+        ensure!(
+            name.is_ascii(),
+            InvalidSegmentSnafu {
+                name,
+                reason: "the name must be ASCII"
+            }
+        );
+        ensure!(
+            summary.start_epoch_et_s <= summary.end_epoch_et_s,
+            InvalidSegmentSnafu {
+                name,
+                reason: "the start epoch is after the end epoch"
+            }
+        );
+
+        let data = data.to_f64_daf_vec().context(SegmentDataSnafu { name })?;
+
+        self.segments.push(SegmentToWrite {
+            summary,
+            name: name.to_string(),
+            data,
+        });
+
+        Ok(())
+    }
+
+    /// Adds a Lagrange Type 9 segment interpolating the provided states with polynomials of the provided degree.
+    ///
+    /// The states must be in chronological order and in the same frame, whose ephemeris and orientation IDs are used
+    /// as the center and frame of the segment.
+    pub fn add_lagrange_segment(
+        &mut self,
+        name: &str,
+        target_id: NaifId,
+        states: &[CartesianState],
+        degree: usize,
+    ) -> Result<(), SPKBuilderError> {
+        ensure!(
+            (1..=MAX_LAGRANGE_DEGREE).contains(&degree),
+            InvalidSegmentSnafu {
+                name,
+                reason: format!("the degree must be between 1 and {MAX_LAGRANGE_DEGREE}")
+            }
+        );
+        ensure!(
+            states.len() > degree,
+            InvalidSegmentSnafu {
+                name,
+                reason: format!(
+                    "a degree of {degree} requires at least {} states",
+                    degree + 1
+                )
+            }
+        );
+
+        let (summary, state_data, epoch_data) = Self::states_to_arrays(
+            name,
+            target_id,
+            states,
+            DafDataType::Type9LagrangeUnequalStep,
+        )?;
+
+        let dataset = LagrangeSetType9 {
+            degree,
+            num_records: states.len(),
+            state_data: &state_data,
+            epoch_data: &epoch_data,
+            epoch_registry: &[],
+        };
+
+        self.add_segment(name, summary, &dataset)
+    }
+
+    /// Adds a Hermite Type 13 segment interpolating the provided states with windows of the provided number of states.
+    ///
+    /// The window size must be even. The states must be in chronological order and in the same frame, whose ephemeris
+    /// and orientation IDs are used as the center and frame of the segment.
+    pub fn add_hermite_segment(
+        &mut self,
+        name: &str,
+        target_id: NaifId,
+        states: &[CartesianState],
+        window_size: usize,
+    ) -> Result<(), SPKBuilderError> {
+        ensure!(
+            window_size.is_multiple_of(2) && (2..=MAX_HERMITE_WINDOW_SIZE).contains(&window_size),
+            InvalidSegmentSnafu {
+                name,
+                reason: format!(
+                    "the window size must be an even number between 2 and {MAX_HERMITE_WINDOW_SIZE}"
+                )
+            }
+        );
+        ensure!(
+            states.len() >= window_size,
+            InvalidSegmentSnafu {
+                name,
+                reason: format!("a window size of {window_size} requires at least as many states")
+            }
+        );
+
+        let (summary, state_data, epoch_data) = Self::states_to_arrays(
+            name,
+            target_id,
+            states,
+            DafDataType::Type13HermiteUnequalStep,
+        )?;
+
+        let dataset = HermiteSetType13 {
+            samples: window_size,
+            num_records: states.len(),
+            state_data: &state_data,
+            epoch_data: &epoch_data,
+            epoch_registry: &[],
+        };
+
+        self.add_segment(name, summary, &dataset)
+    }
+
+    /// Adds a Chebyshev Type 2 or Type 3 segment from polynomials fitted on an ephemeris source.
+    ///
+    /// The ephemeris and orientation IDs of the provided frame are used as the center and frame of the segment.
+    pub fn add_chebyshev_segment(
+        &mut self,
+        name: &str,
+        target_id: NaifId,
+        center_frame: Frame,
+        fit: &ChebyshevFit,
+    ) -> Result<(), SPKBuilderError> {
+        ensure!(
+            target_id != center_frame.ephemeris_id,
+            InvalidSegmentSnafu {
+                name,
+                reason: format!("the target {target_id} is also the center of the segment")
+            }
+        );
+
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: fit.init_epoch.to_et_seconds(),
+            end_epoch_et_s: fit.end_epoch().to_et_seconds(),
+            target_id,
+            center_id: center_frame.ephemeris_id,
+            frame_id: center_frame.orientation_id,
+            data_type_i: fit.data_type as i32,
+            ..Default::default()
+        };
+
+        match (fit.as_type2(), fit.as_type3()) {
+            (Some(set), _) => self.add_segment(name, summary, &set),
+            (_, Some(set)) => self.add_segment(name, summary, &set),
+            _ => InvalidSegmentSnafu {
+                name,
+                reason: format!("{:?} is not a Chebyshev type", fit.data_type),
+            }
+            .fail(),
+        }
+    }
+
+    /// Builds the SPK: the file record, the comment area, the summary and name records, and the segment data.
+    ///
+    /// As in SPICE, the summaries of every 25 segments are stored in a new summary record, linked to the previous one.
+    pub fn build(&self) -> Result<SPK, SPKBuilderError> {
+        ensure!(self.comments.is_ascii(), NonAsciiCommentsSnafu);
+
+        let comment_lines: Vec<&str> = self.comments.lines().collect();
+        let mut spk = MutDAF::<SPKSummaryRecord>::empty(
+            "DAF/SPK",
+            SPK_ND,
+            SPK_NI,
+            &self.internal_filename,
+            &comment_lines,
+        )
+        .context(BuiltSPKSnafu)?;
+
+        for segment in &self.segments {
+            spk.append_data(segment.summary, &segment.name, &segment.data)
+                .context(BuiltSPKSnafu)?;
+        }
+
+        SPK::parse(spk.bytes).context(BuiltSPKSnafu)
+    }
+
+    /// Checks the states of a segment and flattens them into the state and epoch arrays of the Type 9 and 13 segments.
+    fn states_to_arrays(
+        name: &str,
+        target_id: NaifId,
+        states: &[CartesianState],
+        data_type: DafDataType,
+    ) -> Result<(SPKSummaryRecord, Vec<f64>, Vec<f64>), SPKBuilderError> {
+        let first = states
+            .first()
+            .ok_or_else(|| SPKBuilderError::InvalidSegment {
+                name: name.to_string(),
+                reason: "no states provided".to_string(),
+            })?;
+        let frame = first.frame;
+        ensure!(
+            target_id != frame.ephemeris_id,
+            InvalidSegmentSnafu {
+                name,
+                reason: format!("the target {target_id} is also the center of the states")
+            }
+        );
+
+        let mut state_data = Vec::with_capacity(6 * states.len());
+        let mut epoch_data = Vec::with_capacity(states.len());
+        for state in states {
+            ensure!(
+                state.frame.ephemeris_id == frame.ephemeris_id
+                    && state.frame.orientation_id == frame.orientation_id,
+                InvalidSegmentSnafu {
+                    name,
+                    reason: format!("state at {} is not in {frame}", state.epoch)
+                }
+            );
+
+            let epoch_et_s = state.epoch.to_et_seconds();
+            if let Some(prev_et_s) = epoch_data.last() {
+                ensure!(
+                    epoch_et_s > *prev_et_s,
+                    InvalidSegmentSnafu {
+                        name,
+                        reason: format!("state at {} is not after the previous one", state.epoch)
+                    }
+                );
+            }
+
+            state_data.extend_from_slice(state.radius_km.as_slice());
+            state_data.extend_from_slice(state.velocity_km_s.as_slice());
+            epoch_data.push(epoch_et_s);
+        }
+
+        let summary = SPKSummaryRecord {
+            start_epoch_et_s: epoch_data[0],
+            end_epoch_et_s: *epoch_data.last().unwrap(),
+            target_id,
+            center_id: frame.ephemeris_id,
+            frame_id: frame.orientation_id,
+            data_type_i: data_type as i32,
+            ..Default::default()
+        };
+
+        Ok((summary, state_data, epoch_data))
+    }
+}
+
+#[cfg(test)]
+mod ut_spk_builder {
+    use hifitime::{Epoch, TimeUnits};
+
+    use super::{SPKBuilder, SPKBuilderError};
+    use crate::almanac::Almanac;
+    use crate::constants::frames::{EARTH_J2000, MOON_J2000};
+    use crate::math::cartesian::CartesianState;
+    use crate::naif::daf::NAIFSummaryRecord;
+    use crate::naif::spk::fixtures::{circular, sampled_states};
+    use crate::naif::SPK;
+    use crate::prelude::Frame;
+
+    const SC_ID: i32 = -1001;
+
+    /// Samples a circular orbit of 7000 km of radius every minute.
+    fn circular_orbit(start: Epoch, num_states: i64) -> Vec<CartesianState> {
+        sampled_states(EARTH_J2000, start, 1.minutes(), 0..num_states, |i| {
+            circular(7000.0, i as f64 * 60.0)
+        })
+    }
+
+    #[test]
+    fn build_and_load() {
+        let start = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        let states = circular_orbit(start, 121);
+
+        let mut builder = SPKBuilder::new("ANISE SPK BUILDER TEST")
+            .with_comments("Circular orbit of 7000 km.\nSampled every minute.");
+        // The first hour is interpolated with Lagrange polynomials, and the second with Hermite polynomials.
+        builder
+            .add_lagrange_segment("LAGRANGE", SC_ID, &states[..=60], 7)
+            .unwrap();
+        builder
+            .add_hermite_segment("HERMITE", SC_ID, &states[60..], 6)
+            .unwrap();
+        assert_eq!(builder.len(), 2);
+
+        let spk = builder.build().unwrap();
+        let file_rcrd = spk.file_record().unwrap();
+        assert_eq!(file_rcrd.identification().unwrap(), "SPK");
+        assert_eq!(
+            file_rcrd.internal_filename().unwrap(),
+            "ANISE SPK BUILDER TEST"
+        );
+        assert_eq!(
+            spk.comments().unwrap().unwrap(),
+            "Circular orbit of 7000 km.\nSampled every minute."
+        );
+        let summaries = spk.data_summaries().unwrap();
+        assert_eq!(summaries[0].data_type_i, 9);
+        assert_eq!(summaries[1].data_type_i, 13);
+        assert!((summaries[1].start_epoch() - (start + 1.hours())).abs() < 1.microseconds());
+        assert!((summaries[1].end_epoch() - (start + 2.hours())).abs() < 1.microseconds());
+        assert!(summaries[2].is_empty());
+        let name_rcrd = spk.name_record().unwrap();
+        assert_eq!(name_rcrd.nth_name(1, file_rcrd.summary_size()), "HERMITE");
+
+        // Persisting the SPK preserves its content.
+        let path = "../target/spk-builder-test.bsp";
+        spk.persist(path).unwrap();
+        let reloaded = SPK::load(path).unwrap();
+        assert_eq!(reloaded.bytes, spk.bytes);
+
+        let almanac = Almanac::default().with_spk(spk).unwrap();
+        let sc = Frame::from_ephem_j2000(SC_ID);
+
+        // The states are returned at the nodes, and the interpolation in between matches the orbit to better than a
+        // millimeter and a tenth of a millimeter per second.
+        for minutes in [0, 17, 60, 61, 119, 120] {
+            let epoch = start + minutes.minutes();
+            let state = almanac.translate(sc, EARTH_J2000, epoch, None).unwrap();
+            let expected = &states[minutes as usize];
+            assert!((state.radius_km - expected.radius_km).norm() < 1e-9);
+            assert!((state.velocity_km_s - expected.velocity_km_s).norm() < 1e-12);
+        }
+
+        for minutes in [0, 30, 59, 60, 61, 90, 119] {
+            let t_s = minutes as f64 * 60.0 + 30.0;
+            let (pos_km, vel_km_s) = circular(7000.0, t_s);
+            let state = almanac
+                .translate(sc, EARTH_J2000, start + t_s.seconds(), None)
+                .unwrap();
+            assert!(
+                (state.radius_km - pos_km).norm() < 1e-6,
+                "{minutes} min: {}",
+                (state.radius_km - pos_km).norm()
+            );
+            assert!(
+                (state.velocity_km_s - vel_km_s).norm() < 1e-7,
+                "{minutes} min: {}",
+                (state.velocity_km_s - vel_km_s).norm()
+            );
+        }
+
+        // Outside of the coverage.
+        assert!(almanac
+            .translate(sc, EARTH_J2000, start - 1.seconds(), None)
+            .is_err());
+    }
+
+    #[test]
+    fn epoch_directory() {
+        let start = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        let states = circular_orbit(start, 363);
+
+        let mut builder = SPKBuilder::new("DIRECTORY");
+        builder
+            .add_lagrange_segment("LAGRANGE", SC_ID, &states, 7)
+            .unwrap();
+        let spk = builder.build().unwrap();
+
+        // Six doubles per state, one epoch per state, three directory epochs, and the degree and number of states.
+        let summary = spk.data_summaries().unwrap()[0];
+        assert_eq!(
+            summary.end_index() - summary.start_index() + 1,
+            7 * 363 + 3 + 2
+        );
+
+        let almanac = Almanac::default().with_spk(spk).unwrap();
+        let epoch = states[300].epoch + 30.seconds();
+        let (pos_km, _) = circular(7000.0, 300.5 * 60.0);
+        let state = almanac
+            .translate(Frame::from_ephem_j2000(SC_ID), EARTH_J2000, epoch, None)
+            .unwrap();
+        assert!((state.radius_km - pos_km).norm() < 1e-6);
+    }
+
+    #[test]
+    fn invalid_segments() {
+        let start = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1);
+        let states = circular_orbit(start, 121);
+        let mut builder = SPKBuilder::new("INVALID");
+
+        for (degree, window_size) in [(0, 1), (28, 16), (120, 7)] {
+            assert!(matches!(
+                builder.add_lagrange_segment("BAD", SC_ID, &states[..10], degree),
+                Err(SPKBuilderError::InvalidSegment { .. })
+            ));
+            assert!(matches!(
+                builder.add_hermite_segment("BAD", SC_ID, &states[..10], window_size),
+                Err(SPKBuilderError::InvalidSegment { .. })
+            ));
+        }
+
+        // States must be chronological and in a single frame.
+        let mut reversed = states.clone();
+        reversed.reverse();
+        assert!(builder
+            .add_hermite_segment("BAD", SC_ID, &reversed, 8)
+            .is_err());
+        let mut mixed = states.clone();
+        mixed[10].frame = MOON_J2000;
+        assert!(builder
+            .add_hermite_segment("BAD", SC_ID, &mixed, 8)
+            .is_err());
+        assert!(builder.add_hermite_segment("BAD", 399, &states, 8).is_err());
+        assert!(builder.add_hermite_segment("BAD", SC_ID, &[], 8).is_err());
+        assert!(builder.is_empty());
+
+        // The summaries of more than 25 segments span several summary records.
+        for i in 0..30 {
+            builder
+                .add_hermite_segment(&format!("SEGMENT {i}"), SC_ID + i, &states, 2)
+                .unwrap();
+        }
+        let spk = builder.build().unwrap();
+        assert_eq!(spk.summary_record_numbers().unwrap().len(), 2);
+        assert_eq!(spk.nth_name(29).unwrap(), "SEGMENT 29");
+
+        let almanac = Almanac::default().with_spk(spk).unwrap();
+        for i in [0, 24, 25, 29] {
+            let (summary, _, idx) = almanac.spk_summary(SC_ID + i).unwrap();
+            assert_eq!(idx, i as usize);
+            assert_eq!(summary.target_id, SC_ID + i);
+            let state = almanac
+                .translate(
+                    Frame::from_ephem_j2000(SC_ID + i),
+                    EARTH_J2000,
+                    states[5].epoch,
+                    None,
+                )
+                .unwrap();
+            assert!((state.radius_km - states[5].radius_km).norm() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,512 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use hifitime::{Duration, Epoch, TimeUnits};
+use snafu::prelude::*;
+
+use crate::{
+    ephemerides::EphemerisError,
+    math::{
+        interpolation::{chebyshev_eval, chebyshev_fit, chebyshev_nodes, InterpolationError},
+        Vector3,
+    },
+    naif::daf::{
+        datatypes::{Type2ChebyshevSet, Type3ChebyshevSet},
+        DafDataType, NAIFDataSet,
+    },
+};
+
+#[derive(Debug, Snafu, PartialEq)]
+#[snafu(visibility(pub(crate)))]
+pub enum ChebyshevFitError {
+    #[snafu(display("invalid Chebyshev fit configuration: {reason}"))]
+    InvalidFitConfig { reason: String },
+    #[snafu(display("ephemeris source failed at {epoch}: {source}"))]
+    FitSource {
+        epoch: Epoch,
+        #[snafu(source(from(EphemerisError, Box::new)))]
+        source: Box<EphemerisError>,
+    },
+    #[snafu(display("evaluating the fitted polynomials: {source}"))]
+    FitEvaluation {
+        #[snafu(backtrace)]
+        source: InterpolationError,
+    },
+    #[snafu(display(
+        "tolerance not met with {num_records} records: errors of {position_error_km:e} km and {velocity_error_km_s:e} km/s"
+    ))]
+    ToleranceNotMet {
+        num_records: usize,
+        position_error_km: f64,
+        velocity_error_km_s: f64,
+    },
+}
+
+/// Configuration of the fit of Chebyshev polynomials on an ephemeris.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ChebyshevFitConfig {
+    /// Either Type 2 (the velocity is the derivative of the position polynomials) or Type 3 (the velocity has its own polynomials).
+    pub data_type: DafDataType,
+    /// Degree of the polynomials of each record.
+    pub degree: usize,
+    /// Maximum position error between the source and the fitted polynomials, in kilometers.
+    pub position_tolerance_km: f64,
+    /// Maximum velocity error between the source and the fitted polynomials, in kilometers per second.
+    pub velocity_tolerance_km_s: f64,
+    /// Maximum number of records, i.e. of equal length intervals the fitted span may be split into.
+    pub max_records: usize,
+}
+
+impl Default for ChebyshevFitConfig {
+    /// Type 2 polynomials of degree 11 accurate to a centimeter and ten micrometers per second.
+    fn default() -> Self {
+        Self {
+            data_type: DafDataType::Type2ChebyshevTriplet,
+            degree: 11,
+            position_tolerance_km: 1e-5,
+            velocity_tolerance_km_s: 1e-8,
+            max_records: 100_000,
+        }
+    }
+}
+
+/// Chebyshev polynomials fitted on an ephemeris source, stored in the record layout of SPK Type 2 or Type 3.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChebyshevFit {
+    pub data_type: DafDataType,
+    pub init_epoch: Epoch,
+    pub interval_length: Duration,
+    pub rsize: usize,
+    pub num_records: usize,
+    pub record_data: Vec<f64>,
+    /// Largest position error found when checking the fit, in kilometers.
+    pub position_error_km: f64,
+    /// Largest velocity error found when checking the fit, in kilometers per second.
+    pub velocity_error_km_s: f64,
+}
+
+impl ChebyshevFit {
+    /// Fits Chebyshev polynomials on the ephemeris `source` from `start` to `end`.
+    ///
+    /// The source returns the position (km) and velocity (km/s) at the requested epoch, e.g. a closure calling
+    /// [crate::almanac::Almanac::translate] (as done by [crate::almanac::Almanac::fit_chebyshev]) or an analytical model.
+    ///
+    /// SPK Type 2 and Type 3 records all have the same length, so the span is split into twice as many records until
+    /// every record meets the tolerances. Each record interpolates the source at the Chebyshev nodes, and is checked
+    /// against the source at twice as many evenly spaced epochs, including both ends of the record.
+    ///
+    /// # Errors
+    /// The fit fails if the maximum number of records is reached, or if the error stops decreasing with shorter records,
+    /// which happens when the tolerance is below the numerical noise of the source.
+    pub fn fit<F>(
+        mut source: F,
+        start: Epoch,
+        end: Epoch,
+        config: ChebyshevFitConfig,
+    ) -> Result<Self, ChebyshevFitError>
+    where
+        F: FnMut(Epoch) -> Result<(Vector3, Vector3), EphemerisError>,
+    {
+        let num_polynomials = match config.data_type {
+            DafDataType::Type2ChebyshevTriplet => 3,
+            DafDataType::Type3ChebyshevSextuplet => 6,
+            _ => {
+                return Err(ChebyshevFitError::InvalidFitConfig {
+                    reason: format!("{:?} is not a Chebyshev type", config.data_type),
+                })
+            }
+        };
+        ensure!(
+            end > start,
+            InvalidFitConfigSnafu {
+                reason: format!("start {start} is not before end {end}")
+            }
+        );
+        ensure!(
+            config.degree >= 1,
+            InvalidFitConfigSnafu {
+                reason: "the degree must be at least one"
+            }
+        );
+        ensure!(
+            config.position_tolerance_km > 0.0 && config.velocity_tolerance_km_s > 0.0,
+            InvalidFitConfigSnafu {
+                reason: "the tolerances must be strictly positive"
+            }
+        );
+        ensure!(
+            config.max_records >= 1,
+            InvalidFitConfigSnafu {
+                reason: "at least one record is needed"
+            }
+        );
+
+        let nodes: Vec<f64> = chebyshev_nodes(config.degree).collect();
+        let num_checks = 2 * (config.degree + 1);
+        let checks: Vec<f64> = (0..num_checks)
+            .map(|i| -1.0 + 2.0 * i as f64 / (num_checks - 1) as f64)
+            .collect();
+
+        let rsize = 2 + num_polynomials * (config.degree + 1);
+        let start_et_s = start.to_et_seconds();
+        let span_s = end.to_et_seconds() - start_et_s;
+
+        let mut num_records = 1;
+        let mut prev_excess = f64::INFINITY;
+        let mut stalled_doublings = 0;
+        loop {
+            let interval_s = span_s / num_records as f64;
+            let radius_s = interval_s / 2.0;
+
+            let mut record_data = Vec::with_capacity(num_records * rsize);
+            let mut position_error_km = 0.0_f64;
+            let mut velocity_error_km_s = 0.0_f64;
+            let mut within_tolerance = true;
+
+            for rno in 0..num_records {
+                // The midpoint is stored as ET seconds, but the offsets from it are kept to the nanosecond: ET seconds
+                // are only precise to about a tenth of a microsecond nowadays.
+                let midpoint_et_s = start_et_s + (rno as f64 + 0.5) * interval_s;
+                let midpoint = Epoch::from_et_seconds(midpoint_et_s);
+                let epoch_of = |normalized_time: f64| {
+                    (midpoint + (radius_s * normalized_time).seconds())
+                        .max(start)
+                        .min(end)
+                };
+
+                // Sample the source at the nodes, one series of samples per polynomial.
+                let mut samples = vec![Vec::with_capacity(nodes.len()); num_polynomials];
+                for node in &nodes {
+                    let epoch = epoch_of(*node);
+                    let (pos_km, vel_km_s) = source(epoch).context(FitSourceSnafu { epoch })?;
+                    for (pno, series) in samples.iter_mut().enumerate() {
+                        series.push(if pno < 3 {
+                            pos_km[pno]
+                        } else {
+                            vel_km_s[pno - 3]
+                        });
+                    }
+                }
+
+                let record_start = record_data.len();
+                record_data.push(midpoint_et_s);
+                record_data.push(radius_s);
+                for series in &samples {
+                    record_data.extend(chebyshev_fit(series));
+                }
+                let record = &record_data[record_start..];
+                let coeffs = |pno: usize| {
+                    &record[2 + pno * (config.degree + 1)..2 + (pno + 1) * (config.degree + 1)]
+                };
+
+                // Check the fit in between the nodes, as it is exact at the nodes.
+                for normalized_time in &checks {
+                    let epoch = epoch_of(*normalized_time);
+                    let (pos_km, vel_km_s) = source(epoch).context(FitSourceSnafu { epoch })?;
+
+                    let mut fit_pos_km = Vector3::zeros();
+                    let mut fit_vel_km_s = Vector3::zeros();
+                    for i in 0..3 {
+                        let (val, deriv) = chebyshev_eval(
+                            *normalized_time,
+                            coeffs(i),
+                            radius_s,
+                            epoch,
+                            config.degree,
+                        )
+                        .context(FitEvaluationSnafu)?;
+                        fit_pos_km[i] = val;
+                        fit_vel_km_s[i] = if num_polynomials == 3 {
+                            deriv
+                        } else {
+                            chebyshev_eval(
+                                *normalized_time,
+                                coeffs(i + 3),
+                                radius_s,
+                                epoch,
+                                config.degree,
+                            )
+                            .context(FitEvaluationSnafu)?
+                            .0
+                        };
+                    }
+
+                    position_error_km = position_error_km.max((fit_pos_km - pos_km).norm());
+                    velocity_error_km_s = velocity_error_km_s.max((fit_vel_km_s - vel_km_s).norm());
+                }
+
+                if position_error_km > config.position_tolerance_km
+                    || velocity_error_km_s > config.velocity_tolerance_km_s
+                {
+                    within_tolerance = false;
+                    break;
+                }
+            }
+
+            if within_tolerance {
+                return Ok(Self {
+                    data_type: config.data_type,
+                    init_epoch: Epoch::from_et_seconds(start_et_s),
+                    interval_length: interval_s.seconds(),
+                    rsize,
+                    num_records,
+                    record_data,
+                    position_error_km,
+                    velocity_error_km_s,
+                });
+            }
+
+            // Once the numerical noise of the source dominates, shorter records no longer reduce the error.
+            let excess = (position_error_km / config.position_tolerance_km)
+                .max(velocity_error_km_s / config.velocity_tolerance_km_s);
+            if excess >= prev_excess {
+                stalled_doublings += 1;
+            } else {
+                stalled_doublings = 0;
+            }
+            prev_excess = excess;
+
+            if num_records == config.max_records || stalled_doublings == 2 {
+                return Err(ChebyshevFitError::ToleranceNotMet {
+                    num_records,
+                    position_error_km,
+                    velocity_error_km_s,
+                });
+            }
+
+            num_records = (2 * num_records).min(config.max_records);
+        }
+    }
+
+    /// Returns the end of the fitted span.
+    pub fn end_epoch(&self) -> Epoch {
+        self.init_epoch + self.interval_length * self.num_records as i64
+    }
+
+    /// Returns this fit as a Chebyshev Type 2 set, if it was fitted as such.
+    pub fn as_type2(&self) -> Option<Type2ChebyshevSet<'_>> {
+        (self.data_type == DafDataType::Type2ChebyshevTriplet).then_some(Type2ChebyshevSet {
+            init_epoch: self.init_epoch,
+            interval_length: self.interval_length,
+            rsize: self.rsize,
+            num_records: self.num_records,
+            record_data: &self.record_data,
+        })
+    }
+
+    /// Returns this fit as a Chebyshev Type 3 set, if it was fitted as such.
+    pub fn as_type3(&self) -> Option<Type3ChebyshevSet<'_>> {
+        (self.data_type == DafDataType::Type3ChebyshevSextuplet).then_some(Type3ChebyshevSet {
+            init_epoch: self.init_epoch,
+            interval_length: self.interval_length,
+            rsize: self.rsize,
+            num_records: self.num_records,
+            record_data: &self.record_data,
+        })
+    }
+
+    /// Builds the DAF array of the SPK segment of this fit.
+    pub fn to_f64_daf_vec(&self) -> Result<Vec<f64>, InterpolationError> {
+        match (self.as_type2(), self.as_type3()) {
+            (Some(set), _) => set.to_f64_daf_vec(),
+            (_, Some(set)) => set.to_f64_daf_vec(),
+            _ => Err(InterpolationError::CorruptedData {
+                what: "Chebyshev fit is neither Type 2 nor Type 3",
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod ut_chebyshev_fit {
+    use hifitime::{Epoch, TimeScale, TimeUnits};
+
+    use super::{ChebyshevFit, ChebyshevFitConfig, ChebyshevFitError};
+    use crate::almanac::Almanac;
+    use crate::constants::frames::EARTH_J2000;
+    use crate::ephemerides::EphemerisError;
+    use crate::naif::daf::DafDataType;
+    use crate::naif::spk::builder::SPKBuilder;
+    use crate::naif::spk::fixtures::{circular, hermite_spk, sampled_states};
+    use crate::prelude::Frame;
+
+    const SC_ID: i32 = -1001;
+
+    /// Radius of the circular orbit of the tests.
+    ///
+    /// The tests count the time in ET, the time scale of the SPK files, from epochs in ET, which keeps it to the nanosecond.
+    const RADIUS_KM: f64 = 7000.0;
+
+    #[test]
+    fn fit_closure() {
+        let start = Epoch::from_gregorian_at_midnight(2024, 1, 1, TimeScale::ET);
+        let end = start + 6.hours();
+
+        for data_type in [
+            DafDataType::Type2ChebyshevTriplet,
+            DafDataType::Type3ChebyshevSextuplet,
+        ] {
+            let config = ChebyshevFitConfig {
+                data_type,
+                ..Default::default()
+            };
+            let fit = ChebyshevFit::fit(
+                |epoch| Ok(circular(RADIUS_KM, (epoch - start).to_seconds())),
+                start,
+                end,
+                config,
+            )
+            .unwrap();
+
+            // Six hours of a low orbit cannot be fitted by a single polynomial of degree 11.
+            assert!(fit.num_records > 1);
+            assert!(fit.position_error_km <= config.position_tolerance_km);
+            assert!(fit.velocity_error_km_s <= config.velocity_tolerance_km_s);
+            assert!((fit.end_epoch() - end).abs() < 1.microseconds());
+
+            let mut builder = SPKBuilder::new("CHEBYSHEV FIT");
+            builder
+                .add_chebyshev_segment("FIT", SC_ID, EARTH_J2000, &fit)
+                .unwrap();
+            let almanac = Almanac::default()
+                .with_spk(builder.build().unwrap())
+                .unwrap();
+
+            // Check at epochs that are neither nodes nor checks of the fit.
+            let mut epoch = start + 10.seconds();
+            while epoch < end {
+                let state = almanac
+                    .translate(Frame::from_ephem_j2000(SC_ID), EARTH_J2000, epoch, None)
+                    .unwrap();
+                let (pos_km, vel_km_s) = circular(RADIUS_KM, (epoch - start).to_seconds());
+                assert!(
+                    (state.radius_km - pos_km).norm() < config.position_tolerance_km,
+                    "{data_type:?} at {epoch}: {}",
+                    (state.radius_km - pos_km).norm()
+                );
+                assert!(
+                    (state.velocity_km_s - vel_km_s).norm() < config.velocity_tolerance_km_s,
+                    "{data_type:?} at {epoch}: {}",
+                    (state.velocity_km_s - vel_km_s).norm()
+                );
+                epoch += 7.minutes() + 13.seconds();
+            }
+        }
+    }
+
+    #[test]
+    fn fit_almanac() {
+        // Recompress a Hermite ephemeris sampled every minute over a shorter span than the original one.
+        let start = Epoch::from_gregorian_at_midnight(2024, 1, 1, TimeScale::ET);
+        let states = sampled_states(EARTH_J2000, start, 1.minutes(), 0..=240, |i| {
+            circular(RADIUS_KM, i as f64 * 60.0)
+        });
+        let hermite = hermite_spk("HERMITE", [("HERMITE", SC_ID, states, 8)]);
+        let hermite_len = hermite.data_summaries().unwrap()[0].end_idx
+            - hermite.data_summaries().unwrap()[0].start_idx;
+        let almanac = Almanac::default().with_spk(hermite).unwrap();
+
+        let sc = Frame::from_ephem_j2000(SC_ID);
+        let (fit_start, fit_end) = (start + 10.minutes(), start + 3.hours());
+        let config = ChebyshevFitConfig {
+            data_type: DafDataType::Type3ChebyshevSextuplet,
+            degree: 15,
+            ..Default::default()
+        };
+        let fit = almanac
+            .fit_chebyshev(sc, EARTH_J2000, fit_start, fit_end, config)
+            .unwrap();
+        // The Chebyshev polynomials are much more compact than the Hermite states.
+        assert!(fit.to_f64_daf_vec().unwrap().len() < hermite_len as usize / 4);
+
+        let mut builder = SPKBuilder::new("CHEBYSHEV");
+        builder
+            .add_chebyshev_segment("CHEBYSHEV", SC_ID, EARTH_J2000, &fit)
+            .unwrap();
+        let chebyshev = Almanac::default()
+            .with_spk(builder.build().unwrap())
+            .unwrap();
+
+        let mut epoch = fit_start + 10.seconds();
+        while epoch < fit_end {
+            let expected = almanac.translate(sc, EARTH_J2000, epoch, None).unwrap();
+            let state = chebyshev.translate(sc, EARTH_J2000, epoch, None).unwrap();
+            assert!((state.radius_km - expected.radius_km).norm() < config.position_tolerance_km);
+            assert!(
+                (state.velocity_km_s - expected.velocity_km_s).norm()
+                    < config.velocity_tolerance_km_s
+            );
+            epoch += 3.minutes() + 7.seconds();
+        }
+
+        // Outside of the span of the fit.
+        assert!(chebyshev
+            .translate(sc, EARTH_J2000, fit_end + 1.seconds(), None)
+            .is_err());
+        // Outside of the span of the source.
+        assert!(matches!(
+            almanac.fit_chebyshev(sc, EARTH_J2000, fit_start, start + 5.hours(), config),
+            Err(ChebyshevFitError::FitSource { .. })
+        ));
+    }
+
+    #[test]
+    fn fit_errors() {
+        let start = Epoch::from_gregorian_at_midnight(2024, 1, 1, TimeScale::ET);
+        let source = |epoch: Epoch| Ok(circular(RADIUS_KM, (epoch - start).to_seconds()));
+
+        let config = ChebyshevFitConfig {
+            max_records: 4,
+            ..Default::default()
+        };
+        assert!(matches!(
+            ChebyshevFit::fit(source, start, start + 1.days(), config),
+            Err(ChebyshevFitError::ToleranceNotMet { num_records: 4, .. })
+        ));
+
+        for config in [
+            ChebyshevFitConfig {
+                data_type: DafDataType::Type13HermiteUnequalStep,
+                ..Default::default()
+            },
+            ChebyshevFitConfig {
+                degree: 0,
+                ..Default::default()
+            },
+            ChebyshevFitConfig {
+                position_tolerance_km: 0.0,
+                ..Default::default()
+            },
+        ] {
+            assert!(matches!(
+                ChebyshevFit::fit(source, start, start + 1.hours(), config),
+                Err(ChebyshevFitError::InvalidFitConfig { .. })
+            ));
+        }
+        assert!(matches!(
+            ChebyshevFit::fit(source, start, start, ChebyshevFitConfig::default()),
+            Err(ChebyshevFitError::InvalidFitConfig { .. })
+        ));
+
+        // The error of the source is reported with the epoch at which it failed.
+        match ChebyshevFit::fit(
+            |_| Err(EphemerisError::Unreachable),
+            start,
+            start + 1.hours(),
+            ChebyshevFitConfig::default(),
+        ) {
+            Err(ChebyshevFitError::FitSource { epoch, source }) => {
+                assert_eq!(*source, EphemerisError::Unreachable);
+                assert!(epoch > start && epoch < start + 1.hours());
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+//! Ephemerides built in memory by the unit tests, so that they do not depend on the kernels of the data directory.
+
+use hifitime::{Duration, Epoch};
+
+use super::builder::SPKBuilder;
+use crate::math::cartesian::CartesianState;
+use crate::math::Vector3;
+use crate::naif::SPK;
+use crate::prelude::Frame;
+use crate::NaifId;
+
+/// Gravitational parameter of the Earth of the circular orbits, in km^3/s^2.
+pub(crate) const EARTH_GM_KM3_S2: f64 = 398600.435436;
+
+/// Position and velocity on a circular orbit of the Earth in the XY plane, `t_s` seconds after crossing the X axis.
+pub(crate) fn circular(radius_km: f64, t_s: f64) -> (Vector3, Vector3) {
+    circular_at_rate(radius_km, (EARTH_GM_KM3_S2 / radius_km.powi(3)).sqrt(), t_s)
+}
+
+/// Position and velocity on a circular motion in the XY plane at this angular rate, in radians per second, `t_s`
+/// seconds after crossing the X axis, regardless of the gravitational parameter of the center.
+pub(crate) fn circular_at_rate(radius_km: f64, rate_rad_s: f64, t_s: f64) -> (Vector3, Vector3) {
+    let (sin, cos) = (rate_rad_s * t_s).sin_cos();
+    let speed_km_s = radius_km * rate_rad_s;
+    (
+        Vector3::new(radius_km * cos, radius_km * sin, 0.0),
+        Vector3::new(-speed_km_s * sin, speed_km_s * cos, 0.0),
+    )
+}
+
+/// Samples states in this frame every `step` from `start`, where `state_at` returns the position and velocity at each
+/// step number.
+pub(crate) fn sampled_states(
+    frame: Frame,
+    start: Epoch,
+    step: Duration,
+    steps: impl IntoIterator<Item = i64>,
+    state_at: impl Fn(i64) -> (Vector3, Vector3),
+) -> Vec<CartesianState> {
+    steps
+        .into_iter()
+        .map(|i| {
+            let (radius_km, velocity_km_s) = state_at(i);
+            CartesianState {
+                radius_km,
+                velocity_km_s,
+                epoch: start + step * i,
+                frame,
+            }
+        })
+        .collect()
+}
+
+/// Builds an SPK of Hermite segments, each given by its name, target ID, states and window size.
+pub(crate) fn hermite_spk<S: AsRef<str>>(
+    internal_filename: &str,
+    segments: impl IntoIterator<Item = (S, NaifId, Vec<CartesianState>, usize)>,
+) -> SPK {
+    let mut builder = SPKBuilder::new(internal_filename);
+    for (name, target_id, states, window_size) in segments {
+        builder
+            .add_hermite_segment(name.as_ref(), target_id, &states, window_size)
+            .unwrap();
+    }
+    builder.build().unwrap()
+}
@@ -10,3 +10,9 @@
 
 // Defines how to read an SPK
 pub mod summary;
+// Defines how to write an SPK
+pub mod builder;
+pub mod fit;
+
+#[cfg(test)]
+pub(crate) mod fixtures;
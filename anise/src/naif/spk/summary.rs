@@ -183,6 +183,10 @@ impl NAIFSummaryRecord for SPKSummaryRecord {
         self.target_id
     }
 
+    fn center(&self) -> Option<i32> {
+        Some(self.center_id)
+    }
+
     fn start_epoch_et_s(&self) -> f64 {
         self.start_epoch_et_s
     }
@@ -13,7 +13,7 @@ use snafu::prelude::*;
 
 use crate::{
     errors::PhysicsError, math::interpolation::InterpolationError, naif::daf::DAFError,
-    prelude::FrameUid, structure::dataset::DataSetError,
+    naif::sclk::SCLKError, prelude::FrameUid, structure::dataset::DataSetError,
 };
 
 mod paths;
@@ -25,10 +25,6 @@ mod rotations;
 pub enum OrientationError {
     /// Somehow you've entered code that should not be reachable, please file a bug.
     Unreachable,
-    #[snafu(display(
-         "could not load BPC because all {max_slots} are used (modify `MAX_LOADED_BPCS` at build time)"
-     ))]
-    StructureIsFull { max_slots: usize },
     #[snafu(display(
         "Could not rotate from {from} to {to}: no common origin found at epoch {epoch}"
     ))]
@@ -45,6 +41,17 @@ pub enum OrientationError {
         #[snafu(backtrace)]
         source: DAFError,
     },
+    #[snafu(display("when {action} caused {source}"))]
+    CK {
+        action: &'static str,
+        #[snafu(backtrace)]
+        source: DAFError,
+    },
+    #[snafu(display("during a spacecraft clock conversion {source}"))]
+    OrientationSCLK {
+        #[snafu(backtrace)]
+        source: SCLKError,
+    },
     #[snafu(display("during an orientation operation: {source}"))]
     OrientationPhysics {
         #[snafu(backtrace)]
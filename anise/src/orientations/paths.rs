@@ -11,7 +11,9 @@
 use hifitime::Epoch;
 use snafu::{ensure, ResultExt};
 
-use super::{BPCSnafu, NoOrientationsLoadedSnafu, OrientationDataSetSnafu, OrientationError};
+use super::{
+    BPCSnafu, CKSnafu, NoOrientationsLoadedSnafu, OrientationDataSetSnafu, OrientationError,
+};
 use crate::almanac::Almanac;
 use crate::constants::orientations::{ECLIPJ2000, J2000};
 use crate::frames::Frame;
@@ -22,7 +24,7 @@ use crate::NaifId;
 pub const MAX_TREE_DEPTH: usize = 8;
 
 impl Almanac {
-    /// Returns the root of all of the loaded orientations (BPC or planetary), typically this should be J2000.
+    /// Returns the root of all of the loaded orientations (BPC, planetary, or Euler parameters), typically this should be J2000.
     ///
     /// # Algorithm
     ///
@@ -30,17 +32,18 @@ impl Almanac {
     /// 2. For each summary record in each BPC, follow the orientation branch all the way up until the end of this BPC or until the J2000.
     pub fn try_find_orientation_root(&self) -> Result<NaifId, OrientationError> {
         ensure!(
-            self.num_loaded_bpc() > 0 || !self.planetary_data.is_empty(),
+            self.num_loaded_bpc() > 0
+                || self.num_loaded_ck() > 0
+                || !self.planetary_data.is_empty()
+                || !self.euler_param_data.is_empty(),
             NoOrientationsLoadedSnafu
         );
 
         // The common center is the absolute minimum of all centers due to the NAIF numbering.
         let mut common_center = i32::MAX;
 
-        for maybe_bpc in self.bpc_data.iter().take(self.num_loaded_bpc()).rev() {
-            let bpc = maybe_bpc.as_ref().unwrap();
-
-            for summary in bpc.data_summaries().context(BPCSnafu {
+        for bpc in self.bpc_data.iter().rev() {
+            for (_, summary) in bpc.summaries().context(BPCSnafu {
                 action: "finding orientation root",
             })? {
                 // This summary exists, so we need to follow the branch of centers up the tree.
@@ -54,7 +57,22 @@ impl Almanac {
             }
         }
 
-        // If we reached this point, it means that we didn't find J2000 in the loaded BPCs, so let's iterate through the planetary data
+        // Attitude data from the CKs is defined relative to its reference frame
+        for ck in self.ck_data.iter().rev() {
+            for (_, summary) in ck.summaries().context(CKSnafu {
+                action: "finding orientation root",
+            })? {
+                if !summary.is_empty() && summary.reference_frame_id.abs() < common_center.abs() {
+                    common_center = summary.reference_frame_id;
+                    if common_center == J2000 {
+                        // there is nothing higher up
+                        return Ok(common_center);
+                    }
+                }
+            }
+        }
+
+        // If we reached this point, it means that we didn't find J2000 in the loaded BPCs or CKs, so let's iterate through the planetary data
         if !self.planetary_data.is_empty() {
             for id in self.planetary_data.lut.by_id.keys() {
                 if let Ok(pc) = self.planetary_data.get_by_id(*id) {
@@ -69,6 +87,19 @@ impl Almanac {
             }
         }
 
+        // Finally, fixed frames (e.g. from an FK) may be defined relative to a frame that is not otherwise loaded
+        for id in self.euler_param_data.lut.by_id.keys() {
+            if let Ok(euler_param) = self.euler_param_data.get_by_id(*id) {
+                if euler_param.from < common_center {
+                    common_center = euler_param.from;
+                    if common_center == J2000 {
+                        // there is nothing higher up
+                        return Ok(common_center);
+                    }
+                }
+            }
+        }
+
         if common_center == ECLIPJ2000 {
             // Rotation from ecliptic J2000 to J2000 is embedded.
             common_center = J2000;
@@ -95,17 +126,7 @@ impl Almanac {
 
         // Grab the summary data, which we use to find the paths
         // Let's see if this orientation is defined in the loaded BPC files
-        let mut inertial_frame_id = match self.bpc_summary_at_epoch(source.orientation_id, epoch) {
-            Ok((summary, _, _)) => summary.inertial_frame_id,
-            Err(_) => {
-                // Not available as a BPC, so let's see if there's planetary data for it.
-                let planetary_data = self
-                    .planetary_data
-                    .get_by_id(source.orientation_id)
-                    .context(OrientationDataSetSnafu)?;
-                planetary_data.parent_id
-            }
-        };
+        let mut inertial_frame_id = self.orientation_parent_id(source.orientation_id, epoch)?;
 
         of_path[of_path_len] = Some(inertial_frame_id);
         of_path_len += 1;
@@ -123,20 +144,8 @@ impl Almanac {
         }
 
         for _ in 0..MAX_TREE_DEPTH - 1 {
-            inertial_frame_id = match self.bpc_summary_at_epoch(inertial_frame_id, epoch) {
-                Ok((summary, _, _)) => summary.inertial_frame_id,
-                Err(_) => {
-                    // Not available as a BPC, so let's see if there's planetary data for it.
-                    let planetary_data = self
-                        .planetary_data
-                        .get_by_id(inertial_frame_id)
-                        .context(OrientationDataSetSnafu)?;
-                    planetary_data.parent_id
-                }
-            };
+            inertial_frame_id = self.orientation_parent_id(inertial_frame_id, epoch)?;
 
-            // let summary = self.bpc_summary_at_epoch(inertial_frame_id, epoch)?.0;
-            // inertial_frame_id = summary.inertial_frame_id;
             of_path[of_path_len] = Some(inertial_frame_id);
             of_path_len += 1;
             if inertial_frame_id == common_center {
@@ -151,6 +160,29 @@ impl Almanac {
         })
     }
 
+    /// Returns the parent of the provided orientation ID at the provided epoch.
+    ///
+    /// The loaded BPCs are searched first, then the CKs, the planetary data, and finally the Euler parameters (fixed frames, e.g. from an FK).
+    fn orientation_parent_id(&self, id: NaifId, epoch: Epoch) -> Result<NaifId, OrientationError> {
+        if let Ok((summary, _, _)) = self.bpc_summary_at_epoch(id, epoch) {
+            return Ok(summary.inertial_frame_id);
+        } else if let Ok((summary, _, _)) = self.ck_summary_at_epoch(id, epoch) {
+            return Ok(summary.reference_frame_id);
+        }
+
+        match self.planetary_data.get_by_id(id) {
+            Ok(planetary_data) => Ok(planetary_data.parent_id),
+            Err(_) => {
+                // Euler parameters rotate from the parent frame into the fixed frame.
+                let euler_param = self
+                    .euler_param_data
+                    .get_by_id(id)
+                    .context(OrientationDataSetSnafu)?;
+                Ok(euler_param.from)
+            }
+        }
+    }
+
     /// Returns the orientation path between two frames and the common node. This may return a `DisjointRoots` error if the frames do not share a common root, which is considered a file integrity error.
     pub fn common_orientation_path(
         &self,
@@ -16,7 +16,7 @@ use crate::almanac::Almanac;
 use crate::constants::orientations::{ECLIPJ2000, J2000, J2000_TO_ECLIPJ2000_ANGLE_RAD};
 use crate::hifitime::Epoch;
 use crate::math::rotation::{r1, r1_dot, r3, r3_dot, DCM};
-use crate::naif::daf::datatypes::Type2ChebyshevSet;
+use crate::naif::daf::datatypes::{Type2ChebyshevSet, Type3ChebyshevSet};
 use crate::naif::daf::{DAFError, DafDataType, NAIFDataSet, NAIFSummaryRecord};
 use crate::orientations::{BPCSnafu, OrientationDataSetSnafu, OrientationInterpolationSnafu};
 use crate::prelude::Frame;
@@ -53,8 +53,9 @@ impl Almanac {
                 trace!("rotate {source} wrt to {new_frame} @ {epoch:E}");
 
                 // This should not fail because we've fetched the spk_no from above with the spk_summary_at_epoch call.
-                let bpc_data = self.bpc_data[bpc_no]
-                    .as_ref()
+                let bpc_data = self
+                    .bpc_data
+                    .get(bpc_no)
                     .ok_or(OrientationError::Unreachable)?;
 
                 // Compute the angles and their rates
@@ -68,6 +69,16 @@ impl Almanac {
                         data.evaluate(epoch, summary)
                             .context(OrientationInterpolationSnafu)?
                     }
+                    DafDataType::Type3ChebyshevSextuplet => {
+                        // The rates of the angles are interpolated from their own coefficients.
+                        let data = bpc_data.nth_data::<Type3ChebyshevSet>(idx_in_bpc).context(
+                            BPCSnafu {
+                                action: "fetching data for interpolation",
+                            },
+                        )?;
+                        data.evaluate(epoch, summary)
+                            .context(OrientationInterpolationSnafu)?
+                    }
                     dtype => {
                         return Err(OrientationError::BPC {
                             action: "rotation to parent",
@@ -102,14 +113,31 @@ impl Almanac {
                     to: source.orientation_id,
                 })
             }
-            Err(_) => {
-                trace!("query {source} wrt to its parent @ {epoch:E} using planetary data");
-                // Not available as a BPC, so let's see if there's planetary data for it.
-                let planetary_data = self
-                    .planetary_data
-                    .get_by_id(source.orientation_id)
-                    .context(OrientationDataSetSnafu)?;
+            Err(_) => match self.ck_summary_at_epoch(source.orientation_id, epoch) {
+                Ok((summary, ck_no, idx_in_ck)) => {
+                    trace!("query {source} wrt to its parent @ {epoch:E} using CK data");
+                    let (q, av) =
+                        self.ck_attitude_from_summary(summary, ck_no, idx_in_ck, epoch)?;
 
+                    let mut dcm = DCM::from(q);
+                    // The angular velocity is expressed in the reference frame, so dC/dt = -C [w x].
+                    dcm.rot_mat_dt = av.map(|av_rad_s| -dcm.rot_mat * av_rad_s.cross_matrix());
+                    Ok(dcm)
+                }
+                Err(_) => self.planetary_or_fixed_rotation_to_parent(source, epoch),
+            },
+        }
+    }
+
+    /// Returns the rotation to the parent of the `source` from the planetary data or, if not available, from the Euler parameters.
+    fn planetary_or_fixed_rotation_to_parent(
+        &self,
+        source: Frame,
+        epoch: Epoch,
+    ) -> Result<DCM, OrientationError> {
+        match self.planetary_data.get_by_id(source.orientation_id) {
+            Ok(planetary_data) => {
+                trace!("query {source} wrt to its parent @ {epoch:E} using planetary data");
                 // Fetch the parent info
                 let system_data = match self.planetary_data.get_by_id(planetary_data.parent_id) {
                     Ok(parent) => parent,
@@ -120,6 +148,148 @@ impl Almanac {
                     .rotation_to_parent(epoch, &system_data)
                     .context(OrientationPhysicsSnafu)
             }
+            Err(_) => {
+                trace!("query {source} wrt to its parent @ {epoch:E} using Euler parameters");
+                // Not available as planetary data either, so this must be a fixed frame (e.g. from an FK).
+                // Euler parameters are stored from the parent frame to the fixed frame, and are time invariant.
+                let euler_param = self
+                    .euler_param_data
+                    .get_by_id(source.orientation_id)
+                    .context(OrientationDataSetSnafu)?;
+
+                Ok(DCM::from(euler_param))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod ut_rotate_to_parent {
+    use hifitime::{Epoch, TimeUnits};
+
+    use crate::almanac::Almanac;
+    use crate::constants::orientations::J2000;
+    use crate::math::rotation::{r1, r3, r3_dot};
+    use crate::naif::daf::daf::MutDAF;
+    use crate::naif::daf::DafDataType;
+    use crate::naif::pck::BPCSummaryRecord;
+    use crate::naif::BPC;
+    use crate::prelude::Frame;
+
+    const CONSISTENT_ID: i32 = 100_001;
+    const SPINNING_ID: i32 = 100_002;
+    const RECORD_LENGTH_S: f64 = 86_400.0;
+
+    /// Builds a BPC Type 3 segment of two records of one day, whose right ascension, declination and twist are
+    /// Chebyshev polynomials of degree two.
+    fn type3_segment(
+        angle_coeffs: impl Fn(usize, usize) -> [f64; 3],
+        rate_coeffs: impl Fn(usize, usize) -> [f64; 3],
+    ) -> Vec<f64> {
+        let mut data = Vec::new();
+        for rno in 0..2 {
+            data.push((rno as f64 + 0.5) * RECORD_LENGTH_S);
+            data.push(0.5 * RECORD_LENGTH_S);
+            for ano in 0..3 {
+                data.extend_from_slice(&angle_coeffs(rno, ano));
+            }
+            for ano in 0..3 {
+                data.extend_from_slice(&rate_coeffs(rno, ano));
+            }
+        }
+        data.extend_from_slice(&[0.0, RECORD_LENGTH_S, 20.0, 2.0]);
+        data
+    }
+
+    fn type3_almanac() -> Almanac {
+        let angle_coeffs = |rno: usize, ano: usize| {
+            let base = 0.1 * (rno * 3 + ano + 1) as f64;
+            [base, 0.5 * base, -0.2 * base]
+        };
+        // Derivative of c0 T0 + c1 t + c2 (2t^2 - 1) with respect to time, where t is normalized over the radius.
+        let radius_s = 0.5 * RECORD_LENGTH_S;
+        let consistent_rates = |rno: usize, ano: usize| {
+            let coeffs = angle_coeffs(rno, ano);
+            [coeffs[1] / radius_s, 4.0 * coeffs[2] / radius_s, 0.0]
+        };
+
+        let mut daf =
+            MutDAF::<BPCSummaryRecord>::empty("DAF/PCK", 2, 5, "BPC TYPE 3 TEST", &[]).unwrap();
+        let summary = |frame_id| BPCSummaryRecord {
+            start_epoch_et_s: 0.0,
+            end_epoch_et_s: 2.0 * RECORD_LENGTH_S,
+            frame_id,
+            inertial_frame_id: J2000,
+            data_type_i: DafDataType::Type3ChebyshevSextuplet as i32,
+            ..Default::default()
+        };
+        daf.append_data(
+            summary(CONSISTENT_ID),
+            "CONSISTENT",
+            &type3_segment(angle_coeffs, consistent_rates),
+        )
+        .unwrap();
+        // Constant angles, but a twist rate that only the coefficients of the rates provide.
+        daf.append_data(
+            summary(SPINNING_ID),
+            "SPINNING",
+            &type3_segment(
+                |_, ano| [if ano == 2 { 0.7 } else { 0.0 }, 0.0, 0.0],
+                |_, ano| [if ano == 2 { 1e-3 } else { 0.0 }, 0.0, 0.0],
+            ),
+        )
+        .unwrap();
+
+        Almanac::from_bpc(BPC::parse(daf.bytes).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn type3_rates_from_own_coefficients() {
+        let almanac = type3_almanac();
+        let frame = Frame::new(0, SPINNING_ID);
+
+        for epoch_et_s in [0.0, 30_000.0, 100_000.0, 2.0 * RECORD_LENGTH_S] {
+            let dcm = almanac
+                .rotation_to_parent(frame, Epoch::from_et_seconds(epoch_et_s))
+                .unwrap();
+            assert_eq!(dcm.from, J2000);
+            assert_eq!(dcm.to, SPINNING_ID);
+            assert!((dcm.rot_mat - r3(0.7)).norm() < 1e-15);
+            // Differentiating the constant angle polynomials would yield a zero derivative.
+            assert!((dcm.rot_mat_dt.unwrap() - 1e-3 * r3_dot(0.7)).norm() < 1e-15);
+        }
+    }
+
+    #[test]
+    fn type3_rates_match_finite_differences() {
+        let almanac = type3_almanac();
+        let frame = Frame::new(0, CONSISTENT_ID);
+        let step = 1.seconds();
+
+        for (epoch_et_s, rno, t) in [
+            (10_000.0, 0, 10_000.0 / 43_200.0 - 1.0),
+            (43_200.0, 0, 0.0),
+            (150_000.0, 1, 150_000.0 / 43_200.0 - 3.0),
+        ] {
+            let epoch = Epoch::from_et_seconds(epoch_et_s);
+            let dcm = almanac.rotation_to_parent(frame, epoch).unwrap();
+
+            // T0 = 1, T1 = t, T2 = 2t^2 - 1
+            let angle = |ano: usize| {
+                let base = 0.1 * (rno * 3 + ano + 1) as f64;
+                base + 0.5 * base * t - 0.2 * base * (2.0 * t * t - 1.0)
+            };
+            let expected = r3(angle(2)) * r1(angle(1)) * r3(angle(0));
+            assert!((dcm.rot_mat - expected).norm() < 1e-14);
+
+            let before = almanac.rotation_to_parent(frame, epoch - step).unwrap();
+            let after = almanac.rotation_to_parent(frame, epoch + step).unwrap();
+            let finite_diff = (after.rot_mat - before.rot_mat) / (2.0 * step.to_seconds());
+            let err = (dcm.rot_mat_dt.unwrap() - finite_diff).norm();
+            assert!(
+                err < 1e-12,
+                "rotation rate error of {err:e} @ {epoch_et_s} s"
+            );
         }
     }
 }
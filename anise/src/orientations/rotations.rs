@@ -10,6 +10,7 @@
 
 use snafu::ResultExt;
 
+use super::paths::MAX_TREE_DEPTH;
 use super::OrientationError;
 use super::OrientationPhysicsSnafu;
 use crate::almanac::Almanac;
@@ -20,6 +21,7 @@ use crate::math::rotation::DCM;
 use crate::math::units::*;
 use crate::math::Vector3;
 use crate::prelude::Frame;
+use crate::NaifId;
 
 impl Almanac {
     /// Returns the 6x6 DCM needed to rotation the `from_frame` to the `to_frame`.
@@ -51,9 +53,53 @@ impl Almanac {
             ));
         }
 
-        let (node_count, path, common_node) =
-            self.common_orientation_path(from_frame, to_frame, epoch)?;
+        let path = self.common_orientation_path(from_frame, to_frame, epoch)?;
+        self.rotate_along_path(from_frame, to_frame, epoch, path)
+    }
+
+    /// Translates a state with its origin (`to_frame`) and given its units (distance_unit, time_unit), returns that state with respect to the requested frame
+    ///
+    /// **WARNING:** This function only performs the translation and no rotation _whatsoever_. Use the `transform_state_to` function instead to include rotations.
+    #[allow(clippy::too_many_arguments)]
+    pub fn rotate_state_to(
+        &self,
+        position: Vector3,
+        velocity: Vector3,
+        from_frame: Frame,
+        to_frame: Frame,
+        epoch: Epoch,
+        distance_unit: LengthUnit,
+        time_unit: TimeUnit,
+    ) -> Result<CartesianState, OrientationError> {
+        // Compute the frame translation
+        let dcm = self.rotate_from_to(from_frame, to_frame, epoch)?;
 
+        let dist_unit_factor = LengthUnit::Kilometer.from_meters() * distance_unit.to_meters();
+        let time_unit_factor = time_unit.in_seconds();
+
+        let input_state = CartesianState {
+            radius_km: position * dist_unit_factor,
+            velocity_km_s: velocity * dist_unit_factor / time_unit_factor,
+            epoch,
+            frame: from_frame,
+        };
+
+        (dcm * input_state).context(OrientationPhysicsSnafu {})
+    }
+}
+
+impl Almanac {
+    /// Returns the DCM from the `from_frame` to the `to_frame`, along an orientation path computed by
+    /// [Almanac::common_orientation_path] at an epoch where the same segments are used.
+    ///
+    /// The `to_frame` must already include its frame information, if any.
+    pub(crate) fn rotate_along_path(
+        &self,
+        from_frame: Frame,
+        to_frame: Frame,
+        epoch: Epoch,
+        (node_count, path, common_node): (usize, [Option<NaifId>; MAX_TREE_DEPTH], NaifId),
+    ) -> Result<DCM, OrientationError> {
         // The fwrd variables are the states from the `from frame` to the common node
         let mut dcm_fwrd = if from_frame.orient_origin_id_match(common_node) {
             DCM::identity(common_node, common_node)
@@ -70,8 +116,9 @@ impl Almanac {
 
         for cur_node_id in path.iter().take(node_count) {
             let next_parent = cur_node_id.unwrap();
-            if next_parent == J2000 {
-                // The parent rotation of J2000 is itself, so we can skip this.
+            if next_parent == J2000 || next_parent == common_node {
+                // The parent rotation of J2000 is itself, and the common node is the root of the loaded orientations
+                // (e.g. the relative frame of an FK loaded without its BPC), so we can skip this.
                 continue;
             }
 
@@ -90,10 +137,6 @@ impl Almanac {
             } else {
                 return Err(OrientationError::Unreachable);
             }
-
-            if next_parent == common_node {
-                break;
-            }
         }
 
         if dcm_fwrd.from == dcm_bwrd.from {
@@ -110,34 +153,4 @@ impl Almanac {
             (dcm_bwrd * dcm_fwrd).context(OrientationPhysicsSnafu)
         }
     }
-
-    /// Translates a state with its origin (`to_frame`) and given its units (distance_unit, time_unit), returns that state with respect to the requested frame
-    ///
-    /// **WARNING:** This function only performs the translation and no rotation _whatsoever_. Use the `transform_state_to` function instead to include rotations.
-    #[allow(clippy::too_many_arguments)]
-    pub fn rotate_state_to(
-        &self,
-        position: Vector3,
-        velocity: Vector3,
-        from_frame: Frame,
-        to_frame: Frame,
-        epoch: Epoch,
-        distance_unit: LengthUnit,
-        time_unit: TimeUnit,
-    ) -> Result<CartesianState, OrientationError> {
-        // Compute the frame translation
-        let dcm = self.rotate_from_to(from_frame, to_frame, epoch)?;
-
-        let dist_unit_factor = LengthUnit::Kilometer.from_meters() * distance_unit.to_meters();
-        let time_unit_factor = time_unit.in_seconds();
-
-        let input_state = CartesianState {
-            radius_km: position * dist_unit_factor,
-            velocity_km_s: velocity * dist_unit_factor / time_unit_factor,
-            epoch,
-            frame: from_frame,
-        };
-
-        (dcm * input_state).context(OrientationPhysicsSnafu {})
-    }
 }
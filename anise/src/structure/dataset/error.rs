@@ -31,6 +31,8 @@ pub enum DataSetError {
     },
     #[snafu(display("data set conversion error: {action}"))]
     Conversion { action: String },
+    #[snafu(display("cannot merge {kind}: {key} is already loaded"))]
+    MergeConflict { kind: &'static str, key: String },
 }
 
 impl PartialEq for DataSetError {
@@ -76,6 +78,16 @@ impl PartialEq for DataSetError {
                     source: _r_source,
                 },
             ) => l_action == r_action,
+            (
+                Self::MergeConflict {
+                    kind: l_kind,
+                    key: l_key,
+                },
+                Self::MergeConflict {
+                    kind: r_kind,
+                    key: r_key,
+                },
+            ) => l_kind == r_kind && l_key == r_key,
             _ => false,
         }
     }
@@ -9,7 +9,7 @@
  */
 use self::error::{DataDecodingSnafu, DataSetLutSnafu};
 use super::{
-    lookuptable::{LookUpTable, LutError},
+    lookuptable::{lut_name, LookUpTable, LutError},
     metadata::Metadata,
     semver::Semver,
     ANISE_VERSION,
@@ -21,10 +21,7 @@ use crate::{
 };
 use core::fmt;
 use core::ops::Deref;
-use der::{
-    asn1::{OctetString, SequenceOf},
-    Decode, Encode, Reader, Writer,
-};
+use der::{asn1::OctetString, Decode, Encode, Reader, Writer};
 use log::{error, trace};
 use snafu::prelude::*;
 
@@ -45,6 +42,18 @@ mod error;
 pub use datatype::DataSetType;
 pub use error::DataSetError;
 
+/// How to resolve the entries of a data set that have the same NAIF ID or name as an entry of the data set they are merged into.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// The merged entry replaces the existing one, as if the data sets were loaded in order.
+    #[default]
+    LatestWins,
+    /// The existing entry is kept and the merged entry is discarded.
+    KeepFirst,
+    /// Merging fails on the first conflicting entry.
+    Error,
+}
+
 /// The kind of data that can be encoded in a dataset
 pub trait DataSetT: Clone + Default + Encode + for<'a> Decode<'a> {
     const NAME: &'static str;
@@ -52,16 +61,16 @@ pub trait DataSetT: Clone + Default + Encode + for<'a> Decode<'a> {
 
 /// A DataSet is the core structure shared by all ANISE binary data.
 #[derive(Clone, Default, PartialEq, Eq, Debug)]
-pub struct DataSet<T: DataSetT, const ENTRIES: usize> {
+pub struct DataSet<T: DataSetT> {
     pub metadata: Metadata,
     /// All datasets have LookUpTable (LUT) that stores the mapping between a key and its index in the ephemeris list.
-    pub lut: LookUpTable<ENTRIES>,
+    pub lut: LookUpTable,
     pub data_checksum: u32,
     /// The actual data from the dataset
     pub data: Vec<T>,
 }
 
-impl<T: DataSetT, const ENTRIES: usize> DataSet<T, ENTRIES> {
+impl<T: DataSetT> DataSet<T> {
     /// Try to load an Anise file from a pointer of bytes
     pub fn try_from_bytes<B: Deref<Target = [u8]>>(bytes: B) -> Result<Self, DataSetError> {
         match Self::from_der(&bytes) {
@@ -172,34 +181,27 @@ impl<T: DataSetT, const ENTRIES: usize> DataSet<T, ENTRIES> {
 
         match id {
             Some(id) => {
-                match name {
-                    Some(name) => {
+                let mut ids = vec![id];
+                // If the ID is the body of a system with a single object, also insert it for the system ID.
+                if [199, 299].contains(&id) {
+                    ids.push(id / 100);
+                }
+                let prev_lut = self.lut.clone();
+                for id in ids {
+                    let appended = match name {
                         // Both an ID and a name
-                        self.lut.append(id, name, index).context(DataSetLutSnafu {
+                        Some(name) => self.lut.append(id, name, index).context(DataSetLutSnafu {
                             action: "pushing data with ID and name",
-                        })?;
-                        // If the ID is the body of a system with a single object, also insert it for the system ID.
-                        if [199, 299].contains(&id) {
-                            self.lut.append(id / 100, name, index).context({
-                                DataSetLutSnafu {
-                                    action: "pushing data with ID and name",
-                                }
-                            })?;
-                        }
-                    }
-                    None => {
+                        }),
                         // Only an ID and no name
-                        self.lut.append_id(id, index).context(DataSetLutSnafu {
+                        None => self.lut.append_id(id, index).context(DataSetLutSnafu {
                             action: "pushing data with ID only",
-                        })?;
-                        // If the ID is the body of a system with a single object, also insert it for the system ID.
-                        if [199, 299].contains(&id) {
-                            self.lut.append_id(id / 100, index).context({
-                                DataSetLutSnafu {
-                                    action: "pushing data with ID and name",
-                                }
-                            })?;
-                        }
+                        }),
+                    };
+                    if appended.is_err() {
+                        // Do not leave the system ID or the body ID alone in the look up table.
+                        self.lut = prev_lut;
+                        return appended;
                     }
                 }
             }
@@ -270,7 +272,7 @@ impl<T: DataSetT, const ENTRIES: usize> DataSet<T, ENTRIES> {
     /// This will return an error if the ID is not in the lookup table.
     /// Note that this function requires a new heap allocation to change the underlying dataset
     pub fn rm_by_id(&mut self, id: NaifId) -> Result<(), DataSetError> {
-        if let Some(index) = self.lut.by_id.remove(&id) {
+        if let Some(index) = self.lut.by_id.swap_remove(&id) {
             *self
                 .data
                 .get_mut(index as usize)
@@ -300,7 +302,10 @@ impl<T: DataSetT, const ENTRIES: usize> DataSet<T, ENTRIES> {
 
     /// Get a copy of the data with that name, if that name is in the lookup table
     pub fn get_by_name(&self, name: &str) -> Result<T, DataSetError> {
-        if let Some(index) = self.lut.by_name.get(&name.try_into().unwrap()) {
+        let key = lut_name(name).context(DataSetLutSnafu {
+            action: "looking up by name",
+        })?;
+        if let Some(index) = self.lut.by_name.get(&key) {
             self.data
                 .get(*index as usize)
                 .cloned()
@@ -311,9 +316,7 @@ impl<T: DataSetT, const ENTRIES: usize> DataSet<T, ENTRIES> {
         } else {
             Err(DataSetError::DataSetLut {
                 action: "fetching by name",
-                source: LutError::UnknownName {
-                    name: name.try_into().unwrap(),
-                },
+                source: LutError::UnknownName { name: key },
             })
         }
     }
@@ -322,7 +325,10 @@ impl<T: DataSetT, const ENTRIES: usize> DataSet<T, ENTRIES> {
     /// This will return an error if the name is not in the lookup table.
     /// Note that this function requires a new heap allocation to change the underlying dataset
     pub fn set_by_name(&mut self, name: &str, new_value: T) -> Result<(), DataSetError> {
-        if let Some(index) = self.lut.by_name.get(&name.try_into().unwrap()) {
+        let key = lut_name(name).context(DataSetLutSnafu {
+            action: "looking up by name",
+        })?;
+        if let Some(index) = self.lut.by_name.get(&key) {
             *self
                 .data
                 .get_mut(*index as usize)
@@ -335,9 +341,7 @@ impl<T: DataSetT, const ENTRIES: usize> DataSet<T, ENTRIES> {
         } else {
             Err(DataSetError::DataSetLut {
                 action: "setting by name",
-                source: LutError::UnknownName {
-                    name: name.try_into().unwrap(),
-                },
+                source: LutError::UnknownName { name: key },
             })
         }
     }
@@ -346,7 +350,10 @@ impl<T: DataSetT, const ENTRIES: usize> DataSet<T, ENTRIES> {
     /// This will return an error if the name is not in the lookup table.
     /// Note that this function requires a new heap allocation to change the underlying dataset
     pub fn rm_by_name(&mut self, name: &str) -> Result<(), DataSetError> {
-        if let Some(index) = self.lut.by_name.remove(&name.try_into().unwrap()) {
+        let key = lut_name(name).context(DataSetLutSnafu {
+            action: "looking up by name",
+        })?;
+        if let Some(index) = self.lut.by_name.swap_remove(&key) {
             *self
                 .data
                 .get_mut(index as usize)
@@ -369,9 +376,7 @@ impl<T: DataSetT, const ENTRIES: usize> DataSet<T, ENTRIES> {
         } else {
             Err(DataSetError::DataSetLut {
                 action: "removing by ID",
-                source: LutError::UnknownName {
-                    name: name.try_into().unwrap(),
-                },
+                source: LutError::UnknownName { name: key },
             })
         }
     }
@@ -422,6 +427,104 @@ impl<T: DataSetT, const ENTRIES: usize> DataSet<T, ENTRIES> {
         }
     }
 
+    /// Merges all of the entries of the `other` data set into this one.
+    ///
+    /// An entry of `other` conflicts with an entry of this data set if they share a NAIF ID or a name, and the conflict is
+    /// resolved with the provided policy. When the merged entry wins, it is also accessible by those of its IDs and names
+    /// which the existing entry did not have.
+    ///
+    /// This data set is left unchanged if the merge fails, e.g. on a conflict with [MergePolicy::Error] or if the look
+    /// up table is full.
+    pub fn merge(&mut self, other: &Self, policy: MergePolicy) -> Result<(), DataSetError> {
+        let mut merged = self.clone();
+
+        for (other_index, item) in other.data.iter().enumerate() {
+            let other_index = other_index as u32;
+            let ids: Vec<NaifId> = other
+                .lut
+                .by_id
+                .iter()
+                .filter(|(_, index)| **index == other_index)
+                .map(|(id, _)| *id)
+                .collect();
+            let names: Vec<&str> = other
+                .lut
+                .by_name
+                .iter()
+                .filter(|(_, index)| **index == other_index)
+                .map(|(name, _)| name.as_str())
+                .collect();
+
+            let existing = ids
+                .iter()
+                .find_map(|id| {
+                    merged
+                        .lut
+                        .by_id
+                        .get(id)
+                        .map(|index| (*index, format!("ID {id}")))
+                })
+                .or_else(|| {
+                    names.iter().find_map(|name| {
+                        merged
+                            .lut
+                            .by_name
+                            .get(&lut_name(name).ok()?)
+                            .map(|index| (*index, format!("name `{name}`")))
+                    })
+                });
+
+            let index = match existing {
+                // Entries that were removed from the look up table are not merged.
+                None if ids.is_empty() && names.is_empty() => continue,
+                None => {
+                    merged.data.push(item.clone());
+                    merged.data.len() as u32 - 1
+                }
+                Some((index, key)) => match policy {
+                    MergePolicy::LatestWins => {
+                        trace!("{key} replaced when merging {}", T::NAME);
+                        merged.data[index as usize] = item.clone();
+                        index
+                    }
+                    MergePolicy::KeepFirst => {
+                        trace!("{key} kept when merging {}", T::NAME);
+                        continue;
+                    }
+                    MergePolicy::Error => {
+                        return Err(DataSetError::MergeConflict { kind: T::NAME, key })
+                    }
+                },
+            };
+
+            for id in ids {
+                if !merged.lut.by_id.contains_key(&id) {
+                    merged.lut.append_id(id, index).context(DataSetLutSnafu {
+                        action: "merging data sets",
+                    })?;
+                }
+            }
+            for name in names {
+                if merged.lut.by_name.get(&lut_name(name).unwrap()).is_none() {
+                    merged
+                        .lut
+                        .append_name(name, index)
+                        .context(DataSetLutSnafu {
+                            action: "merging data sets",
+                        })?;
+                }
+            }
+        }
+
+        if self.data.is_empty() {
+            merged.metadata = other.metadata.clone();
+        }
+        merged.set_crc32();
+        *self = merged;
+
+        Ok(())
+    }
+
     /// Returns the length of the LONGEST of the two look up tables
     pub fn len(&self) -> usize {
         self.lut.len()
@@ -434,8 +537,8 @@ impl<T: DataSetT, const ENTRIES: usize> DataSet<T, ENTRIES> {
 
     /// Returns this data as a data sequence, cloning all of the entries into this sequence.
     fn build_data_seq(&self) -> (Vec<u32>, OctetString) {
-        let mut buf = Vec::with_capacity(ENTRIES * 2);
-        let mut meta = Vec::with_capacity(ENTRIES * 2);
+        let mut buf = vec![];
+        let mut meta = Vec::with_capacity(self.data.len() + 1);
         meta.push(self.data.len() as u32);
         for data in &self.data {
             let mut this_buf = vec![];
@@ -448,7 +551,7 @@ impl<T: DataSetT, const ENTRIES: usize> DataSet<T, ENTRIES> {
     }
 }
 
-impl<T: DataSetT, const ENTRIES: usize> Encode for DataSet<T, ENTRIES> {
+impl<T: DataSetT> Encode for DataSet<T> {
     fn encoded_len(&self) -> der::Result<der::Length> {
         let (bytes_meta, bytes) = self.build_data_seq();
         self.metadata.encoded_len()?
@@ -468,22 +571,22 @@ impl<T: DataSetT, const ENTRIES: usize> Encode for DataSet<T, ENTRIES> {
     }
 }
 
-impl<'a, T: DataSetT, const ENTRIES: usize> Decode<'a> for DataSet<T, ENTRIES> {
+impl<'a, T: DataSetT> Decode<'a> for DataSet<T> {
     fn decode<D: Reader<'a>>(decoder: &mut D) -> der::Result<Self> {
         let metadata = decoder.decode()?;
-        let lut: LookUpTable<ENTRIES> = decoder.decode()?;
+        let lut: LookUpTable = decoder.decode()?;
         let crc32_checksum = decoder.decode()?;
         // Metadata of the bytes to decode.
         // The first integer contains the number of usable items in the data.
         // The other integers are the encoded lengths of each of the data.
-        let bytes_meta: SequenceOf<u32, ENTRIES> = decoder.decode()?;
+        let bytes_meta: Vec<u32> = decoder.decode()?;
         let der_octets: OctetString = decoder.decode()?;
         let bytes = der_octets.as_bytes();
 
         let mut data = vec![];
 
         let mut idx = 0;
-        for meta_idx in 0..*bytes_meta.get(0).unwrap() as usize {
+        for meta_idx in 0..*bytes_meta.first().unwrap() as usize {
             let next_len = *bytes_meta.get(meta_idx + 1).unwrap() as usize;
             let this_data = T::from_der(&bytes[idx..idx + next_len]).unwrap();
             data.push(this_data);
@@ -499,7 +602,7 @@ impl<'a, T: DataSetT, const ENTRIES: usize> Decode<'a> for DataSet<T, ENTRIES> {
     }
 }
 
-impl<T: DataSetT, const ENTRIES: usize> fmt::Display for DataSet<T, ENTRIES> {
+impl<T: DataSetT> fmt::Display for DataSet<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
@@ -520,12 +623,11 @@ mod dataset_ut {
         SpacecraftDataSet,
     };
 
-    use super::{DataSet, Decode, Encode};
+    use super::{DataSet, DataSetError, Decode, Encode, MergePolicy};
 
     #[test]
     fn zero_repr() {
-        // For this test, we want a data set with zero entries allowed in the LUT.
-        let repr = DataSet::<SpacecraftData, 2>::default();
+        let repr = DataSet::<SpacecraftData>::default();
 
         let mut buf = vec![];
         repr.encode_to_vec(&mut buf).unwrap();
@@ -536,8 +638,8 @@ mod dataset_ut {
         assert_eq!(repr, repr_dec);
 
         dbg!(repr);
-        assert_eq!(core::mem::size_of::<DataSet<SpacecraftData, 2>>(), 256);
-        assert_eq!(core::mem::size_of::<DataSet<SpacecraftData, 128>>(), 8824);
+        // The size does not depend on the number of entries.
+        assert_eq!(core::mem::size_of::<DataSet<SpacecraftData>>(), 216);
     }
 
     #[test]
@@ -611,7 +713,7 @@ mod dataset_ut {
         let mut buf = vec![];
         dataset.encode_to_vec(&mut buf).unwrap();
 
-        let repr_dec = DataSet::<SpacecraftData, 4>::from_der(&buf).unwrap();
+        let repr_dec = DataSet::<SpacecraftData>::from_der(&buf).unwrap();
 
         assert_eq!(dataset, repr_dec);
 
@@ -714,7 +816,7 @@ mod dataset_ut {
 
         dbg!(size_of::<SpacecraftDataSet>());
 
-        let mut dataset = DataSet::<SpacecraftData, 16>::default();
+        let mut dataset = DataSet::<SpacecraftData>::default();
         dataset
             .push(srp_sc.clone(), Some(-20), Some("SRP spacecraft"))
             .unwrap();
@@ -802,4 +904,76 @@ mod dataset_ut {
         // Check that the associated name is no reachable
         assert!(dataset.get_by_id(-52).is_err(), "still reachable by id");
     }
+
+    #[test]
+    fn merge_datasets() {
+        let first_sc = SpacecraftData {
+            name: "first".try_into().unwrap(),
+            mass_kg: Some(Mass::from_dry_and_fuel_masses(100.0, 0.0)),
+            ..Default::default()
+        };
+        let second_sc = SpacecraftData {
+            name: "second".try_into().unwrap(),
+            mass_kg: Some(Mass::from_dry_and_fuel_masses(200.0, 0.0)),
+            ..Default::default()
+        };
+
+        let mut first = DataSet::<SpacecraftData>::default();
+        first
+            .push(first_sc.clone(), Some(-10), Some("SC A"))
+            .unwrap();
+        first.push(first_sc.clone(), Some(-11), None).unwrap();
+        first.set_crc32();
+
+        let mut second = DataSet::<SpacecraftData>::default();
+        second
+            .push(second_sc.clone(), Some(-10), Some("SC A2"))
+            .unwrap();
+        second.push(second_sc.clone(), None, Some("SC B")).unwrap();
+
+        // Keeping the first entries only adds the new ones.
+        let mut merged = first.clone();
+        merged.merge(&second, MergePolicy::KeepFirst).unwrap();
+        assert_eq!(merged.get_by_id(-10).unwrap(), first_sc);
+        assert_eq!(merged.get_by_name("SC B").unwrap(), second_sc);
+        assert!(merged.get_by_name("SC A2").is_err());
+        assert!(merged.check_integrity().is_ok());
+
+        // The latest entries replace the conflicting ones, and are also reachable by their new names.
+        let mut merged = first.clone();
+        merged.merge(&second, MergePolicy::LatestWins).unwrap();
+        assert_eq!(merged.get_by_id(-10).unwrap(), second_sc);
+        assert_eq!(merged.get_by_name("SC A").unwrap(), second_sc);
+        assert_eq!(merged.get_by_name("SC A2").unwrap(), second_sc);
+        assert_eq!(merged.get_by_id(-11).unwrap(), first_sc);
+        assert_eq!(merged.data.len(), 3);
+        assert!(merged.check_integrity().is_ok());
+
+        // Conflicts may be errors, in which case the data set is not modified.
+        let mut merged = first.clone();
+        assert_eq!(
+            merged.merge(&second, MergePolicy::Error),
+            Err(DataSetError::MergeConflict {
+                kind: "spacecraft data",
+                key: "ID -10".to_string()
+            })
+        );
+        assert_eq!(merged, first);
+
+        // Merging into an empty data set is a copy.
+        let mut merged = DataSet::<SpacecraftData>::default();
+        merged.merge(&first, MergePolicy::Error).unwrap();
+        assert_eq!(merged, first);
+
+        // Data sets grow as needed.
+        let mut many = DataSet::<SpacecraftData>::default();
+        for id in 0..256 {
+            many.push(second_sc.clone(), Some(-1000 - id), None)
+                .unwrap();
+        }
+        let mut merged = first.clone();
+        merged.merge(&many, MergePolicy::Error).unwrap();
+        assert_eq!(merged.lut.by_id.len(), 258);
+        assert_eq!(merged.get_by_id(-1255).unwrap(), second_sc);
+    }
 }
@@ -7,11 +7,10 @@
  *
  * Documentation: https://nyxspace.com/
  */
-use der::{
-    asn1::{OctetStringRef, SequenceOf},
-    Decode, Encode, Reader, Writer,
-};
-use heapless::{FnvIndexMap, String};
+use der::{asn1::OctetStringRef, Decode, Encode, Reader, Writer};
+use fnv::FnvBuildHasher;
+use heapless::String;
+use indexmap::IndexMap;
 use log::warn;
 use snafu::prelude::*;
 
@@ -20,17 +19,12 @@ use crate::NaifId;
 /// Maximum length of a look up table name string
 pub const KEY_NAME_LEN: usize = 32;
 
+/// Insertion ordered map of a look up table, hashed with FNV like the fixed size maps of `heapless`.
+pub type LutMap<K> = IndexMap<K, u32, FnvBuildHasher>;
+
 #[derive(Debug, Snafu, PartialEq)]
 #[snafu(visibility(pub(crate)))]
 pub enum LutError {
-    #[snafu(display(
-        "ID LUT is full with all {max_slots} taken (increase ENTRIES at build time)"
-    ))]
-    IdLutFull { max_slots: usize },
-    #[snafu(display(
-        "Names LUT is full with all {max_slots} taken (increase ENTRIES at build time)"
-    ))]
-    NameLutFull { max_slots: usize },
     #[snafu(display("must provide either an ID or a name for a loop up, but provided neither"))]
     NoKeyProvided,
     #[snafu(display("ID {id} not in look up table"))]
@@ -39,62 +33,69 @@ pub enum LutError {
     UnknownName { name: String<KEY_NAME_LEN> },
     #[snafu(display("Look up table index is not in dataset"))]
     InvalidIndex { index: u32 },
+    #[snafu(display(
+        "name `{name}` is longer than the {KEY_NAME_LEN} bytes of a look up table name"
+    ))]
+    NameTooLong { name: std::string::String },
+}
+
+/// Returns the look up table key of this name, or an error if the name is too long.
+pub(crate) fn lut_name(name: &str) -> Result<String<KEY_NAME_LEN>, LutError> {
+    name.try_into().map_err(|_| LutError::NameTooLong {
+        name: name.to_string(),
+    })
 }
 
 /// A LookUpTable allows finding the [Entry] associated with either an ID or a name.
 ///
+/// The table grows as entries are appended, so the number of entries is only limited by the available memory. The
+/// names are stored inline as fixed length strings, so looking up an entry does not allocate.
+///
 /// # Note
 /// _Both_ the IDs and the name MUST be unique in the look up table.
 #[derive(Clone, Default, Debug, PartialEq, Eq)]
-pub struct LookUpTable<const ENTRIES: usize> {
+pub struct LookUpTable {
     /// Unique IDs of each item in the
-    pub by_id: FnvIndexMap<NaifId, u32, ENTRIES>,
+    pub by_id: LutMap<NaifId>,
     /// Corresponding index for each hash
-    pub by_name: FnvIndexMap<String<32>, u32, ENTRIES>,
+    pub by_name: LutMap<String<KEY_NAME_LEN>>,
 }
 
-impl<const ENTRIES: usize> LookUpTable<ENTRIES> {
+impl LookUpTable {
+    /// Appends an entry with both an ID and a name.
+    ///
+    /// Neither index is modified if the name is invalid.
     pub fn append(&mut self, id: i32, name: &str, index: u32) -> Result<(), LutError> {
-        self.by_id
-            .insert(id, index)
-            .map_err(|_| LutError::IdLutFull { max_slots: ENTRIES })?;
-        self.by_name
-            .insert(name.try_into().unwrap(), index)
-            .map_err(|_| LutError::NameLutFull { max_slots: ENTRIES })?;
+        let name = lut_name(name)?;
+        self.by_id.insert(id, index);
+        self.by_name.insert(name, index);
         Ok(())
     }
 
     pub fn append_id(&mut self, id: i32, index: u32) -> Result<(), LutError> {
-        self.by_id
-            .insert(id, index)
-            .map_err(|_| LutError::IdLutFull { max_slots: ENTRIES })?;
+        self.by_id.insert(id, index);
         Ok(())
     }
 
     pub fn append_name(&mut self, name: &str, index: u32) -> Result<(), LutError> {
-        self.by_name
-            .insert(name.try_into().unwrap(), index)
-            .map_err(|_| LutError::NameLutFull { max_slots: ENTRIES })?;
+        self.by_name.insert(lut_name(name)?, index);
         Ok(())
     }
 
     /// Returns the list of entries of this LUT
-    pub fn entries(&self) -> FnvIndexMap<u32, (Option<NaifId>, Option<String<32>>), ENTRIES> {
-        let mut rtn = FnvIndexMap::default();
+    pub fn entries(
+        &self,
+    ) -> IndexMap<u32, (Option<NaifId>, Option<String<KEY_NAME_LEN>>), FnvBuildHasher> {
+        let mut rtn = IndexMap::default();
 
         for (id, entry) in &self.by_id {
             // IDs are unique, and this is the first iteration, so we can't be overwriting anything
-            rtn.insert(*entry, (Some(*id), None)).unwrap();
+            rtn.insert(*entry, (Some(*id), None));
         }
 
         // Now map to the names
         for (name, entry) in &self.by_name {
-            if !rtn.contains_key(entry) {
-                rtn.insert(*entry, (None, Some(name.clone()))).unwrap();
-            } else {
-                let val = rtn.get_mut(entry).unwrap();
-                val.1 = Some(name.clone());
-            }
+            rtn.entry(*entry).or_insert((None, None)).1 = Some(name.clone());
         }
 
         rtn
@@ -105,8 +106,7 @@ impl<const ENTRIES: usize> LookUpTable<ENTRIES> {
     /// This will return an error if the current ID is not in the LUT, or if the new ID is already in the LUT.
     pub fn reid(&mut self, current_id: i32, new_id: i32) -> Result<(), LutError> {
         if let Some(entry) = self.by_id.swap_remove(&current_id) {
-            // We can unwrap the insertion because we just removed something.
-            self.by_id.insert(new_id, entry).unwrap();
+            self.by_id.insert(new_id, entry);
             Ok(())
         } else {
             Err(LutError::UnknownId { id: current_id })
@@ -117,7 +117,7 @@ impl<const ENTRIES: usize> LookUpTable<ENTRIES> {
     ///
     /// If this item was inserted with a name, it will rename accessible by the name.
     pub fn rmid(&mut self, id: i32) -> Result<(), LutError> {
-        if self.by_id.remove(&id).is_none() {
+        if self.by_id.swap_remove(&id).is_none() {
             Err(LutError::UnknownId { id })
         } else {
             Ok(())
@@ -128,16 +128,13 @@ impl<const ENTRIES: usize> LookUpTable<ENTRIES> {
     ///
     /// This will return an error if the current ID is not in the LUT, or if the new ID is already in the LUT.
     pub fn rename(&mut self, current_name: &str, new_name: &str) -> Result<(), LutError> {
-        if let Some(entry) = self.by_name.swap_remove(&current_name.try_into().unwrap()) {
-            // We can unwrap the insertion because we just removed something.
-            self.by_name
-                .insert(new_name.try_into().unwrap(), entry)
-                .unwrap();
+        let current_name = lut_name(current_name)?;
+        let new_name = lut_name(new_name)?;
+        if let Some(entry) = self.by_name.swap_remove(&current_name) {
+            self.by_name.insert(new_name, entry);
             Ok(())
         } else {
-            Err(LutError::UnknownName {
-                name: current_name.try_into().unwrap(),
-            })
+            Err(LutError::UnknownName { name: current_name })
         }
     }
 
@@ -145,10 +142,9 @@ impl<const ENTRIES: usize> LookUpTable<ENTRIES> {
     ///
     /// If this item was inserted with a name, it will rename accessible by the name.
     pub fn rmname(&mut self, name: &str) -> Result<(), LutError> {
-        if self.by_name.remove(&name.try_into().unwrap()).is_none() {
-            Err(LutError::UnknownName {
-                name: name.try_into().unwrap(),
-            })
+        let name = lut_name(name)?;
+        if self.by_name.swap_remove(&name).is_none() {
+            Err(LutError::UnknownName { name })
         } else {
             Ok(())
         }
@@ -188,39 +184,21 @@ impl<const ENTRIES: usize> LookUpTable<ENTRIES> {
     ///
     /// # Note
     /// The list of entries might be duplicated if all items have both a name and an ID.
-    fn der_encoding(
-        &self,
-    ) -> (
-        SequenceOf<i32, ENTRIES>,
-        SequenceOf<u32, ENTRIES>,
-        SequenceOf<OctetStringRef, ENTRIES>,
-        SequenceOf<u32, ENTRIES>,
-    ) {
-        // Build the list of entries
-        let mut id_entries = SequenceOf::<u32, ENTRIES>::new();
-        let mut name_entries = SequenceOf::<u32, ENTRIES>::new();
-
-        // Build the list of keys
-        let mut ids = SequenceOf::<i32, ENTRIES>::new();
-        for (id, index) in &self.by_id {
-            ids.add(*id).unwrap();
-            id_entries.add(*index).unwrap();
-        }
-        // Build the list of names
-        let mut names = SequenceOf::<OctetStringRef, ENTRIES>::new();
-        for (name, index) in &self.by_name {
-            names
-                .add(OctetStringRef::new(name.as_bytes()).unwrap())
-                .unwrap();
-
-            name_entries.add(*index).unwrap();
-        }
+    fn der_encoding(&self) -> (Vec<i32>, Vec<u32>, Vec<OctetStringRef<'_>>, Vec<u32>) {
+        let ids = self.by_id.keys().copied().collect();
+        let id_entries = self.by_id.values().copied().collect();
+        let names = self
+            .by_name
+            .keys()
+            .map(|name| OctetStringRef::new(name.as_bytes()).unwrap())
+            .collect();
+        let name_entries = self.by_name.values().copied().collect();
 
         (ids, id_entries, names, name_entries)
     }
 }
 
-impl<const ENTRIES: usize> Encode for LookUpTable<ENTRIES> {
+impl Encode for LookUpTable {
     fn encoded_len(&self) -> der::Result<der::Length> {
         let (ids, names, id_entries, name_entries) = self.der_encoding();
         ids.encoded_len()?
@@ -238,27 +216,25 @@ impl<const ENTRIES: usize> Encode for LookUpTable<ENTRIES> {
     }
 }
 
-impl<'a, const ENTRIES: usize> Decode<'a> for LookUpTable<ENTRIES> {
+impl<'a> Decode<'a> for LookUpTable {
     fn decode<R: Reader<'a>>(decoder: &mut R) -> der::Result<Self> {
         // Decode as sequences and use that to build the look up table.
         let mut lut = Self::default();
-        let ids: SequenceOf<i32, ENTRIES> = decoder.decode()?;
-        let id_entries: SequenceOf<u32, ENTRIES> = decoder.decode()?;
-        let names: SequenceOf<OctetStringRef, ENTRIES> = decoder.decode()?;
-        let name_entries: SequenceOf<u32, ENTRIES> = decoder.decode()?;
+        let ids: Vec<i32> = decoder.decode()?;
+        let id_entries: Vec<u32> = decoder.decode()?;
+        let names: Vec<OctetStringRef> = decoder.decode()?;
+        let name_entries: Vec<u32> = decoder.decode()?;
 
         for (id, index) in ids.iter().zip(id_entries.iter()) {
-            lut.by_id.insert(*id, *index).unwrap();
+            lut.by_id.insert(*id, *index);
         }
 
         for (name, entry) in names.iter().zip(name_entries.iter()) {
             let key = core::str::from_utf8(name.as_bytes()).unwrap();
-            lut.by_name
-                .insert(
-                    key[..KEY_NAME_LEN.min(key.len())].try_into().unwrap(),
-                    *entry,
-                )
-                .unwrap();
+            lut.by_name.insert(
+                key[..KEY_NAME_LEN.min(key.len())].try_into().unwrap(),
+                *entry,
+            );
         }
 
         if !lut.check_integrity() {
@@ -274,10 +250,10 @@ impl<'a, const ENTRIES: usize> Decode<'a> for LookUpTable<ENTRIES> {
 
 #[cfg(test)]
 mod lut_ut {
-    use super::{Decode, Encode, LookUpTable};
+    use super::{Decode, Encode, LookUpTable, LutError};
     #[test]
     fn zero_repr() {
-        let repr = LookUpTable::<2>::default();
+        let repr = LookUpTable::default();
 
         let mut buf = vec![];
         repr.encode_to_vec(&mut buf).unwrap();
@@ -287,13 +263,14 @@ mod lut_ut {
         assert_eq!(repr, repr_dec);
 
         dbg!(repr);
-        assert_eq!(core::mem::size_of::<LookUpTable<64>>(), 4368);
+        assert_eq!(core::mem::size_of::<LookUpTable>(), 112);
     }
 
     #[test]
     fn repr_ids_only() {
-        let mut repr = LookUpTable::<32>::default();
-        for i in 0..32 {
+        // Look up tables are not limited to a fixed number of entries.
+        let mut repr = LookUpTable::default();
+        for i in 0..1024 {
             let id = -20 - i;
             repr.append_id(id, 0).unwrap();
         }
@@ -308,10 +285,10 @@ mod lut_ut {
 
     #[test]
     fn repr_names_only() {
-        const LUT_SIZE: usize = 32;
+        const LUT_SIZE: usize = 256;
         // Create a vector to store the strings and declare it before repr for borrow checker
         let mut names = Vec::new();
-        let mut repr = LookUpTable::<LUT_SIZE>::default();
+        let mut repr = LookUpTable::default();
 
         for i in 0..LUT_SIZE {
             names.push(format!("Name{}", i));
@@ -331,7 +308,7 @@ mod lut_ut {
 
     #[test]
     fn test_integrity_checker() {
-        let mut lut = LookUpTable::<8>::default();
+        let mut lut = LookUpTable::default();
         assert!(lut.check_integrity()); // Empty, passes
 
         lut.append(1, "a", 0).unwrap();
@@ -346,4 +323,24 @@ mod lut_ut {
         lut.append_name("b", 11).unwrap();
         assert!(lut.check_integrity()); // Name added, passes
     }
+
+    #[test]
+    fn name_too_long() {
+        let mut lut = LookUpTable::default();
+        let long_name = "a name that is longer than thirty two bytes";
+        assert_eq!(
+            lut.append(1, long_name, 0),
+            Err(LutError::NameTooLong {
+                name: long_name.to_string()
+            })
+        );
+        // Neither index is modified.
+        assert!(lut.is_empty());
+        assert_eq!(
+            lut.append_name(long_name, 0),
+            Err(LutError::NameTooLong {
+                name: long_name.to_string()
+            })
+        );
+    }
 }
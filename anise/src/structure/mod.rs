@@ -22,10 +22,7 @@ pub mod spacecraft;
 use self::{
     dataset::DataSet, planetocentric::PlanetaryData, semver::Semver, spacecraft::SpacecraftData,
 };
-use crate::{
-    almanac::{MAX_PLANETARY_DATA, MAX_SPACECRAFT_DATA},
-    math::rotation::Quaternion,
-};
+use crate::{math::rotation::Quaternion, naif::sclk::SCLK};
 
 /// The current version of ANISE
 pub const ANISE_VERSION: Semver = Semver {
@@ -35,8 +32,10 @@ pub const ANISE_VERSION: Semver = Semver {
 };
 
 /// Spacecraft Data Set allow mapping an ID and/or name to spacecraft data, optionally including mass, drag, SRP, an inertia information
-pub type SpacecraftDataSet = DataSet<SpacecraftData, MAX_SPACECRAFT_DATA>;
+pub type SpacecraftDataSet = DataSet<SpacecraftData>;
 /// Planetary Data Set allow mapping an ID and/or name to planetary data, optionally including shape information and rotation information
-pub type PlanetaryDataSet = DataSet<PlanetaryData, MAX_PLANETARY_DATA>;
+pub type PlanetaryDataSet = DataSet<PlanetaryData>;
 /// Euler Parameter Data Set allow mapping an ID and/or name to a time invariant Quaternion
-pub type EulerParameterDataSet = DataSet<Quaternion, MAX_PLANETARY_DATA>;
+pub type EulerParameterDataSet = DataSet<Quaternion>;
+/// SCLK Data Set allow mapping the ID of a spacecraft clock to its partitions and coefficients, to convert clock readings to epochs
+pub type SCLKDataSet = DataSet<SCLK>;
@@ -13,6 +13,8 @@ use der::{Decode, Encode, Reader, Writer};
 use serde_derive::{Deserialize, Serialize};
 use serde_dhall::StaticType;
 
+use crate::math::Vector3;
+
 #[cfg(feature = "python")]
 use pyo3::exceptions::PyTypeError;
 #[cfg(feature = "python")]
@@ -58,6 +60,20 @@ impl Ellipsoid {
             polar_radius_km,
         }
     }
+
+    /// Returns the radius of the disk with the same area as the silhouette of this ellipsoid seen from far along
+    /// this line of sight, expressed in the body fixed frame of the ellipsoid.
+    pub fn equivalent_radius_km(&self, line_of_sight: Vector3) -> f64 {
+        let (a, b, c) = (
+            self.semi_major_equatorial_radius_km,
+            self.semi_minor_equatorial_radius_km,
+            self.polar_radius_km,
+        );
+        let u = line_of_sight.normalize();
+        // The orthographic projection of the ellipsoid is an ellipse of area π a b c ‖diag(1/a, 1/b, 1/c) u‖.
+        let scaled = Vector3::new(u.x / a, u.y / b, u.z / c);
+        (a * b * c * scaled.norm()).sqrt()
+    }
 }
 
 #[cfg_attr(feature = "python", pymethods)]
@@ -1,7 +1,7 @@
 // Start by creating the ANISE planetary data
 use anise::{
     constants::frames::{EARTH_ITRF93, EARTH_J2000},
-    naif::kpl::parser::convert_tpc,
+    naif::kpl::parser::{convert_fk, convert_tpc},
     prelude::{Aberration, Almanac, Orbit, BPC, SPK},
 };
 use core::str::FromStr;
@@ -77,3 +77,97 @@ fn test_state_transformation() {
 
     assert_eq!(orig_state, from_state_itrf93_to_eme2k);
 }
+
+#[test]
+fn test_load_text_kernels() {
+    let gm_kernel = "KPL/PCK
+
+\\begindata
+BODY399_GM = ( 3.9860043543609598E+05 )
+BODY301_GM = ( 4.9028000661637961E+03 )
+BODY10_GM = ( 1.3271244004193938E+11 )
+\\begintext
+";
+    let pck_kernel = "KPL/PCK
+
+\\begindata
+BODY399_POLE_RA = (    0.      -0.641         0. )
+BODY399_POLE_DEC = (  +90.      -0.557         0. )
+BODY399_PM = (  190.147  +360.9856235     0. )
+BODY399_LONG_AXIS = (    0.                    )
+BODY399_RADII = ( 6378.1366   6378.1366   6356.7519 )
+BODY301_RADII = ( 1737.4   1737.4   1737.4 )
+BODY599_RADII = ( 71492   71492   66854 )
+\\begintext
+";
+    let gm_path = "../target/test-load-gm.tpc";
+    let pck_path = "../target/test-load-pck.tpc";
+    std::fs::write(gm_path, gm_kernel).unwrap();
+    std::fs::write(pck_path, pck_kernel).unwrap();
+    let dataset = convert_tpc(pck_path, gm_path).unwrap();
+    assert_eq!(dataset.len(), 2);
+
+    // The gravity parameters and the orientations may be loaded in any order.
+    for kernels in [[gm_path, pck_path], [pck_path, gm_path]] {
+        let almanac = Almanac::new(kernels[0]).unwrap().load(kernels[1]).unwrap();
+
+        for id in [399, 301] {
+            assert_eq!(
+                almanac.planetary_data.get_by_id(id).unwrap(),
+                dataset.get_by_id(id).unwrap(),
+                "{id} from {kernels:?}"
+            );
+        }
+        // Contrary to the conversion, bodies with only a gravity parameter or only a shape are loaded.
+        assert_eq!(
+            almanac.planetary_data.get_by_id(10).unwrap().mu_km3_s2,
+            1.3271244004193938E+11
+        );
+        assert!(almanac
+            .planetary_data
+            .get_by_id(599)
+            .unwrap()
+            .shape
+            .is_some());
+        almanac.planetary_data.check_integrity().unwrap();
+    }
+
+    // The NAIF kernels define more bodies than the planetary data used to hold.
+    let dataset = convert_tpc("../data/pck00008.tpc", "../data/gm_de431.tpc").unwrap();
+    let almanac = Almanac::new("../data/gm_de431.tpc")
+        .unwrap()
+        .load("../data/pck00008.tpc")
+        .unwrap();
+    assert!(almanac.planetary_data.len() > 64);
+    for id in dataset.lut.by_id.keys() {
+        assert_eq!(
+            almanac.planetary_data.get_by_id(*id).unwrap(),
+            dataset.get_by_id(*id).unwrap(),
+            "{id}"
+        );
+    }
+
+    let fk_dataset = convert_fk("../data/moon_080317.txt", false).unwrap();
+    let almanac = Almanac::new("../data/moon_080317.txt").unwrap();
+    assert_eq!(almanac.euler_param_data.len(), fk_dataset.len());
+    for id in fk_dataset.lut.by_id.keys() {
+        assert_eq!(
+            almanac.euler_param_data.get_by_id(*id).unwrap(),
+            fk_dataset.get_by_id(*id).unwrap()
+        );
+    }
+
+    // Loading the same frame kernel again replaces its frames.
+    let reloaded = almanac.load("../data/moon_080317.txt").unwrap();
+    assert_eq!(reloaded.euler_param_data.len(), fk_dataset.len());
+
+    // Text kernels that cannot be converted are reported.
+    let err = Almanac::default()
+        .load_text_kernel(b"KPL/PCK\n\\begindata\nBODY399_POLE_RA = ( 0. -0.641 0. )\n")
+        .err()
+        .unwrap();
+    assert_eq!(
+        err.to_string(),
+        "data set conversion error: no pole declination for TPC body 399 encountered when converting KPL/PCK text kernel"
+    );
+}
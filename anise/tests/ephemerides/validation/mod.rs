@@ -8,9 +8,14 @@
  * Documentation: https://nyxspace.com/
  */
 
+mod type01_type21_mda;
 mod type02_chebyshev_jpl_de;
+mod type08_lagrange;
 mod type09_lagrange;
+mod type12_hermite;
 mod type13_hermite;
+mod type15_type17_conics;
+mod type18_type19_esoc;
 
 mod compare;
 mod validate;
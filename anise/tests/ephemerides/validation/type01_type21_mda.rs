@@ -0,0 +1,116 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use super::{compare::*, validate::Validation};
+use anise::naif::{
+    daf::{datatypes::MDASetType21, NAIFDataSet},
+    spk::{builder::SPKBuilder, summary::SPKSummaryRecord},
+};
+use hifitime::Epoch;
+
+/// Number of records of the generated Type 21 segment, each valid for a day.
+const NUM_RECORDS: usize = 30;
+/// Dimension of the difference arrays, larger than the maximum of Type 1.
+const MAX_DIM: usize = 20;
+const RECORD_DURATION_S: f64 = 86_400.0;
+const STEP_S: f64 = 3_600.0;
+
+const POS_KM: [f64; 3] = [384_400.0, -12_000.0, 35_000.0];
+const VEL_KM_S: [f64; 3] = [-0.01, 1.02, 0.1];
+const ACC_KM_S2: [f64; 3] = [-2.6e-6, 1.0e-7, -2.4e-7];
+const JERK_KM_S3: [f64; 3] = [1.0e-13, -6.5e-12, -6.0e-13];
+
+/// Builds the record of a motion with a constant jerk, referenced at `ref_et_s` seconds past the start of the motion.
+///
+/// The modified difference arrays of such a motion have integration order two: the acceleration at the reference
+/// epoch, and the jerk scaled by the first step size.
+fn cubic_record(start_et_s: f64, ref_et_s: f64) -> Vec<f64> {
+    let mut record = vec![start_et_s + ref_et_s];
+    record.extend((0..MAX_DIM).map(|i| STEP_S * (i + 1) as f64));
+    for i in 0..3 {
+        record.push(
+            POS_KM[i]
+                + VEL_KM_S[i] * ref_et_s
+                + ACC_KM_S2[i] * ref_et_s.powi(2) / 2.0
+                + JERK_KM_S3[i] * ref_et_s.powi(3) / 6.0,
+        );
+        record.push(VEL_KM_S[i] + ACC_KM_S2[i] * ref_et_s + JERK_KM_S3[i] * ref_et_s.powi(2) / 2.0);
+    }
+    for i in 0..3 {
+        let mut diffs = vec![0.0; MAX_DIM];
+        diffs[0] = ACC_KM_S2[i] + JERK_KM_S3[i] * ref_et_s;
+        diffs[1] = JERK_KM_S3[i] * STEP_S;
+        record.extend(diffs);
+    }
+    record.extend_from_slice(&[3.0, 2.0, 2.0, 2.0]);
+    record
+}
+
+/// Validates the modified difference arrays of Type 21 against SPICE, on an SPK written by ANISE since SPICE cannot
+/// write this data type.
+#[ignore = "Requires Rust SPICE -- must be executed serially"]
+#[test]
+fn validate_mda_type21() {
+    let path = "../target/spk-type21-mda.bsp";
+    let _ = std::fs::remove_file(path);
+
+    let start_et_s = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1).to_et_seconds();
+
+    // Each record is referenced at the middle of its day, and valid until the end of that day.
+    let mut data = Vec::new();
+    for rec_no in 0..NUM_RECORDS {
+        data.extend(cubic_record(
+            start_et_s,
+            (rec_no as f64 + 0.5) * RECORD_DURATION_S,
+        ));
+    }
+    data.extend((1..=NUM_RECORDS).map(|rec_no| start_et_s + rec_no as f64 * RECORD_DURATION_S));
+    data.extend_from_slice(&[MAX_DIM as f64, NUM_RECORDS as f64]);
+
+    let dataset = MDASetType21::from_f64_slice(&data).unwrap();
+    dataset.check_integrity().unwrap();
+
+    let mut builder = SPKBuilder::new("ANISE Type 21 validation");
+    builder
+        .add_segment(
+            "MDA with constant jerk",
+            SPKSummaryRecord {
+                start_epoch_et_s: start_et_s,
+                end_epoch_et_s: start_et_s + NUM_RECORDS as f64 * RECORD_DURATION_S,
+                target_id: -1021,
+                center_id: 399,
+                frame_id: 1,
+                data_type_i: 21,
+                ..Default::default()
+            },
+            &dataset,
+        )
+        .unwrap();
+    builder.build().unwrap().persist(path).unwrap();
+
+    let file_name = "spk-type21-validation-mda".to_string();
+    let comparator = CompareEphem::new(vec![path.to_string()], file_name.clone(), 10_000, None);
+
+    let err_count = comparator.run();
+
+    assert_eq!(err_count, 0, "None of the queries should fail!");
+
+    // The MDA evaluation is the same algorithm as SPICE, so only the order of operations may differ.
+    let validator = Validation {
+        file_name,
+        max_q75_err: 1e-8,
+        max_q99_err: 5e-8,
+        max_abs_err: 1e-7,
+    };
+
+    validator.validate();
+
+    spice::unload(path);
+}
@@ -0,0 +1,98 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use super::{compare::*, validate::Validation};
+use hifitime::Epoch;
+use spice::cstr;
+
+/// Number of equally spaced states written in the equal step validation files.
+pub(super) const NUM_STATES: usize = 720;
+/// Step between each of these states, in seconds.
+pub(super) const STEP_S: f64 = 3600.0;
+
+/// Samples the Moon with respect to the Earth from DE440s with SPICE, to write equal step SPKs.
+pub(super) fn de440s_moon_states() -> (f64, Vec<[f64; 6]>) {
+    let de440s = "../data/de440s.bsp";
+    spice::furnsh(de440s);
+
+    let start_et_s = Epoch::from_gregorian_utc_at_midnight(2020, 1, 1).to_et_seconds();
+
+    let states = (0..NUM_STATES)
+        .map(|i| {
+            spice::spkezr(
+                "MOON",
+                start_et_s + (i as f64) * STEP_S,
+                "J2000",
+                "NONE",
+                "EARTH",
+            )
+            .0
+        })
+        .collect();
+
+    // Make sure that only the file under test is used by SPICE
+    spice::unload(de440s);
+
+    (start_et_s, states)
+}
+
+#[ignore = "Requires Rust SPICE -- must be executed serially"]
+#[test]
+fn validate_lagrange_type8_equal_steps() {
+    let path = "../target/spk-type8-moon.bsp";
+    let _ = std::fs::remove_file(path);
+
+    let (start_et_s, states) = de440s_moon_states();
+    let end_et_s = start_et_s + ((NUM_STATES - 1) as f64) * STEP_S;
+
+    // Write a Type 8 SPK with SPICE
+    let mut handle = 0;
+    unsafe {
+        spice::c::spkopn_c(
+            cstr!(path),
+            cstr!("ANISE Type 8 validation"),
+            0,
+            &mut handle,
+        );
+        spice::c::spkw08_c(
+            handle,
+            301,
+            399,
+            cstr!("J2000"),
+            start_et_s,
+            end_et_s,
+            cstr!("Moon Lagrange Type 8"),
+            7,
+            NUM_STATES as i32,
+            states.as_ptr(),
+            start_et_s,
+            STEP_S,
+        );
+        spice::c::spkcls_c(handle);
+    }
+
+    let file_name = "spk-type8-validation-equal-steps".to_string();
+    let comparator = CompareEphem::new(vec![path.to_string()], file_name.clone(), 10_000, None);
+
+    let err_count = comparator.run();
+
+    assert_eq!(err_count, 0, "None of the queries should fail!");
+
+    let validator = Validation {
+        file_name,
+        max_q75_err: 5e-9,
+        max_q99_err: 2e-8,
+        max_abs_err: 1e-7,
+    };
+
+    validator.validate();
+
+    spice::unload(path);
+}
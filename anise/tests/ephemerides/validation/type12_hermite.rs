@@ -0,0 +1,70 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use super::{
+    compare::*,
+    type08_lagrange::{de440s_moon_states, NUM_STATES, STEP_S},
+    validate::Validation,
+};
+use spice::cstr;
+
+#[ignore = "Requires Rust SPICE -- must be executed serially"]
+#[test]
+fn validate_hermite_type12_equal_steps() {
+    let path = "../target/spk-type12-moon.bsp";
+    let _ = std::fs::remove_file(path);
+
+    let (start_et_s, states) = de440s_moon_states();
+    let end_et_s = start_et_s + ((NUM_STATES - 1) as f64) * STEP_S;
+
+    // Write a Type 12 SPK with SPICE: a Hermite polynomial of degree 7 uses a window of four states.
+    let mut handle = 0;
+    unsafe {
+        spice::c::spkopn_c(
+            cstr!(path),
+            cstr!("ANISE Type 12 validation"),
+            0,
+            &mut handle,
+        );
+        spice::c::spkw12_c(
+            handle,
+            301,
+            399,
+            cstr!("J2000"),
+            start_et_s,
+            end_et_s,
+            cstr!("Moon Hermite Type 12"),
+            7,
+            NUM_STATES as i32,
+            states.as_ptr(),
+            start_et_s,
+            STEP_S,
+        );
+        spice::c::spkcls_c(handle);
+    }
+
+    let file_name = "spk-type12-validation-equal-steps".to_string();
+    let comparator = CompareEphem::new(vec![path.to_string()], file_name.clone(), 10_000, None);
+
+    let err_count = comparator.run();
+
+    assert_eq!(err_count, 0, "None of the queries should fail!");
+
+    let validator = Validation {
+        file_name,
+        max_q75_err: 5e-9,
+        max_q99_err: 2e-8,
+        max_abs_err: 1e-7,
+    };
+
+    validator.validate();
+
+    spice::unload(path);
+}
@@ -0,0 +1,188 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use super::{compare::*, validate::Validation};
+use core::f64::consts::FRAC_PI_2;
+use hifitime::Epoch;
+use spice::cstr;
+
+const GM_EARTH_KM3_S2: f64 = 398_600.435_436;
+const J2_EARTH: f64 = 1.08262668e-3;
+const RADIUS_EARTH_KM: f64 = 6378.1363;
+/// Duration of the conic segments, in seconds.
+const DURATION_S: f64 = 10.0 * 86_400.0;
+
+/// Returns the pole and the direction of periapsis of an orbit of these angles, in degrees.
+fn pole_and_periapsis(inc_deg: f64, raan_deg: f64, aop_deg: f64) -> ([f64; 3], [f64; 3]) {
+    let (sin_inc, cos_inc) = inc_deg.to_radians().sin_cos();
+    let (sin_raan, cos_raan) = raan_deg.to_radians().sin_cos();
+    let (sin_aop, cos_aop) = aop_deg.to_radians().sin_cos();
+    (
+        [sin_inc * sin_raan, -sin_inc * cos_raan, cos_inc],
+        [
+            cos_raan * cos_aop - sin_raan * sin_aop * cos_inc,
+            sin_raan * cos_aop + cos_raan * sin_aop * cos_inc,
+            sin_aop * sin_inc,
+        ],
+    )
+}
+
+#[ignore = "Requires Rust SPICE -- must be executed serially"]
+#[test]
+fn validate_precessing_conics_type15() {
+    let path = "../target/spk-type15-conics.bsp";
+    let _ = std::fs::remove_file(path);
+
+    let epoch_et_s = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1).to_et_seconds();
+    let earth_pole = [0.0, 0.0, 1.0];
+
+    // An elliptical orbit whose nodes regress and whose apsides precess, and a parabolic flyby.
+    let (elliptical_pole, elliptical_periapsis) = pole_and_periapsis(35.0, 40.0, 60.0);
+    let (parabolic_pole, parabolic_periapsis) = pole_and_periapsis(110.0, 200.0, 15.0);
+
+    let mut handle = 0;
+    unsafe {
+        spice::c::spkopn_c(
+            cstr!(path),
+            cstr!("ANISE Type 15 validation"),
+            0,
+            &mut handle,
+        );
+        spice::c::spkw15_c(
+            handle,
+            -1015,
+            399,
+            cstr!("J2000"),
+            epoch_et_s,
+            epoch_et_s + DURATION_S,
+            cstr!("Elliptical with J2"),
+            epoch_et_s,
+            elliptical_pole.as_ptr(),
+            elliptical_periapsis.as_ptr(),
+            8000.0 * (1.0 - 0.1_f64.powi(2)),
+            0.1,
+            0.0,
+            earth_pole.as_ptr(),
+            GM_EARTH_KM3_S2,
+            J2_EARTH,
+            RADIUS_EARTH_KM,
+        );
+        spice::c::spkw15_c(
+            handle,
+            -1016,
+            399,
+            cstr!("J2000"),
+            epoch_et_s,
+            epoch_et_s + DURATION_S,
+            cstr!("Parabolic flyby"),
+            epoch_et_s + 0.5 * DURATION_S,
+            parabolic_pole.as_ptr(),
+            parabolic_periapsis.as_ptr(),
+            20_000.0,
+            1.0,
+            0.0,
+            earth_pole.as_ptr(),
+            GM_EARTH_KM3_S2,
+            J2_EARTH,
+            RADIUS_EARTH_KM,
+        );
+        spice::c::spkcls_c(handle);
+    }
+
+    let file_name = "spk-type15-validation-conics".to_string();
+    let comparator = CompareEphem::new(vec![path.to_string()], file_name.clone(), 10_000, None);
+
+    let err_count = comparator.run();
+
+    assert_eq!(err_count, 0, "None of the queries should fail!");
+
+    // SPICE propagates the conic with universal variables, whereas ANISE solves the Kepler or Barker equations.
+    let validator = Validation {
+        file_name,
+        max_q75_err: 1e-7,
+        max_q99_err: 1e-6,
+        max_abs_err: 1e-5,
+    };
+
+    validator.validate();
+
+    spice::unload(path);
+}
+
+#[ignore = "Requires Rust SPICE -- must be executed serially"]
+#[test]
+fn validate_equinoctial_type17() {
+    let path = "../target/spk-type17-equinoctial.bsp";
+    let _ = std::fs::remove_file(path);
+
+    let epoch_et_s = Epoch::from_gregorian_utc_at_midnight(2024, 1, 1).to_et_seconds();
+
+    let sma_km: f64 = 8000.0;
+    let ecc = 0.1;
+    let (periapsis_longitude_rad, node_longitude_rad) =
+        (100.0_f64.to_radians(), 40.0_f64.to_radians());
+    let tan_half_inc = (35.0_f64.to_radians() / 2.0).tan();
+    let mean_motion_rad_s = (GM_EARTH_KM3_S2 / sma_km.powi(3)).sqrt();
+
+    let elements = [
+        sma_km,
+        ecc * periapsis_longitude_rad.sin(),
+        ecc * periapsis_longitude_rad.cos(),
+        periapsis_longitude_rad + 1.0,
+        tan_half_inc * node_longitude_rad.sin(),
+        tan_half_inc * node_longitude_rad.cos(),
+        1e-6,
+        mean_motion_rad_s + 1e-6,
+        -2e-6,
+    ];
+
+    let mut handle = 0;
+    unsafe {
+        spice::c::spkopn_c(
+            cstr!(path),
+            cstr!("ANISE Type 17 validation"),
+            0,
+            &mut handle,
+        );
+        // The reference plane is the ecliptic, seen from the Earth equator.
+        spice::c::spkw17_c(
+            handle,
+            -1017,
+            399,
+            cstr!("J2000"),
+            epoch_et_s - 0.5 * DURATION_S,
+            epoch_et_s + 0.5 * DURATION_S,
+            cstr!("Equinoctial with rates"),
+            epoch_et_s,
+            elements.as_ptr(),
+            -FRAC_PI_2,
+            FRAC_PI_2 - 23.4_f64.to_radians(),
+        );
+        spice::c::spkcls_c(handle);
+    }
+
+    let file_name = "spk-type17-validation-equinoctial".to_string();
+    let comparator = CompareEphem::new(vec![path.to_string()], file_name.clone(), 10_000, None);
+
+    let err_count = comparator.run();
+
+    assert_eq!(err_count, 0, "None of the queries should fail!");
+
+    let validator = Validation {
+        file_name,
+        max_q75_err: 1e-7,
+        max_q99_err: 1e-6,
+        max_abs_err: 1e-5,
+    };
+
+    validator.validate();
+
+    spice::unload(path);
+}
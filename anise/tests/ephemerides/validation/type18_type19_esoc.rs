@@ -0,0 +1,143 @@
+/*
+ * ANISE Toolkit
+ * Copyright (C) 2021-onward Christopher Rabotin <christopher.rabotin@gmail.com> et al. (cf. AUTHORS.md)
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ *
+ * Documentation: https://nyxspace.com/
+ */
+
+use super::{
+    compare::*,
+    type08_lagrange::{de440s_moon_states, NUM_STATES, STEP_S},
+    validate::Validation,
+};
+use spice::cstr;
+
+#[ignore = "Requires Rust SPICE -- must be executed serially"]
+#[test]
+fn validate_esoc_type18_lagrange() {
+    let path = "../target/spk-type18-moon.bsp";
+    let _ = std::fs::remove_file(path);
+
+    let (start_et_s, states) = de440s_moon_states();
+    let epochs: Vec<f64> = (0..NUM_STATES)
+        .map(|i| start_et_s + (i as f64) * STEP_S)
+        .collect();
+
+    // Write a Type 18 SPK of subtype 1 (Lagrange) with SPICE
+    let mut handle = 0;
+    unsafe {
+        spice::c::spkopn_c(
+            cstr!(path),
+            cstr!("ANISE Type 18 validation"),
+            0,
+            &mut handle,
+        );
+        spice::c::spkw18_c(
+            handle,
+            1,
+            301,
+            399,
+            cstr!("J2000"),
+            epochs[0],
+            epochs[NUM_STATES - 1],
+            cstr!("Moon ESOC Type 18"),
+            7,
+            NUM_STATES as i32,
+            states.as_ptr() as *const _,
+            epochs.as_ptr(),
+        );
+        spice::c::spkcls_c(handle);
+    }
+
+    let file_name = "spk-type18-validation-lagrange".to_string();
+    let comparator = CompareEphem::new(vec![path.to_string()], file_name.clone(), 10_000, None);
+
+    let err_count = comparator.run();
+
+    assert_eq!(err_count, 0, "None of the queries should fail!");
+
+    let validator = Validation {
+        file_name,
+        max_q75_err: 5e-9,
+        max_q99_err: 2e-8,
+        max_abs_err: 1e-7,
+    };
+
+    validator.validate();
+
+    spice::unload(path);
+}
+
+#[ignore = "Requires Rust SPICE -- must be executed serially"]
+#[test]
+fn validate_esoc_type19_minisegments() {
+    let path = "../target/spk-type19-moon.bsp";
+    let _ = std::fs::remove_file(path);
+
+    let (start_et_s, states) = de440s_moon_states();
+    let epochs: Vec<f64> = (0..NUM_STATES)
+        .map(|i| start_et_s + (i as f64) * STEP_S)
+        .collect();
+
+    // Two intervals sharing the middle state: a Lagrange mini-segment, then a six element Hermite mini-segment.
+    let mid = NUM_STATES / 2;
+    let npkts = [(mid + 1) as i32, (NUM_STATES - mid) as i32];
+    let subtypes = [1, 2];
+    let degrees = [7, 7];
+    let mut packets = Vec::with_capacity(6 * (NUM_STATES + 1));
+    let mut minisegment_epochs = Vec::with_capacity(NUM_STATES + 1);
+    for idx in (0..=mid).chain(mid..NUM_STATES) {
+        packets.extend_from_slice(&states[idx]);
+        minisegment_epochs.push(epochs[idx]);
+    }
+    let bounds = [epochs[0], epochs[mid], epochs[NUM_STATES - 1]];
+
+    let mut handle = 0;
+    unsafe {
+        spice::c::spkopn_c(
+            cstr!(path),
+            cstr!("ANISE Type 19 validation"),
+            0,
+            &mut handle,
+        );
+        spice::c::spkw19_c(
+            handle,
+            301,
+            399,
+            cstr!("J2000"),
+            bounds[0],
+            bounds[2],
+            cstr!("Moon ESOC Type 19"),
+            2,
+            npkts.as_ptr(),
+            subtypes.as_ptr(),
+            degrees.as_ptr(),
+            packets.as_ptr(),
+            minisegment_epochs.as_ptr(),
+            bounds.as_ptr(),
+            1,
+        );
+        spice::c::spkcls_c(handle);
+    }
+
+    let file_name = "spk-type19-validation-minisegments".to_string();
+    let comparator = CompareEphem::new(vec![path.to_string()], file_name.clone(), 10_000, None);
+
+    let err_count = comparator.run();
+
+    assert_eq!(err_count, 0, "None of the queries should fail!");
+
+    let validator = Validation {
+        file_name,
+        max_q75_err: 5e-9,
+        max_q99_err: 2e-8,
+        max_abs_err: 1e-7,
+    };
+
+    validator.validate();
+
+    spice::unload(path);
+}
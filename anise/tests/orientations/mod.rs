@@ -2,9 +2,12 @@ use std::path::PathBuf;
 
 use anise::constants::frames::{
     EARTH_ITRF93, EME2000, IAU_JUPITER_FRAME, IAU_MOON_FRAME, JUPITER_BARYCENTER_J2000, MOON_J2000,
+    MOON_ME_FRAME, MOON_PA_FRAME,
 };
-use anise::constants::orientations::{ECLIPJ2000, IAU_JUPITER, IAU_MOON, ITRF93, J2000};
-use anise::math::rotation::DCM;
+use anise::constants::orientations::{
+    ECLIPJ2000, IAU_JUPITER, IAU_MOON, ITRF93, J2000, MOON_ME, MOON_PA,
+};
+use anise::math::rotation::{r1, r2, r3, Quaternion, DCM};
 use anise::math::Matrix3;
 use anise::naif::kpl::parser::convert_tpc;
 
@@ -321,3 +324,54 @@ fn regression_test_issue_112_test_iau_jupiter() {
         dcm.rot_mat - spice_dcm.rot_mat
     );
 }
+
+#[test]
+fn test_moon_fk_rotations() {
+    use core::str::FromStr;
+
+    // The Moon FK only defines fixed frames, all relative to the MOON_PA_DE421 BPC frame.
+    let almanac = Almanac::new("../data/moon_fk.epa").unwrap();
+
+    assert_eq!(almanac.try_find_orientation_root(), Ok(31006));
+
+    let epoch = Epoch::from_str("2030-01-01 00:00:00").unwrap();
+
+    let dcm = almanac
+        .rotate_from_to(MOON_ME_FRAME, MOON_PA_FRAME, epoch)
+        .unwrap();
+
+    assert_eq!(dcm.from, MOON_ME);
+    assert_eq!(dcm.to, MOON_PA);
+
+    // TKFRAME_31007_ANGLES = (67.92   78.56   0.30 ) in arcseconds, about axes 3, 2, 1: this rotates from MOON_PA_DE421 into MOON_ME_DE421.
+    let pa_to_me = r1((0.30 / 3600.0_f64).to_radians())
+        * r2((78.56 / 3600.0_f64).to_radians())
+        * r3((67.92 / 3600.0_f64).to_radians());
+
+    assert!(
+        (dcm.rot_mat - pa_to_me.transpose()).norm() < 1e-10,
+        "dcm error! got: {}want:{}",
+        dcm.rot_mat,
+        pa_to_me.transpose()
+    );
+
+    // Fixed frames do not rotate with respect to one another.
+    assert!(dcm.rot_mat_dt.is_none());
+
+    // From the FK: "For DE-421, the rotation angle of this matrix is approximately 0.0288473 degrees"
+    let (_, angle_rad) = Quaternion::from(dcm).uvec_angle();
+    assert!((angle_rad.to_degrees() - 0.0288473).abs() < 1e-7);
+
+    // And the reverse rotation is its transpose.
+    let rev_dcm = almanac
+        .rotate_from_to(MOON_PA_FRAME, MOON_ME_FRAME, epoch)
+        .unwrap();
+    assert_eq!(rev_dcm.from, MOON_PA);
+    assert_eq!(rev_dcm.to, MOON_ME);
+    assert!((rev_dcm.rot_mat - pa_to_me).norm() < 1e-10);
+
+    // But there is no path to J2000 without the BPC.
+    assert!(almanac
+        .rotate_from_to(MOON_ME_FRAME, MOON_J2000, epoch)
+        .is_err());
+}
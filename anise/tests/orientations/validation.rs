@@ -19,7 +19,7 @@ use anise::{
         rotation::{Quaternion, DCM},
         Matrix3, Vector3,
     },
-    naif::kpl::parser::convert_tpc,
+    naif::{daf::NAIFSummaryRecord, kpl::parser::convert_tpc, pck::BPCSummaryRecord},
     prelude::{Almanac, Frame, BPC},
 };
 use hifitime::{Duration, Epoch, TimeSeries, TimeUnits};
@@ -538,6 +538,115 @@ fn validate_bpc_rotations() {
     println!("actualized max error in rotation direction = {actual_max_uvec_err_deg:.3e} deg");
 }
 
+#[ignore = "Requires Rust SPICE -- must be executed serially"]
+#[test]
+fn validate_bpc_type3_rotations() {
+    // There is no public Type 3 BPC, so the Chebyshev Type 2 segments of the Earth BPC are converted to Type 3, whose
+    // rates are stored as the coefficients of the derivatives of the angle polynomials.
+    let type2_path = "../data/earth_latest_high_prec.bpc";
+    let type3_path = "../target/earth_latest_high_prec_type3.bpc";
+
+    let type2 = BPC::load(type2_path).unwrap();
+    let segments: Vec<(BPCSummaryRecord, Vec<f64>)> = type2
+        .data_summaries()
+        .unwrap()
+        .iter()
+        .enumerate()
+        .filter(|(_, summary)| !summary.is_empty())
+        .map(|(idx, summary)| (*summary, type2_to_type3(type2.nth_data_f64(idx).unwrap())))
+        .collect();
+
+    let mut daf = type2.to_mutable();
+    for idx in (0..segments.len()).rev() {
+        daf.delete_nth_data(idx).unwrap();
+    }
+    for (sno, (mut summary, data)) in segments.into_iter().enumerate() {
+        summary.data_type_i = 3;
+        daf.append_data(summary, &format!("ITRF93 TYPE 3 #{sno}"), &data)
+            .unwrap();
+    }
+    daf.persist(type3_path).unwrap();
+
+    // The converted BPC is loaded last, so SPICE uses it over the Type 2 BPC loaded by the other tests.
+    spice::furnsh(type3_path);
+    let almanac = Almanac::from_bpc(BPC::load(type3_path).unwrap()).unwrap();
+    assert_eq!(
+        almanac.bpc_data[0].data_summaries().unwrap()[0].data_type_i,
+        3
+    );
+
+    for (num, epoch) in TimeSeries::inclusive(
+        Epoch::from_tdb_duration(0.11.centuries()),
+        Epoch::from_tdb_duration(0.2.centuries()),
+        1.days(),
+    )
+    .enumerate()
+    {
+        let dcm = almanac
+            .rotate_from_to(EARTH_ITRF93, EME2000, epoch)
+            .unwrap();
+
+        let mut rot_data: [[f64; 6]; 6] = [[0.0; 6]; 6];
+        unsafe {
+            spice::c::sxform_c(
+                cstr!("ITRF93"),
+                cstr!("J2000"),
+                epoch.to_tdb_seconds(),
+                rot_data.as_mut_ptr(),
+            );
+        }
+
+        let rot_mat = Matrix3::from_fn(|i, j| rot_data[i][j]);
+        let rot_mat_dt = Matrix3::from_fn(|i, j| rot_data[i + 3][j]);
+
+        assert!(
+            (dcm.rot_mat - rot_mat).norm() < DCM_EPSILON,
+            "#{num} {epoch}\ngot: {}want:{rot_mat}err = {:.3e}",
+            dcm.rot_mat,
+            (dcm.rot_mat - rot_mat).norm()
+        );
+
+        assert!(
+            (dcm.rot_mat_dt.unwrap() - rot_mat_dt).norm() < 1e-13,
+            "#{num} {epoch}\ngot: {}want:{rot_mat_dt}err = {:.3e}",
+            dcm.rot_mat_dt.unwrap(),
+            (dcm.rot_mat_dt.unwrap() - rot_mat_dt).norm()
+        );
+    }
+
+    spice::unload(type3_path);
+}
+
+/// Converts the array of a Chebyshev Type 2 BPC segment into that of a Type 3 segment, whose rate coefficients are
+/// those of the derivatives of the angle polynomials, like SPICE's `chbder`.
+fn type2_to_type3(type2: &[f64]) -> Vec<f64> {
+    let directory = &type2[type2.len() - 4..];
+    let (rsize, num_records) = (directory[2] as usize, directory[3] as usize);
+    let num_coeffs = (rsize - 2) / 3;
+
+    let mut type3 = Vec::with_capacity(num_records * (2 + 6 * num_coeffs) + 4);
+    for record in type2[..rsize * num_records].chunks(rsize) {
+        let radius_s = record[1];
+        type3.extend_from_slice(record);
+        for coeffs in record[2..].chunks(num_coeffs) {
+            // d_{k-1} = d_{k+1} + 2k c_k, with the first coefficient halved
+            let mut rates = vec![0.0; num_coeffs + 1];
+            for k in (1..num_coeffs).rev() {
+                rates[k - 1] = rates[k + 1] + 2.0 * k as f64 * coeffs[k];
+            }
+            rates[0] /= 2.0;
+            type3.extend(rates[..num_coeffs].iter().map(|rate| rate / radius_s));
+        }
+    }
+    type3.extend_from_slice(&[
+        directory[0],
+        directory[1],
+        (2 + 6 * num_coeffs) as f64,
+        num_records as f64,
+    ]);
+    type3
+}
+
 #[ignore = "Requires Rust SPICE -- must be executed serially"]
 #[test]
 fn validate_bpc_to_iau_rotations() {
@@ -777,3 +886,109 @@ fn validate_bpc_to_iau_rotations() {
     println!("actualized max error in position = {actual_pos_err_km:.6e} km");
     println!("actualized max error in velocity = {actual_vel_err_km_s:.6e} km/s");
 }
+
+/// Validates the fixed frames of the Moon FK (converted into Euler parameters) chained with the Moon PA DE421 BPC.
+#[ignore = "Requires Rust SPICE -- must be executed serially"]
+#[test]
+fn validate_fk_moon_rotations() {
+    let fk = "../data/moon_080317.txt";
+    let bpc = "../data/moon_pa_de421_1900-2050.bpc";
+    spice::furnsh(fk);
+    spice::furnsh(bpc);
+
+    let almanac = Almanac::new("../data/moon_fk.epa")
+        .unwrap()
+        .with_bpc(BPC::load(bpc).unwrap())
+        .unwrap();
+
+    println!("{almanac}");
+
+    let mut actual_max_err_deg = 0.0;
+
+    for (frame, name) in [(MOON_ME_FRAME, "MOON_ME"), (MOON_PA_FRAME, "MOON_PA")] {
+        for (num, epoch) in TimeSeries::inclusive(
+            Epoch::from_tdb_duration(-0.5.centuries()),
+            Epoch::from_tdb_duration(0.5.centuries()),
+            7.days(),
+        )
+        .enumerate()
+        {
+            let dcm = almanac.rotate_from_to(frame, MOON_J2000, epoch).unwrap();
+
+            let mut rot_data: [[f64; 6]; 6] = [[0.0; 6]; 6];
+            unsafe {
+                spice::c::sxform_c(
+                    cstr!(name),
+                    cstr!("J2000"),
+                    epoch.to_tdb_seconds(),
+                    rot_data.as_mut_ptr(),
+                );
+            }
+
+            let rot_mat = Matrix3::new(
+                rot_data[0][0],
+                rot_data[0][1],
+                rot_data[0][2],
+                rot_data[1][0],
+                rot_data[1][1],
+                rot_data[1][2],
+                rot_data[2][0],
+                rot_data[2][1],
+                rot_data[2][2],
+            );
+
+            let rot_mat_dt = Matrix3::new(
+                rot_data[3][0],
+                rot_data[3][1],
+                rot_data[3][2],
+                rot_data[4][0],
+                rot_data[4][1],
+                rot_data[4][2],
+                rot_data[5][0],
+                rot_data[5][1],
+                rot_data[5][2],
+            );
+
+            let spice_dcm = DCM {
+                rot_mat,
+                from: frame.orientation_id,
+                to: J2000,
+                rot_mat_dt: Some(rot_mat_dt),
+            };
+
+            let q_anise = Quaternion::from(dcm);
+            let q_spice = Quaternion::from(spice_dcm);
+
+            let (_, anise_angle) = q_anise.uvec_angle();
+            let (_, spice_angle) = q_spice.uvec_angle();
+
+            let deg_err = (anise_angle - spice_angle).to_degrees();
+
+            assert!(
+                deg_err.abs() < MAX_ERR_DEG,
+                "#{num} @ {epoch} rotation error for {name}: {deg_err:e} deg"
+            );
+
+            if deg_err.abs() > actual_max_err_deg {
+                actual_max_err_deg = deg_err.abs();
+            }
+
+            assert!(
+                (dcm.rot_mat - rot_mat).norm() < DCM_EPSILON,
+                "#{num} {epoch} {name}\ngot: {}want:{rot_mat}err = {:.3e}: {:.3e}",
+                dcm.rot_mat,
+                (dcm.rot_mat - rot_mat).norm(),
+                dcm.rot_mat - rot_mat
+            );
+
+            assert!(
+                (dcm.rot_mat_dt.unwrap() - rot_mat_dt).norm() < 1e-13,
+                "#{num} {epoch} {name}\ngot: {}want:{rot_mat_dt}err = {:.3e}",
+                dcm.rot_mat_dt.unwrap(),
+                (dcm.rot_mat_dt.unwrap() - rot_mat_dt).norm(),
+            );
+        }
+    }
+
+    println!("actualized max error in rotation angle = {actual_max_err_deg:.3e} deg");
+}